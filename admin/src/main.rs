@@ -1,11 +1,12 @@
 use clap::{Parser, Subcommand};
 use rcxcore::{
-    kill::{generate_kill_blob, KillRequest},
+    kill::{generate_kill_blob, KillReason, KillRequest},
     keystore::KeyStore,
     device::registry::DeviceRegistry,
 };
 
 use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Parser)]
 #[command(author, version, about)]
@@ -23,6 +24,10 @@ enum Command {
         #[arg(long)]
         replay: u64,
 
+        /// stolen | compromised | decommission | admin
+        #[arg(long, default_value = "admin")]
+        reason: String,
+
         #[arg(long)]
         out: String,
     },
@@ -36,17 +41,25 @@ fn main() {
     let registry = DeviceRegistry::open().expect("registry");
 
     match cli.cmd {
-        Command::Generate { device_id, replay, out } => {
+        Command::Generate { device_id, replay, reason, out } => {
             let id_bytes = hex::decode(device_id).expect("hex device id");
             let mut id = [0u8; 32];
             id.copy_from_slice(&id_bytes);
 
+            let reason = parse_reason(&reason).expect("kill reason");
+            let issued_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .expect("system clock");
+
             let blob = generate_kill_blob(
                 keystore.master_key(),
                 &registry,
                 KillRequest {
                     target_device_id: id,
                     replay,
+                    reason,
+                    issued_at,
                 },
             );
 
@@ -54,3 +67,13 @@ fn main() {
         }
     }
 }
+
+fn parse_reason(s: &str) -> Option<KillReason> {
+    match s {
+        "stolen" => Some(KillReason::Stolen),
+        "compromised" => Some(KillReason::Compromised),
+        "decommission" => Some(KillReason::Decommission),
+        "admin" => Some(KillReason::Admin),
+        _ => None,
+    }
+}
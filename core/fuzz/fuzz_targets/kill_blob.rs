@@ -0,0 +1,27 @@
+//! Kill blob parsing on arbitrary bytes.
+//!
+//! Asserts:
+//! - No panic on any input
+//! - Blobs shorter than nonce + tag never split
+//! - Payloads of any length other than V1 (41) / V2 (50) never parse
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rcxcore::fuzzing::{parse_kill_plaintext, split_kill_blob};
+
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const PLAINTEXT_LEN_V1: usize = 41;
+const PLAINTEXT_LEN_V2: usize = 50;
+
+fuzz_target!(|data: &[u8]| {
+    match split_kill_blob(data) {
+        Some(sealed) => assert_eq!(sealed, data.len() - NONCE_LEN),
+        None => assert!(data.len() < NONCE_LEN + TAG_LEN),
+    }
+
+    if parse_kill_plaintext(data) {
+        assert!(data.len() == PLAINTEXT_LEN_V1 || data.len() == PLAINTEXT_LEN_V2);
+    }
+});
@@ -20,14 +20,25 @@
 //! ❄️ SUBJECT TO SECURE_CORE_API_FREEZE ❄️
 
 use core::marker::PhantomData;
-use core::sync::atomic::Ordering;
+use core::sync::atomic::{AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::time::Duration;
 use zeroize::Zeroizing;
 
+use crate::crypto::aad::{Aad, AAD_VERSION_V1, AAD_VERSION_V3};
+use crate::crypto::aes_gcm::TAG_LEN;
+use crate::crypto::cipher::{CipherSuite, COMMIT_LEN};
+use crate::crypto::selftest;
 use crate::crypto::file::{
     encrypt_chunk,
+    encrypt_chunk_in_place,
+    encrypt_chunk_with_epoch,
     decrypt_chunk,
+    decrypt_chunk_with_epoch,
+    verify_chunk_with,
+    DecryptConfig,
     FileId,
     CloudId,
+    MAX_CHUNK_SIZE,
 };
 
 use crate::keystore::{
@@ -35,9 +46,18 @@ use crate::keystore::{
     KeyStoreError,
 };
 
+
 use crate::keystore::recovery::{
+    decode_binding,
+    encode_binding,
+    provision_phrase,
+    recover_from_guarded,
     recover_from_phrase,
+    recover_with_verifier,
+    rewrap_phrase,
+    RecoveryAuthority,
     RecoveryConfig,
+    RecoveryError,
 };
 
 use crate::keystore::session::{
@@ -46,7 +66,19 @@ use crate::keystore::session::{
     SessionError,
 };
 
-use crate::keystore::master::GLOBAL_KILLED;
+use crate::bridge::diagnostics::{Diagnostics, HealthReport, FEATURES};
+use crate::bridge::events::{CoreEvent, EventHub, EventSink, IdleLock};
+use crate::keystore::master::{self, GLOBAL_KILLED};
+use crate::kill::audit::encode_body as encode_kill_audit;
+use crate::logging::encrypted::{log_file_sizes, probe_log_root, EncryptedLog};
+use crate::memory::{GuardedKey32, GuardedVec, SensitiveBuffer};
+
+#[cfg(feature = "kem")]
+use core::cell::RefCell;
+#[cfg(feature = "kem")]
+use crate::crypto::kem::KEMError;
+#[cfg(feature = "kem")]
+use crate::keystore::kem_ring::KemKeyRing;
 
 /* ─────────────────────────────────────────────
    PUBLIC ERROR MODEL (FROZEN SURFACE)
@@ -66,6 +98,13 @@ pub enum CoreError {
    CORE HANDLE
    ───────────────────────────────────────────── */
 
+/// Failed phrase attempts (unlock + probe) before lockout.
+///
+/// SECURITY:
+/// - Process-lifetime counter
+/// - Bounds online guessing through the bridge
+const MAX_FAILED_UNLOCKS: u32 = 10;
+
 /// Secure Core handle.
 ///
 /// Owns exactly ONE keystore.
@@ -76,6 +115,21 @@ pub enum CoreError {
 /// - Kill-aware
 pub struct Core {
     keystore: KeyStore,
+    // Decrypt-side AAD version floor (monotonic, never lowered)
+    min_aad_version: AtomicU8,
+    // Cipher suite code for sessions opened by the NEXT unlock
+    cipher_suite: AtomicU8,
+    // Failed phrase attempts (shared by unlock + probe)
+    failed_unlocks: AtomicU32,
+    // Bound device fingerprint (0 = unbound, set-once)
+    device_fingerprint: AtomicU64,
+    // Lifecycle event sink (host code)
+    events: EventHub,
+    // Host-driven inactivity auto-lock
+    idle: IdleLock,
+    // Device KEM secrets (current + grace-period retired)
+    #[cfg(feature = "kem")]
+    kem: RefCell<Option<KemKeyRing>>,
     // Explicitly forbid Send + Sync across language boundaries
     _no_send_sync: PhantomData<*const ()>,
 }
@@ -86,10 +140,31 @@ impl Core {
     pub fn new() -> Self {
         Self {
             keystore: KeyStore::new(),
+            min_aad_version: AtomicU8::new(AAD_VERSION_V1),
+            cipher_suite: AtomicU8::new(CipherSuite::Aes256Gcm.code()),
+            failed_unlocks: AtomicU32::new(0),
+            device_fingerprint: AtomicU64::new(0),
+            events: EventHub::new(),
+            idle: IdleLock::new(),
+            #[cfg(feature = "kem")]
+            kem: RefCell::new(None),
             _no_send_sync: PhantomData,
         }
     }
 
+    /// Core whose keystore auto-locks after `secs` without a session
+    /// operation (`0` = disabled, same as `new`).
+    ///
+    /// Complements the host-clock `arm_idle_lock` / `tick`: this
+    /// window is enforced by the keystore itself on the late call,
+    /// and still surfaces as `CoreEvent::Lock`.
+    pub fn new_with_idle_limit(secs: u64) -> Self {
+        Self {
+            keystore: KeyStore::new_with_idle_limit(secs),
+            ..Self::new()
+        }
+    }
+
     #[inline(always)]
     fn require_alive(&self) -> Result<(), CoreError> {
         if GLOBAL_KILLED.load(Ordering::SeqCst) {
@@ -99,29 +174,397 @@ impl Core {
         }
     }
 
+    #[inline(always)]
+    fn require_unlock_attempts(&self) -> Result<(), CoreError> {
+        if self.failed_unlocks.load(Ordering::SeqCst) >= MAX_FAILED_UNLOCKS {
+            Err(CoreError::Denied)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    fn record_unlock_failure(&self) {
+        self.failed_unlocks.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Select the AEAD for chunks sealed after the NEXT unlock
+    /// (`CipherSuite::code`: 0 = AES-256-GCM, 1 = ChaCha20-Poly1305).
+    ///
+    /// SECURITY:
+    /// - Unknown / not compiled-in suite => `InvalidInput`
+    /// - While unlocked => `Denied` (a session never switches suite)
+    /// - Decryption follows the suite recorded in each chunk's AAD
+    pub fn set_cipher_suite(&self, code: u8) -> Result<(), CoreError> {
+        self.require_alive()?;
+
+        let suite = CipherSuite::from_code(code).ok_or(CoreError::InvalidInput)?;
+
+        if self.keystore.is_unlocked() {
+            return Err(CoreError::Denied);
+        }
+
+        self.cipher_suite.store(suite.code(), Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Suite for new sessions (stored codes are validated on set).
+    #[inline(always)]
+    fn session_suite(&self) -> Result<CipherSuite, CoreError> {
+        CipherSuite::from_code(self.cipher_suite.load(Ordering::SeqCst))
+            .ok_or(CoreError::InvalidInput)
+    }
+
     /// Unlock Secure Core using a recovery phrase.
+    ///
+    /// LOWER ASSURANCE (kept for compatibility): `phrase` has usually
+    /// been copied through unlocked memory already. FFI adapters
+    /// should use `unlock_with_guarded_phrase`.
     pub fn unlock_with_phrase(
         &self,
         phrase: Vec<u8>,
     ) -> Result<(), CoreError> {
+        let phrase = Zeroizing::new(phrase);
+        self.unlock_with(|core| core.recover_phrase(phrase))
+    }
+
+    /// Unlock Secure Core using a phrase held in LOCKED memory.
+    ///
+    /// Adapters copy the host buffer straight into a `GuardedVec`
+    /// and wipe the source, so the only full copy of the phrase is
+    /// page-locked and zeroized on drop.
+    pub fn unlock_with_guarded_phrase(
+        &self,
+        phrase: GuardedVec,
+    ) -> Result<(), CoreError> {
+        self.unlock_with(|core| match core.read_phrase_binding()? {
+            Some((cfg, v)) => recover_from_guarded(&phrase, &cfg, Some(&v)),
+            None => recover_from_guarded(&phrase, &RecoveryConfig::default(), None),
+        })
+    }
+
+    fn unlock_with(
+        &self,
+        recover: impl FnOnce(&Self) -> Result<RecoveryAuthority, RecoveryError>,
+    ) -> Result<(), CoreError> {
+        self.require_alive()?;
+        self.require_unlock_attempts()?;
+
+        // Core is single-session: one keystore session per handle
+        if self.keystore.is_unlocked() {
+            return Err(CoreError::Denied);
+        }
+
+        let auth = recover(self).map_err(|_| {
+            self.record_unlock_failure();
+            CoreError::IntegrityFailure
+        })?;
+
+        self.keystore
+            .unlock_with_suite(auth, self.session_suite()?)
+            .map_err(|e| self.keystore_error(e))?;
+
+        self.failed_unlocks.store(0, Ordering::SeqCst);
+        self.events.emit(CoreEvent::Unlock);
+        Ok(())
+    }
+
+    /// Check whether a phrase WOULD unlock, without unlocking.
+    ///
+    /// SECURITY:
+    /// - Runs the full KDF + integrity check
+    /// - Keystore state is NEVER changed
+    /// - Derived authority is dropped (zeroized) immediately
+    /// - Counts against the unlock-attempt limit
+    pub fn probe_phrase(
+        &self,
+        phrase: Vec<u8>,
+    ) -> Result<bool, CoreError> {
         self.require_alive()?;
+        self.require_unlock_attempts()?;
 
         let phrase = Zeroizing::new(phrase);
 
-        let auth = recover_from_phrase(
-            phrase,
-            &RecoveryConfig::default(),
-        )
-        .map_err(|_| CoreError::IntegrityFailure)?;
+        match self.recover_phrase(phrase) {
+            Ok(auth) => {
+                drop(auth);
+                Ok(true)
+            }
+            Err(RecoveryError::IntegrityFailure) => {
+                self.record_unlock_failure();
+                Ok(false)
+            }
+            Err(e) => Err(map_recovery_error(e)),
+        }
+    }
+
+    /// Provision the recovery phrase verifier (set-once).
+    ///
+    /// SECURITY:
+    /// - Already provisioned => `Denied` (use `change_phrase`)
+    /// - Keystore state is NEVER changed
+    pub fn provision_phrase(&self, phrase: Zeroizing<Vec<u8>>) -> Result<(), CoreError> {
+        self.provision_phrase_with(phrase, RecoveryConfig::default)
+    }
+
+    /// `provision_phrase` with the KDF cost calibrated to `target`
+    /// unlock latency on this device.
+    ///
+    /// The calibrated cost is persisted with the verifier, so every
+    /// later unlock / change / rewrap re-derives at the SAME cost.
+    pub fn provision_phrase_calibrated(
+        &self,
+        phrase: Zeroizing<Vec<u8>>,
+        target: Duration,
+    ) -> Result<(), CoreError> {
+        self.provision_phrase_with(phrase, || RecoveryConfig::calibrated(target))
+    }
+
+    fn provision_phrase_with(
+        &self,
+        phrase: Zeroizing<Vec<u8>>,
+        cfg: impl FnOnce() -> RecoveryConfig,
+    ) -> Result<(), CoreError> {
+        self.require_alive()?;
+
+        let mut log = EncryptedLog::open_phrase_verifier().map_err(|_| CoreError::Denied)?;
+        if log.read_fixed().map_err(|_| CoreError::IntegrityFailure)?.is_some() {
+            return Err(CoreError::Denied);
+        }
 
+        // Calibrate only once provisioning is known to proceed
+        let cfg = cfg();
+        let (auth, verifier) = provision_phrase(phrase, &cfg).map_err(map_recovery_error)?;
+
+        // The new key hierarchy's write-version ledger exists before it does
+        auth.start_nonce_ledger().map_err(|_| CoreError::Denied)?;
+        drop(auth);
+
+        log.replace_fixed(&encode_binding(&verifier, &cfg)).map_err(|_| CoreError::Denied)
+    }
+
+    /// Change the recovery phrase (old → new) atomically.
+    ///
+    /// SECURITY:
+    /// - `old` MUST match the provisioned verifier (counts against
+    ///   the unlock-attempt limit); otherwise nothing changes
+    /// - The new verifier replaces the old one in a single rename:
+    ///   every failure before it leaves the old phrase in force
+    /// - An unlocked Core is rekeyed to the new session key; data
+    ///   sealed under the old key is NOT re-encrypted here
+    /// - The new session key starts its own write-version ledger
+    ///   (`NonceLedger::create`) before the commit point
+    pub fn change_phrase(
+        &self,
+        old: Zeroizing<Vec<u8>>,
+        new: Zeroizing<Vec<u8>>,
+    ) -> Result<(), CoreError> {
+        self.require_alive()?;
+        self.require_unlock_attempts()?;
+
+        let mut log = EncryptedLog::open_phrase_verifier().map_err(|_| CoreError::Denied)?;
+        let stored = log
+            .read_fixed()
+            .map_err(|_| CoreError::IntegrityFailure)?
+            .ok_or(CoreError::Denied)?;
+
+        // The new phrase is bound at the SAME (persisted) cost
+        let (cfg, verifier) = decode_binding(&stored).ok_or(CoreError::IntegrityFailure)?;
+
+        match recover_with_verifier(old, &cfg, verifier) {
+            Ok(auth) => drop(auth),
+            Err(RecoveryError::IntegrityFailure) => {
+                self.record_unlock_failure();
+                return Err(CoreError::IntegrityFailure);
+            }
+            Err(e) => return Err(map_recovery_error(e)),
+        }
+        self.failed_unlocks.store(0, Ordering::SeqCst);
+
+        let (auth, new_verifier) = provision_phrase(new, &cfg).map_err(map_recovery_error)?;
+        auth.start_nonce_ledger().map_err(|_| CoreError::Denied)?;
+
+        // Commit point
+        log.replace_fixed(&encode_binding(&new_verifier, &cfg)).map_err(|_| CoreError::Denied)?;
+
+        if !self.keystore.is_unlocked() {
+            return Ok(());
+        }
+
+        // Rekey: no session may outlive the old phrase
+        self.lock();
         self.keystore
-            .unlock(auth)
-            .map_err(map_keystore_error)
+            .unlock_with_suite(auth, self.session_suite()?)
+            .map_err(|e| self.keystore_error(e))?;
+        self.events.emit(CoreEvent::Unlock);
+        Ok(())
+    }
+
+    /// Rotate the recovery phrase (old → new) KEEPING the session key.
+    ///
+    /// Unlike `change_phrase`, file keys stay valid: the new phrase
+    /// unwraps the same session key instead of deriving a new one.
+    ///
+    /// SECURITY:
+    /// - `old` MUST recover (counts against the unlock-attempt
+    ///   limit); otherwise nothing changes
+    /// - The wrapped verifier replaces the old binding in a single
+    ///   rename, then is read back: every failure before the rename
+    ///   leaves the old phrase in force
+    /// - Keystore state is NEVER changed
+    pub fn rewrap_recovery(
+        &self,
+        old_phrase: Zeroizing<Vec<u8>>,
+        new_phrase: Zeroizing<Vec<u8>>,
+    ) -> Result<(), CoreError> {
+        self.require_alive()?;
+        self.require_unlock_attempts()?;
+
+        let mut log = EncryptedLog::open_phrase_verifier().map_err(|_| CoreError::Denied)?;
+        let stored = log.read_fixed().map_err(|_| CoreError::IntegrityFailure)?;
+
+        let (cfg, verifier) = match stored.as_deref() {
+            Some(s) => decode_binding(s).ok_or(CoreError::IntegrityFailure)?,
+            None => (RecoveryConfig::default(), &[][..]),
+        };
+
+        let recovered = match stored {
+            Some(_) => recover_with_verifier(old_phrase, &cfg, verifier),
+            None => recover_from_phrase(old_phrase, &cfg),
+        };
+        let auth = match recovered {
+            Ok(auth) => auth,
+            Err(RecoveryError::IntegrityFailure) => {
+                self.record_unlock_failure();
+                return Err(CoreError::IntegrityFailure);
+            }
+            Err(e) => return Err(map_recovery_error(e)),
+        };
+        self.failed_unlocks.store(0, Ordering::SeqCst);
+
+        let (auth, wrapped) = rewrap_phrase(auth, new_phrase, &cfg).map_err(map_recovery_error)?;
+        drop(auth);
+        let binding = encode_binding(&wrapped, &cfg);
+
+        // Commit point
+        log.replace_fixed(&binding).map_err(|_| CoreError::Denied)?;
+
+        match log.read_fixed() {
+            Ok(Some(stored)) if stored == binding => Ok(()),
+            _ => Err(CoreError::IntegrityFailure),
+        }
+    }
+
+    /// Phrase → authority, against the provisioned verifier when
+    /// one exists (unreadable verifier => `IntegrityFailure`).
+    fn recover_phrase(
+        &self,
+        phrase: Zeroizing<Vec<u8>>,
+    ) -> Result<RecoveryAuthority, RecoveryError> {
+        match self.read_phrase_binding()? {
+            Some((cfg, v)) => recover_with_verifier(phrase, &cfg, &v),
+            None => recover_from_phrase(phrase, &RecoveryConfig::default()),
+        }
+    }
+
+    /// Provisioned binding (KDF cost + verifier), if any.
+    ///
+    /// Unreadable / undecodable => `IntegrityFailure`.
+    fn read_phrase_binding(&self) -> Result<Option<(RecoveryConfig, Vec<u8>)>, RecoveryError> {
+        let Some(stored) = self.read_phrase_verifier()? else {
+            return Ok(None);
+        };
+        let (cfg, verifier) = decode_binding(&stored).ok_or(RecoveryError::IntegrityFailure)?;
+        Ok(Some((cfg, verifier.to_vec())))
+    }
+
+    /// Provisioned verifier, if any (unreadable => `IntegrityFailure`).
+    fn read_phrase_verifier(&self) -> Result<Option<Vec<u8>>, RecoveryError> {
+        EncryptedLog::open_phrase_verifier()
+            .and_then(|mut log| log.read_fixed())
+            .map_err(|_| RecoveryError::IntegrityFailure)
     }
 
     /// User-initiated local lock.
     pub fn lock(&self) {
+        let was_unlocked = self.keystore.is_unlocked();
         self.keystore.lock();
+        self.wipe_kem_ring();
+
+        if was_unlocked && !self.keystore.is_unlocked() {
+            self.events.emit(CoreEvent::Lock);
+        }
+    }
+
+    /* ───────────── EVENTS / IDLE LOCK ───────────── */
+
+    /// Register (or replace) the lifecycle event sink.
+    pub fn set_event_sink(&self, sink: Box<dyn EventSink>) {
+        self.events.set(Some(sink));
+    }
+
+    /// Arm inactivity auto-lock.
+    ///
+    /// `now_ms` is the HOST monotonic clock; the same clock MUST
+    /// be passed to `touch` / `tick`.
+    pub fn arm_idle_lock(&self, timeout_ms: u64, now_ms: u64) -> Result<(), CoreError> {
+        self.require_alive()?;
+
+        if timeout_ms == 0 {
+            return Err(CoreError::InvalidInput);
+        }
+
+        self.idle.arm(timeout_ms, now_ms);
+        Ok(())
+    }
+
+    /// Disarm inactivity auto-lock.
+    pub fn disarm_idle_lock(&self) {
+        self.idle.disarm();
+    }
+
+    /// Record user activity (resets the idle deadline).
+    pub fn touch(&self, now_ms: u64) {
+        self.idle.touch(now_ms);
+    }
+
+    /// Host-driven idle check. Returns `true` if this call locked.
+    ///
+    /// SECURITY:
+    /// - No threads: locking only happens inside `tick`
+    /// - Emits `CoreEvent::Lock` through the registered sink
+    pub fn tick(&self, now_ms: u64) -> bool {
+        #[cfg(feature = "kem")]
+        if let Ok(mut ring) = self.kem.try_borrow_mut() {
+            if let Some(ring) = ring.as_mut() {
+                ring.prune(now_ms);
+            }
+        }
+
+        if !self.keystore.is_unlocked() || !self.idle.expired(now_ms) {
+            return false;
+        }
+
+        self.lock();
+        !self.keystore.is_unlocked()
+    }
+
+    /// `map_keystore_error`, emitting `CoreEvent::Lock` if the
+    /// keystore just auto-locked on idle (it has no sink of its own).
+    fn keystore_error(&self, err: KeyStoreError) -> CoreError {
+        if self.keystore.take_idle_lock() {
+            self.wipe_kem_ring();
+            self.events.emit(CoreEvent::Lock);
+        }
+        map_keystore_error(err)
+    }
+
+    /// Check whether Secure Core is unlocked (never true once killed).
+    ///
+    /// Authoritative (keystore state, not inferred from error codes);
+    /// poison fails closed and escalates to kill.
+    pub fn is_unlocked(&self) -> bool {
+        !GLOBAL_KILLED.load(Ordering::SeqCst) && self.keystore.is_unlocked()
     }
 
     /// Check whether Secure Core is killed.
@@ -129,8 +572,337 @@ impl Core {
         GLOBAL_KILLED.load(Ordering::SeqCst)
     }
 
+    /// Why Secure Core was killed (`KillCause` as `u8`).
+    ///
+    /// Diagnostic only: distinguishes an admin kill from a
+    /// poison-induced one. `0` = alive; killed without a recorded
+    /// cause reads as `0xFF` (unknown).
+    pub fn kill_cause(&self) -> u8 {
+        master::kill_cause() as u8
+    }
+
+    /* ───────────── OUTPUT SIZING ───────────── */
+
+    /// Exact `encrypt_chunk` output length for `plaintext_len` bytes.
+    ///
+    /// `None` on overflow. Hosts MUST size buffers with this rather
+    /// than hard-coding the AEAD tag length.
+    #[inline(always)]
+    pub const fn ciphertext_len(plaintext_len: usize) -> Option<usize> {
+        plaintext_len.checked_add(TAG_LEN)
+    }
+
+    /// Exact `decrypt_chunk` output length for `ciphertext_len` bytes.
+    ///
+    /// `None` if shorter than the AEAD tag.
+    #[inline(always)]
+    pub const fn plaintext_len(ciphertext_len: usize) -> Option<usize> {
+        ciphertext_len.checked_sub(TAG_LEN)
+    }
+
+    /* ───────────── DEVICE BINDING ───────────── */
+
+    /// Bind this core to the device fingerprint (set-once).
+    ///
+    /// SECURITY:
+    /// - Zero is not a valid fingerprint
+    /// - Re-binding to a DIFFERENT fingerprint is `Denied`
+    pub fn bind_device_fingerprint(
+        &self,
+        fingerprint: u64,
+    ) -> Result<(), CoreError> {
+        self.require_alive()?;
+
+        if fingerprint == 0 {
+            return Err(CoreError::InvalidInput);
+        }
+
+        match self.device_fingerprint.compare_exchange(
+            0,
+            fingerprint,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        ) {
+            Ok(_) => Ok(()),
+            Err(current) if current == fingerprint => Ok(()),
+            Err(_) => Err(CoreError::Denied),
+        }
+    }
+
+    #[inline(always)]
+    fn require_fingerprint(&self) -> Result<u64, CoreError> {
+        match self.device_fingerprint.load(Ordering::SeqCst) {
+            0 => Err(CoreError::Denied),
+            fp => Ok(fp),
+        }
+    }
+
+    /// Attest the current kill state for remote verification.
+    ///
+    /// Returns `HMAC(attest_key, killed || fingerprint || nonce)`.
+    ///
+    /// SECURITY:
+    /// - Works AFTER kill (honestly reports killed)
+    /// - Requires a bound device fingerprint
+    /// - Nonce MUST be a fresh server challenge (1..=64 bytes)
+    pub fn attest_state(&self, nonce: &[u8]) -> Result<[u8; 32], CoreError> {
+        let fingerprint = self.require_fingerprint()?;
+
+        self.keystore
+            .attest_state(fingerprint, nonce)
+            .map_err(|e| self.keystore_error(e))
+    }
+
+    /// Run `f` with the device-bound key (`Session::device_key`:
+    /// `Purpose::Recovery`, own domain, `device_fingerprint` context).
+    ///
+    /// For protocol MACs (pairing, server handshake): stable across
+    /// unlocks of the same vault on the same device.
+    ///
+    /// SECURITY:
+    /// - Key is only lent to `f`; bytes never leave guarded memory
+    /// - Zeroized when `f` returns
+    /// - Locked / killed / unbound fingerprint => error (fail-closed)
+    pub fn with_attestation_key<R>(
+        &self,
+        f: impl FnOnce(&GuardedKey32) -> R,
+    ) -> Result<R, CoreError> {
+        self.require_alive()?;
+        let fingerprint = self.require_fingerprint()?;
+
+        let key = self
+            .keystore
+            .device_key(fingerprint)
+            .map_err(|e| self.keystore_error(e))?;
+
+        Ok(f(&key))
+    }
+
+    /* ───────────── KEM SECRET ROTATION ───────────── */
+
+    /// Rotate the device KEM static secret.
+    ///
+    /// The previous secret stays usable for importing OLD backups
+    /// (`with_backup_key`) until `now_ms + grace_ms` (host clock,
+    /// see `tick`), then is wiped. Returns the NEW public key and
+    /// emits `KemRotated`.
+    ///
+    /// The ring lives with the unlocked keystore: lock (explicit or
+    /// idle) drops it, kill wipes its secrets (`kem_ring`).
+    #[cfg(feature = "kem")]
+    pub fn rotate_kem_secret(&self, now_ms: u64, grace_ms: u64) -> Result<[u8; 32], CoreError> {
+        self.require_kem_session()?;
+
+        let mut ring = self.kem.try_borrow_mut().map_err(|_| CoreError::Denied)?;
+
+        let public = match ring.as_mut() {
+            Some(r) => r.rotate(now_ms, grace_ms),
+            None => KemKeyRing::generate().and_then(|r| ring.insert(r).public_key()),
+        }
+        .map_err(map_kem_error)?;
+
+        drop(ring);
+        self.events.emit(CoreEvent::KemRotated);
+        Ok(public)
+    }
+
+    /// Current device KEM public key (generated on first use).
+    #[cfg(feature = "kem")]
+    pub fn kem_public_key(&self) -> Result<[u8; 32], CoreError> {
+        self.require_kem_session()?;
+
+        let mut ring = self.kem.try_borrow_mut().map_err(|_| CoreError::Denied)?;
+
+        match ring.as_ref() {
+            Some(r) => r.public_key(),
+            None => KemKeyRing::generate().and_then(|r| ring.insert(r).public_key()),
+        }
+        .map_err(map_kem_error)
+    }
+
+    /// Decapsulate an imported backup's key and lend it to `f`.
+    ///
+    /// The current secret is tried first, then every retired secret
+    /// still inside its grace period; the first key accepted by
+    /// `verify` (e.g. the backup MAC check) is used.
+    ///
+    /// SECURITY:
+    /// - X25519 never fails on a wrong secret: `verify` is the ONLY
+    ///   thing that selects a key
+    /// - No key accepted => `IntegrityFailure`
+    /// - Key is only lent to `f`; zeroized when `f` returns
+    /// - Locked / killed => error (fail-closed)
+    #[cfg(feature = "kem")]
+    pub fn with_backup_key<R>(
+        &self,
+        ephemeral_public: &[u8; 32],
+        context: &[u8],
+        now_ms: u64,
+        verify: impl FnMut(&GuardedKey32) -> bool,
+        f: impl FnOnce(&GuardedKey32) -> R,
+    ) -> Result<R, CoreError> {
+        self.require_kem_session()?;
+
+        let mut ring = self.kem.try_borrow_mut().map_err(|_| CoreError::Denied)?;
+        let ring = ring.as_mut().ok_or(CoreError::IntegrityFailure)?;
+
+        let key = ring
+            .decapsulate_verified(ephemeral_public, context, now_ms, verify)
+            .map_err(|e| match e {
+                KEMError::Killed => CoreError::Killed,
+                KEMError::Derive => CoreError::IntegrityFailure,
+            })?;
+
+        Ok(f(&key))
+    }
+
+    #[cfg(feature = "kem")]
+    fn require_kem_session(&self) -> Result<(), CoreError> {
+        self.require_alive()?;
+
+        if self.keystore.is_unlocked() {
+            Ok(())
+        } else {
+            self.wipe_kem_ring();
+            Err(CoreError::Locked)
+        }
+    }
+
+    /// Drop (wipe) the device KEM ring (lock / idle lock).
+    fn wipe_kem_ring(&self) {
+        #[cfg(feature = "kem")]
+        if let Ok(mut ring) = self.kem.try_borrow_mut() {
+            ring.take();
+        }
+    }
+
+    /// Export the device's kill history as an authenticated blob.
+    ///
+    /// Format + verification: `kill::audit`.
+    ///
+    /// SECURITY:
+    /// - Read-only (logs are never created or written)
+    /// - Kill log hash chain MUST verify (`IntegrityFailure`)
+    /// - Works AFTER kill (contents are non-secret)
+    /// - Requires a keystore that has been unlocked at least once
+    ///   in this process (audit key is derived from it)
+    pub fn export_kill_audit(&self) -> Result<Vec<u8>, CoreError> {
+        let records = match EncryptedLog::open_device_kill_log_read_only() {
+            Ok(Some(mut log)) => log.verify_chain().and_then(|()| log.read_records()),
+            Ok(None) => Ok(Vec::new()),
+            Err(()) => Err(()),
+        }
+        .map_err(|_| CoreError::IntegrityFailure)?;
+
+        let tokens = match EncryptedLog::open_replay_log_read_only() {
+            Ok(Some(mut log)) => log.read_all_u64(),
+            Ok(None) => Ok(Vec::new()),
+            Err(()) => Err(()),
+        }
+        .map_err(|_| CoreError::IntegrityFailure)?;
+
+        let mut blob = encode_kill_audit(self.is_killed(), &records, &tokens)
+            .map_err(|_| CoreError::InvalidInput)?;
+
+        let tag = self
+            .keystore
+            .mac_kill_audit(&blob)
+            .map_err(|e| self.keystore_error(e))?;
+
+        blob.extend_from_slice(&tag);
+        Ok(blob)
+    }
+
+    /* ───────────── DIAGNOSTICS ───────────── */
+
+    /// Sanitized, non-secret state dump for bug reports.
+    ///
+    /// SECURITY:
+    /// - Never touches key material
+    /// - Available after kill
+    pub fn diagnostics(&self) -> Diagnostics {
+        Diagnostics {
+            version: env!("CARGO_PKG_VERSION"),
+            features: FEATURES,
+            killed: self.is_killed(),
+            unlocked: self.keystore.is_unlocked(),
+            device_fingerprint: self.device_fingerprint.load(Ordering::SeqCst),
+            log_sizes: log_file_sizes(),
+        }
+    }
+
+    /// One-call health verdict for fleet monitoring.
+    ///
+    /// SECURITY:
+    /// - Read-only: runs the KATs, reads the verifier READ-ONLY and
+    ///   writes only a scratch file (never a real log)
+    /// - Safe to call repeatedly, locked or unlocked, after kill
+    pub fn health_check(&self) -> HealthReport {
+        let provisioned = match EncryptedLog::open_phrase_verifier_read_only() {
+            Ok(Some(mut log)) => matches!(log.read_fixed(), Ok(Some(_))),
+            _ => false,
+        };
+
+        HealthReport::new(selftest::run(), !self.is_killed(), provisioned, probe_log_root())
+    }
+
+    /// Raise the minimum AAD version accepted on decrypt.
+    ///
+    /// SECURITY:
+    /// - Monotonic: lowering the floor is `Denied`, even when racing
+    ///   another raise
+    /// - Above the newest chunk layout (`AAD_VERSION_V3`) =>
+    ///   `InvalidInput` (would refuse every chunk)
+    /// - Retired formats are refused before any key derivation
+    pub fn set_min_aad_version(&self, version: u8) -> Result<(), CoreError> {
+        self.require_alive()?;
+
+        if version > AAD_VERSION_V3 {
+            return Err(CoreError::InvalidInput);
+        }
+
+        self.min_aad_version
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+                (version >= current).then_some(version)
+            })
+            .map(|_| ())
+            .map_err(|_| CoreError::Denied)
+    }
+
+    #[inline(always)]
+    fn decrypt_config(&self) -> DecryptConfig {
+        DecryptConfig {
+            min_aad_version: self.min_aad_version.load(Ordering::SeqCst),
+        }
+    }
+
     /* ───────────── FILE CRYPTO ───────────── */
 
+    /// Derive and cache the file key so the first chunk operation
+    /// on `file_id` skips key derivation.
+    ///
+    /// SECURITY:
+    /// - No-op when locked or killed
+    /// - Cached key is wiped with the session (lock / kill)
+    pub fn prewarm(&self, file_id: FileId, cloud_id: CloudId) {
+        if self.require_alive().is_err() {
+            return;
+        }
+
+        if self.require_live_file(file_id).is_err() {
+            return;
+        }
+
+        let Some(aad) = Aad::new(file_id, 0, cloud_id, AAD_VERSION_V1) else {
+            return;
+        };
+
+        let _ = self
+            .keystore
+            .with_session(|s| s.prewarm(&aad))
+            .map_err(|e| self.keystore_error(e));
+    }
+
     /// Encrypt a file chunk.
     pub fn encrypt_chunk(
         &self,
@@ -141,6 +913,7 @@ impl Core {
         out: &mut [u8],
     ) -> Result<EncryptResult, CoreError> {
         self.require_alive()?;
+        self.require_live_file(file_id)?;
 
         self.keystore
             .with_session(|s| {
@@ -153,10 +926,44 @@ impl Core {
                     out,
                 )
             })
-            .map_err(map_keystore_error)
+            .map_err(|e| self.keystore_error(e))
+    }
+
+    /// `encrypt_chunk` without a separate plaintext buffer.
+    ///
+    /// `buf` holds the plaintext followed by `TAG_LEN` spare bytes
+    /// (`ciphertext_len(plaintext_len)` in total) and is sealed in
+    /// place into the same output as `encrypt_chunk`.
+    ///
+    /// SECURITY:
+    /// - `buf` is wiped on ALL failures (plaintext included)
+    pub fn encrypt_chunk_in_place(
+        &self,
+        file_id: FileId,
+        cloud_id: CloudId,
+        chunk: u32,
+        buf: &mut [u8],
+    ) -> Result<EncryptResult, CoreError> {
+        let result = self
+            .require_alive()
+            .and_then(|()| self.require_live_file(file_id))
+            .and_then(|()| {
+                self.keystore
+                    .with_session(|s| encrypt_chunk_in_place(s, file_id, cloud_id, chunk, buf))
+                    .map_err(|e| self.keystore_error(e))
+            });
+
+        if result.is_err() {
+            buf.fill(0);
+        }
+        result
     }
 
-    /// Decrypt + verify a file chunk.
+    /// Decrypt + verify a file chunk sealed by `encrypt_chunk`
+    /// under the CURRENT session (its suite and ratchet generation).
+    ///
+    /// Hosts that store `EncryptResult::aad` should use
+    /// `decrypt_stored_chunk` (chunks from any suite / generation).
     pub fn decrypt_chunk(
         &self,
         file_id: FileId,
@@ -166,31 +973,430 @@ impl Core {
         out: &mut [u8],
     ) -> Result<VerifyResult, CoreError> {
         self.require_alive()?;
+        self.require_live_file(file_id)?;
+
+        let cfg = self.decrypt_config();
 
         self.keystore
             .with_session(|s| {
-                decrypt_chunk(
-                    s,
-                    file_id,
-                    cloud_id,
-                    chunk,
-                    ciphertext,
-                    out,
-                )
+                let aad = Aad::new(file_id, chunk, cloud_id, AAD_VERSION_V1)
+                    .ok_or(SessionError::InvalidInput)?
+                    .with_cipher(s.suite())
+                    .with_generation(s.generation());
+
+                decrypt_chunk(s, file_id, cloud_id, chunk, &aad.serialize(), None, &cfg, ciphertext, out)
             })
-            .map_err(map_keystore_error)
+            .map_err(|e| self.keystore_error(e))
     }
-}
 
-/* ───────────── ERROR MAPPING ───────────── */
+    /// Decrypt + verify the chunk expected at `(file_id, cloud_id,
+    /// chunk)` using the AAD and key commitment stored with it
+    /// (`EncryptResult::aad`, `EncryptResult::commitment`).
+    ///
+    /// SECURITY:
+    /// - Stored position fields must match the request (`InvalidInput`)
+    /// - This Core's `min_aad_version` floor applies
+    /// - Commitment mismatch => `VerifyResult(false)`, checked
+    ///   before the AEAD tag (no key partitioning)
+    #[allow(clippy::too_many_arguments)]
+    pub fn decrypt_stored_chunk(
+        &self,
+        file_id: FileId,
+        cloud_id: CloudId,
+        chunk: u32,
+        aad: &[u8],
+        commitment: &[u8; COMMIT_LEN],
+        ciphertext: &[u8],
+        out: &mut [u8],
+    ) -> Result<VerifyResult, CoreError> {
+        self.require_alive()?;
+        self.require_live_file(file_id)?;
 
-#[inline(always)]
-fn map_keystore_error(err: KeyStoreError) -> CoreError {
-    match err {
-        KeyStoreError::Locked => CoreError::Locked,
-        KeyStoreError::Killed => CoreError::Killed,
-        KeyStoreError::Poisoned => CoreError::Killed,
+        let cfg = self.decrypt_config();
+
+        self.keystore
+            .with_session(|s| {
+                decrypt_chunk(s, file_id, cloud_id, chunk, aad, Some(commitment), &cfg, ciphertext, out)
+            })
+            .map_err(|e| self.keystore_error(e))
+    }
+
+    /// Authenticate a stored chunk without producing plaintext
+    /// (backup integrity audits).
+    pub fn verify_chunk(
+        &self,
+        file_id: FileId,
+        cloud_id: CloudId,
+        chunk: u32,
+        ciphertext: &[u8],
+    ) -> Result<VerifyResult, CoreError> {
+        self.require_alive()?;
+        self.require_live_file(file_id)?;
+
+        let cfg = self.decrypt_config();
+
+        self.keystore
+            .with_session(|s| {
+                verify_chunk_with(
+                    s,
+                    file_id,
+                    cloud_id,
+                    chunk,
+                    AAD_VERSION_V1,
+                    &cfg,
+                    ciphertext,
+                )
+            })
+            .map_err(|e| self.keystore_error(e))
+    }
+
+    /// Decrypt a whole file, aborting on the FIRST bad chunk.
+    ///
+    /// `chunks[i]` is decrypted as chunk index `i`; `sink` receives
+    /// each VERIFIED plaintext chunk in order and returns `false`
+    /// to stop early (`Denied`).
+    ///
+    /// SECURITY:
+    /// - All chunk lengths are validated before any decryption
+    /// - First authentication failure => `IntegrityFailure`,
+    ///   no further plaintext is delivered
+    /// - Plaintext lives in ONE guarded buffer, wiped between chunks
+    pub fn decrypt_file(
+        &self,
+        file_id: FileId,
+        cloud_id: CloudId,
+        chunks: &[&[u8]],
+        sink: &mut dyn FnMut(&[u8]) -> bool,
+    ) -> Result<(), CoreError> {
+        self.require_alive()?;
+
+        if u32::try_from(chunks.len()).is_err() {
+            return Err(CoreError::InvalidInput);
+        }
+
+        let mut max_pt = 0usize;
+        for ct in chunks {
+            let pt_len = ct
+                .len()
+                .checked_sub(TAG_LEN)
+                .ok_or(CoreError::InvalidInput)?;
+
+            if pt_len > MAX_CHUNK_SIZE {
+                return Err(CoreError::InvalidInput);
+            }
+            max_pt = max_pt.max(pt_len);
+        }
+
+        let mut buf = GuardedVec::zeroed(max_pt);
+
+        for (index, ct) in chunks.iter().enumerate() {
+            let pt_len = ct.len() - TAG_LEN;
+            let out = &mut buf.borrow_mut()[..pt_len];
+
+            let verified = self.decrypt_chunk(
+                file_id,
+                cloud_id,
+                index as u32,
+                ct,
+                out,
+            );
+
+            let delivered = match verified {
+                Ok(VerifyResult(true)) => sink(out),
+                Ok(VerifyResult(false)) => {
+                    out.fill(0);
+                    return Err(CoreError::IntegrityFailure);
+                }
+                Err(e) => {
+                    out.fill(0);
+                    return Err(e);
+                }
+            };
+
+            out.fill(0);
+
+            if !delivered {
+                return Err(CoreError::Denied);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that an interrupted upload can resume under this session.
+    ///
+    /// Authenticates the last known-good chunk; `Ok(false)` means
+    /// the key hierarchy changed (e.g. rekey) and the host MUST
+    /// restart the upload from chunk 0.
+    ///
+    /// SECURITY:
+    /// - Plaintext is decrypted into guarded memory and wiped
+    /// - Nothing is returned beyond the verification bit
+    pub fn verify_resumable(
+        &self,
+        file_id: FileId,
+        cloud_id: CloudId,
+        last_index: u32,
+        last_chunk_ciphertext: &[u8],
+    ) -> Result<bool, CoreError> {
+        match self.decrypt_chunk_guarded(file_id, cloud_id, last_index, last_chunk_ciphertext) {
+            Ok(pt) => {
+                drop(pt);
+                Ok(true)
+            }
+            Err(CoreError::IntegrityFailure) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /* ───────────── FILE TOMBSTONES ───────────── */
+
+    /// Permanently refuse all further crypto on `file_id`.
+    ///
+    /// The file key stays derivable from the session key, but the
+    /// Core will never again use it.
+    ///
+    /// SECURITY:
+    /// - Authenticated tombstone persisted BEFORE it takes effect
+    /// - Monotonic: there is no API to lift a tombstone
+    /// - Survives restart (replayed on the first file operation)
+    /// - No log root yet => refused (`CryptoFailure`); file crypto
+    ///   itself never needs a log root
+    pub fn tombstone_file(&self, file_id: FileId) -> Result<(), CoreError> {
+        self.require_alive()?;
+
+        self.keystore
+            .with_session(|s| s.revoke_file(file_id))
+            .map(|_| ())
+            .map_err(|e| self.keystore_error(e))
+    }
+
+    /* ───────────── APPLICATION INDEX ───────────── */
+
+    /// Seal the application's file index (format: `keystore::index`).
+    ///
+    /// SECURITY:
+    /// - Random nonce per call; fresh persisted, MAC-authenticated
+    ///   `index_version` per call (rollback floor)
+    /// - Missing / unverifiable version log => refused
+    /// - Keys derived from the session key (`Purpose::Metadata`)
+    pub fn seal_index(&self, plaintext: &[u8]) -> Result<Vec<u8>, CoreError> {
+        self.require_alive()?;
+
+        self.keystore
+            .with_session(|s| s.seal_index(plaintext))
+            .map(|sealed| sealed.0)
+            .map_err(|e| self.keystore_error(e))
+    }
+
+    /// Open an index sealed by `seal_index`.
+    ///
+    /// SECURITY:
+    /// - Authenticated BEFORE the version is considered
+    /// - Older than the highest `index_version` seen => `IntegrityFailure`
+    pub fn open_index(&self, blob: &[u8]) -> Result<Vec<u8>, CoreError> {
+        self.require_alive()?;
+
+        let (_, plaintext) = self
+            .keystore
+            .with_session(|s| s.open_index(blob))
+            .map_err(|e| self.keystore_error(e))?
+            .0
+            .ok_or(CoreError::IntegrityFailure)?;
+
+        Ok(plaintext)
+    }
+
+    /// Refuse (fail-closed) any operation on a tombstoned file.
+    ///
+    /// Reads the session's revocation set (`keystore::tombstone`):
+    /// the session refuses the same file as `Killed`, the Core
+    /// reports it as `Denied` before any key is derived.
+    fn require_live_file(&self, file_id: FileId) -> Result<(), CoreError> {
+        let status = self
+            .keystore
+            .with_session(|s| s.file_status(file_id))
+            .map_err(|e| self.keystore_error(e))?;
+
+        if status.revoked {
+            Err(CoreError::Denied)
+        } else {
+            Ok(())
+        }
+    }
+
+    /* ───────────── EPOCH-BOUND FILE CRYPTO ───────────── */
+
+    /// Encrypt a chunk bound to the file's current epoch.
+    ///
+    /// The application owns the epoch and MUST increase it on
+    /// every in-place update of the file.
+    pub fn encrypt_chunk_with_epoch(
+        &self,
+        file_id: FileId,
+        cloud_id: CloudId,
+        chunk: u32,
+        epoch: u64,
+        plaintext: &[u8],
+        out: &mut [u8],
+    ) -> Result<EncryptResult, CoreError> {
+        self.require_alive()?;
+        self.require_live_file(file_id)?;
+
+        self.keystore
+            .with_session(|s| {
+                encrypt_chunk_with_epoch(
+                    s,
+                    file_id,
+                    cloud_id,
+                    chunk,
+                    epoch,
+                    plaintext,
+                    out,
+                )
+            })
+            .map_err(|e| self.keystore_error(e))
+    }
+
+    /// Decrypt + verify a chunk under the file's current epoch.
+    ///
+    /// Chunks from a previous epoch return `VerifyResult(false)`.
+    pub fn decrypt_chunk_with_epoch(
+        &self,
+        file_id: FileId,
+        cloud_id: CloudId,
+        chunk: u32,
+        epoch: u64,
+        ciphertext: &[u8],
+        out: &mut [u8],
+    ) -> Result<VerifyResult, CoreError> {
+        self.require_alive()?;
+        self.require_live_file(file_id)?;
+
+        let cfg = self.decrypt_config();
+
+        self.keystore
+            .with_session(|s| {
+                decrypt_chunk_with_epoch(
+                    s,
+                    file_id,
+                    cloud_id,
+                    chunk,
+                    epoch,
+                    &cfg,
+                    ciphertext,
+                    out,
+                )
+            })
+            .map_err(|e| self.keystore_error(e))
+    }
+
+    /* ───────────── GUARDED PLAINTEXT ───────────── */
+
+    /// Allocate a core-owned buffer for host plaintext.
+    ///
+    /// The core zeroizes every live registered buffer on kill.
+    /// Dropping the handle deregisters (and wipes) the buffer.
+    pub fn register_sensitive_buffer(
+        &self,
+        len: usize,
+    ) -> Result<SensitiveBuffer, CoreError> {
+        self.require_alive()?;
+
+        if len > MAX_CHUNK_SIZE {
+            return Err(CoreError::InvalidInput);
+        }
+
+        self.keystore
+            .register_sensitive(len)
+            .map_err(|e| self.keystore_error(e))
+    }
+
+    /// Encrypt a file chunk whose plaintext is held in guarded memory.
+    pub fn encrypt_chunk_guarded(
+        &self,
+        file_id: FileId,
+        cloud_id: CloudId,
+        chunk: u32,
+        plaintext: &GuardedVec,
+        out: &mut [u8],
+    ) -> Result<EncryptResult, CoreError> {
+        self.encrypt_chunk(
+            file_id,
+            cloud_id,
+            chunk,
+            plaintext.borrow(),
+            out,
+        )
+    }
+
+    /// Decrypt + verify a file chunk directly into guarded memory.
+    ///
+    /// SECURITY:
+    /// - Plaintext never lands in an unguarded host buffer
+    /// - Returned buffer is page-locked and zeroized on drop
+    /// - Authentication failure => `IntegrityFailure` (buffer wiped)
+    pub fn decrypt_chunk_guarded(
+        &self,
+        file_id: FileId,
+        cloud_id: CloudId,
+        chunk: u32,
+        ciphertext: &[u8],
+    ) -> Result<GuardedVec, CoreError> {
+        self.require_alive()?;
+
+        let pt_len = ciphertext
+            .len()
+            .checked_sub(TAG_LEN)
+            .ok_or(CoreError::InvalidInput)?;
+
+        // Bound the locked allocation BEFORE touching mlock
+        if pt_len > MAX_CHUNK_SIZE {
+            return Err(CoreError::InvalidInput);
+        }
+
+        let mut out = GuardedVec::zeroed(pt_len);
+
+        let verified = self.decrypt_chunk(
+            file_id,
+            cloud_id,
+            chunk,
+            ciphertext,
+            out.borrow_mut(),
+        )?;
+
+        if !verified.0 {
+            return Err(CoreError::IntegrityFailure);
+        }
+
+        Ok(out)
+    }
+}
+
+/* ───────────── ERROR MAPPING ───────────── */
+
+#[inline(always)]
+fn map_recovery_error(err: RecoveryError) -> CoreError {
+    match err {
+        RecoveryError::IntegrityFailure => CoreError::IntegrityFailure,
+        RecoveryError::KdfFailure => CoreError::CryptoFailure,
+        RecoveryError::InvalidInput
+        | RecoveryError::InvalidWordCount
+        | RecoveryError::UnknownWord
+        | RecoveryError::BadChecksum
+        | RecoveryError::InsufficientShares
+        | RecoveryError::DuplicateShare => CoreError::InvalidInput,
+    }
+}
+
+#[inline(always)]
+fn map_keystore_error(err: KeyStoreError) -> CoreError {
+    match err {
+        KeyStoreError::Locked => CoreError::Locked,
+        KeyStoreError::Killed => CoreError::Killed,
+        KeyStoreError::Poisoned => CoreError::Killed,
         KeyStoreError::AlreadyUnlocked => CoreError::Denied,
+        KeyStoreError::Reentrant => CoreError::Denied,
         KeyStoreError::Session(se) => map_session_error(se),
     }
 }
@@ -206,4 +1412,740 @@ fn map_session_error(err: SessionError) -> CoreError {
     }
 }
 
+#[cfg(feature = "kem")]
+fn map_kem_error(err: KEMError) -> CoreError {
+    match err {
+        KEMError::Killed => CoreError::Killed,
+        KEMError::Derive => CoreError::CryptoFailure,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystore::recovery::RecoveryAuthority;
+    use crate::memory::GuardedKey32;
+
+    fn unlocked_core() -> Core {
+        crate::logging::encrypted::init_test_log_root();
+
+        let core = Core::new();
+        let key = GuardedKey32::init_with(|k| k.fill(0x42));
+        assert!(core
+            .keystore
+            .unlock(RecoveryAuthority::from_session_key(key))
+            .is_ok());
+        core
+    }
 
+    #[test]
+    fn idle_tick_locks_and_emits_event() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        struct Recorder(Rc<RefCell<Vec<CoreEvent>>>);
+
+        impl EventSink for Recorder {
+            fn on_event(&self, event: CoreEvent) {
+                self.0.borrow_mut().push(event);
+            }
+        }
+
+        let core = unlocked_core();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        core.set_event_sink(Box::new(Recorder(seen.clone())));
+
+        assert_eq!(core.arm_idle_lock(60_000, 1_000), Ok(()));
+
+        // Mocked clock: activity pushes the deadline out
+        assert!(!core.tick(30_000));
+        core.touch(30_000);
+        assert!(!core.tick(61_000));
+        assert!(core.keystore.is_unlocked());
+
+        assert!(core.tick(90_000));
+        assert!(!core.keystore.is_unlocked());
+        assert!(!core.is_unlocked());
+        assert_eq!(*seen.borrow(), vec![CoreEvent::Lock]);
+
+        // Already locked: no further events
+        assert!(!core.tick(200_000));
+        assert_eq!(seen.borrow().len(), 1);
+    }
+
+    #[test]
+    fn keystore_idle_lock_is_emitted_as_a_lock_event() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use std::time::Duration;
+
+        struct Recorder(Rc<RefCell<Vec<CoreEvent>>>);
+
+        impl EventSink for Recorder {
+            fn on_event(&self, event: CoreEvent) {
+                self.0.borrow_mut().push(event);
+            }
+        }
+
+        crate::logging::encrypted::init_test_log_root();
+
+        let core = Core::new_with_idle_limit(60);
+        assert!(core
+            .keystore
+            .unlock(RecoveryAuthority::from_session_key(GuardedKey32::init_with(|k| k.fill(0x42))))
+            .is_ok());
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        core.set_event_sink(Box::new(Recorder(seen.clone())));
+
+        let mut out = [0u8; 4 + TAG_LEN];
+        assert!(core.encrypt_chunk(5, 1, 0, b"data", &mut out).is_ok());
+        assert!(seen.borrow().is_empty());
+
+        // Mocked clock: the late call locks and reports it once
+        core.keystore.backdate_activity(Duration::from_secs(120));
+        assert_eq!(core.encrypt_chunk(5, 1, 0, b"data", &mut out).err(), Some(CoreError::Locked));
+        assert!(!core.is_unlocked());
+        assert_eq!(*seen.borrow(), vec![CoreEvent::Lock]);
+
+        assert_eq!(core.encrypt_chunk(5, 1, 0, b"data", &mut out).err(), Some(CoreError::Locked));
+        assert_eq!(seen.borrow().len(), 1);
+    }
+
+    #[test]
+    fn prewarm_is_noop_when_locked_and_transparent_when_unlocked() {
+        let locked = Core::new();
+        locked.prewarm(5, 1);
+        assert!(!locked.keystore.is_unlocked());
+
+        let core = unlocked_core();
+        let mut cold = vec![0u8; 4 + TAG_LEN];
+        assert!(core.encrypt_chunk(5, 1, 0, b"data", &mut cold).is_ok());
+
+        core.prewarm(5, 1);
+        let mut warm = vec![0u8; 4 + TAG_LEN];
+        assert!(core.encrypt_chunk(5, 1, 0, b"data", &mut warm).is_ok());
+
+        // Same derived key either way
+        assert_eq!(cold, warm);
+    }
+
+    #[test]
+    fn decrypt_file_stops_at_first_bad_chunk() {
+        let core = unlocked_core();
+        let parts: [&[u8]; 3] = [b"chunk zero", b"chunk one!", b"chunk two."];
+
+        let mut cts: Vec<Vec<u8>> = Vec::new();
+        for (i, pt) in parts.iter().enumerate() {
+            let mut ct = vec![0u8; pt.len() + TAG_LEN];
+            assert!(core.encrypt_chunk(9, 2, i as u32, pt, &mut ct).is_ok());
+            cts.push(ct);
+        }
+
+        // Tamper with the middle chunk
+        cts[1][0] ^= 0x01;
+
+        let refs: Vec<&[u8]> = cts.iter().map(|c| c.as_slice()).collect();
+        let mut delivered: Vec<Vec<u8>> = Vec::new();
+
+        let res = core.decrypt_file(9, 2, &refs, &mut |pt| {
+            delivered.push(pt.to_vec());
+            true
+        });
+
+        assert_eq!(res, Err(CoreError::IntegrityFailure));
+        assert_eq!(delivered, vec![parts[0].to_vec()]);
+    }
+
+    #[test]
+    fn resumable_upload_requires_same_key_hierarchy() {
+        let core = unlocked_core();
+        let plaintext = b"last uploaded chunk";
+
+        let mut ct = vec![0u8; plaintext.len() + TAG_LEN];
+        assert!(core.encrypt_chunk(11, 4, 3, plaintext, &mut ct).is_ok());
+
+        assert_eq!(core.verify_resumable(11, 4, 3, &ct), Ok(true));
+        assert_eq!(core.verify_resumable(11, 4, 2, &ct), Ok(false));
+
+        // Simulated rekey: different session key
+        core.lock();
+        let rekeyed = GuardedKey32::init_with(|k| k.fill(0x43));
+        assert!(core
+            .keystore
+            .unlock(RecoveryAuthority::from_session_key(rekeyed))
+            .is_ok());
+
+        assert_eq!(core.verify_resumable(11, 4, 3, &ct), Ok(false));
+    }
+
+    #[cfg(feature = "kem")]
+    #[test]
+    fn kem_rotation_changes_public_key_and_emits_event() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        struct Recorder(Rc<RefCell<Vec<CoreEvent>>>);
+
+        impl EventSink for Recorder {
+            fn on_event(&self, event: CoreEvent) {
+                self.0.borrow_mut().push(event);
+            }
+        }
+
+        let core = unlocked_core();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        core.set_event_sink(Box::new(Recorder(seen.clone())));
+
+        let before = core.kem_public_key();
+        let after = core.rotate_kem_secret(0, 1_000);
+
+        assert!(matches!((before, after), (Ok(a), Ok(b)) if a != b));
+        assert_eq!(*seen.borrow(), vec![CoreEvent::KemRotated]);
+    }
+
+    #[cfg(feature = "kem")]
+    #[test]
+    fn backup_under_rotated_key_imports_until_lock() -> Result<(), CoreError> {
+        use crate::crypto::kem::encapsulate;
+
+        const CONTEXT: &[u8; 32] = b"rcxcloud:test:backup:context:v1!";
+
+        let core = unlocked_core();
+        let old_pub = core.kem_public_key()?;
+        let (encap, backup_key) = encapsulate(&old_pub, CONTEXT).map_err(map_kem_error)?;
+        let expected = *backup_key.borrow();
+
+        core.rotate_kem_secret(1_000, 10_000)?;
+
+        // Retired secret, inside its grace period
+        let imported = core.with_backup_key(
+            &encap.ephemeral_public,
+            CONTEXT,
+            5_000,
+            |k| *k.borrow() == expected,
+            |k| *k.borrow() == expected,
+        );
+        assert_eq!(imported, Ok(true));
+
+        // Nothing verifies => no key is lent
+        let rejected = core.with_backup_key(&encap.ephemeral_public, CONTEXT, 5_000, |_| false, |_| ());
+        assert_eq!(rejected, Err(CoreError::IntegrityFailure));
+
+        // Lock wipes the ring: nothing decapsulates any more
+        core.lock();
+        let locked = core.with_backup_key(&encap.ephemeral_public, CONTEXT, 5_000, |_| true, |_| ());
+        assert_eq!(locked, Err(CoreError::Locked));
+        assert!(core.kem.borrow().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn kill_audit_mac_verifies_with_derived_audit_key() {
+        use crate::crypto::attest::ATTESTATION_CONTEXT;
+        use crate::crypto::derive::{derive_key, Purpose};
+        use crate::kill::audit::{verify_kill_audit, AUDIT_CONTEXT};
+
+        // Persists a kill record: keep it out of the shared log root
+        assert!(crate::test_support::isolated(
+            "bridge::api::tests::kill_audit_mac_verifies_with_derived_audit_key",
+            || {
+                let core = unlocked_core();
+
+                let log = EncryptedLog::open_device_kill_log();
+                assert!(log.is_ok());
+                let Ok(mut log) = log else { return };
+                assert!(log.append_record(b"KILLED").is_ok());
+                drop(log);
+
+                let blob = core.export_kill_audit();
+                assert!(blob.is_ok());
+                let Ok(mut blob) = blob else { return };
+                assert!(blob.windows(6).any(|w| w == b"KILLED"));
+
+                // Auditor re-derives: session -> attestation -> audit key
+                let session = GuardedKey32::init_with(|k| k.fill(0x42));
+                let mut attest = GuardedKey32::zeroed();
+                let mut audit_key = GuardedKey32::zeroed();
+                assert!(derive_key(&session, Purpose::Recovery, ATTESTATION_CONTEXT, &mut attest).is_ok());
+                assert!(derive_key(&attest, Purpose::Recovery, AUDIT_CONTEXT, &mut audit_key).is_ok());
+
+                assert!(verify_kill_audit(&audit_key, &blob));
+
+                // Any altered byte breaks the MAC
+                blob[0] ^= 0x01;
+                assert!(!verify_kill_audit(&audit_key, &blob));
+            },
+        ));
+    }
+
+    #[test]
+    fn in_place_encrypt_matches_encrypt_chunk() {
+        let core = unlocked_core();
+        let plaintext = b"sealed where it lies";
+
+        let mut ct = vec![0u8; plaintext.len() + TAG_LEN];
+        assert!(core.encrypt_chunk(9, 1, 2, plaintext, &mut ct).is_ok());
+
+        let mut buf = plaintext.to_vec();
+        buf.resize(plaintext.len() + TAG_LEN, 0);
+        assert!(core.encrypt_chunk_in_place(9, 1, 2, &mut buf).is_ok());
+        assert_eq!(buf, ct);
+
+        // No room for the tag: refused, plaintext wiped
+        let mut short = plaintext[..TAG_LEN - 1].to_vec();
+        assert!(core.encrypt_chunk_in_place(9, 1, 3, &mut short).is_err());
+        assert!(short.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn guarded_decrypt_round_trips_chunk() {
+        let core = unlocked_core();
+        let plaintext = b"guarded chunk plaintext";
+
+        let mut ct = vec![0u8; plaintext.len() + TAG_LEN];
+        assert!(core.encrypt_chunk(7, 1, 0, plaintext, &mut ct).is_ok());
+
+        let pt = core.decrypt_chunk_guarded(7, 1, 0, &ct);
+        assert!(matches!(&pt, Ok(p) if p.borrow() == plaintext));
+    }
+
+    #[test]
+    fn tombstoned_file_is_refused() {
+        let core = unlocked_core();
+        let file: FileId = 0x7B5E_0000_0000_0001;
+
+        let mut ct = vec![0u8; 4 + TAG_LEN];
+        assert!(core.encrypt_chunk(file, 1, 0, b"data", &mut ct).is_ok());
+
+        assert_eq!(core.tombstone_file(file), Ok(()));
+        assert_eq!(core.tombstone_file(file), Ok(()));
+
+        let mut out = [0xAAu8; 4];
+        assert_eq!(
+            core.encrypt_chunk(file, 1, 1, b"data", &mut ct).err(),
+            Some(CoreError::Denied)
+        );
+        assert_eq!(
+            core.decrypt_chunk(file, 1, 0, &ct, &mut out).err(),
+            Some(CoreError::Denied)
+        );
+        assert_eq!(
+            core.decrypt_chunk_with_epoch(file, 1, 0, 0, &ct, &mut out).err(),
+            Some(CoreError::Denied)
+        );
+
+        // Other files are unaffected
+        assert!(core.encrypt_chunk(file + 1, 1, 0, b"data", &mut ct).is_ok());
+    }
+
+    #[test]
+    fn file_crypto_works_without_a_log_root() {
+        // Fresh process: nothing calls `init_log_root` (WASM hosts,
+        // or a bridge that has not configured one yet)
+        assert!(crate::test_support::isolated(
+            "bridge::api::tests::file_crypto_works_without_a_log_root",
+            || {
+                let core = Core::new();
+                let key = GuardedKey32::init_with(|k| k.fill(0x42));
+                assert!(core
+                    .keystore
+                    .unlock(RecoveryAuthority::from_session_key(key))
+                    .is_ok());
+
+                let mut ct = vec![0u8; 4 + TAG_LEN];
+                assert!(core.encrypt_chunk(3, 1, 0, b"data", &mut ct).is_ok());
+
+                let mut pt = [0u8; 4];
+                assert!(matches!(core.decrypt_chunk(3, 1, 0, &ct, &mut pt), Ok(VerifyResult(true))));
+                assert_eq!(&pt, b"data");
+
+                // Only writes that must persist are refused
+                assert_eq!(core.tombstone_file(3), Err(CoreError::CryptoFailure));
+                assert!(core.encrypt_chunk(3, 1, 1, b"data", &mut ct).is_ok());
+
+                // No version log => no index seal (never restarts at 0)
+                assert_eq!(core.seal_index(b"index"), Err(CoreError::CryptoFailure));
+            },
+        ));
+    }
+
+    #[test]
+    fn tombstone_survives_reload() {
+        let file: FileId = 0x7B5E_0000_0000_0002;
+
+        let first = unlocked_core();
+        assert_eq!(first.tombstone_file(file), Ok(()));
+        drop(first);
+
+        // Fresh handle, same key hierarchy: replayed from the log
+        let second = unlocked_core();
+        let mut ct = vec![0u8; 4 + TAG_LEN];
+        assert_eq!(
+            second.encrypt_chunk(file, 1, 0, b"data", &mut ct).err(),
+            Some(CoreError::Denied)
+        );
+
+        // Lock drops the cache; unlock replays it again
+        second.lock();
+        assert!(second
+            .keystore
+            .unlock(RecoveryAuthority::from_session_key(GuardedKey32::init_with(|k| k.fill(0x42))))
+            .is_ok());
+        assert_eq!(
+            second.encrypt_chunk(file, 1, 0, b"data", &mut ct).err(),
+            Some(CoreError::Denied)
+        );
+    }
+
+    // One test: the version log is shared process-wide
+    #[test]
+    fn index_round_trips_and_rejects_rollback() -> Result<(), CoreError> {
+        let core = unlocked_core();
+
+        let older = core.seal_index(b"name=a.txt;size=3;chunks=1")?;
+        assert_eq!(
+            core.open_index(&older).as_deref(),
+            Ok(&b"name=a.txt;size=3;chunks=1"[..])
+        );
+
+        let mut tampered = older.clone();
+        tampered[10] ^= 0x01;
+        assert_eq!(core.open_index(&tampered), Err(CoreError::IntegrityFailure));
+
+        let newer = core.seal_index(b"name=b.txt;size=5;chunks=1")?;
+        assert_eq!(
+            core.open_index(&newer).as_deref(),
+            Ok(&b"name=b.txt;size=5;chunks=1"[..])
+        );
+        assert_eq!(core.open_index(&older), Err(CoreError::IntegrityFailure));
+
+        core.lock();
+        assert_eq!(core.seal_index(b"x"), Err(CoreError::Locked));
+        assert_eq!(core.open_index(&newer), Err(CoreError::Locked));
+        Ok(())
+    }
+
+    #[test]
+    fn changed_phrase_is_the_only_one_that_unlocks() {
+        crate::logging::encrypted::init_test_log_root();
+
+        let old = || Zeroizing::new(b"old recovery phrase".to_vec());
+        let new = || Zeroizing::new(b"new recovery phrase".to_vec());
+
+        let core = Core::new();
+        assert_eq!(core.provision_phrase(old()), Ok(()));
+        assert_eq!(core.provision_phrase(new()), Err(CoreError::Denied));
+        assert_eq!(core.unlock_with_phrase(old().to_vec()), Ok(()));
+
+        let mut before = [0u8; 4 + TAG_LEN];
+        assert!(core.encrypt_chunk(9, 9, 0, b"data", &mut before).is_ok());
+
+        // Wrong old phrase: nothing changes
+        assert_eq!(
+            core.change_phrase(Zeroizing::new(b"guess".to_vec()), new()),
+            Err(CoreError::IntegrityFailure)
+        );
+
+        // Active session is rekeyed in place
+        assert_eq!(core.change_phrase(old(), new()), Ok(()));
+        let mut after = [0u8; 4 + TAG_LEN];
+        assert!(core.encrypt_chunk(9, 9, 0, b"data", &mut after).is_ok());
+        assert_ne!(before, after);
+
+        core.lock();
+        assert_eq!(
+            core.unlock_with_phrase(old().to_vec()),
+            Err(CoreError::IntegrityFailure)
+        );
+        assert_eq!(core.unlock_with_phrase(new().to_vec()), Ok(()));
+    }
+
+    #[test]
+    fn output_sizing_rejects_overflow_and_short_input() {
+        assert_eq!(Core::ciphertext_len(10), Some(10 + TAG_LEN));
+        assert_eq!(Core::plaintext_len(10 + TAG_LEN), Some(10));
+        assert_eq!(Core::plaintext_len(TAG_LEN), Some(0));
+
+        assert_eq!(Core::ciphertext_len(usize::MAX), None);
+        assert_eq!(Core::ciphertext_len(usize::MAX - TAG_LEN + 1), None);
+        assert_eq!(Core::plaintext_len(TAG_LEN - 1), None);
+        assert_eq!(Core::plaintext_len(0), None);
+    }
+
+    #[test]
+    fn probe_phrase_never_unlocks() {
+        // Fresh process => fresh log root, so this test owns the verifier
+        assert!(crate::test_support::isolated(
+            "bridge::api::tests::probe_phrase_never_unlocks",
+            || {
+                crate::logging::encrypted::init_test_log_root();
+
+                let phrase = || Zeroizing::new(b"probe recovery phrase".to_vec());
+                let core = Core::new();
+                assert_eq!(core.provision_phrase(phrase()), Ok(()));
+
+                assert_eq!(core.probe_phrase(b"not the recovery phrase".to_vec()), Ok(false));
+                assert_eq!(core.failed_unlocks.load(Ordering::SeqCst), 1);
+
+                // Correct phrase: confirmed, yet the keystore stays locked
+                assert_eq!(core.probe_phrase(phrase().to_vec()), Ok(true));
+                assert!(!core.is_unlocked());
+
+                let mut out = [0u8; TAG_LEN];
+                assert!(matches!(
+                    core.encrypt_chunk(1, 1, 0, &[], &mut out),
+                    Err(CoreError::Locked)
+                ));
+            },
+        ));
+    }
+
+    #[test]
+    fn attestation_verifies_against_derived_key() {
+        use crate::crypto::attest::ATTESTATION_CONTEXT;
+        use crate::crypto::derive::{derive_key, Purpose};
+        use hmac::{Hmac, Mac};
+        use sha2::Sha256;
+
+        let core = unlocked_core();
+        assert!(core.bind_device_fingerprint(0xF00D).is_ok());
+
+        let nonce = b"server-challenge";
+        let tag = core.attest_state(nonce);
+
+        // Independent verifier: same session key, same derivation
+        let session = GuardedKey32::init_with(|k| k.fill(0x42));
+        let mut key = GuardedKey32::zeroed();
+        assert!(derive_key(&session, Purpose::Recovery, ATTESTATION_CONTEXT, &mut key).is_ok());
+
+        let expected = Hmac::<Sha256>::new_from_slice(key.borrow()).map(|mut mac| {
+            mac.update(b"rcxcloud:attest:state:v1");
+            mac.update(&[0u8]);
+            mac.update(&0xF00Du64.to_be_bytes());
+            mac.update(&(nonce.len() as u16).to_be_bytes());
+            mac.update(nonce);
+            mac.finalize().into_bytes()
+        });
+
+        assert!(matches!((tag, expected), (Ok(t), Ok(e)) if t[..] == e[..]));
+    }
+
+    #[test]
+    fn attestation_key_is_scoped_and_deterministic() {
+        use hmac::{Hmac, Mac};
+        use sha2::Sha256;
+
+        let mac = |k: &GuardedKey32| {
+            Hmac::<Sha256>::new_from_slice(k.borrow())
+                .map(|mut m| {
+                    m.update(b"pairing-transcript");
+                    m.finalize().into_bytes().to_vec()
+                })
+                .unwrap_or_default()
+        };
+
+        let core = unlocked_core();
+        assert_eq!(core.with_attestation_key(mac).err(), Some(CoreError::Denied));
+
+        assert!(core.bind_device_fingerprint(0xF00D).is_ok());
+        let first = core.with_attestation_key(mac);
+        assert!(matches!(&first, Ok(t) if t.len() == 32));
+
+        core.lock();
+        assert_eq!(core.with_attestation_key(mac).err(), Some(CoreError::Locked));
+
+        // Same vault, same device => same key
+        assert!(core
+            .keystore
+            .unlock(RecoveryAuthority::from_session_key(GuardedKey32::init_with(|k| k.fill(0x42))))
+            .is_ok());
+        assert_eq!(core.with_attestation_key(mac), first);
+    }
+
+    #[test]
+    fn kill_wipes_registered_sensitive_buffers() {
+        assert!(crate::test_support::isolated(
+            "bridge::api::tests::kill_wipes_registered_sensitive_buffers",
+            || {
+                use crate::keystore::master::{escalate, KillCause};
+
+                let core = unlocked_core();
+                let buf = core.register_sensitive_buffer(16);
+                assert!(buf.as_ref().is_ok_and(|b| b.with_mut(|d| d.fill(0xEE)).is_some()));
+                assert!(buf.as_ref().is_ok_and(|b| !b.is_zeroed()));
+
+                // Not a verified kill: any cause goes through the fuse
+                escalate(KillCause::LocalFail);
+
+                assert!(buf.as_ref().is_ok_and(|b| b.is_zeroed()));
+                assert!(buf.is_ok_and(|b| b.with_mut(|d| d.fill(0xEE)).is_none()));
+            },
+        ));
+    }
+
+    #[test]
+    fn attestation_key_is_not_the_kill_key() {
+        use crate::crypto::derive::{derive_key, Purpose};
+
+        let core = unlocked_core();
+        assert!(core.bind_device_fingerprint(0xF00D).is_ok());
+
+        // Per-device kill key for the same root + fingerprint
+        let session = GuardedKey32::init_with(|k| k.fill(0x42));
+        let mut kill_key = GuardedKey32::zeroed();
+        assert!(derive_key(&session, Purpose::Recovery, 0xF00D, &mut kill_key).is_ok());
+
+        let lent = core.with_attestation_key(|k| *k.borrow());
+        assert!(matches!(lent, Ok(k) if k != *kill_key.borrow()));
+    }
+
+    #[test]
+    fn health_check_passes_until_the_kill_fuse_blows() {
+        assert!(crate::test_support::isolated(
+            "bridge::api::tests::health_check_passes_until_the_kill_fuse_blows",
+            || {
+                use crate::keystore::master::{escalate, KillCause};
+
+                crate::logging::encrypted::init_test_log_root();
+
+                let core = Core::new();
+                assert!(!core.health_check().provisioned);
+                assert!(!core.health_check().passed);
+
+                // Provisioned, healthy build => all checks pass
+                assert_eq!(core.provision_phrase(Zeroizing::new(b"health phrase".to_vec())), Ok(()));
+                assert_eq!(core.health_check(), HealthReport::new(true, true, true, true));
+
+                escalate(KillCause::LocalFail);
+
+                // Only the fuse check fails; still callable, and read-only
+                let killed = core.health_check();
+                assert_eq!(killed, HealthReport::new(true, false, true, true));
+                assert!(!killed.passed);
+                assert_eq!(core.health_check(), killed);
+            },
+        ));
+    }
+
+    #[test]
+    fn diagnostics_never_contain_key_bytes() {
+        let core = Core::new();
+        let key = GuardedKey32::init_with(|k| k.fill(0xA5));
+        assert!(core
+            .keystore
+            .unlock(RecoveryAuthority::from_session_key(key))
+            .is_ok());
+        assert!(core.bind_device_fingerprint(0x0123_4567_89AB_CDEF).is_ok());
+
+        let dump = core.diagnostics().to_string();
+
+        assert!(dump.contains(env!("CARGO_PKG_VERSION")));
+        assert!(dump.contains("0123456789abcdef"));
+        assert!(dump.contains("unlocked: true"));
+
+        // No session-key or attestation-key bytes in any encoding
+        assert!(!dump.to_lowercase().contains("a5a5"));
+        assert!(!dump.contains('\u{a5}'));
+    }
+
+    #[test]
+    fn guarded_decrypt_rejects_tampered_chunk() {
+        let core = unlocked_core();
+
+        let mut ct = vec![0u8; 4 + TAG_LEN];
+        assert!(core.encrypt_chunk(7, 1, 0, b"data", &mut ct).is_ok());
+        ct[0] ^= 0x01;
+
+        assert!(matches!(
+            core.decrypt_chunk_guarded(7, 1, 0, &ct),
+            Err(CoreError::IntegrityFailure)
+        ));
+    }
+
+    #[cfg(feature = "chacha")]
+    #[test]
+    fn selected_suite_seals_chunks_after_the_next_unlock() -> Result<(), CoreError> {
+        crate::logging::encrypted::init_test_log_root();
+
+        let core = Core::new();
+        assert_eq!(core.set_cipher_suite(0xEE), Err(CoreError::InvalidInput));
+        assert_eq!(core.set_cipher_suite(CipherSuite::ChaCha20Poly1305.code()), Ok(()));
+
+        let key = GuardedKey32::init_with(|k| k.fill(0x42));
+        core.unlock_with(|_| Ok(RecoveryAuthority::from_session_key(key)))?;
+
+        // A live session never switches suite
+        assert_eq!(core.set_cipher_suite(CipherSuite::Aes256Gcm.code()), Err(CoreError::Denied));
+
+        let file = 0x5E00_0000_0000_0001;
+        let mut ct = [0u8; 4 + TAG_LEN];
+        let sealed = core.encrypt_chunk(file, 1, 0, b"data", &mut ct)?;
+        assert!(
+            CipherSuite::from_aad_version(sealed.aad_version) == Some(CipherSuite::ChaCha20Poly1305)
+        );
+
+        let mut pt = [0u8; 4];
+        assert!(matches!(core.decrypt_chunk(file, 1, 0, &ct, &mut pt), Ok(VerifyResult(true))));
+        assert_eq!(&pt, b"data");
+        Ok(())
+    }
+
+    #[test]
+    fn stored_chunk_decrypts_only_at_its_position() -> Result<(), CoreError> {
+        let core = unlocked_core();
+        let file = 0x5E00_0000_0000_0002;
+
+        let mut ct = [0u8; 4 + TAG_LEN];
+        let sealed = core.encrypt_chunk(file, 1, 3, b"data", &mut ct)?;
+
+        let mut pt = [0u8; 4];
+        assert!(matches!(
+            core.decrypt_stored_chunk(file, 1, 3, &sealed.aad, &sealed.commitment, &ct, &mut pt),
+            Ok(VerifyResult(true))
+        ));
+
+        // A commitment to any other key fails before the tag
+        let mut forged = sealed.commitment;
+        forged[0] ^= 0x01;
+        assert!(matches!(
+            core.decrypt_stored_chunk(file, 1, 3, &sealed.aad, &forged, &ct, &mut pt),
+            Ok(VerifyResult(false))
+        ));
+
+        // The stored AAD cannot relocate the chunk
+        assert_eq!(
+            core.decrypt_stored_chunk(file, 1, 4, &sealed.aad, &sealed.commitment, &ct, &mut pt).err(),
+            Some(CoreError::InvalidInput)
+        );
+
+        // The Core's floor reaches the stored-AAD path
+        core.set_min_aad_version(crate::crypto::aad::AAD_VERSION_V2)?;
+        assert_eq!(
+            core.decrypt_stored_chunk(file, 1, 3, &sealed.aad, &sealed.commitment, &ct, &mut pt).err(),
+            Some(CoreError::InvalidInput)
+        );
+        assert_eq!(core.decrypt_chunk(file, 1, 3, &ct, &mut pt).err(), Some(CoreError::InvalidInput));
+        Ok(())
+    }
+
+    #[test]
+    fn min_aad_version_only_rises_within_chunk_layouts() {
+        use crate::crypto::aad::{AAD_VERSION_STREAM, AAD_VERSION_V2};
+
+        let core = Core::new();
+
+        // Stream chunks are not a one-shot layout: no floor above V3
+        assert_eq!(core.set_min_aad_version(AAD_VERSION_STREAM), Err(CoreError::InvalidInput));
+        assert_eq!(core.set_min_aad_version(u8::MAX), Err(CoreError::InvalidInput));
+
+        assert_eq!(core.set_min_aad_version(AAD_VERSION_V2), Ok(()));
+        assert_eq!(core.set_min_aad_version(AAD_VERSION_V2), Ok(()));
+        assert_eq!(core.set_min_aad_version(AAD_VERSION_V1), Err(CoreError::Denied));
+        assert_eq!(core.set_min_aad_version(0), Err(CoreError::Denied));
+
+        assert_eq!(core.set_min_aad_version(AAD_VERSION_V3), Ok(()));
+        assert_eq!(core.set_min_aad_version(AAD_VERSION_V2), Err(CoreError::Denied));
+        assert_eq!(core.decrypt_config().min_aad_version, AAD_VERSION_V3);
+    }
+}
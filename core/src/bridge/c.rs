@@ -19,7 +19,20 @@ int rcx_core_unlock_with_phrase(
     size_t len
 );
 
-int rcx_core_encrypt_chunk(
+/* Preferred: phrase copied into locked memory, `phrase` is zeroed */
+int rcx_unlock_with_phrase_guarded(
+    uint64_t handle,
+    uint8_t* phrase,
+    size_t len
+);
+
+/* AEAD for chunks sealed after the NEXT unlock:
+ * 0 = AES-256-GCM, 1 = ChaCha20-Poly1305 (if compiled in) */
+int rcx_set_cipher_suite(uint64_t handle, uint8_t suite);
+
+/* Chunk crypto: returns bytes written (>= 0) or -BridgeError.
+ * `out` is zeroed on any failure; `in` and `out` MUST NOT overlap. */
+int rcx_encrypt_chunk(
     uint64_t handle,
     uint64_t file_id,
     uint16_t cloud_id,
@@ -27,10 +40,10 @@ int rcx_core_encrypt_chunk(
     const uint8_t* in,
     size_t in_len,
     uint8_t* out,
-    size_t out_len
+    size_t out_cap
 );
 
-int rcx_core_decrypt_chunk(
+int rcx_decrypt_chunk(
     uint64_t handle,
     uint64_t file_id,
     uint16_t cloud_id,
@@ -38,11 +51,22 @@ int rcx_core_decrypt_chunk(
     const uint8_t* in,
     size_t in_len,
     uint8_t* out,
-    size_t out_len
+    size_t out_cap
 );
 
 int rcx_core_is_killed(void);
 
+/* 1 = unlocked, 0 = locked / killed / unknown handle */
+int rcx_is_unlocked(uint64_t handle);
+
+/* Kill cause: 0 none, 1 verified kill, 2 poison, 3 local fail, 0xFF unknown */
+int rcx_kill_cause(uint64_t handle, uint8_t* out_cause);
+
+/* Exact chunk output sizing (never hard-code the AEAD tag length) */
+int rcx_ciphertext_len(size_t plaintext_len, size_t* out_len);
+
+int rcx_plaintext_len(size_t ciphertext_len, size_t* out_len);
+
 #ifdef __cplusplus
 }
 #endif
@@ -0,0 +1,108 @@
+//! Sanitized diagnostic dump (bug reports).
+//!
+//! SECURITY:
+//! - Every field is individually audited as NON-SECRET
+//! - No key material, no plaintext, no log contents
+//! - Log files are reported by size only
+//!
+//! ⚠️ Adding a field is a SECURITY DECISION: document why it
+//! is non-secret next to the field.
+
+use core::fmt;
+
+/// Non-secret Secure Core state snapshot.
+#[derive(Clone)]
+pub struct Diagnostics {
+    /// Crate version (public build metadata)
+    pub version: &'static str,
+    /// Compiled feature gates (public build metadata)
+    pub features: &'static [&'static str],
+    /// Kill fuse state (reported to servers anyway via attestation)
+    pub killed: bool,
+    /// Session presence only — never the session key
+    pub unlocked: bool,
+    /// Bound device fingerprint (non-secret, truncated hash); 0 = unbound
+    pub device_fingerprint: u64,
+    /// Log file sizes (metadata only, never contents)
+    pub log_sizes: [(&'static str, Option<u64>); 11],
+}
+
+/// Machine-readable health verdict (`Core::health_check`).
+///
+/// Every field is a pass / fail flag (NON-SECRET).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HealthReport {
+    /// Cryptographic known-answer tests passed
+    pub self_test: bool,
+    /// Kill fuse not blown
+    pub not_killed: bool,
+    /// Recovery phrase verifier present
+    pub provisioned: bool,
+    /// Log root accepted a scratch write
+    pub log_writable: bool,
+    /// All of the above
+    pub passed: bool,
+}
+
+impl HealthReport {
+    pub(crate) fn new(
+        self_test: bool,
+        not_killed: bool,
+        provisioned: bool,
+        log_writable: bool,
+    ) -> Self {
+        Self {
+            self_test,
+            not_killed,
+            provisioned,
+            log_writable,
+            passed: self_test && not_killed && provisioned && log_writable,
+        }
+    }
+}
+
+/// Feature gates compiled into this build.
+pub(crate) const FEATURES: &[&str] = &[
+    #[cfg(feature = "android")]
+    "android",
+    #[cfg(feature = "chacha")]
+    "chacha",
+    #[cfg(feature = "desktop-media")]
+    "desktop-media",
+    #[cfg(feature = "kem")]
+    "kem",
+    #[cfg(feature = "kem-pq")]
+    "kem-pq",
+    #[cfg(feature = "kill-admin")]
+    "kill-admin",
+    #[cfg(feature = "scrypt")]
+    "scrypt",
+    #[cfg(feature = "std-errors")]
+    "std-errors",
+];
+
+/// Copy-pasteable, redacted report.
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "rcxcore {}", self.version)?;
+
+        if self.features.is_empty() {
+            writeln!(f, "features: (none)")?;
+        } else {
+            writeln!(f, "features: {}", self.features.join(", "))?;
+        }
+
+        writeln!(f, "killed: {}", self.killed)?;
+        writeln!(f, "unlocked: {}", self.unlocked)?;
+        writeln!(f, "device_fingerprint: {:016x}", self.device_fingerprint)?;
+
+        for (name, size) in self.log_sizes.iter() {
+            match size {
+                Some(n) => writeln!(f, "log {}: {} bytes", name, n)?,
+                None => writeln!(f, "log {}: absent", name)?,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,13 @@
+//! Bridge return codes (FFI-stable).
+//!
+//! ❄️ SUBJECT TO API FREEZE
+//! - Integer values are part of the C / JNI / WASM ABI
+//! - `Display` / `std::error::Error` are Rust-host conveniences
+//!   behind the `std-errors` feature and never alter the repr
+//! - `message` / `message_for_code` are the allocation-free strings
+//!   behind both `Display` and `rcx_error_message`
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(i32)]
 pub enum BridgeError {
     Ok = 0,
@@ -7,4 +17,242 @@ pub enum BridgeError {
     CryptoFailure = 4,
     IntegrityFailure = 5,
     Denied = 6,
-}
\ No newline at end of file
+}
+
+/* ───────────── MESSAGES ───────────── */
+
+/// Message for a code that is not a `BridgeError`.
+pub const UNKNOWN_ERROR_MESSAGE: &str = "unknown error";
+
+impl BridgeError {
+    /// Stable, static ASCII description.
+    ///
+    /// ⚠️ Fixed strings only: never internal state or secret-derived data.
+    pub const fn message(self) -> &'static str {
+        match self {
+            BridgeError::Ok => "ok",
+            BridgeError::Locked => "secure core is locked",
+            BridgeError::Killed => "secure core has been killed",
+            BridgeError::InvalidInput => "invalid input",
+            BridgeError::CryptoFailure => "cryptographic operation failed",
+            BridgeError::IntegrityFailure => "integrity verification failed",
+            BridgeError::Denied => "operation denied",
+        }
+    }
+
+    /// ABI code => variant (`None` for unknown codes).
+    pub const fn from_code(code: i32) -> Option<Self> {
+        Some(match code {
+            0 => BridgeError::Ok,
+            1 => BridgeError::Locked,
+            2 => BridgeError::Killed,
+            3 => BridgeError::InvalidInput,
+            4 => BridgeError::CryptoFailure,
+            5 => BridgeError::IntegrityFailure,
+            6 => BridgeError::Denied,
+            _ => return None,
+        })
+    }
+}
+
+/// Message for a raw ABI code (unknown => `UNKNOWN_ERROR_MESSAGE`).
+pub const fn message_for_code(code: i32) -> &'static str {
+    match BridgeError::from_code(code) {
+        Some(err) => err.message(),
+        None => UNKNOWN_ERROR_MESSAGE,
+    }
+}
+
+/* ───────────── CORE → BRIDGE MAPPING ───────────── */
+
+// The ONLY mapping from Core errors to ABI codes: JNI (`jint`)
+// and WASM / C (`i32`) both go through it.
+impl From<crate::bridge::api::CoreError> for BridgeError {
+    fn from(err: crate::bridge::api::CoreError) -> Self {
+        use crate::bridge::api::CoreError;
+
+        match err {
+            CoreError::Locked => BridgeError::Locked,
+            CoreError::Killed => BridgeError::Killed,
+            CoreError::InvalidInput => BridgeError::InvalidInput,
+            CoreError::CryptoFailure => BridgeError::CryptoFailure,
+            CoreError::IntegrityFailure => BridgeError::IntegrityFailure,
+            CoreError::Denied => BridgeError::Denied,
+        }
+    }
+}
+
+/// JNI return code (`jint`) for a caught bridge call.
+///
+/// Panic => `CryptoFailure` (fail-closed).
+#[cfg(any(target_os = "android", test))]
+pub(crate) fn jni_status<P>(result: Result<Result<(), BridgeError>, P>) -> i32 {
+    match result {
+        Ok(Ok(())) => BridgeError::Ok as i32,
+        Ok(Err(e)) => e as i32,
+        Err(_) => BridgeError::CryptoFailure as i32,
+    }
+}
+
+/// WASM / C return code for a caught bridge call.
+///
+/// Panic => `CryptoFailure` (fail-closed).
+#[cfg(any(target_arch = "wasm32", test))]
+pub(crate) fn wasm_status<P>(result: Result<Result<(), BridgeError>, P>) -> i32 {
+    match result {
+        Ok(Ok(())) => BridgeError::Ok as i32,
+        Ok(Err(e)) => e as i32,
+        Err(_) => BridgeError::CryptoFailure as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::api::CoreError;
+
+    const ALL_CORE_ERRORS: [CoreError; 6] = [
+        CoreError::Locked,
+        CoreError::Killed,
+        CoreError::InvalidInput,
+        CoreError::CryptoFailure,
+        CoreError::IntegrityFailure,
+        CoreError::Denied,
+    ];
+
+    // Adding a `CoreError` variant fails to compile here until it is
+    // listed in `ALL_CORE_ERRORS`
+    fn index(err: CoreError) -> usize {
+        match err {
+            CoreError::Locked => 0,
+            CoreError::Killed => 1,
+            CoreError::InvalidInput => 2,
+            CoreError::CryptoFailure => 3,
+            CoreError::IntegrityFailure => 4,
+            CoreError::Denied => 5,
+        }
+    }
+
+    // The exact path both bridges take: `map_err(BridgeError::from)`
+    // inside `catch_unwind`, then the per-host status function
+    fn caught(err: CoreError) -> Result<Result<(), BridgeError>, ()> {
+        Ok(Err(err).map_err(BridgeError::from))
+    }
+
+    #[test]
+    fn core_errors_map_identically_on_jni_and_wasm() {
+        let mut seen = Vec::new();
+
+        for (i, err) in ALL_CORE_ERRORS.into_iter().enumerate() {
+            assert_eq!(index(err), i);
+
+            let jni = jni_status(caught(err));
+            let wasm = wasm_status(caught(err));
+
+            assert_eq!(jni, wasm);
+            assert_eq!(BridgeError::from_code(wasm), Some(BridgeError::from(err)));
+            assert_ne!(wasm, BridgeError::Ok as i32);
+            assert!(!seen.contains(&wasm));
+            seen.push(wasm);
+        }
+
+        assert_eq!(jni_status::<()>(Ok(Ok(()))), BridgeError::Ok as i32);
+        assert_eq!(wasm_status::<()>(Ok(Ok(()))), BridgeError::Ok as i32);
+        assert_eq!(jni_status(Err(())), BridgeError::CryptoFailure as i32);
+        assert_eq!(wasm_status(Err(())), BridgeError::CryptoFailure as i32);
+    }
+
+    #[test]
+    fn every_code_round_trips_to_an_ascii_message() {
+        for code in 0..=6 {
+            let err = BridgeError::from_code(code);
+            assert_eq!(err.map(|e| e as i32), Some(code));
+
+            let msg = message_for_code(code);
+            assert!(msg.is_ascii() && !msg.is_empty());
+            assert_ne!(msg, UNKNOWN_ERROR_MESSAGE);
+        }
+
+        assert_eq!(BridgeError::from_code(7), None);
+        assert_eq!(message_for_code(-1), UNKNOWN_ERROR_MESSAGE);
+    }
+}
+
+/* ───────────── RUST HOST ERGONOMICS (std-errors) ───────────── */
+
+#[cfg(feature = "std-errors")]
+mod std_errors {
+    use super::BridgeError;
+    use crate::bridge::api::CoreError;
+    use core::fmt;
+
+    // ⚠️ Messages are STABLE: hosts may match on them.
+    // They MUST NOT include internal state or secret-derived data.
+
+    impl fmt::Display for CoreError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(match self {
+                CoreError::Locked => "secure core is locked",
+                CoreError::Killed => "secure core has been killed",
+                CoreError::InvalidInput => "invalid input",
+                CoreError::CryptoFailure => "cryptographic operation failed",
+                CoreError::IntegrityFailure => "integrity verification failed",
+                CoreError::Denied => "operation denied",
+            })
+        }
+    }
+
+    impl std::error::Error for CoreError {}
+
+    impl fmt::Display for BridgeError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.message())
+        }
+    }
+
+    impl std::error::Error for BridgeError {}
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn core_error_display_is_stable() {
+            let cases = [
+                (CoreError::Locked, "secure core is locked"),
+                (CoreError::Killed, "secure core has been killed"),
+                (CoreError::InvalidInput, "invalid input"),
+                (CoreError::CryptoFailure, "cryptographic operation failed"),
+                (CoreError::IntegrityFailure, "integrity verification failed"),
+                (CoreError::Denied, "operation denied"),
+            ];
+
+            for (err, msg) in cases {
+                assert_eq!(err.to_string(), msg);
+            }
+        }
+
+        #[test]
+        fn bridge_error_display_is_stable() {
+            let cases = [
+                (BridgeError::Ok, "ok"),
+                (BridgeError::Locked, "secure core is locked"),
+                (BridgeError::Killed, "secure core has been killed"),
+                (BridgeError::InvalidInput, "invalid input"),
+                (BridgeError::CryptoFailure, "cryptographic operation failed"),
+                (BridgeError::IntegrityFailure, "integrity verification failed"),
+                (BridgeError::Denied, "operation denied"),
+            ];
+
+            for (err, msg) in cases {
+                assert_eq!(err.to_string(), msg);
+            }
+        }
+
+        #[test]
+        fn bridge_repr_is_unchanged() {
+            assert_eq!(BridgeError::Ok as i32, 0);
+            assert_eq!(BridgeError::Denied as i32, 6);
+        }
+    }
+}
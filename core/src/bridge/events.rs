@@ -0,0 +1,115 @@
+//! Lifecycle events + host-driven idle lock.
+//!
+//! SECURITY:
+//! - Events carry NO data (state transitions only)
+//! - Sink is host code: it runs AFTER the transition completed
+//! - No threads, no timers: time is supplied by the host (`tick`)
+//! - A re-entrant emit (sink calling back into Core) is dropped
+
+use core::cell::{Cell, RefCell};
+
+/* ───────────── EVENTS ───────────── */
+
+/// Secure Core lifecycle transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreEvent {
+    Unlock,
+    Lock,
+    /// Device KEM static secret was rotated
+    #[cfg(feature = "kem")]
+    KemRotated,
+}
+
+/// Host-provided event receiver.
+pub trait EventSink {
+    fn on_event(&self, event: CoreEvent);
+}
+
+/// Single registered sink (replaceable).
+pub(crate) struct EventHub {
+    sink: RefCell<Option<Box<dyn EventSink>>>,
+}
+
+impl EventHub {
+    pub(crate) const fn new() -> Self {
+        Self {
+            sink: RefCell::new(None),
+        }
+    }
+
+    pub(crate) fn set(&self, sink: Option<Box<dyn EventSink>>) {
+        if let Ok(mut g) = self.sink.try_borrow_mut() {
+            *g = sink;
+        }
+    }
+
+    pub(crate) fn emit(&self, event: CoreEvent) {
+        if let Ok(g) = self.sink.try_borrow() {
+            if let Some(sink) = g.as_ref() {
+                sink.on_event(event);
+            }
+        }
+    }
+}
+
+/* ───────────── IDLE LOCK ───────────── */
+
+/// Inactivity deadline tracker (host clock, milliseconds).
+pub(crate) struct IdleLock {
+    timeout_ms: Cell<Option<u64>>,
+    last_activity_ms: Cell<u64>,
+}
+
+impl IdleLock {
+    pub(crate) const fn new() -> Self {
+        Self {
+            timeout_ms: Cell::new(None),
+            last_activity_ms: Cell::new(0),
+        }
+    }
+
+    pub(crate) fn arm(&self, timeout_ms: u64, now_ms: u64) {
+        self.timeout_ms.set(Some(timeout_ms));
+        self.last_activity_ms.set(now_ms);
+    }
+
+    pub(crate) fn disarm(&self) {
+        self.timeout_ms.set(None);
+    }
+
+    pub(crate) fn touch(&self, now_ms: u64) {
+        // Host clock is assumed monotonic; never move backwards
+        if now_ms > self.last_activity_ms.get() {
+            self.last_activity_ms.set(now_ms);
+        }
+    }
+
+    /// Whether the inactivity deadline has passed.
+    pub(crate) fn expired(&self, now_ms: u64) -> bool {
+        match self.timeout_ms.get() {
+            Some(t) => now_ms.saturating_sub(self.last_activity_ms.get()) >= t,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_lock_expires_only_when_armed() {
+        let idle = IdleLock::new();
+        assert!(!idle.expired(u64::MAX));
+
+        idle.arm(1_000, 5_000);
+        assert!(!idle.expired(5_999));
+        assert!(idle.expired(6_000));
+
+        idle.touch(5_500);
+        assert!(!idle.expired(6_000));
+
+        idle.disarm();
+        assert!(!idle.expired(u64::MAX));
+    }
+}
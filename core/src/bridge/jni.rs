@@ -8,22 +8,25 @@
 #![allow(non_snake_case)]
 
 use crate::bridge::api::Core;
-use crate::bridge::error::BridgeError;
+use crate::bridge::error::{jni_status, BridgeError};
+use crate::bridge::out_pool::{OutputPool, DEFAULT_MAX_RETAINED};
+use crate::keystore::recovery::MAX_PHRASE_LEN;
+use crate::memory::GuardedVec;
 
-use jni::objects::{JByteArray, JClass};
-use jni::sys::{jbyteArray, jint, jlong};
+use jni::objects::{JByteArray, JByteBuffer, JClass};
+use jni::sys::{jbyte, jbyteArray, jint, jlong};
 use jni::JNIEnv;
 
+use std::cell::RefCell;
 use std::panic::{self, AssertUnwindSafe};
 use std::sync::OnceLock;
+use zeroize::Zeroize;
 
 /* ───────────── CONSTANTS ───────────── */
 
-/// AES-GCM authentication tag length (bytes).
-/// WARNING: Java buffers MUST account for this overhead.
-/// Encrypt Out = In + 16
-/// Decrypt Out = In - 16
-const AEAD_TAG_LEN: usize = 16;
+// Return codes come from `jni_status`; WASM / C use `wasm_status`.
+// Pin `jint == i32` so both hosts see identical codes.
+const _: fn(jint) -> i32 = core::convert::identity;
 
 /* ───────────── SINGLETON ───────────── */
 
@@ -34,6 +37,118 @@ fn core() -> &'static Core {
     CORE.get_or_init(Core::new)
 }
 
+/* ───────────── OUTPUT POOL ───────────── */
+
+thread_local! {
+    // Reused by the `*Into` variants (one per JVM thread)
+    static OUT_POOL: RefCell<OutputPool> =
+        RefCell::new(OutputPool::new(DEFAULT_MAX_RETAINED));
+}
+
+/// Validate a caller-provided output array against the exact length.
+fn require_out_len(
+    env: &mut JNIEnv,
+    out: &JByteArray,
+    required: usize,
+) -> Result<(), BridgeError> {
+    let len = env
+        .get_array_length(out)
+        .map_err(|_| BridgeError::InvalidInput)?;
+
+    if usize::try_from(len).ok() != Some(required) {
+        return Err(BridgeError::InvalidInput);
+    }
+
+    Ok(())
+}
+
+/* ───────────── DIRECT BUFFERS ───────────── */
+
+/// Run `f` over `in_len` bytes of `input` and exactly `out_len(in_len)`
+/// bytes of `out`, both direct `ByteBuffer`s (no JVM array copy).
+///
+/// SECURITY:
+/// - Lengths checked against BOTH buffer capacities before any slice
+/// - Overlapping buffers rejected (no aliased `&` / `&mut`)
+/// - Output region zeroized if `f` fails
+#[allow(unsafe_code)]
+fn with_direct_buffers(
+    env: &JNIEnv,
+    input: &JByteBuffer,
+    in_len: jint,
+    out: &JByteBuffer,
+    out_len: fn(usize) -> Option<usize>,
+    f: impl FnOnce(&[u8], &mut [u8]) -> Result<(), BridgeError>,
+) -> Result<(), BridgeError> {
+    let in_len = usize::try_from(in_len).map_err(|_| BridgeError::InvalidInput)?;
+    let need = out_len(in_len).ok_or(BridgeError::InvalidInput)?;
+
+    let in_ptr = env
+        .get_direct_buffer_address(input)
+        .map_err(|_| BridgeError::InvalidInput)?;
+    let in_cap = env
+        .get_direct_buffer_capacity(input)
+        .map_err(|_| BridgeError::InvalidInput)?;
+    let out_ptr = env
+        .get_direct_buffer_address(out)
+        .map_err(|_| BridgeError::InvalidInput)?;
+    let out_cap = env
+        .get_direct_buffer_capacity(out)
+        .map_err(|_| BridgeError::InvalidInput)?;
+
+    if in_len > in_cap || need > out_cap {
+        return Err(BridgeError::InvalidInput);
+    }
+
+    let (a, b) = (in_ptr as usize, out_ptr as usize);
+    if a < b.saturating_add(need) && b < a.saturating_add(in_len) {
+        return Err(BridgeError::InvalidInput);
+    }
+
+    // SAFETY: both pointers are non-null direct-buffer addresses,
+    // lengths are within their capacities, and the regions are disjoint
+    let data = unsafe { core::slice::from_raw_parts(in_ptr as *const u8, in_len) };
+    let buf = unsafe { core::slice::from_raw_parts_mut(out_ptr, need) };
+
+    let result = f(data, buf);
+    if result.is_err() {
+        buf.zeroize();
+    }
+    result
+}
+
+/// Copy a Java `byte[]` straight into locked memory, then wipe it.
+///
+/// SECURITY:
+/// - Length capped BEFORE allocation (`MAX_PHRASE_LEN`)
+/// - No intermediate `Vec`: JNI writes into the `GuardedVec`
+/// - Java array is zeroed even if the copy fails
+#[allow(unsafe_code)]
+fn read_guarded_wiping(env: &mut JNIEnv, array: &JByteArray) -> Result<GuardedVec, BridgeError> {
+    let len = env
+        .get_array_length(array)
+        .map_err(|_| BridgeError::InvalidInput)?;
+    let len = usize::try_from(len).map_err(|_| BridgeError::InvalidInput)?;
+    if len == 0 || len > MAX_PHRASE_LEN {
+        return Err(BridgeError::InvalidInput);
+    }
+
+    let mut guarded = GuardedVec::try_zeroed(len).ok_or(BridgeError::CryptoFailure)?;
+
+    // SAFETY: `jbyte` (i8) and u8 share size / alignment; the view
+    // covers exactly the guarded allocation and is dropped below
+    let view = unsafe {
+        core::slice::from_raw_parts_mut(guarded.borrow_mut().as_mut_ptr().cast::<jbyte>(), len)
+    };
+    let copied = env.get_byte_array_region(array, 0, view);
+
+    let wiped = env.set_byte_array_region(array, 0, &vec![0 as jbyte; len]);
+
+    copied.map_err(|_| BridgeError::InvalidInput)?;
+    wiped.map_err(|_| BridgeError::InvalidInput)?;
+    Ok(guarded)
+}
+
 /* ───────────── HELPERS ───────────── */
 
 #[inline(always)]
@@ -57,22 +172,33 @@ pub extern "system" fn Java_com_rcxcloud_core_SecureCore_unlockWithPhrase(
     phrase: JByteArray,
 ) -> jint {
     let result = panic::catch_unwind(AssertUnwindSafe(|| {
-        let phrase = env
-            .convert_byte_array(phrase)
-            .map_err(|_| BridgeError::InvalidInput)?;
+        // Only full copy lives in locked memory; the Java array is wiped
+        let phrase = read_guarded_wiping(&mut env, &phrase)?;
 
         core()
-            .unlock_with_phrase(phrase)
+            .unlock_with_guarded_phrase(phrase)
             .map_err(BridgeError::from)?;
 
         Ok(())
     }));
 
-    match result {
-        Ok(Ok(())) => BridgeError::Ok as jint,
-        Ok(Err(e)) => e as jint,
-        Err(_) => BridgeError::CryptoFailure as jint,
-    }
+    jni_status(result)
+}
+
+/// AEAD for chunks sealed after the next unlock
+/// (0 = AES-256-GCM, 1 = ChaCha20-Poly1305).
+#[no_mangle]
+pub extern "system" fn Java_com_rcxcloud_core_SecureCore_setCipherSuite(
+    _: JNIEnv,
+    _: JClass,
+    suite: jint,
+) -> jint {
+    let result = panic::catch_unwind(|| {
+        let suite = u8::try_from(suite).map_err(|_| BridgeError::InvalidInput)?;
+        core().set_cipher_suite(suite).map_err(BridgeError::from)
+    });
+
+    jni_status(result)
 }
 
 #[no_mangle]
@@ -98,6 +224,30 @@ pub extern "system" fn Java_com_rcxcloud_core_SecureCore_isKilled(
     }
 }
 
+/// `KillCause` as jint (panic => 0xFF unknown, fail-closed).
+#[no_mangle]
+pub extern "system" fn Java_com_rcxcloud_core_SecureCore_killCause(
+    _: JNIEnv,
+    _: JClass,
+) -> jint {
+    panic::catch_unwind(|| core().kill_cause())
+        .map(jint::from)
+        .unwrap_or(0xFF)
+}
+
+/// 1 = unlocked, 0 = locked / killed (panic => 0, fail-closed).
+#[no_mangle]
+pub extern "system" fn Java_com_rcxcloud_core_SecureCore_isUnlocked(
+    _: JNIEnv,
+    _: JClass,
+) -> jint {
+    let result = panic::catch_unwind(|| core().is_unlocked());
+    match result {
+        Ok(true) => 1,
+        _ => 0,
+    }
+}
+
 /* ───────────── FILE ENCRYPTION ───────────── */
 
 #[no_mangle]
@@ -117,7 +267,7 @@ pub extern "system" fn Java_com_rcxcloud_core_SecureCore_encryptChunk(
         let file_id = u64::try_from(file_id).ok()?;
 
         // ✅ Check for integer overflow on allocation
-        let required_cap = data.len().checked_add(AEAD_TAG_LEN)?;
+        let required_cap = Core::ciphertext_len(data.len())?;
         let mut out = vec![0u8; required_cap];
 
         core()
@@ -144,15 +294,13 @@ pub extern "system" fn Java_com_rcxcloud_core_SecureCore_decryptChunk(
 ) -> jbyteArray {
     let result = panic::catch_unwind(AssertUnwindSafe(|| {
         let data = env.convert_byte_array(ciphertext).ok()?;
-        if data.len() < AEAD_TAG_LEN {
-            return None;
-        }
+        let plain_len = Core::plaintext_len(data.len())?;
 
         let cloud_id = u16::try_from(cloud_id).ok()?;
         let chunk = u32::try_from(chunk).ok()?;
         let file_id = u64::try_from(file_id).ok()?;
 
-        let mut out = vec![0u8; data.len() - AEAD_TAG_LEN];
+        let mut out = vec![0u8; plain_len];
 
         let verified = core()
             .decrypt_chunk(file_id, cloud_id, chunk, &data, &mut out)
@@ -171,3 +319,191 @@ pub extern "system" fn Java_com_rcxcloud_core_SecureCore_decryptChunk(
         _ => fail_null(),
     }
 }
+
+/* ───────────── CALLER-PROVIDED OUTPUT (POOLED) ───────────── */
+
+/// Cap the per-thread retained output scratch (bytes).
+#[no_mangle]
+pub extern "system" fn Java_com_rcxcloud_core_SecureCore_setOutputPoolLimit(
+    _: JNIEnv,
+    _: JClass,
+    max_bytes: jint,
+) -> jint {
+    let result = panic::catch_unwind(|| {
+        let max = usize::try_from(max_bytes).map_err(|_| BridgeError::InvalidInput)?;
+        OUT_POOL.with(|p| p.borrow_mut().set_max_retained(max));
+        Ok(())
+    });
+
+    jni_status(result)
+}
+
+/// Encrypt into a caller-provided array (length MUST be
+/// `Core::ciphertext_len(in)`).
+///
+/// The plaintext is read straight into the pooled scratch and
+/// sealed in place (no per-call input allocation).
+#[no_mangle]
+pub extern "system" fn Java_com_rcxcloud_core_SecureCore_encryptChunkInto(
+    mut env: JNIEnv,
+    _: JClass,
+    file_id: jlong,
+    cloud_id: jint,
+    chunk: jint,
+    plaintext: JByteArray,
+    out: JByteArray,
+) -> jint {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let in_len = env
+            .get_array_length(&plaintext)
+            .map_err(|_| BridgeError::InvalidInput)?;
+        let in_len = usize::try_from(in_len).map_err(|_| BridgeError::InvalidInput)?;
+
+        let cloud_id = u16::try_from(cloud_id).map_err(|_| BridgeError::InvalidInput)?;
+        let chunk = u32::try_from(chunk).map_err(|_| BridgeError::InvalidInput)?;
+        let file_id = u64::try_from(file_id).map_err(|_| BridgeError::InvalidInput)?;
+
+        let required = Core::ciphertext_len(in_len).ok_or(BridgeError::InvalidInput)?;
+        require_out_len(&mut env, &out, required)?;
+
+        OUT_POOL.with(|p| {
+            p.borrow_mut().process_in_place(
+                required,
+                |jbuf| {
+                    env.get_byte_array_region(&plaintext, 0, &mut jbuf[..in_len])
+                        .map_err(|_| BridgeError::InvalidInput)
+                },
+                |buf| {
+                    core()
+                        .encrypt_chunk_in_place(file_id, cloud_id, chunk, buf)
+                        .map(|_| ())
+                        .map_err(BridgeError::from)
+                },
+                |jbuf| {
+                    env.set_byte_array_region(&out, 0, jbuf)
+                        .map_err(|_| BridgeError::InvalidInput)
+                },
+            )
+        })
+    }));
+
+    jni_status(result)
+}
+
+/// Decrypt into a caller-provided array (length MUST be
+/// `Core::plaintext_len(in)`).
+///
+/// Authentication failure => `IntegrityFailure`; `out` untouched.
+#[no_mangle]
+pub extern "system" fn Java_com_rcxcloud_core_SecureCore_decryptChunkInto(
+    mut env: JNIEnv,
+    _: JClass,
+    file_id: jlong,
+    cloud_id: jint,
+    chunk: jint,
+    ciphertext: JByteArray,
+    out: JByteArray,
+) -> jint {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let data = env
+            .convert_byte_array(&ciphertext)
+            .map_err(|_| BridgeError::InvalidInput)?;
+
+        let cloud_id = u16::try_from(cloud_id).map_err(|_| BridgeError::InvalidInput)?;
+        let chunk = u32::try_from(chunk).map_err(|_| BridgeError::InvalidInput)?;
+        let file_id = u64::try_from(file_id).map_err(|_| BridgeError::InvalidInput)?;
+
+        let required = Core::plaintext_len(data.len()).ok_or(BridgeError::InvalidInput)?;
+        require_out_len(&mut env, &out, required)?;
+
+        OUT_POOL.with(|p| {
+            p.borrow_mut().process(
+                required,
+                |buf| {
+                    let verified = core()
+                        .decrypt_chunk(file_id, cloud_id, chunk, &data, buf)
+                        .map_err(BridgeError::from)?;
+
+                    if verified.0 {
+                        Ok(())
+                    } else {
+                        Err(BridgeError::IntegrityFailure)
+                    }
+                },
+                |jbuf| {
+                    env.set_byte_array_region(&out, 0, jbuf)
+                        .map_err(|_| BridgeError::InvalidInput)
+                },
+            )
+        })
+    }));
+
+    jni_status(result)
+}
+
+/* ───────────── DIRECT BYTEBUFFER (ZERO-COPY) ───────────── */
+
+/// Encrypt `in_len` bytes of direct `plaintext` into direct `out`
+/// (capacity MUST be at least `Core::ciphertext_len(in_len)`).
+#[no_mangle]
+pub extern "system" fn Java_com_rcxcloud_core_SecureCore_encryptChunkDirect(
+    env: JNIEnv,
+    _: JClass,
+    file_id: jlong,
+    cloud_id: jint,
+    chunk: jint,
+    plaintext: JByteBuffer,
+    in_len: jint,
+    out: JByteBuffer,
+) -> jint {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let cloud_id = u16::try_from(cloud_id).map_err(|_| BridgeError::InvalidInput)?;
+        let chunk = u32::try_from(chunk).map_err(|_| BridgeError::InvalidInput)?;
+        let file_id = u64::try_from(file_id).map_err(|_| BridgeError::InvalidInput)?;
+
+        with_direct_buffers(&env, &plaintext, in_len, &out, Core::ciphertext_len, |data, buf| {
+            core()
+                .encrypt_chunk(file_id, cloud_id, chunk, data, buf)
+                .map(|_| ())
+                .map_err(BridgeError::from)
+        })
+    }));
+
+    jni_status(result)
+}
+
+/// Decrypt `in_len` bytes of direct `ciphertext` into direct `out`
+/// (capacity MUST be at least `Core::plaintext_len(in_len)`).
+///
+/// Authentication failure => `IntegrityFailure`; `out` region zeroized.
+#[no_mangle]
+pub extern "system" fn Java_com_rcxcloud_core_SecureCore_decryptChunkDirect(
+    env: JNIEnv,
+    _: JClass,
+    file_id: jlong,
+    cloud_id: jint,
+    chunk: jint,
+    ciphertext: JByteBuffer,
+    in_len: jint,
+    out: JByteBuffer,
+) -> jint {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let cloud_id = u16::try_from(cloud_id).map_err(|_| BridgeError::InvalidInput)?;
+        let chunk = u32::try_from(chunk).map_err(|_| BridgeError::InvalidInput)?;
+        let file_id = u64::try_from(file_id).map_err(|_| BridgeError::InvalidInput)?;
+
+        with_direct_buffers(&env, &ciphertext, in_len, &out, Core::plaintext_len, |data, buf| {
+            let verified = core()
+                .decrypt_chunk(file_id, cloud_id, chunk, data, buf)
+                .map_err(BridgeError::from)?;
+
+            if verified.0 {
+                Ok(())
+            } else {
+                Err(BridgeError::IntegrityFailure)
+            }
+        })
+    }));
+
+    jni_status(result)
+}
@@ -16,9 +16,13 @@
 #![deny(clippy::derive_debug)]
 
 pub mod api;
+pub mod diagnostics;
 pub mod error;
+pub mod events;
 pub mod handle;
 
+pub(crate) mod out_pool;
+
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
 
@@ -27,5 +31,13 @@ pub mod jni;
 
 // ❄️ ONLY THESE ARE PUBLIC
 pub use api::{Core, CoreError};
+pub use diagnostics::{Diagnostics, HealthReport};
 pub use error::BridgeError;
-pub use handle::CoreHandle;
\ No newline at end of file
+pub use events::{CoreEvent, EventSink};
+pub use handle::CoreHandle;
+
+// Guarded plaintext returned by `Core::decrypt_chunk_guarded`
+pub use crate::memory::GuardedVec;
+
+// Host-held buffer wiped on kill (`Core::register_sensitive_buffer`)
+pub use crate::memory::SensitiveBuffer;
\ No newline at end of file
@@ -0,0 +1,249 @@
+//! Reusable bridge output buffers (allocation-churn reduction).
+//!
+//! Lets FFI adapters write results into caller-provided arrays
+//! (e.g. JNI `set_byte_array_region`) without allocating a fresh
+//! Rust `Vec` per call.
+//!
+//! SECURITY:
+//! - Buffers are wiped after EVERY use (success or failure)
+//! - Retained capacity is capped (configurable)
+//! - No unsafe reinterpretation: u8 → i8 is an explicit copy
+
+use crate::crypto::aes_gcm::TAG_LEN;
+use zeroize::Zeroize;
+
+/// Default retained capacity: one max-size chunk + tag.
+pub(crate) const DEFAULT_MAX_RETAINED: usize = 4 * 1024 * 1024 + TAG_LEN;
+
+/* ───────────── POOL ───────────── */
+
+/// Per-adapter reusable output scratch.
+pub(crate) struct OutputPool {
+    bytes: Vec<u8>,
+    jbytes: Vec<i8>,
+    max_retained: usize,
+}
+
+impl OutputPool {
+    pub(crate) const fn new(max_retained: usize) -> Self {
+        Self {
+            bytes: Vec::new(),
+            jbytes: Vec::new(),
+            max_retained,
+        }
+    }
+
+    /// Change the retained-capacity cap (wipes + shrinks if needed).
+    pub(crate) fn set_max_retained(&mut self, max_retained: usize) {
+        self.max_retained = max_retained;
+        self.trim();
+    }
+
+    /// Produce `len` bytes, then hand them to `emit` as `i8`.
+    ///
+    /// SECURITY:
+    /// - `produce` output is wiped before returning
+    /// - `emit` view is wiped before returning
+    /// - `produce` failure => `emit` is never called
+    pub(crate) fn process<E>(
+        &mut self,
+        len: usize,
+        produce: impl FnOnce(&mut [u8]) -> Result<(), E>,
+        emit: impl FnOnce(&[i8]) -> Result<(), E>,
+    ) -> Result<(), E> {
+        self.reserve(len);
+
+        let res = produce(&mut self.bytes[..len]).and_then(|()| self.emit(len, emit));
+
+        self.wipe(len);
+        res
+    }
+
+    /// `load` the input straight into the scratch, transform it IN
+    /// PLACE with `produce`, then hand the `len` bytes to `emit`.
+    ///
+    /// `load` sees `len` zeroed bytes and fills its input prefix;
+    /// the rest stays zero (e.g. tag space for in-place sealing).
+    ///
+    /// SECURITY:
+    /// - No separate input allocation
+    /// - Loaded input is wiped from the `i8` view before `produce`
+    /// - Same wiping as `process` on success and failure
+    pub(crate) fn process_in_place<E>(
+        &mut self,
+        len: usize,
+        load: impl FnOnce(&mut [i8]) -> Result<(), E>,
+        produce: impl FnOnce(&mut [u8]) -> Result<(), E>,
+        emit: impl FnOnce(&[i8]) -> Result<(), E>,
+    ) -> Result<(), E> {
+        self.reserve(len);
+
+        let res = load(&mut self.jbytes[..len])
+            .and_then(|()| {
+                for (b, j) in self.bytes[..len].iter_mut().zip(&self.jbytes[..len]) {
+                    *b = *j as u8;
+                }
+                self.jbytes[..len].zeroize();
+
+                produce(&mut self.bytes[..len])
+            })
+            .and_then(|()| self.emit(len, emit));
+
+        self.wipe(len);
+        res
+    }
+
+    fn reserve(&mut self, len: usize) {
+        if self.bytes.len() < len {
+            self.bytes.resize(len, 0);
+        }
+        if self.jbytes.len() < len {
+            self.jbytes.resize(len, 0);
+        }
+    }
+
+    /// Convert the produced bytes to `i8` and emit them.
+    fn emit<E>(
+        &mut self,
+        len: usize,
+        emit: impl FnOnce(&[i8]) -> Result<(), E>,
+    ) -> Result<(), E> {
+        for (j, b) in self.jbytes[..len].iter_mut().zip(&self.bytes[..len]) {
+            *j = *b as i8;
+        }
+        self.bytes[..len].zeroize();
+
+        emit(&self.jbytes[..len])
+    }
+
+    fn wipe(&mut self, len: usize) {
+        self.bytes[..len].zeroize();
+        self.jbytes[..len].zeroize();
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        if self.bytes.len() > self.max_retained {
+            self.bytes.zeroize();
+            self.bytes = Vec::new();
+        }
+        if self.jbytes.len() > self.max_retained {
+            self.jbytes.zeroize();
+            self.jbytes = Vec::new();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn process_emits_and_wipes() {
+        let mut pool = OutputPool::new(DEFAULT_MAX_RETAINED);
+        let mut seen = Vec::new();
+
+        let res: Result<(), ()> = pool.process(
+            4,
+            |out| {
+                out.copy_from_slice(&[1, 2, 0xFF, 0x80]);
+                Ok(())
+            },
+            |j| {
+                seen.extend_from_slice(j);
+                Ok(())
+            },
+        );
+
+        assert_eq!(res, Ok(()));
+        assert_eq!(seen, vec![1i8, 2, -1, -128]);
+        assert!(pool.bytes.iter().all(|b| *b == 0));
+        assert!(pool.jbytes.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn produce_failure_skips_emit() {
+        let mut pool = OutputPool::new(DEFAULT_MAX_RETAINED);
+        let mut emitted = false;
+
+        let res = pool.process(
+            8,
+            |out| {
+                out.fill(0xAA);
+                Err("fail")
+            },
+            |_| {
+                emitted = true;
+                Ok(())
+            },
+        );
+
+        assert_eq!(res, Err("fail"));
+        assert!(!emitted);
+        assert!(pool.bytes.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn in_place_loads_transforms_and_wipes() {
+        let mut pool = OutputPool::new(DEFAULT_MAX_RETAINED);
+        let mut seen = Vec::new();
+
+        let res: Result<(), ()> = pool.process_in_place(
+            4,
+            |j| {
+                // Input prefix only; the spare byte arrives zeroed
+                assert_eq!(j, &[0i8; 4]);
+                j[..3].copy_from_slice(&[1, -1, -128]);
+                Ok(())
+            },
+            |buf| {
+                assert_eq!(buf, &[1, 0xFF, 0x80, 0]);
+                buf[3] = 0x7F;
+                Ok(())
+            },
+            |j| {
+                seen.extend_from_slice(j);
+                Ok(())
+            },
+        );
+
+        assert_eq!(res, Ok(()));
+        assert_eq!(seen, vec![1i8, -1, -128, 127]);
+        assert!(pool.bytes.iter().all(|b| *b == 0));
+        assert!(pool.jbytes.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn in_place_load_failure_skips_produce() {
+        let mut pool = OutputPool::new(DEFAULT_MAX_RETAINED);
+        let mut produced = false;
+
+        let res = pool.process_in_place(
+            8,
+            |j| {
+                j.fill(0x55);
+                Err("load")
+            },
+            |_| {
+                produced = true;
+                Ok(())
+            },
+            |_| Ok(()),
+        );
+
+        assert_eq!(res, Err("load"));
+        assert!(!produced);
+        assert!(pool.jbytes.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn oversized_buffers_are_not_retained() {
+        let mut pool = OutputPool::new(8);
+
+        let res: Result<(), ()> = pool.process(32, |_| Ok(()), |_| Ok(()));
+
+        assert_eq!(res, Ok(()));
+        assert!(pool.bytes.is_empty());
+        assert!(pool.jbytes.is_empty());
+    }
+}
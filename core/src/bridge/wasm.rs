@@ -4,31 +4,73 @@
 //! - Panic-safe
 //! - Kill-aware
 //! - Random Handle Generation
-//! - Fail-closed
+//! - Fail-closed (unknown / destroyed handle => `Denied`)
+
+// Raw host pointers are the ABI: every deref is null / length checked
+#![allow(unsafe_code)]
 
 use crate::bridge::api::Core;
-use crate::bridge::error::BridgeError;
+use crate::bridge::error::{message_for_code, wasm_status, BridgeError};
 use crate::bridge::handle::CoreHandle;
+use crate::crypto::aes_gcm::TAG_LEN;
+use crate::crypto::file::MAX_CHUNK_SIZE;
 use crate::keystore::master::GLOBAL_KILLED;
+use crate::keystore::recovery::MAX_PHRASE_LEN;
+use crate::memory::GuardedVec;
 
 use core::num::NonZeroU64;
 use core::sync::atomic::Ordering;
+use std::collections::HashMap;
 use std::panic::{catch_unwind, AssertUnwindSafe};
-use std::sync::OnceLock;
-use rand_core::{OsRng, RngCore};
+use std::sync::{Mutex, OnceLock};
+use crate::crypto::rng::{OsRngBackend, SecureRng};
 
 /* ───────────── GLOBAL CORE REGISTRY ───────────── */
 
-static CORE: OnceLock<Core> = OnceLock::new();
-static CORE_ID: OnceLock<NonZeroU64> = OnceLock::new();
+/// Live cores by handle id (init / destroy may cycle).
+static CORES: OnceLock<Mutex<HashMap<NonZeroU64, Core>>> = OnceLock::new();
+
+fn cores() -> &'static Mutex<HashMap<NonZeroU64, Core>> {
+    CORES.get_or_init(|| Mutex::new(HashMap::new()))
+}
 
 /* ───────────── HELPERS ───────────── */
 
+/// Random, non-zero handle id unique among `live` cores.
+fn new_handle_id<R: SecureRng>(
+    rng: &mut R,
+    live: &HashMap<NonZeroU64, Core>,
+) -> Result<NonZeroU64, BridgeError> {
+    loop {
+        let mut bytes = [0u8; 8];
+        rng.try_fill_bytes(&mut bytes)
+            .map_err(|_| BridgeError::CryptoFailure)?;
+        if let Some(id) = NonZeroU64::new(u64::from_ne_bytes(bytes)) {
+            if !live.contains_key(&id) {
+                return Ok(id);
+            }
+        }
+    }
+}
+
 #[inline(always)]
 fn killed() -> bool {
     GLOBAL_KILLED.load(Ordering::SeqCst)
 }
 
+/// Run `f` on the core behind `handle`.
+///
+/// Unknown / destroyed handle or poisoned registry => `Denied`.
+fn with_core<R>(
+    handle: u64,
+    f: impl FnOnce(&Core) -> Result<R, BridgeError>,
+) -> Result<R, BridgeError> {
+    let id = NonZeroU64::new(handle).ok_or(BridgeError::Denied)?;
+    let cores = cores().lock().map_err(|_| BridgeError::Denied)?;
+    let core = cores.get(&id).ok_or(BridgeError::Denied)?;
+    f(core)
+}
+
 /* ───────────── ABI ───────────── */
 
 #[no_mangle]
@@ -38,16 +80,11 @@ pub extern "C" fn rcx_init(out_handle: *mut u64) -> i32 {
             return Err(BridgeError::Killed);
         }
 
-        CORE.get_or_init(Core::new);
+        let mut cores = cores().lock().map_err(|_| BridgeError::Denied)?;
 
-        // ✅ FIX: Generate random handle, do not use hardcoded '1'
-        let mut bytes = [0u8; 8];
-        OsRng.fill_bytes(&mut bytes);
-        let val = u64::from_ne_bytes(bytes);
-        // Ensure non-zero (fallback to 1 if RNG yields 0, extremely unlikely)
-        let id = NonZeroU64::new(val).unwrap_or(NonZeroU64::new(1).unwrap());
-        
-        CORE_ID.set(id).ok();
+        let id = new_handle_id(&mut OsRngBackend, &cores)?;
+
+        cores.insert(id, Core::new());
 
         unsafe {
             *out_handle = id.get();
@@ -56,11 +93,7 @@ pub extern "C" fn rcx_init(out_handle: *mut u64) -> i32 {
         Ok(())
     }));
 
-    match result {
-        Ok(Ok(())) => BridgeError::Ok as i32,
-        Ok(Err(e)) => e as i32,
-        Err(_) => BridgeError::CryptoFailure as i32,
-    }
+    wasm_status(result)
 }
 
 #[no_mangle]
@@ -74,22 +107,409 @@ pub extern "C" fn rcx_unlock_with_phrase(
             return Err(BridgeError::InvalidInput);
         }
 
-        // Validate Handle
-        if CORE_ID.get().map(|id| id.get()) != Some(handle) {
-            return Err(BridgeError::Denied);
+        // Cap the host-claimed length BEFORE building a slice from it:
+        // a bogus `len` must never reach `from_raw_parts`
+        if len > MAX_PHRASE_LEN {
+            return Err(BridgeError::InvalidInput);
+        }
+
+        with_core(handle, |core| {
+            let phrase = unsafe { core::slice::from_raw_parts(ptr, len) }.to_vec();
+
+            core.unlock_with_phrase(phrase).map_err(BridgeError::from)
+        })
+    }));
+
+    wasm_status(result)
+}
+
+/// Unlock from a phrase in host memory, copied straight into
+/// locked memory; the host buffer `ptr[..len]` is ZEROED afterwards.
+///
+/// Preferred over `rcx_unlock_with_phrase` (which leaves an
+/// unlocked `Vec` copy and the host buffer intact).
+#[no_mangle]
+pub extern "C" fn rcx_unlock_with_phrase_guarded(
+    handle: u64,
+    ptr: *mut u8,
+    len: usize,
+) -> i32 {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        if killed() || ptr.is_null() || len == 0 {
+            return Err(BridgeError::InvalidInput);
+        }
+
+        // Cap BEFORE building a slice (see `rcx_unlock_with_phrase`)
+        if len > MAX_PHRASE_LEN {
+            return Err(BridgeError::InvalidInput);
+        }
+
+        let phrase = GuardedVec::try_zeroed(len).map(|mut buf| {
+            buf.borrow_mut()
+                .copy_from_slice(unsafe { core::slice::from_raw_parts(ptr, len) });
+            buf
+        });
+
+        // Wipe the host copy whether or not the allocation succeeded
+        unsafe {
+            core::ptr::write_bytes(ptr, 0, len);
+        }
+        let phrase = phrase.ok_or(BridgeError::CryptoFailure)?;
+
+        with_core(handle, |core| {
+            core.unlock_with_guarded_phrase(phrase).map_err(BridgeError::from)
+        })
+    }));
+
+    wasm_status(result)
+}
+
+/// Select the AEAD for chunks sealed after the next unlock
+/// (`CipherSuite::code`; see `Core::set_cipher_suite`).
+#[no_mangle]
+pub extern "C" fn rcx_set_cipher_suite(handle: u64, suite: u8) -> i32 {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        with_core(handle, |core| core.set_cipher_suite(suite).map_err(BridgeError::from))
+    }));
+
+    wasm_status(result)
+}
+
+/* ───────────── CHUNK CRYPTO ───────────── */
+
+/// Encrypt `in_ptr[..in_len]` into `out_ptr[..out_cap]`.
+///
+/// Returns the ciphertext length written (`>= 0`) or a NEGATED
+/// `BridgeError` code. Size `out_cap` with `rcx_ciphertext_len`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn rcx_encrypt_chunk(
+    handle: u64,
+    file_id: u64,
+    cloud_id: u16,
+    chunk: u32,
+    in_ptr: *const u8,
+    in_len: usize,
+    out_ptr: *mut u8,
+    out_cap: usize,
+) -> i32 {
+    chunk_op(in_ptr, in_len, out_ptr, out_cap, Core::ciphertext_len, |input, out| {
+        with_core(handle, |core| {
+            core.encrypt_chunk(file_id, cloud_id, chunk, input, out)
+                .map(|r| r.total_len)
+                .map_err(BridgeError::from)
+        })
+    })
+}
+
+/// Decrypt + verify `in_ptr[..in_len]` into `out_ptr[..out_cap]`.
+///
+/// Returns the plaintext length written (`>= 0`) or a NEGATED
+/// `BridgeError` code. Size `out_cap` with `rcx_plaintext_len`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn rcx_decrypt_chunk(
+    handle: u64,
+    file_id: u64,
+    cloud_id: u16,
+    chunk: u32,
+    in_ptr: *const u8,
+    in_len: usize,
+    out_ptr: *mut u8,
+    out_cap: usize,
+) -> i32 {
+    chunk_op(in_ptr, in_len, out_ptr, out_cap, Core::plaintext_len, |input, out| {
+        with_core(handle, |core| {
+            let verified = core
+                .decrypt_chunk(file_id, cloud_id, chunk, input, out)
+                .map_err(BridgeError::from)?;
+
+            if !verified.0 {
+                return Err(BridgeError::IntegrityFailure);
+            }
+            Ok(out.len())
+        })
+    })
+}
+
+/// Shared pointer handling for the chunk entry points.
+///
+/// SECURITY:
+/// - Null pointers, `in_len` beyond one chunk + tag, `out_cap`
+///   below the exact output length, or overlapping buffers =>
+///   `InvalidInput`, checked BEFORE any slice is built
+/// - Only `in_ptr[..in_len]` is read and only the exact output
+///   length is written
+/// - Panic-safe; on ANY failure `out_ptr[..out_cap]` is zeroed
+///   before returning
+fn chunk_op(
+    in_ptr: *const u8,
+    in_len: usize,
+    out_ptr: *mut u8,
+    out_cap: usize,
+    out_len_for: fn(usize) -> Option<usize>,
+    op: impl FnOnce(&[u8], &mut [u8]) -> Result<usize, BridgeError>,
+) -> i32 {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        if killed() {
+            return Err(BridgeError::Killed);
+        }
+        if in_ptr.is_null() || out_ptr.is_null() || in_len > MAX_CHUNK_SIZE + TAG_LEN {
+            return Err(BridgeError::InvalidInput);
+        }
+
+        let out_len = out_len_for(in_len).ok_or(BridgeError::InvalidInput)?;
+        if out_cap < out_len {
+            return Err(BridgeError::InvalidInput);
+        }
+
+        // `&[u8]` and `&mut [u8]` over the same bytes would alias
+        let (in_start, out_start) = (in_ptr as usize, out_ptr as usize);
+        if in_start < out_start.saturating_add(out_len) && out_start < in_start.saturating_add(in_len) {
+            return Err(BridgeError::InvalidInput);
+        }
+
+        let input = unsafe { core::slice::from_raw_parts(in_ptr, in_len) };
+        let out = unsafe { core::slice::from_raw_parts_mut(out_ptr, out_len) };
+
+        let written = op(input, out)?;
+        i32::try_from(written).map_err(|_| BridgeError::InvalidInput)
+    }));
+
+    let err = match result {
+        Ok(Ok(written)) => return written,
+        Ok(Err(e)) => e,
+        Err(_) => BridgeError::CryptoFailure,
+    };
+
+    if !out_ptr.is_null() {
+        unsafe {
+            core::ptr::write_bytes(out_ptr, 0, out_cap);
         }
+    }
+    -(err as i32)
+}
 
-        let phrase = unsafe { core::slice::from_raw_parts(ptr, len) }.to_vec();
+/// Lock, wipe and drop the core behind `handle` (logout).
+///
+/// Works even when killed (destroying only removes state).
+/// Unknown / already-destroyed handle => `Denied`.
+#[no_mangle]
+pub extern "C" fn rcx_destroy(handle: u64) -> i32 {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let id = NonZeroU64::new(handle).ok_or(BridgeError::Denied)?;
 
-        CORE.get()
-            .unwrap()
-            .unlock_with_phrase(phrase)
-            .map_err(BridgeError::from)
+        let core = cores()
+            .lock()
+            .map_err(|_| BridgeError::Denied)?
+            .remove(&id)
+            .ok_or(BridgeError::Denied)?;
+
+        // Zeroize session keys before the core itself is dropped
+        core.lock();
+        drop(core);
+        Ok(())
+    }));
+
+    wasm_status(result)
+}
+
+/// Write `Core::ciphertext_len(plaintext_len)` to `*out_len`.
+///
+/// Overflow => `InvalidInput` (nothing written). Stateless: no
+/// handle needed, allowed after kill.
+#[no_mangle]
+pub extern "C" fn rcx_ciphertext_len(plaintext_len: usize, out_len: *mut usize) -> i32 {
+    write_len(Core::ciphertext_len(plaintext_len), out_len)
+}
+
+/// Write `Core::plaintext_len(ciphertext_len)` to `*out_len`.
+///
+/// Shorter than the AEAD tag => `InvalidInput` (nothing written).
+#[no_mangle]
+pub extern "C" fn rcx_plaintext_len(ciphertext_len: usize, out_len: *mut usize) -> i32 {
+    write_len(Core::plaintext_len(ciphertext_len), out_len)
+}
+
+/// 1 = unlocked, 0 = locked / killed / unknown handle (fail-closed).
+#[no_mangle]
+pub extern "C" fn rcx_is_unlocked(handle: u64) -> i32 {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        with_core(handle, |core| Ok(core.is_unlocked()))
     }));
 
     match result {
-        Ok(Ok(())) => BridgeError::Ok as i32,
+        Ok(Ok(true)) => 1,
+        _ => 0,
+    }
+}
+
+/// Write `Core::kill_cause()` to `*out_cause` (diagnostic only).
+#[no_mangle]
+pub extern "C" fn rcx_kill_cause(handle: u64, out_cause: *mut u8) -> i32 {
+    if out_cause.is_null() {
+        return BridgeError::InvalidInput as i32;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        // Readable after a kill: this is what triage asks for
+        with_core(handle, |core| Ok(core.kill_cause()))
+    }));
+
+    match result {
+        Ok(Ok(cause)) => {
+            unsafe {
+                out_cause.write(cause);
+            }
+            BridgeError::Ok as i32
+        }
         Ok(Err(e)) => e as i32,
         Err(_) => BridgeError::CryptoFailure as i32,
     }
 }
+
+fn write_len(len: Option<usize>, out_len: *mut usize) -> i32 {
+    let Some(len) = len else {
+        return BridgeError::InvalidInput as i32;
+    };
+    if out_len.is_null() {
+        return BridgeError::InvalidInput as i32;
+    }
+
+    unsafe {
+        out_len.write(len);
+    }
+    BridgeError::Ok as i32
+}
+
+/// Copy the static message for `code` into `out[..cap]`.
+///
+/// Returns the byte length written (no NUL terminator), or the
+/// NEGATED required length if `out` is null or `cap` is too small
+/// (nothing is written then). No allocation, no secret material.
+#[no_mangle]
+pub extern "C" fn rcx_error_message(code: i32, out: *mut u8, cap: usize) -> i32 {
+    let msg = message_for_code(code).as_bytes();
+
+    // Messages are short fixed strings; the cast cannot truncate
+    let len = msg.len() as i32;
+
+    if out.is_null() || cap < msg.len() {
+        return -len;
+    }
+
+    unsafe {
+        core::ptr::copy_nonoverlapping(msg.as_ptr(), out, msg.len());
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::rng::DeterministicRng;
+
+    #[test]
+    fn handle_ids_skip_live_collisions() -> Result<(), ()> {
+        let mut live = HashMap::new();
+
+        let first = new_handle_id(&mut DeterministicRng::from_seed([9; 32]), &live).map_err(|_| ())?;
+        live.insert(first, Core::new());
+
+        // Same seed replays `first`, which is now live => next draw
+        let second = new_handle_id(&mut DeterministicRng::from_seed([9; 32]), &live).map_err(|_| ())?;
+        assert!(second != first);
+        Ok(())
+    }
+
+    #[test]
+    fn over_cap_phrase_len_is_rejected_before_slice() {
+        // Dangling pointer: any read through it would be UB, so
+        // rejection MUST happen before slice construction
+        let ptr = core::ptr::NonNull::<u8>::dangling().as_ptr();
+
+        let rc = rcx_unlock_with_phrase(0, ptr, MAX_PHRASE_LEN + 1);
+        assert_eq!(rc, BridgeError::InvalidInput as i32);
+
+        let rc = rcx_unlock_with_phrase(0, ptr, usize::MAX);
+        assert_eq!(rc, BridgeError::InvalidInput as i32);
+    }
+
+    #[test]
+    fn length_helpers_reject_overflow_and_short_input() {
+        let mut len = 0usize;
+
+        assert_eq!(rcx_ciphertext_len(10, &mut len), BridgeError::Ok as i32);
+        assert_eq!(Core::plaintext_len(len), Some(10));
+
+        assert_eq!(rcx_ciphertext_len(usize::MAX, &mut len), BridgeError::InvalidInput as i32);
+        assert_eq!(rcx_plaintext_len(3, &mut len), BridgeError::InvalidInput as i32);
+        assert_eq!(rcx_plaintext_len(64, core::ptr::null_mut()), BridgeError::InvalidInput as i32);
+    }
+
+    #[test]
+    fn chunk_entry_points_reject_and_wipe() {
+        let input = [0x11u8; 32];
+        let mut out = [0xEEu8; 64];
+
+        // Null input
+        let rc = rcx_encrypt_chunk(7, 1, 1, 0, core::ptr::null(), 4, out.as_mut_ptr(), out.len());
+        assert_eq!(rc, -(BridgeError::InvalidInput as i32));
+        assert!(out.iter().all(|b| *b == 0));
+
+        // Output smaller than plaintext + tag
+        out.fill(0xEE);
+        let rc = rcx_encrypt_chunk(7, 1, 1, 0, input.as_ptr(), input.len(), out.as_mut_ptr(), 40);
+        assert_eq!(rc, -(BridgeError::InvalidInput as i32));
+        assert!(out[..40].iter().all(|b| *b == 0));
+
+        // Ciphertext shorter than the tag
+        let rc = rcx_decrypt_chunk(7, 1, 1, 0, input.as_ptr(), TAG_LEN - 1, out.as_mut_ptr(), out.len());
+        assert_eq!(rc, -(BridgeError::InvalidInput as i32));
+
+        // Unknown handle: well-formed call, still denied and wiped
+        out.fill(0xEE);
+        let rc = rcx_encrypt_chunk(0, 1, 1, 0, input.as_ptr(), input.len(), out.as_mut_ptr(), out.len());
+        assert_eq!(rc, -(BridgeError::Denied as i32));
+        assert!(out.iter().all(|b| *b == 0));
+
+        // In-place (aliasing) buffers are refused
+        let rc = rcx_encrypt_chunk(7, 1, 1, 0, out.as_ptr(), 16, out.as_mut_ptr(), out.len());
+        assert_eq!(rc, -(BridgeError::InvalidInput as i32));
+    }
+
+    #[test]
+    fn destroyed_handle_is_denied() {
+        let mut handle = 0u64;
+        assert_eq!(rcx_init(&mut handle), BridgeError::Ok as i32);
+        assert_ne!(handle, 0);
+
+        assert_eq!(rcx_destroy(handle), BridgeError::Ok as i32);
+        assert_eq!(rcx_destroy(handle), BridgeError::Denied as i32);
+
+        let phrase = b"phrase";
+        let rc = rcx_unlock_with_phrase(handle, phrase.as_ptr(), phrase.len());
+        assert_eq!(rc, BridgeError::Denied as i32);
+
+        // A fresh cycle works after destroy
+        let mut again = 0u64;
+        assert_eq!(rcx_init(&mut again), BridgeError::Ok as i32);
+        assert_eq!(rcx_destroy(again), BridgeError::Ok as i32);
+    }
+
+    #[test]
+    fn error_message_needs_a_large_enough_buffer() {
+        let code = BridgeError::Killed as i32;
+        let want = message_for_code(code).as_bytes();
+
+        let mut small = [0u8; 4];
+        let rc = rcx_error_message(code, small.as_mut_ptr(), small.len());
+        assert_eq!(rc, -(want.len() as i32));
+        assert_eq!(small, [0u8; 4]);
+
+        let mut buf = [0u8; 64];
+        let rc = rcx_error_message(code, buf.as_mut_ptr(), buf.len());
+        assert_eq!(rc, want.len() as i32);
+        assert_eq!(&buf[..want.len()], want);
+    }
+}
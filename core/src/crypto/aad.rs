@@ -10,16 +10,112 @@
 //! - Deterministic serialization
 //! - Versioned
 //! - Used ONLY for file encryption AEAD
+//!
+//! WIRE FORMAT (stable contract; all integers big-endian):
+//!
+//! | offset | len | field                                      |
+//! |--------|-----|--------------------------------------------|
+//! | 0      | 8   | file_id (u64)                              |
+//! | 8      | 4   | chunk (u32)                                |
+//! | 12     | 2   | cloud_id (u16)                             |
+//! | 14     | 1   | version (low bits: layout, high: cipher)   |
+//! | 15     | 8   | epoch (u64)       — V2 only (total 23)     |
+//! | 15     | 4   | generation (u32)  — V3 only (total 19)     |
+//! | 15     | 4   | generation (u32)  — STREAM (total 19)      |
+//!
+//! V1 is exactly 15 bytes. The order is FROZEN: these bytes are
+//! authenticated into every sealed chunk, so reordering them would
+//! orphan all existing ciphertext. New fields => new layout version.
 
 /// Current supported AAD format version.
 pub const AAD_VERSION_V1: u8 = 1;
 
+/// AAD format bound to a monotonic file epoch (anti-rollback).
+///
+/// Layout: V1 layout || epoch_be (8)
+pub const AAD_VERSION_V2: u8 = 2;
+
+/// AAD format bound to a session ratchet generation (`Session::rekey`).
+///
+/// Layout: V1 layout || generation_be (4)
+pub const AAD_VERSION_V3: u8 = 3;
+
+/// AAD format of `keystore::stream` chunks.
+///
+/// Layout: V1 layout || generation_be (4), generation 0 included.
+/// A stream chunk can never authenticate as a one-shot chunk (or
+/// the reverse), even under the same file key and chunk index.
+pub const AAD_VERSION_STREAM: u8 = 4;
+
+// Cipher-suite bits (see `cipher`) share the version byte
+use crate::crypto::cipher::{CipherSuite, AAD_CIPHER_MASK};
+
+/// Largest serialized AAD (V2).
+pub const AAD_MAX_LEN: usize = 23;
+
+/// Known AAD layouts (version byte with cipher-suite bits masked off).
+///
+/// Unknown layouts are NEVER guessed: `from_u8` fails closed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AadVersion {
+    V1 = AAD_VERSION_V1,
+    V2 = AAD_VERSION_V2,
+    V3 = AAD_VERSION_V3,
+    Stream = AAD_VERSION_STREAM,
+}
+
+impl AadVersion {
+    /// Layout written by `encrypt_chunk`.
+    pub const CURRENT: AadVersion = AadVersion::V1;
+
+    /// Layout of a full version byte (cipher bits ignored).
+    #[inline(always)]
+    pub const fn from_u8(version: u8) -> Option<Self> {
+        match version & !AAD_CIPHER_MASK {
+            AAD_VERSION_V1 => Some(AadVersion::V1),
+            AAD_VERSION_V2 => Some(AadVersion::V2),
+            AAD_VERSION_V3 => Some(AadVersion::V3),
+            AAD_VERSION_STREAM => Some(AadVersion::Stream),
+            _ => None,
+        }
+    }
+
+    /// Exact serialized length of this layout.
+    #[inline(always)]
+    pub const fn serialized_len(self) -> usize {
+        match self {
+            AadVersion::V1 => 15,
+            AadVersion::V2 => 23,
+            AadVersion::V3 | AadVersion::Stream => 19,
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct Aad {
     file_id: u64,
     chunk: u32,
     cloud_id: u16,
     version: u8,
+    epoch: u64,
+    generation: u32,
+}
+
+/// Serialized AAD (fixed-capacity, stack-only, non-secret).
+#[derive(Clone, Copy)]
+pub struct SerializedAad {
+    bytes: [u8; AAD_MAX_LEN],
+    len: usize,
+}
+
+impl core::ops::Deref for SerializedAad {
+    type Target = [u8];
+
+    #[inline(always)]
+    fn deref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
 }
 
 impl Aad {
@@ -30,7 +126,7 @@ impl Aad {
         cloud_id: u16,
         version: u8,
     ) -> Option<Self> {
-        if version != AAD_VERSION_V1 {
+        if version & !AAD_CIPHER_MASK != AAD_VERSION_V1 {
             return None;
         }
 
@@ -39,17 +135,139 @@ impl Aad {
             chunk,
             cloud_id,
             version,
+            epoch: 0,
+            generation: 0,
         })
     }
 
+    /// Epoch-bound AAD (V2).
+    ///
+    /// Chunks sealed under one epoch fail authentication
+    /// under any other epoch (file-level rollback defence).
+    #[inline(always)]
+    pub fn with_epoch(
+        file_id: u64,
+        chunk: u32,
+        cloud_id: u16,
+        epoch: u64,
+    ) -> Self {
+        Self {
+            file_id,
+            chunk,
+            cloud_id,
+            version: AAD_VERSION_V2,
+            epoch,
+            generation: 0,
+        }
+    }
+
+    /// Stream-chunk AAD (`AAD_VERSION_STREAM`).
+    ///
+    /// Only `keystore::stream` builds these; the session selects
+    /// the stream nonce domain from the version byte.
+    #[inline(always)]
+    pub fn stream(file_id: u64, chunk: u32, cloud_id: u16) -> Self {
+        Self {
+            file_id,
+            chunk,
+            cloud_id,
+            version: AAD_VERSION_STREAM,
+            epoch: 0,
+            generation: 0,
+        }
+    }
+
+    /// Same AAD, recording `suite` in the version byte.
+    #[inline(always)]
+    pub fn with_cipher(self, suite: CipherSuite) -> Self {
+        Self {
+            version: (self.version & !AAD_CIPHER_MASK) | suite.aad_flag(),
+            ..self
+        }
+    }
+
+    /// Same AAD, bound to ratchet `generation` (V1 <-> V3).
+    ///
+    /// Generation 0 is plain V1 (pre-ratchet chunks are unchanged).
+    /// Epoch-bound (V2) AAD is returned as-is; stream AAD keeps its
+    /// layout and only records the generation.
+    #[inline(always)]
+    pub fn with_generation(self, generation: u32) -> Self {
+        let cipher = self.version & AAD_CIPHER_MASK;
+
+        match self.format_version() {
+            AAD_VERSION_V2 => self,
+            AAD_VERSION_STREAM => Self { generation, ..self },
+            _ if generation == 0 => Self { version: AAD_VERSION_V1 | cipher, generation, ..self },
+            _ => Self { version: AAD_VERSION_V3 | cipher, generation, ..self },
+        }
+    }
+
+    /// Same AAD, different chunk index (hot-loop template reuse).
+    ///
+    /// Static fields were validated when the template was built.
+    #[inline(always)]
+    pub fn at_chunk(self, chunk: u32) -> Self {
+        Self { chunk, ..self }
+    }
+
+    /// Canonical wire encoding (see module WIRE FORMAT).
     #[inline(always)]
-    pub fn serialize(&self) -> [u8; 15] {
-        let mut out = [0u8; 15];
+    pub fn serialize(&self) -> SerializedAad {
+        let mut out = [0u8; AAD_MAX_LEN];
         out[..8].copy_from_slice(&self.file_id.to_be_bytes());
         out[8..12].copy_from_slice(&self.chunk.to_be_bytes());
         out[12..14].copy_from_slice(&self.cloud_id.to_be_bytes());
         out[14] = self.version;
-        out
+
+        let len = match self.format_version() {
+            AAD_VERSION_V2 => {
+                out[15..23].copy_from_slice(&self.epoch.to_be_bytes());
+                23
+            }
+            AAD_VERSION_V3 | AAD_VERSION_STREAM => {
+                out[15..19].copy_from_slice(&self.generation.to_be_bytes());
+                19
+            }
+            _ => 15,
+        };
+
+        SerializedAad { bytes: out, len }
+    }
+
+    /// Decode the canonical wire encoding, dispatching on ITS version
+    /// byte. Exact inverse of `serialize`.
+    ///
+    /// SECURITY:
+    /// - Unknown layout / wrong length => `None` (fail-closed)
+    /// - Non-canonical V3 (generation 0) => `None`
+    /// - Still UNAUTHENTICATED until the AEAD tag verifies
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        // Every layout shares the V1 prefix; the version byte is at 14
+        let version = *bytes.get(14)?;
+        let layout = AadVersion::from_u8(version)?;
+        if bytes.len() != layout.serialized_len() {
+            return None;
+        }
+
+        let file_id = u64::from_be_bytes(bytes[..8].try_into().ok()?);
+        let chunk = u32::from_be_bytes(bytes[8..12].try_into().ok()?);
+        let cloud_id = u16::from_be_bytes(bytes[12..14].try_into().ok()?);
+
+        let (epoch, generation) = match layout {
+            AadVersion::V1 => (0, 0),
+            AadVersion::V2 => (u64::from_be_bytes(bytes[15..23].try_into().ok()?), 0),
+            AadVersion::V3 => {
+                let generation = u32::from_be_bytes(bytes[15..19].try_into().ok()?);
+                if generation == 0 {
+                    return None;
+                }
+                (0, generation)
+            }
+            AadVersion::Stream => (0, u32::from_be_bytes(bytes[15..19].try_into().ok()?)),
+        };
+
+        Some(Self { file_id, chunk, cloud_id, version, epoch, generation })
     }
 
     #[inline(always)]
@@ -58,6 +276,110 @@ impl Aad {
     pub fn chunk(&self) -> u32 { self.chunk }
     #[inline(always)]
     pub fn cloud_id(&self) -> u16 { self.cloud_id }
+    /// Full version byte (format + cipher-suite bits).
     #[inline(always)]
     pub fn version(&self) -> u8 { self.version }
+    /// Layout version only (cipher-suite bits masked off).
+    #[inline(always)]
+    pub fn format_version(&self) -> u8 { self.version & !AAD_CIPHER_MASK }
+    #[inline(always)]
+    pub fn epoch(&self) -> u64 { self.epoch }
+    /// Ratchet generation (0 unless V3 / STREAM).
+    #[inline(always)]
+    pub fn generation(&self) -> u32 { self.generation }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_layout_parses_back() -> Result<(), ()> {
+        let v1 = Aad::new(9, 4, 2, AAD_VERSION_V1).ok_or(())?;
+
+        for aad in [
+            v1,
+            Aad::with_epoch(9, 4, 2, 77),
+            v1.with_generation(3),
+            Aad::stream(9, 4, 2).with_generation(3),
+        ] {
+            let bytes = aad.serialize();
+            let parsed = Aad::deserialize(&bytes).ok_or(())?;
+
+            assert!(parsed.serialize()[..] == bytes[..]);
+            assert_eq!(parsed.epoch(), aad.epoch());
+            assert_eq!(parsed.generation(), aad.generation());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn wire_format_matches_documented_vectors() -> Result<(), ()> {
+        let v1 = Aad::new(0x0102_0304_0506_0708, 0x0A0B_0C0D, 0x0E0F, AAD_VERSION_V1).ok_or(())?;
+
+        let expected_v1 = [
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // file_id
+            0x0A, 0x0B, 0x0C, 0x0D, // chunk
+            0x0E, 0x0F, // cloud_id
+            0x01, // version
+        ];
+        assert!(v1.serialize()[..] == expected_v1[..]);
+
+        let v2 = Aad::with_epoch(0x0102_0304_0506_0708, 0x0A0B_0C0D, 0x0E0F, 0x1122_3344_5566_7788);
+        let mut expected_v2 = expected_v1.to_vec();
+        expected_v2[14] = AAD_VERSION_V2;
+        expected_v2.extend_from_slice(&[0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88]);
+        assert!(v2.serialize()[..] == expected_v2[..]);
+
+        let v3 = v1.with_generation(0x0000_0102);
+        let mut expected_v3 = expected_v1.to_vec();
+        expected_v3[14] = AAD_VERSION_V3;
+        expected_v3.extend_from_slice(&[0x00, 0x00, 0x01, 0x02]);
+        assert!(v3.serialize()[..] == expected_v3[..]);
+
+        let back = Aad::deserialize(&expected_v1).ok_or(())?;
+        assert_eq!(
+            (back.file_id(), back.chunk(), back.cloud_id(), back.version()),
+            (0x0102_0304_0506_0708, 0x0A0B_0C0D, 0x0E0F, AAD_VERSION_V1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_or_malformed_layout_fails_closed() -> Result<(), ()> {
+        assert!(AadVersion::from_u8(0).is_none());
+        assert!(AadVersion::from_u8(5).is_none());
+        assert!(AadVersion::from_u8(AAD_VERSION_V1 | AAD_CIPHER_MASK) == Some(AadVersion::V1));
+
+        let v1 = Aad::new(9, 4, 2, AAD_VERSION_V1).ok_or(())?;
+        let mut bytes = [0u8; AAD_MAX_LEN];
+        bytes[..15].copy_from_slice(&v1.serialize());
+
+        // Wrong length for the claimed layout
+        assert!(Aad::deserialize(&bytes).is_none());
+        assert!(Aad::deserialize(&bytes[..14]).is_none());
+
+        // Unknown version byte
+        bytes[14] = 9;
+        assert!(Aad::deserialize(&bytes[..15]).is_none());
+
+        // V3 claiming generation 0
+        bytes[14] = AAD_VERSION_V3;
+        assert!(Aad::deserialize(&bytes[..19]).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn stream_aad_never_matches_one_shot_aad() -> Result<(), ()> {
+        let one_shot = Aad::new(9, 4, 2, AAD_VERSION_V1).ok_or(())?;
+        let stream = Aad::stream(9, 4, 2);
+
+        for generation in [0, 3] {
+            let a = one_shot.with_generation(generation);
+            let b = stream.with_generation(generation);
+            assert!(a.serialize()[..] != b.serialize()[..]);
+            assert_eq!(b.format_version(), AAD_VERSION_STREAM);
+        }
+        Ok(())
+    }
 }
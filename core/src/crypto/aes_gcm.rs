@@ -10,10 +10,11 @@
 //! - Output buffers wiped on ALL failures
 //! - Fail-closed
 //! - No panics
+//! - AAD MUST be non-empty (authentication binding)
 
 use crate::memory::GuardedKey32;
+use aes_gcm::aead::AeadInPlace;
 use aes_gcm::{Aes256Gcm, KeyInit, Nonce, Tag};
-use aead::{AeadInPlace, Payload};
 
 /// AES-GCM standard nonce length (96-bit).
 pub const NONCE_LEN: usize = 12;
@@ -21,6 +22,21 @@ pub const NONCE_LEN: usize = 12;
 /// AES-GCM authentication tag length.
 pub const TAG_LEN: usize = 16;
 
+/* ───────────── AAD BINDING ───────────── */
+
+/// Every AEAD call in Secure Core MUST bind context via AAD.
+///
+/// Empty AAD is refused in EVERY build profile (fail-closed error,
+/// never a panic).
+#[inline(always)]
+pub(crate) fn require_aad(aad: &[u8]) -> Result<(), ()> {
+    if aad.is_empty() {
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
 /* ───────────── ENCRYPT ───────────── */
 
 /// Encrypt + authenticate.
@@ -35,36 +51,96 @@ pub fn seal(
     out: &mut [u8],
 ) -> Result<(), ()> {
     let pt_len = plaintext.len();
-    let required = pt_len + TAG_LEN;
 
-    if out.len() != required {
+    if out.len() != pt_len + TAG_LEN {
         out.fill(0);
         return Err(());
     }
 
-    let cipher = Aes256Gcm::new_from_slice(key.borrow())
-        .map_err(|_| {
-            out.fill(0);
-            ()
-        })?;
-
     out[..pt_len].copy_from_slice(plaintext);
+    seal_in_place(key, nonce, aad, out)
+}
+
+/// Encrypt + authenticate without a separate plaintext buffer.
+///
+/// Buffer layout:
+/// `[ plaintext | TAG_LEN spare ]` in, `[ ciphertext | tag ]` out
+pub fn seal_in_place(
+    key: &GuardedKey32,
+    nonce: &[u8; NONCE_LEN],
+    aad: &[u8],
+    buf: &mut [u8],
+) -> Result<(), ()> {
+    let Some(pt_len) = buf.len().checked_sub(TAG_LEN) else {
+        buf.fill(0);
+        return Err(());
+    };
+
+    let (ct, tag) = buf.split_at_mut(pt_len);
+    let tag: &mut [u8; TAG_LEN] = tag.try_into().map_err(|_| ())?;
+
+    if require_aad(aad).is_err() {
+        ct.fill(0);
+        tag.fill(0);
+        return Err(());
+    }
+
+    let Ok(cipher) = Aes256Gcm::new_from_slice(key.borrow()) else {
+        ct.fill(0);
+        tag.fill(0);
+        return Err(());
+    };
+
+    match cipher.encrypt_in_place_detached(Nonce::from_slice(nonce), aad, ct) {
+        Ok(t) => {
+            tag.copy_from_slice(t.as_slice());
+            Ok(())
+        }
+        Err(_) => {
+            ct.fill(0);
+            tag.fill(0);
+            Err(())
+        }
+    }
+}
+
+/// Encrypt + authenticate with the tag kept separately.
+///
+/// Output layout:
+/// `out_ct = ciphertext` (same length as plaintext), `out_tag = tag`
+pub fn seal_detached(
+    key: &GuardedKey32,
+    nonce: &[u8; NONCE_LEN],
+    plaintext: &[u8],
+    aad: &[u8],
+    out_ct: &mut [u8],
+    out_tag: &mut [u8; TAG_LEN],
+) -> Result<(), ()> {
+    if out_ct.len() != plaintext.len() || require_aad(aad).is_err() {
+        out_ct.fill(0);
+        out_tag.fill(0);
+        return Err(());
+    }
 
-    let tag = cipher
-        .encrypt_in_place_detached(
-            Nonce::from_slice(nonce),
-            Payload {
-                msg: &mut out[..pt_len],
-                aad,
-            },
-        )
-        .map_err(|_| {
-            out[..pt_len].fill(0);
-            ()
-        })?;
-
-    out[pt_len..].copy_from_slice(tag.as_slice());
-    Ok(())
+    let Ok(cipher) = Aes256Gcm::new_from_slice(key.borrow()) else {
+        out_ct.fill(0);
+        out_tag.fill(0);
+        return Err(());
+    };
+
+    out_ct.copy_from_slice(plaintext);
+
+    match cipher.encrypt_in_place_detached(Nonce::from_slice(nonce), aad, out_ct) {
+        Ok(tag) => {
+            out_tag.copy_from_slice(tag.as_slice());
+            Ok(())
+        }
+        Err(_) => {
+            out_ct.fill(0);
+            out_tag.fill(0);
+            Err(())
+        }
+    }
 }
 
 /* ───────────── DECRYPT ───────────── */
@@ -85,9 +161,29 @@ pub fn open(
         return false;
     }
 
-    let ct_len = input.len() - TAG_LEN;
+    let (ct, tag) = input.split_at(input.len() - TAG_LEN);
 
-    if out.len() != ct_len {
+    let Ok(tag) = <&[u8; TAG_LEN]>::try_from(tag) else {
+        out.fill(0);
+        return false;
+    };
+
+    open_detached(key, nonce, ct, tag, aad, out)
+}
+
+/// Authenticate + decrypt with a separately stored tag.
+///
+/// SECURITY:
+/// - `out` is wiped on authentication failure
+pub fn open_detached(
+    key: &GuardedKey32,
+    nonce: &[u8; NONCE_LEN],
+    ciphertext: &[u8],
+    tag: &[u8; TAG_LEN],
+    aad: &[u8],
+    out: &mut [u8],
+) -> bool {
+    if out.len() != ciphertext.len() || require_aad(aad).is_err() {
         out.fill(0);
         return false;
     }
@@ -100,17 +196,13 @@ pub fn open(
         }
     };
 
-    let tag = Tag::from_slice(&input[ct_len..]);
-
-    out.copy_from_slice(&input[..ct_len]);
+    out.copy_from_slice(ciphertext);
 
     let res = cipher.decrypt_in_place_detached(
         Nonce::from_slice(nonce),
-        Payload {
-            msg: out,
-            aad,
-        },
-        tag,
+        aad,
+        out,
+        Tag::from_slice(tag),
     );
 
     if res.is_err() {
@@ -119,4 +211,138 @@ pub fn open(
     }
 
     true
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_aad_is_rejected() {
+        let key = GuardedKey32::init_with(|k| k.fill(0x11));
+        let nonce = [0u8; NONCE_LEN];
+        let mut out = [0xAAu8; 4 + TAG_LEN];
+
+        assert!(seal(&key, &nonce, b"data", &[], &mut out).is_err());
+        assert!(out.iter().all(|b| *b == 0));
+
+        // A valid ciphertext still refuses to open without its AAD
+        let mut ct = [0u8; 4 + TAG_LEN];
+        let mut pt = [0xAAu8; 4];
+        assert!(seal(&key, &nonce, b"data", b"ctx", &mut ct).is_ok());
+        assert!(!open(&key, &nonce, &ct, &[], &mut pt));
+        assert!(pt.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn non_empty_aad_round_trips() {
+        let key = GuardedKey32::init_with(|k| k.fill(0x11));
+        let nonce = [7u8; NONCE_LEN];
+        let mut ct = [0u8; 4 + TAG_LEN];
+        let mut pt = [0u8; 4];
+
+        assert!(seal(&key, &nonce, b"data", b"ctx", &mut ct).is_ok());
+        assert!(open(&key, &nonce, &ct, b"ctx", &mut pt));
+        assert_eq!(&pt, b"data");
+    }
+
+    #[test]
+    fn in_place_seal_matches_seal() {
+        let key = GuardedKey32::init_with(|k| k.fill(0x11));
+        let nonce = [7u8; NONCE_LEN];
+
+        let mut ct = [0u8; 4 + TAG_LEN];
+        assert!(seal(&key, &nonce, b"data", b"ctx", &mut ct).is_ok());
+
+        let mut buf = [0u8; 4 + TAG_LEN];
+        buf[..4].copy_from_slice(b"data");
+        assert!(seal_in_place(&key, &nonce, b"ctx", &mut buf).is_ok());
+        assert_eq!(buf, ct);
+
+        // No room for the tag => refused and wiped
+        let mut short = [0xAAu8; TAG_LEN - 1];
+        assert!(seal_in_place(&key, &nonce, b"ctx", &mut short).is_err());
+        assert!(short.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn detached_round_trip() {
+        let key = GuardedKey32::init_with(|k| k.fill(0x22));
+        let nonce = [9u8; NONCE_LEN];
+        let mut ct = [0u8; 5];
+        let mut tag = [0u8; TAG_LEN];
+        let mut pt = [0u8; 5];
+
+        assert!(seal_detached(&key, &nonce, b"block", b"ctx", &mut ct, &mut tag).is_ok());
+        assert!(open_detached(&key, &nonce, &ct, &tag, b"ctx", &mut pt));
+        assert_eq!(&pt, b"block");
+
+        // Same bytes as the combined layout
+        let mut combined = [0u8; 5 + TAG_LEN];
+        assert!(seal(&key, &nonce, b"block", b"ctx", &mut combined).is_ok());
+        assert_eq!(&combined[..5], &ct);
+        assert_eq!(&combined[5..], &tag);
+    }
+
+    #[test]
+    fn flipped_detached_tag_fails_closed() {
+        let key = GuardedKey32::init_with(|k| k.fill(0x22));
+        let nonce = [9u8; NONCE_LEN];
+        let mut ct = [0u8; 5];
+        let mut tag = [0u8; TAG_LEN];
+
+        assert!(seal_detached(&key, &nonce, b"block", b"ctx", &mut ct, &mut tag).is_ok());
+        tag[0] ^= 0x01;
+
+        let mut pt = [0xAAu8; 5];
+        assert!(!open_detached(&key, &nonce, &ct, &tag, b"ctx", &mut pt));
+        assert!(pt.iter().all(|b| *b == 0));
+    }
+
+    /// NIST GCM spec test case 16 (AES-256, 96-bit IV, with AAD).
+    #[test]
+    fn nist_gcm_known_answer() {
+        const KEY: [u8; 32] = [
+            0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, 0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30,
+            0x83, 0x08, 0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, 0x6d, 0x6a, 0x8f, 0x94,
+            0x67, 0x30, 0x83, 0x08,
+        ];
+        const IV: [u8; NONCE_LEN] = [
+            0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad, 0xde, 0xca, 0xf8, 0x88,
+        ];
+        const AAD: [u8; 20] = [
+            0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad, 0xbe, 0xef, 0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad,
+            0xbe, 0xef, 0xab, 0xad, 0xda, 0xd2,
+        ];
+        const PT: [u8; 60] = [
+            0xd9, 0x31, 0x32, 0x25, 0xf8, 0x84, 0x06, 0xe5, 0xa5, 0x59, 0x09, 0xc5, 0xaf, 0xf5,
+            0x26, 0x9a, 0x86, 0xa7, 0xa9, 0x53, 0x15, 0x34, 0xf7, 0xda, 0x2e, 0x4c, 0x30, 0x3d,
+            0x8a, 0x31, 0x8a, 0x72, 0x1c, 0x3c, 0x0c, 0x95, 0x95, 0x68, 0x09, 0x53, 0x2f, 0xcf,
+            0x0e, 0x24, 0x49, 0xa6, 0xb5, 0x25, 0xb1, 0x6a, 0xed, 0xf5, 0xaa, 0x0d, 0xe6, 0x57,
+            0xba, 0x63, 0x7b, 0x39,
+        ];
+        const CT: [u8; 60] = [
+            0x52, 0x2d, 0xc1, 0xf0, 0x99, 0x56, 0x7d, 0x07, 0xf4, 0x7f, 0x37, 0xa3, 0x2a, 0x84,
+            0x42, 0x7d, 0x64, 0x3a, 0x8c, 0xdc, 0xbf, 0xe5, 0xc0, 0xc9, 0x75, 0x98, 0xa2, 0xbd,
+            0x25, 0x55, 0xd1, 0xaa, 0x8c, 0xb0, 0x8e, 0x48, 0x59, 0x0d, 0xbb, 0x3d, 0xa7, 0xb0,
+            0x8b, 0x10, 0x56, 0x82, 0x88, 0x38, 0xc5, 0xf6, 0x1e, 0x63, 0x93, 0xba, 0x7a, 0x0a,
+            0xbc, 0xc9, 0xf6, 0x62,
+        ];
+        const TAG: [u8; TAG_LEN] = [
+            0x76, 0xfc, 0x6e, 0xce, 0x0f, 0x4e, 0x17, 0x68, 0xcd, 0xdf, 0x88, 0x53, 0xbb, 0x2d,
+            0x55, 0x1b,
+        ];
+
+        let key = GuardedKey32::init_with(|k| k.copy_from_slice(&KEY));
+        let mut ct = [0u8; 60];
+        let mut tag = [0u8; TAG_LEN];
+
+        assert!(seal_detached(&key, &IV, &PT, &AAD, &mut ct, &mut tag).is_ok());
+        assert_eq!(ct, CT);
+        assert_eq!(tag, TAG);
+
+        let mut pt = [0u8; 60];
+        assert!(open_detached(&key, &IV, &CT, &TAG, &AAD, &mut pt));
+        assert_eq!(pt, PT);
+    }
+}
@@ -0,0 +1,62 @@
+//! Kill-state attestation MAC (Secure Core).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! PURPOSE:
+//! Let a remote verifier confirm that a reported kill / alive
+//! state is authentic and fresh (challenge nonce).
+//!
+//! FORMAL INVARIANTS (ENFORCED):
+//! - HMAC-SHA256 only
+//! - Key is purpose-bound (`Purpose::Recovery`, fixed context)
+//! - Fixed, unambiguous message encoding
+//! - Output is NON-SECRET (MAC tag)
+//! - No panics
+
+use crate::memory::GuardedKey32;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Attestation key derivation context (`derive_key`).
+///
+/// ⚠️ MUST NEVER CHANGE.
+pub const ATTESTATION_CONTEXT: u64 = 0x4154_5445_5354_0001; // "ATTEST" v1
+
+/// Domain separation label (ATTESTATION ONLY).
+///
+/// ⚠️ MUST NEVER CHANGE.
+const ATTEST_LABEL: &[u8] = b"rcxcloud:attest:state:v1";
+
+/// Maximum accepted challenge nonce length.
+pub const MAX_ATTEST_NONCE_LEN: usize = 64;
+
+/// Compute the state attestation tag.
+///
+/// Message encoding:
+/// `label || killed (1) || fingerprint_be (8) || nonce_len_be (2) || nonce`
+///
+/// SECURITY:
+/// - Empty or oversized nonce => Err (no freshness otherwise)
+pub fn attest_state(
+    key: &GuardedKey32,
+    killed: bool,
+    fingerprint: u64,
+    nonce: &[u8],
+) -> Result<[u8; 32], ()> {
+    if nonce.is_empty() || nonce.len() > MAX_ATTEST_NONCE_LEN {
+        return Err(());
+    }
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.borrow())
+        .map_err(|_| ())?;
+
+    mac.update(ATTEST_LABEL);
+    mac.update(&[killed as u8]);
+    mac.update(&fingerprint.to_be_bytes());
+    mac.update(&(nonce.len() as u16).to_be_bytes());
+    mac.update(nonce);
+
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&mac.finalize().into_bytes());
+    Ok(out)
+}
@@ -0,0 +1,124 @@
+//! ChaCha20-Poly1305 — AEAD ONLY.
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! Constant-time software AEAD for targets without AES hardware.
+//!
+//! ENFORCED INVARIANTS:
+//! - Same contract as `aes_gcm` (layout, sizes, failure behavior)
+//! - Nonce is caller-provided (96-bit, derived, never random here)
+//! - Verify-then-decrypt
+//! - No plaintext written on authentication failure
+//! - Output buffers wiped on ALL failures
+//! - AAD MUST be non-empty (authentication binding)
+
+use crate::crypto::aes_gcm::{require_aad, NONCE_LEN, TAG_LEN};
+use crate::memory::GuardedKey32;
+use chacha20poly1305::aead::AeadInPlace;
+use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce, Tag};
+
+/* ───────────── ENCRYPT ───────────── */
+
+/// Encrypt + authenticate.
+///
+/// Output layout:
+/// `[ ciphertext | tag ]`
+pub fn seal(
+    key: &GuardedKey32,
+    nonce: &[u8; NONCE_LEN],
+    plaintext: &[u8],
+    aad: &[u8],
+    out: &mut [u8],
+) -> Result<(), ()> {
+    let pt_len = plaintext.len();
+
+    if out.len() != pt_len + TAG_LEN {
+        out.fill(0);
+        return Err(());
+    }
+
+    out[..pt_len].copy_from_slice(plaintext);
+    seal_in_place(key, nonce, aad, out)
+}
+
+/// Encrypt + authenticate without a separate plaintext buffer.
+///
+/// Buffer layout:
+/// `[ plaintext | TAG_LEN spare ]` in, `[ ciphertext | tag ]` out
+pub fn seal_in_place(
+    key: &GuardedKey32,
+    nonce: &[u8; NONCE_LEN],
+    aad: &[u8],
+    buf: &mut [u8],
+) -> Result<(), ()> {
+    let Some(pt_len) = buf.len().checked_sub(TAG_LEN) else {
+        buf.fill(0);
+        return Err(());
+    };
+
+    if require_aad(aad).is_err() {
+        buf.fill(0);
+        return Err(());
+    }
+
+    let cipher = ChaCha20Poly1305::new_from_slice(key.borrow()).map_err(|_| {
+        buf.fill(0);
+    })?;
+
+    let tag = cipher
+        .encrypt_in_place_detached(Nonce::from_slice(nonce), aad, &mut buf[..pt_len])
+        .map_err(|_| {
+            buf.fill(0);
+        })?;
+
+    buf[pt_len..].copy_from_slice(tag.as_slice());
+    Ok(())
+}
+
+/* ───────────── DECRYPT ───────────── */
+
+/// Authenticate + decrypt.
+///
+/// INPUT layout:
+/// `[ ciphertext | tag ]`
+pub fn open(
+    key: &GuardedKey32,
+    nonce: &[u8; NONCE_LEN],
+    input: &[u8],
+    aad: &[u8],
+    out: &mut [u8],
+) -> bool {
+    if input.len() < TAG_LEN {
+        out.fill(0);
+        return false;
+    }
+
+    let ct_len = input.len() - TAG_LEN;
+
+    if out.len() != ct_len || require_aad(aad).is_err() {
+        out.fill(0);
+        return false;
+    }
+
+    let cipher = match ChaCha20Poly1305::new_from_slice(key.borrow()) {
+        Ok(c) => c,
+        Err(_) => {
+            out.fill(0);
+            return false;
+        }
+    };
+
+    let tag = Tag::from_slice(&input[ct_len..]);
+
+    out.copy_from_slice(&input[..ct_len]);
+
+    if cipher
+        .decrypt_in_place_detached(Nonce::from_slice(nonce), aad, out, tag)
+        .is_err()
+    {
+        out.fill(0);
+        return false;
+    }
+
+    true
+}
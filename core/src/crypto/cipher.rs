@@ -0,0 +1,430 @@
+//! AEAD backend selection (Secure Core).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! FORMAL INVARIANTS (ENFORCED):
+//! - AES-256-GCM is the default suite
+//! - The suite is recorded in the AAD version byte
+//!   (`AAD_CIPHER_MASK` bits) and therefore authenticated
+//! - Decryption selects the backend from the AAD, never from
+//!   caller preference
+//! - Each suite derives its OWN file key (no cross-algorithm
+//!   key / nonce reuse)
+//! - ChaCha20-Poly1305 is gated behind the `chacha` feature
+//! - Neither AEAD is key-committing: chunks carry a separate
+//!   key commitment (`commitment`), checked BEFORE the tag
+
+use crate::crypto::aes_gcm::{self, NONCE_LEN};
+use crate::crypto::derive::Purpose;
+use crate::memory::{ct_eq, GuardedKey32};
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+#[cfg(feature = "chacha")]
+use crate::crypto::chacha;
+
+/// AAD version bits reserved for the cipher suite.
+pub const AAD_CIPHER_MASK: u8 = 0x80;
+
+/// AAD version flag: ChaCha20-Poly1305.
+pub const AAD_CIPHER_CHACHA: u8 = 0x80;
+
+/// Key-commitment length (stored next to the chunk, not inside it).
+pub const COMMIT_LEN: usize = 32;
+
+/// HKDF info label of the key commitment (MUST NEVER CHANGE).
+const COMMIT_LABEL: &[u8] = b"rcxcloud:aead:commit:v1";
+
+/* ───────────── KEY COMMITMENT ───────────── */
+
+/// Commitment to `(key, nonce)`:
+/// `HKDF-SHA256(ikm = key, info = COMMIT_LABEL || nonce)`.
+///
+/// GCM / Poly1305 alone are not key-committing: one ciphertext can
+/// be crafted to authenticate under two keys (partitioning /
+/// cross-file confusion). The commitment pins the one key.
+pub fn commitment(key: &GuardedKey32, nonce: &[u8; NONCE_LEN]) -> Result<[u8; COMMIT_LEN], ()> {
+    let mut info = [0u8; COMMIT_LABEL.len() + NONCE_LEN];
+    info[..COMMIT_LABEL.len()].copy_from_slice(COMMIT_LABEL);
+    info[COMMIT_LABEL.len()..].copy_from_slice(nonce);
+
+    let mut out = [0u8; COMMIT_LEN];
+    Hkdf::<Sha256>::new(None, key.borrow())
+        .expand(&info, &mut out)
+        .map_err(|_| ())?;
+    Ok(out)
+}
+
+/// Whether `stored` commits to `(key, nonce)`.
+///
+/// SECURITY:
+/// - Constant-time comparison
+/// - Derivation failure => false (fail-closed)
+pub fn commitment_matches(
+    key: &GuardedKey32,
+    nonce: &[u8; NONCE_LEN],
+    stored: &[u8; COMMIT_LEN],
+) -> bool {
+    match commitment(key, nonce) {
+        Ok(expected) => ct_eq(&expected, stored),
+        Err(()) => false,
+    }
+}
+
+/* ───────────── BACKEND TRAIT ───────────── */
+
+/// Stateless AEAD backend.
+///
+/// Contract (every backend):
+/// - `out` layout `[ ciphertext | tag ]`, exact size
+/// - `seal_in_place` buffer: `[ plaintext | TAG_LEN spare ]` in,
+///   `[ ciphertext | tag ]` out
+/// - Output wiped on ALL failures
+/// - Empty AAD refused
+pub trait Aead {
+    fn seal(
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        plaintext: &[u8],
+        aad: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), ()>;
+
+    fn seal_in_place(
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<(), ()>;
+
+    fn open(
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        input: &[u8],
+        aad: &[u8],
+        out: &mut [u8],
+    ) -> bool;
+}
+
+/// AES-256-GCM backend (default).
+pub struct Aes256Gcm;
+
+impl Aead for Aes256Gcm {
+    #[inline(always)]
+    fn seal(
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        plaintext: &[u8],
+        aad: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), ()> {
+        aes_gcm::seal(key, nonce, plaintext, aad, out)
+    }
+
+    #[inline(always)]
+    fn seal_in_place(
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<(), ()> {
+        aes_gcm::seal_in_place(key, nonce, aad, buf)
+    }
+
+    #[inline(always)]
+    fn open(
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        input: &[u8],
+        aad: &[u8],
+        out: &mut [u8],
+    ) -> bool {
+        aes_gcm::open(key, nonce, input, aad, out)
+    }
+}
+
+/// ChaCha20-Poly1305 backend (no AES hardware required).
+#[cfg(feature = "chacha")]
+pub struct ChaCha20Poly1305;
+
+#[cfg(feature = "chacha")]
+impl Aead for ChaCha20Poly1305 {
+    #[inline(always)]
+    fn seal(
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        plaintext: &[u8],
+        aad: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), ()> {
+        chacha::seal(key, nonce, plaintext, aad, out)
+    }
+
+    #[inline(always)]
+    fn seal_in_place(
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<(), ()> {
+        chacha::seal_in_place(key, nonce, aad, buf)
+    }
+
+    #[inline(always)]
+    fn open(
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        input: &[u8],
+        aad: &[u8],
+        out: &mut [u8],
+    ) -> bool {
+        chacha::open(key, nonce, input, aad, out)
+    }
+}
+
+/* ───────────── SUITE ───────────── */
+
+/// Cipher suite chosen at `Session` construction.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum CipherSuite {
+    #[default]
+    Aes256Gcm,
+    #[cfg(feature = "chacha")]
+    ChaCha20Poly1305,
+}
+
+impl CipherSuite {
+    /// Every suite compiled into this build (test / audit coverage).
+    pub const ALL: &'static [CipherSuite] = &[
+        CipherSuite::Aes256Gcm,
+        #[cfg(feature = "chacha")]
+        CipherSuite::ChaCha20Poly1305,
+    ];
+
+    /// AAD version bits recording this suite.
+    #[inline(always)]
+    pub const fn aad_flag(self) -> u8 {
+        match self {
+            CipherSuite::Aes256Gcm => 0,
+            #[cfg(feature = "chacha")]
+            CipherSuite::ChaCha20Poly1305 => AAD_CIPHER_CHACHA,
+        }
+    }
+
+    /// Suite recorded in an AAD version byte.
+    ///
+    /// `None` if the suite is unknown or not compiled in.
+    #[inline(always)]
+    pub fn from_aad_version(version: u8) -> Option<Self> {
+        match version & AAD_CIPHER_MASK {
+            0 => Some(CipherSuite::Aes256Gcm),
+            #[cfg(feature = "chacha")]
+            AAD_CIPHER_CHACHA => Some(CipherSuite::ChaCha20Poly1305),
+            _ => None,
+        }
+    }
+
+    /// Stable host-facing code (FFI): 0 = AES-256-GCM,
+    /// 1 = ChaCha20-Poly1305.
+    #[inline(always)]
+    pub const fn code(self) -> u8 {
+        match self {
+            CipherSuite::Aes256Gcm => 0,
+            #[cfg(feature = "chacha")]
+            CipherSuite::ChaCha20Poly1305 => 1,
+        }
+    }
+
+    /// Suite for a host-facing code.
+    ///
+    /// `None` if the code is unknown or the suite not compiled in.
+    #[inline(always)]
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.iter().copied().find(|s| s.code() == code)
+    }
+
+    /// File-key derivation purpose (suite-separated).
+    #[inline(always)]
+    pub(crate) fn file_key_purpose(self) -> Purpose {
+        match self {
+            CipherSuite::Aes256Gcm => Purpose::FileEncryption,
+            #[cfg(feature = "chacha")]
+            CipherSuite::ChaCha20Poly1305 => Purpose::FileEncryptionChaCha,
+        }
+    }
+
+    pub fn seal(
+        self,
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        plaintext: &[u8],
+        aad: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), ()> {
+        match self {
+            CipherSuite::Aes256Gcm => Aes256Gcm::seal(key, nonce, plaintext, aad, out),
+            #[cfg(feature = "chacha")]
+            CipherSuite::ChaCha20Poly1305 => {
+                ChaCha20Poly1305::seal(key, nonce, plaintext, aad, out)
+            }
+        }
+    }
+
+    pub fn seal_in_place(
+        self,
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<(), ()> {
+        match self {
+            CipherSuite::Aes256Gcm => Aes256Gcm::seal_in_place(key, nonce, aad, buf),
+            #[cfg(feature = "chacha")]
+            CipherSuite::ChaCha20Poly1305 => {
+                ChaCha20Poly1305::seal_in_place(key, nonce, aad, buf)
+            }
+        }
+    }
+
+    pub fn open(
+        self,
+        key: &GuardedKey32,
+        nonce: &[u8; NONCE_LEN],
+        input: &[u8],
+        aad: &[u8],
+        out: &mut [u8],
+    ) -> bool {
+        match self {
+            CipherSuite::Aes256Gcm => Aes256Gcm::open(key, nonce, input, aad, out),
+            #[cfg(feature = "chacha")]
+            CipherSuite::ChaCha20Poly1305 => {
+                ChaCha20Poly1305::open(key, nonce, input, aad, out)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::aes_gcm::TAG_LEN;
+
+    fn round_trip<A: Aead>() {
+        let key = GuardedKey32::init_with(|k| k.fill(0x24));
+        let nonce = [3u8; NONCE_LEN];
+        let pt = b"backend round trip";
+
+        let mut ct = [0u8; 18 + TAG_LEN];
+        assert!(A::seal(&key, &nonce, pt, b"aad", &mut ct).is_ok());
+
+        let mut out = [0u8; 18];
+        assert!(A::open(&key, &nonce, &ct, b"aad", &mut out));
+        assert_eq!(&out, pt);
+
+        // Wrong AAD => rejected, output wiped
+        assert!(!A::open(&key, &nonce, &ct, b"aae", &mut out));
+        assert!(out.iter().all(|b| *b == 0));
+    }
+
+    /// Correctness backbone: every compiled suite × size matrix.
+    ///
+    /// New suites are covered automatically via `CipherSuite::ALL`.
+    #[test]
+    fn all_suites_round_trip_size_matrix() {
+        use crate::crypto::file::MAX_CHUNK_SIZE;
+
+        let key = GuardedKey32::init_with(|k| k.fill(0x5C));
+        let nonce = [9u8; NONCE_LEN];
+        let aad = b"matrix";
+
+        for suite in CipherSuite::ALL {
+            for len in [0usize, 1, TAG_LEN, MAX_CHUNK_SIZE] {
+                let pt: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+
+                let mut ct = vec![0u8; len + TAG_LEN];
+                assert!(suite.seal(&key, &nonce, &pt, aad, &mut ct).is_ok());
+
+                let mut out = vec![0u8; len];
+                assert!(suite.open(&key, &nonce, &ct, aad, &mut out));
+                assert!(out == pt);
+
+                // Tamper (ciphertext, or tag for empty plaintext)
+                ct[0] ^= 0x01;
+                assert!(!suite.open(&key, &nonce, &ct, aad, &mut out));
+                assert!(out.iter().all(|b| *b == 0));
+            }
+        }
+    }
+
+    #[test]
+    fn aes_gcm_backend_round_trips() {
+        round_trip::<Aes256Gcm>();
+    }
+
+    #[cfg(feature = "chacha")]
+    #[test]
+    fn chacha_backend_round_trips() {
+        round_trip::<ChaCha20Poly1305>();
+    }
+
+    #[cfg(feature = "chacha")]
+    #[test]
+    fn chacha_session_chunk_decrypts_from_aad_suite() -> Result<(), ()> {
+        use crate::crypto::aad::{Aad, AAD_VERSION_V1};
+        use crate::keystore::session::{Session, VerifyResult};
+
+        crate::logging::encrypted::init_test_log_root();
+        let key = || GuardedKey32::init_with(|k| k.fill(0x42));
+        let mut chacha = Session::with_suite(key(), CipherSuite::ChaCha20Poly1305);
+        let mut aes = Session::new(key());
+
+        let pt = b"chacha chunk";
+        let aad = Aad::new(1, 0, 2, AAD_VERSION_V1).ok_or(())?;
+
+        let mut ct = [0u8; 12 + TAG_LEN];
+        let res = chacha.encrypt(pt, aad, &mut ct);
+        assert!(matches!(res, Ok(r) if r.aad_version == AAD_VERSION_V1 | AAD_CIPHER_CHACHA));
+
+        // Stored version selects ChaCha even on an AES-default session
+        let stored = Aad::new(1, 0, 2, AAD_VERSION_V1 | AAD_CIPHER_CHACHA).ok_or(())?;
+
+        let mut out = [0u8; 12];
+        assert!(aes.decrypt_verify(&ct, stored, &mut out) == Ok(VerifyResult(true)));
+        assert_eq!(&out, pt);
+
+        // Claiming AES for a ChaCha chunk fails authentication
+        assert!(aes.decrypt_verify(&ct, aad, &mut out) == Ok(VerifyResult(false)));
+        Ok(())
+    }
+
+    #[test]
+    fn commitment_binds_key_and_nonce() -> Result<(), ()> {
+        let key = GuardedKey32::init_with(|k| k.fill(0x33));
+        let other = GuardedKey32::init_with(|k| k.fill(0x34));
+        let nonce = [4u8; NONCE_LEN];
+
+        let commit = commitment(&key, &nonce)?;
+        assert!(commitment_matches(&key, &nonce, &commit));
+        assert!(!commitment_matches(&other, &nonce, &commit));
+        assert!(!commitment_matches(&key, &[5u8; NONCE_LEN], &commit));
+
+        let mut forged = commit;
+        forged[0] ^= 0x01;
+        assert!(!commitment_matches(&key, &nonce, &forged));
+        Ok(())
+    }
+
+    #[test]
+    fn suite_is_recovered_from_aad_version() {
+        assert!(CipherSuite::from_aad_version(1) == Some(CipherSuite::Aes256Gcm));
+
+        #[cfg(feature = "chacha")]
+        assert!(
+            CipherSuite::from_aad_version(1 | AAD_CIPHER_CHACHA)
+                == Some(CipherSuite::ChaCha20Poly1305)
+        );
+
+        #[cfg(not(feature = "chacha"))]
+        assert!(CipherSuite::from_aad_version(1 | AAD_CIPHER_CHACHA).is_none());
+    }
+}
@@ -25,6 +25,10 @@ pub enum Purpose {
     /// File chunk encryption keys
     FileEncryption,
 
+    /// File chunk encryption keys (ChaCha20-Poly1305 suite)
+    #[cfg(feature = "chacha")]
+    FileEncryptionChaCha,
+
     /// File metadata protection
     Metadata,
 
@@ -44,6 +48,8 @@ impl Purpose {
     fn label(self) -> &'static [u8] {
         match self {
             Purpose::FileEncryption => b"rcx:file:enc:v1",
+            #[cfg(feature = "chacha")]
+            Purpose::FileEncryptionChaCha => b"rcx:file:enc:chacha:v1",
             Purpose::Metadata       => b"rcx:meta:v1",
             Purpose::Pairing        => b"rcx:pair:v1",
             Purpose::Recovery       => b"rcx:recovery:v1",
@@ -51,6 +57,11 @@ impl Purpose {
     }
 }
 
+/// Maximum application-supplied domain length (bytes).
+///
+/// Keeps the HKDF `info` buffer fixed-size and stack-only.
+pub const MAX_DOMAIN_LEN: usize = 16;
+
 /// Deterministically derive a child key into an existing buffer.
 ///
 /// SECURITY:
@@ -69,27 +80,57 @@ pub fn derive_key(
     purpose: Purpose,
     context: u64,
     out: &mut GuardedKey32,
+) -> Result<(), ()> {
+    derive_key_with_domain(parent, purpose, &[], context, out)
+}
+
+/// `derive_key` separated by an application domain (e.g. tenant).
+///
+/// An empty `domain` is byte-for-byte identical to `derive_key`.
+///
+/// SECURITY:
+/// - `domain.len() > MAX_DOMAIN_LEN` => `Err(())`
+/// - Domain is length-prefixed (unambiguous)
+#[inline(always)]
+pub fn derive_key_with_domain(
+    parent: &GuardedKey32,
+    purpose: Purpose,
+    domain: &[u8],
+    context: u64,
+    out: &mut GuardedKey32,
 ) -> Result<(), ()> {
     let label = purpose.label();
 
     // Hard safety limit: labels must stay short & fixed
-    if label.len() > 32 {
+    if label.len() > 32 || domain.len() > MAX_DOMAIN_LEN {
         return Err(());
     }
 
     // ───────────── INFO ENCODING ─────────────
     //
-    // info = label || context_be
+    // info = label || context_be                          (no domain)
+    // info = label || len(domain) || domain || context_be (domain)
     //
     // Properties:
-    // - fixed-width
+    // - bounded width
     // - unambiguous
     // - deterministic
     // - forward-auditable
-    let mut info = [0u8; 32 + 8];
+    let mut info = [0u8; 32 + 1 + MAX_DOMAIN_LEN + 8];
+    let mut len = 0usize;
+
     info[..label.len()].copy_from_slice(label);
-    info[label.len()..label.len() + 8]
-        .copy_from_slice(&context.to_be_bytes());
+    len += label.len();
+
+    if !domain.is_empty() {
+        info[len] = domain.len() as u8;
+        len += 1;
+        info[len..len + domain.len()].copy_from_slice(domain);
+        len += domain.len();
+    }
+
+    info[len..len + 8].copy_from_slice(&context.to_be_bytes());
+    len += 8;
 
     // HKDF extract+expand
     // - No salt (parent key already high entropy)
@@ -101,8 +142,40 @@ pub fn derive_key(
 // NOTE: salt intentionally None; parent key is high-entropy IKM
 
     hkdf.expand(
-        &info[..label.len() + 8],
+        &info[..len],
         out.borrow_mut(),
     )
     .map_err(|_| ())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent() -> GuardedKey32 {
+        GuardedKey32::init_with(|k| k.fill(0x17))
+    }
+
+    #[test]
+    fn empty_domain_matches_derive_key() {
+        let mut a = GuardedKey32::zeroed();
+        let mut b = GuardedKey32::zeroed();
+
+        assert!(derive_key(&parent(), Purpose::FileEncryption, 9, &mut a).is_ok());
+        assert!(derive_key_with_domain(&parent(), Purpose::FileEncryption, &[], 9, &mut b).is_ok());
+        assert_eq!(a.borrow(), b.borrow());
+    }
+
+    #[test]
+    fn domains_separate_keys_and_are_capped() {
+        let mut a = GuardedKey32::zeroed();
+        let mut b = GuardedKey32::zeroed();
+
+        assert!(derive_key_with_domain(&parent(), Purpose::FileEncryption, b"tenant-a", 9, &mut a).is_ok());
+        assert!(derive_key_with_domain(&parent(), Purpose::FileEncryption, b"tenant-b", 9, &mut b).is_ok());
+        assert_ne!(a.borrow(), b.borrow());
+
+        let long = [0x61u8; MAX_DOMAIN_LEN + 1];
+        assert!(derive_key_with_domain(&parent(), Purpose::FileEncryption, &long, 9, &mut a).is_err());
+    }
+}
@@ -13,8 +13,9 @@
 
 #![deny(clippy::derive_debug)]
 
-use crate::crypto::aad::{Aad, AAD_VERSION_V1};
+use crate::crypto::aad::{Aad, AadVersion, AAD_VERSION_STREAM, AAD_VERSION_V1, AAD_VERSION_V2};
 use crate::crypto::aes_gcm::TAG_LEN;
+use crate::crypto::cipher::{AAD_CIPHER_MASK, COMMIT_LEN};
 use crate::keystore::session::{EncryptResult, Session, SessionError, VerifyResult};
 
 pub type FileId = u64;
@@ -23,12 +24,110 @@ pub type CloudId = u16;
 /// Maximum allowed plaintext chunk size (DoS-safe).
 pub const MAX_CHUNK_SIZE: usize = 4 * 1024 * 1024; // 4 MiB
 
+// Guarded plaintext buffers must fit one maximum chunk
+const _: () = assert!(MAX_CHUNK_SIZE <= crate::memory::MAX_GUARDED_VEC_LEN);
+
+/// Highest chunk index (the full `u32` range is nonce-bound).
+///
+/// Callers MUST NOT wrap past it: index reuse under the same
+/// file key is nonce reuse.
+pub const MAX_CHUNK_INDEX: u32 = u32::MAX;
+
+/* ───────────── CHUNK PLANNING ───────────── */
+
+/// Canonical plaintext chunk size used by `plan_chunks`.
+///
+/// ⚠️ MUST NEVER CHANGE: both ends derive boundaries from it.
+pub const CANONICAL_CHUNK_SIZE: usize = MAX_CHUNK_SIZE;
+
+/// One planned chunk: AAD index, plaintext offset and length.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ChunkSpan {
+    pub index: u32,
+    pub offset: u64,
+    pub len: usize,
+}
+
+/// Deterministic chunk boundaries for one file (see `plan_chunks`).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ChunkPlan {
+    total_len: u64,
+    chunk_count: u32,
+}
+
+impl ChunkPlan {
+    /// Number of chunks (>= 1).
+    #[inline(always)]
+    pub fn chunk_count(&self) -> u32 {
+        self.chunk_count
+    }
+
+    #[inline(always)]
+    pub fn total_len(&self) -> u64 {
+        self.total_len
+    }
+
+    /// Span of chunk `index` (`None` if out of range).
+    pub fn chunk(&self, index: u32) -> Option<ChunkSpan> {
+        if index >= self.chunk_count {
+            return None;
+        }
+
+        let offset = u64::from(index) * CANONICAL_CHUNK_SIZE as u64;
+        let len = (self.total_len - offset).min(CANONICAL_CHUNK_SIZE as u64) as usize;
+
+        Some(ChunkSpan { index, offset, len })
+    }
+
+    /// Every span, in order.
+    pub fn spans(&self) -> impl Iterator<Item = ChunkSpan> + '_ {
+        (0..self.chunk_count).filter_map(|i| self.chunk(i))
+    }
+}
+
+/// Plan canonical chunk boundaries from the file length alone.
+///
+/// SECURITY:
+/// - Fixed `CANONICAL_CHUNK_SIZE`: encrypt and decrypt (and any
+///   re-chunking) agree on every `(file_id, chunk)` pair, so AAD
+///   and nonce derivation stay canonical
+/// - An empty file is ONE empty chunk (its tag still authenticates it)
+/// - A count that would not fit the `u32` AAD chunk index =>
+///   `InvalidInput` (never wraps)
+pub fn plan_chunks(total_len: u64) -> Result<ChunkPlan, SessionError> {
+    let count = total_len.div_ceil(CANONICAL_CHUNK_SIZE as u64).max(1);
+    let chunk_count = u32::try_from(count).map_err(|_| SessionError::InvalidInput)?;
+
+    Ok(ChunkPlan { total_len, chunk_count })
+}
+
+/* ───────────── DECRYPT POLICY ───────────── */
+
+/// Decrypt-side format policy.
+///
+/// SECURITY:
+/// - `min_aad_version` is a hard floor: chunks claiming an older
+///   AAD version are refused BEFORE any key derivation
+/// - Lets deployments retire old chunk formats (downgrade-proof)
+#[derive(Clone, Copy)]
+pub struct DecryptConfig {
+    pub min_aad_version: u8,
+}
+
+impl Default for DecryptConfig {
+    fn default() -> Self {
+        Self {
+            min_aad_version: AAD_VERSION_V1,
+        }
+    }
+}
+
 /* ───────────── ENCRYPT ───────────── */
 
-/// Encrypt a single file chunk.
+/// Encrypt a single file chunk under `AadVersion::CURRENT`.
 ///
 /// Output format:
-/// `[ ciphertext | tag ]`
+/// `[ ciphertext | tag ]`; store `EncryptResult::aad` with it.
 pub fn encrypt_chunk(
     session: &mut Session,
     file_id: FileId,
@@ -36,6 +135,87 @@ pub fn encrypt_chunk(
     chunk_index: u32,
     plaintext: &[u8],
     out: &mut [u8],
+) -> Result<EncryptResult, SessionError> {
+    let aad = Aad::new(
+        file_id,
+        chunk_index,
+        cloud_id,
+        AadVersion::CURRENT as u8,
+    )
+    .ok_or_else(|| {
+        out.fill(0);
+        SessionError::InvalidInput
+    })?;
+
+    encrypt_with_aad(session, aad, plaintext, out)
+}
+
+/// `encrypt_chunk` without a separate plaintext buffer.
+///
+/// `buf` layout: `[ plaintext | TAG_LEN spare ]` in,
+/// `[ ciphertext | tag ]` out; wiped on ALL failures.
+pub fn encrypt_chunk_in_place(
+    session: &mut Session,
+    file_id: FileId,
+    cloud_id: CloudId,
+    chunk_index: u32,
+    buf: &mut [u8],
+) -> Result<EncryptResult, SessionError> {
+    let aad = Aad::new(
+        file_id,
+        chunk_index,
+        cloud_id,
+        AadVersion::CURRENT as u8,
+    );
+
+    let too_large = buf.len().saturating_sub(TAG_LEN) > MAX_CHUNK_SIZE;
+    match aad {
+        Some(aad) if !too_large => session.encrypt_in_place(aad, buf),
+        _ => {
+            buf.fill(0);
+            Err(SessionError::InvalidInput)
+        }
+    }
+}
+
+/// Encrypt a chunk bound to a monotonic file epoch (AAD V2).
+///
+/// The epoch is managed by the application and MUST increase
+/// on every in-place update of the file.
+pub fn encrypt_chunk_with_epoch(
+    session: &mut Session,
+    file_id: FileId,
+    cloud_id: CloudId,
+    chunk_index: u32,
+    epoch: u64,
+    plaintext: &[u8],
+    out: &mut [u8],
+) -> Result<EncryptResult, SessionError> {
+    let aad = Aad::with_epoch(file_id, chunk_index, cloud_id, epoch);
+
+    encrypt_with_aad(session, aad, plaintext, out)
+}
+
+/// Encrypt a chunk from a per-file AAD template.
+///
+/// Only the chunk index varies; file / cloud id, version and
+/// epoch come from `template` (built once per file), so every
+/// chunk of one file is guaranteed consistent AAD.
+pub fn encrypt_chunk_from_template(
+    session: &mut Session,
+    template: &Aad,
+    chunk_index: u32,
+    plaintext: &[u8],
+    out: &mut [u8],
+) -> Result<EncryptResult, SessionError> {
+    encrypt_with_aad(session, template.at_chunk(chunk_index), plaintext, out)
+}
+
+fn encrypt_with_aad(
+    session: &mut Session,
+    aad: Aad,
+    plaintext: &[u8],
+    out: &mut [u8],
 ) -> Result<EncryptResult, SessionError> {
     // ───── Input validation ─────
 
@@ -50,17 +230,6 @@ pub fn encrypt_chunk(
         return Err(SessionError::OutputTooSmall);
     }
 
-    let aad = Aad::new(
-        file_id,
-        chunk_index,
-        cloud_id,
-        AAD_VERSION_V1,
-    )
-    .ok_or_else(|| {
-        out.fill(0);
-        SessionError::InvalidInput
-    })?;
-
     // ───── Encrypt via session ─────
 
     match session.encrypt(plaintext, aad, out) {
@@ -74,50 +243,195 @@ pub fn encrypt_chunk(
 
 /* ───────────── DECRYPT ───────────── */
 
-/// Decrypt + verify a single file chunk.
+/// Decrypt + verify the chunk the CALLER expects at
+/// `(file_id, cloud_id, chunk_index)`, using its STORED AAD.
 ///
-/// Returns VerifyResult(false) on auth failure.
+/// Only the format fields (version byte, epoch, generation) are
+/// taken from the stored AAD (`AadVersion::from_u8`), so chunks
+/// written under any supported layout keep decrypting after
+/// `AadVersion::CURRENT` moves on.
+///
+/// SECURITY:
+/// - Position fields are NEVER trusted from storage: a stored AAD
+///   naming another file / cloud / chunk => `InvalidInput`
+///   (a chunk cannot be spliced into another position)
+/// - Unknown layout / malformed / stream AAD => `InvalidInput`
+/// - Versions below `cfg.min_aad_version` => `InvalidInput`
+/// - `commitment` (the stored `EncryptResult::commitment`) is
+///   checked BEFORE the AEAD tag; hosts that store it MUST pass it
+/// - Returns VerifyResult(false) on auth / commitment failure
+#[allow(clippy::too_many_arguments)]
 pub fn decrypt_chunk(
     session: &mut Session,
     file_id: FileId,
     cloud_id: CloudId,
     chunk_index: u32,
+    aad: &[u8],
+    commitment: Option<&[u8; COMMIT_LEN]>,
+    cfg: &DecryptConfig,
     ciphertext: &[u8],
     out: &mut [u8],
 ) -> Result<VerifyResult, SessionError> {
-    // ───── Input validation ─────
-
-    if ciphertext.len() < TAG_LEN {
+    let Some(aad) = Aad::deserialize(aad) else {
         out.fill(0);
         return Err(SessionError::InvalidInput);
-    }
+    };
 
-    let ct_len = ciphertext.len() - TAG_LEN;
+    let misplaced = aad.file_id() != file_id
+        || aad.cloud_id() != cloud_id
+        || aad.chunk() != chunk_index;
 
-    if ct_len > MAX_CHUNK_SIZE {
+    if misplaced
+        || aad.format_version() == AAD_VERSION_STREAM
+        || aad.format_version() < cfg.min_aad_version
+    {
         out.fill(0);
         return Err(SessionError::InvalidInput);
     }
 
-    if out.len() != ct_len {
+    decrypt_with_aad(session, aad, commitment, ciphertext, out)
+}
+
+/// Decrypt + verify a chunk stored under `aad_version`.
+///
+/// SECURITY:
+/// - Versions below `cfg.min_aad_version` => `InvalidInput`
+/// - The version is still authenticated by the AEAD tag
+#[allow(clippy::too_many_arguments)]
+pub fn decrypt_chunk_with(
+    session: &mut Session,
+    file_id: FileId,
+    cloud_id: CloudId,
+    chunk_index: u32,
+    aad_version: u8,
+    cfg: &DecryptConfig,
+    ciphertext: &[u8],
+    out: &mut [u8],
+) -> Result<VerifyResult, SessionError> {
+    // ───── Downgrade floor ─────
+
+    // Floor applies to the layout version (cipher bits masked off)
+    if aad_version & !AAD_CIPHER_MASK < cfg.min_aad_version {
         out.fill(0);
-        return Err(SessionError::OutputTooSmall);
+        return Err(SessionError::InvalidInput);
     }
 
     let aad = Aad::new(
         file_id,
         chunk_index,
         cloud_id,
-        AAD_VERSION_V1,
+        aad_version,
     )
     .ok_or_else(|| {
         out.fill(0);
         SessionError::InvalidInput
     })?;
 
+    decrypt_with_aad(session, aad, None, ciphertext, out)
+}
+
+/// Decrypt + verify a chunk under the CURRENT file epoch (AAD V2).
+///
+/// SECURITY:
+/// - Chunk MUST have been sealed under this session's cipher suite
+/// - Chunks from any other epoch fail authentication
+///   => `VerifyResult(false)` (rollback rejected)
+/// - Subject to the same `min_aad_version` floor
+#[allow(clippy::too_many_arguments)]
+pub fn decrypt_chunk_with_epoch(
+    session: &mut Session,
+    file_id: FileId,
+    cloud_id: CloudId,
+    chunk_index: u32,
+    epoch: u64,
+    cfg: &DecryptConfig,
+    ciphertext: &[u8],
+    out: &mut [u8],
+) -> Result<VerifyResult, SessionError> {
+    if AAD_VERSION_V2 < cfg.min_aad_version {
+        out.fill(0);
+        return Err(SessionError::InvalidInput);
+    }
+
+    // Epoch-bound files are rewritten in place under the current
+    // session, so its cipher suite is assumed
+    let aad = Aad::with_epoch(file_id, chunk_index, cloud_id, epoch)
+        .with_cipher(session.suite());
+
+    decrypt_with_aad(session, aad, None, ciphertext, out)
+}
+
+/* ───────────── VERIFY ONLY ───────────── */
+
+/// Authenticate a chunk stored under `aad_version`; NO plaintext out.
+///
+/// Same floor, revocation and size checks as `decrypt_chunk_with`.
+pub fn verify_chunk_with(
+    session: &mut Session,
+    file_id: FileId,
+    cloud_id: CloudId,
+    chunk_index: u32,
+    aad_version: u8,
+    cfg: &DecryptConfig,
+    ciphertext: &[u8],
+) -> Result<VerifyResult, SessionError> {
+    if aad_version & !AAD_CIPHER_MASK < cfg.min_aad_version {
+        return Err(SessionError::InvalidInput);
+    }
+
+    let aad = Aad::new(file_id, chunk_index, cloud_id, aad_version)
+        .ok_or(SessionError::InvalidInput)?;
+
+    session.require_live_file(aad.file_id())?;
+
+    if ciphertext.len() < TAG_LEN || ciphertext.len() - TAG_LEN > MAX_CHUNK_SIZE {
+        return Err(SessionError::InvalidInput);
+    }
+
+    session.verify_only(ciphertext, aad)
+}
+
+fn decrypt_with_aad(
+    session: &mut Session,
+    aad: Aad,
+    commitment: Option<&[u8; COMMIT_LEN]>,
+    ciphertext: &[u8],
+    out: &mut [u8],
+) -> Result<VerifyResult, SessionError> {
+    // ───── Per-file revocation (before any key derivation) ─────
+
+    if let Err(e) = session.require_live_file(aad.file_id()) {
+        out.fill(0);
+        return Err(e);
+    }
+
+    // ───── Input validation ─────
+
+    if ciphertext.len() < TAG_LEN {
+        out.fill(0);
+        return Err(SessionError::InvalidInput);
+    }
+
+    let ct_len = ciphertext.len() - TAG_LEN;
+
+    if ct_len > MAX_CHUNK_SIZE {
+        out.fill(0);
+        return Err(SessionError::InvalidInput);
+    }
+
+    if out.len() != ct_len {
+        out.fill(0);
+        return Err(SessionError::OutputTooSmall);
+    }
+
     // ───── Decrypt via session ─────
 
-    match session.decrypt_verify(ciphertext, aad, out) {
+    let result = match commitment {
+        Some(c) => session.decrypt_verify_committed(ciphertext, aad, c, out),
+        None => session.decrypt_verify(ciphertext, aad, out),
+    };
+
+    match result {
         Ok(v) => Ok(v),
         Err(e) => {
             out.fill(0);
@@ -126,3 +440,226 @@ pub fn decrypt_chunk(
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::GuardedKey32;
+
+    fn session() -> Session {
+        // Revocations are replayed from the log before any file op
+        crate::logging::encrypted::init_test_log_root();
+        Session::new(GuardedKey32::init_with(|k| k.fill(0x42)))
+    }
+
+    #[test]
+    fn chunk_plan_is_canonical_and_bounded() -> Result<(), ()> {
+        let size = CANONICAL_CHUNK_SIZE as u64;
+
+        let empty = plan_chunks(0).map_err(|_| ())?;
+        assert_eq!(empty.chunk_count(), 1);
+        assert!(empty.chunk(0) == Some(ChunkSpan { index: 0, offset: 0, len: 0 }));
+
+        let exact = plan_chunks(2 * size).map_err(|_| ())?;
+        assert_eq!(exact.chunk_count(), 2);
+
+        let plan = plan_chunks(2 * size + 5).map_err(|_| ())?;
+        assert_eq!(plan.chunk_count(), 3);
+        assert!(plan.chunk(2) == Some(ChunkSpan { index: 2, offset: 2 * size, len: 5 }));
+        assert!(plan.chunk(3).is_none());
+
+        // Spans tile the file exactly
+        let covered: u64 = plan.spans().map(|s| s.len as u64).sum();
+        assert_eq!(covered, plan.total_len());
+
+        // Last index that still fits `u32`, then one byte too many
+        let max = u64::from(u32::MAX) * size;
+        assert!(matches!(plan_chunks(max), Ok(p) if p.chunk_count() == u32::MAX));
+        assert!(matches!(plan_chunks(max + 1), Err(SessionError::InvalidInput)));
+        assert!(plan_chunks(u64::MAX).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn template_encryption_matches_per_chunk_aad() {
+        let mut s = session();
+        let plaintext = b"templated chunk";
+
+        let template = Aad::new(5, 0, 3, AAD_VERSION_V1);
+        assert!(template.is_some());
+
+        for chunk in [0u32, 1, 7] {
+            let mut direct = vec![0u8; plaintext.len() + TAG_LEN];
+            let mut templated = vec![0u8; plaintext.len() + TAG_LEN];
+
+            assert!(encrypt_chunk(&mut s, 5, 3, chunk, plaintext, &mut direct).is_ok());
+            assert!(template.map_or(false, |t| {
+                encrypt_chunk_from_template(&mut s, &t, chunk, plaintext, &mut templated)
+                    .is_ok()
+            }));
+
+            assert_eq!(direct, templated);
+        }
+    }
+
+    #[test]
+    fn max_chunk_index_round_trips() -> Result<(), ()> {
+        let mut s = session();
+        let plaintext = b"last chunk";
+
+        let mut ct = vec![0u8; plaintext.len() + TAG_LEN];
+        let sealed = encrypt_chunk(&mut s, 4, 1, MAX_CHUNK_INDEX, plaintext, &mut ct).map_err(|_| ())?;
+
+        let cfg = DecryptConfig::default();
+        let mut out = vec![0u8; plaintext.len()];
+        let res = decrypt_chunk(&mut s, 4, 1, MAX_CHUNK_INDEX, &sealed.aad, Some(&sealed.commitment), &cfg, &ct, &mut out);
+        assert!(res == Ok(VerifyResult(true)));
+        assert_eq!(&out, plaintext);
+
+        // Index is bound: the same chunk fails at the neighbour index
+        let neighbour = Aad::new(4, MAX_CHUNK_INDEX - 1, 1, AAD_VERSION_V1).ok_or(())?;
+        let res = decrypt_chunk(&mut s, 4, 1, MAX_CHUNK_INDEX - 1, &neighbour.serialize(), Some(&sealed.commitment), &cfg, &ct, &mut out);
+        assert!(res == Ok(VerifyResult(false)));
+
+        // Unknown layout in the stored AAD fails closed
+        let mut unknown = [0u8; 15];
+        unknown.copy_from_slice(&sealed.aad);
+        unknown[14] = 0x7F;
+        let res = decrypt_chunk(&mut s, 4, 1, MAX_CHUNK_INDEX, &unknown, Some(&sealed.commitment), &cfg, &ct, &mut out);
+        assert!(matches!(res, Err(SessionError::InvalidInput)));
+        Ok(())
+    }
+
+    #[test]
+    fn stored_aad_cannot_move_a_chunk() -> Result<(), ()> {
+        let mut s = session();
+        let plaintext = b"chunk seven";
+
+        let mut ct = vec![0u8; plaintext.len() + TAG_LEN];
+        let sealed = encrypt_chunk(&mut s, 4, 1, 7, plaintext, &mut ct).map_err(|_| ())?;
+        let cfg = DecryptConfig::default();
+        let mut out = vec![0u8; plaintext.len()];
+
+        // Asked for another file / cloud / chunk: the stored
+        // position is never adopted
+        for (file, cloud, chunk) in [(5, 1, 7), (4, 2, 7), (4, 1, 8)] {
+            let res = decrypt_chunk(&mut s, file, cloud, chunk, &sealed.aad, Some(&sealed.commitment), &cfg, &ct, &mut out);
+            assert!(matches!(res, Err(SessionError::InvalidInput)));
+            assert!(out.iter().all(|b| *b == 0));
+        }
+
+        // The caller's floor applies to the stored version
+        let floor = DecryptConfig { min_aad_version: AAD_VERSION_V2 };
+        let res = decrypt_chunk(&mut s, 4, 1, 7, &sealed.aad, Some(&sealed.commitment), &floor, &ct, &mut out);
+        assert!(matches!(res, Err(SessionError::InvalidInput)));
+
+        let res = decrypt_chunk(&mut s, 4, 1, 7, &sealed.aad, Some(&sealed.commitment), &cfg, &ct, &mut out);
+        assert!(res == Ok(VerifyResult(true)));
+        Ok(())
+    }
+
+    #[test]
+    fn chunk_commitment_is_checked_before_the_tag() -> Result<(), ()> {
+        let mut s = session();
+        let plaintext = b"committed";
+
+        let mut ct = vec![0u8; plaintext.len() + TAG_LEN];
+        let sealed = encrypt_chunk(&mut s, 4, 1, 2, plaintext, &mut ct).map_err(|_| ())?;
+        let mut other_ct = vec![0u8; plaintext.len() + TAG_LEN];
+        let other = encrypt_chunk(&mut s, 5, 1, 2, plaintext, &mut other_ct).map_err(|_| ())?;
+
+        let cfg = DecryptConfig::default();
+        let mut out = vec![0u8; plaintext.len()];
+
+        // Another file's commitment (another key) is refused even
+        // though the tag itself would verify
+        let res = decrypt_chunk(&mut s, 4, 1, 2, &sealed.aad, Some(&other.commitment), &cfg, &ct, &mut out);
+        assert!(res == Ok(VerifyResult(false)));
+        assert!(out.iter().all(|b| *b == 0));
+
+        let mut forged = sealed.commitment;
+        forged[31] ^= 0x01;
+        let res = decrypt_chunk(&mut s, 4, 1, 2, &sealed.aad, Some(&forged), &cfg, &ct, &mut out);
+        assert!(res == Ok(VerifyResult(false)));
+
+        let res = decrypt_chunk(&mut s, 4, 1, 2, &sealed.aad, Some(&sealed.commitment), &cfg, &ct, &mut out);
+        assert!(res == Ok(VerifyResult(true)));
+        assert_eq!(&out, plaintext);
+        Ok(())
+    }
+
+    #[test]
+    fn flipped_aead_id_fails_authentication() -> Result<(), ()> {
+        use crate::crypto::cipher::AAD_CIPHER_CHACHA;
+
+        let mut s = session();
+        let plaintext = b"header bound";
+
+        let mut ct = vec![0u8; plaintext.len() + TAG_LEN];
+        let res = encrypt_chunk(&mut s, 6, 2, 0, plaintext, &mut ct).map_err(|_| ())?;
+
+        // Attacker flips the backend selector in the stored header
+        let tampered = res.aad_version ^ AAD_CIPHER_CHACHA;
+
+        let mut out = vec![0xAAu8; plaintext.len()];
+        let res = decrypt_chunk_with(
+            &mut s,
+            6,
+            2,
+            0,
+            tampered,
+            &DecryptConfig::default(),
+            &ct,
+            &mut out,
+        );
+
+        assert!(res == Ok(VerifyResult(false)));
+        assert!(out.iter().all(|b| *b == 0));
+        Ok(())
+    }
+
+    #[test]
+    fn decrypt_refuses_aad_version_below_floor() {
+        let mut s = session();
+        let plaintext = b"v1 chunk";
+
+        let mut ct = vec![0u8; plaintext.len() + TAG_LEN];
+        assert!(encrypt_chunk(&mut s, 1, 2, 0, plaintext, &mut ct).is_ok());
+
+        let mut out = vec![0xAAu8; plaintext.len()];
+        let res = decrypt_chunk_with(
+            &mut s,
+            1,
+            2,
+            0,
+            AAD_VERSION_V1,
+            &DecryptConfig { min_aad_version: 2 },
+            &ct,
+            &mut out,
+        );
+
+        assert!(res == Err(SessionError::InvalidInput));
+        assert!(out.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn stale_epoch_chunk_fails_authentication() {
+        let mut s = session();
+        let plaintext = b"epoch 2 contents";
+
+        let mut ct = vec![0u8; plaintext.len() + TAG_LEN];
+        assert!(encrypt_chunk_with_epoch(&mut s, 9, 1, 0, 2, plaintext, &mut ct).is_ok());
+
+        let cfg = DecryptConfig::default();
+        let mut out = vec![0u8; plaintext.len()];
+
+        // Current epoch is 3: the epoch-2 chunk is a rollback
+        let res = decrypt_chunk_with_epoch(&mut s, 9, 1, 0, 3, &cfg, &ct, &mut out);
+        assert!(res == Ok(VerifyResult(false)));
+        assert!(out.iter().all(|b| *b == 0));
+
+        // Same epoch still decrypts
+        let res = decrypt_chunk_with_epoch(&mut s, 9, 1, 0, 2, &cfg, &ct, &mut out);
+        assert!(res == Ok(VerifyResult(true)));
+        assert_eq!(&out[..], plaintext);
+    }
+}
@@ -11,8 +11,9 @@
 //! - Bounded resource usage
 //! - Fail-closed
 
-use crate::memory::GuardedKey32;
+use crate::memory::{GuardedKey32, GuardedVec};
 use argon2::{Argon2, Algorithm, Version, Params as AParams};
+use std::time::{Duration, Instant};
 use zeroize::Zeroizing;
 
 /* ───────────── PARAMETERS ───────────── */
@@ -39,6 +40,57 @@ impl Default for Params {
     }
 }
 
+impl Params {
+    /// Smallest `time` reaching `target` latency on THIS device.
+    ///
+    /// Runs trial derivations at `mem_kib` (clamped to the limits)
+    /// on a throwaway input / salt, raising `time` from `MIN_TIME`
+    /// until one takes at least `target` or `MAX_TIME` is reached.
+    ///
+    /// SECURITY:
+    /// - Never exceeds `MAX_MEM_KIB` / `MAX_TIME`
+    /// - At most `MAX_TIME - MIN_TIME` trials (bounded wall time)
+    /// - A failed trial stops calibration at the cost reached so far
+    /// - Result MUST be persisted (see `RecoveryKdf::to_bytes`):
+    ///   re-derivation needs the SAME cost
+    /// - Needs a wall clock (not for `wasm32-unknown-unknown`)
+    pub fn calibrate(target: Duration, mem_kib: u32) -> Params {
+        let input = Zeroizing::new(CALIBRATION_INPUT.to_vec());
+
+        Self::calibrate_with(target, mem_kib, |params| {
+            let mut out = GuardedKey32::zeroed();
+            let start = Instant::now();
+            derive_single_key(&input, CALIBRATION_SALT, params, &mut out).ok()?;
+            Some(start.elapsed())
+        })
+    }
+
+    /// `calibrate` with an injectable trial (tests use a fake clock).
+    fn calibrate_with<F>(target: Duration, mem_kib: u32, mut trial: F) -> Params
+    where
+        F: FnMut(&Params) -> Option<Duration>,
+    {
+        let mut params = Params {
+            mem_kib: mem_kib.clamp(MIN_MEM_KIB, MAX_MEM_KIB),
+            time: MIN_TIME,
+            lanes: MIN_LANES,
+        };
+
+        while params.time < MAX_TIME {
+            match trial(&params) {
+                Some(elapsed) if elapsed < target => params.time += 1,
+                _ => break,
+            }
+        }
+
+        params
+    }
+}
+
+/// Throwaway calibration material (NEVER a user secret).
+const CALIBRATION_INPUT: &[u8] = b"rcxcloud:argon2:calibration";
+const CALIBRATION_SALT: &[u8] = b"rcxcloud-calibration-v1";
+
 /* ───────────── LIMITS ───────────── */
 
 const MIN_MEM_KIB: u32 = 8 * 1024;      // 8 MiB
@@ -94,8 +146,10 @@ pub fn derive_single_key(
 /// SECURITY:
 /// - Replaces forbidden `[u8; 64]` stack output
 /// - Each key is independently guarded and zeroized
+/// - Input is borrowed: callers hold it in `Zeroizing` or locked
+///   (`GuardedVec`) heap memory
 pub fn derive_two_keys(
-    input: &Zeroizing<Vec<u8>>,
+    input: &[u8],
     salt: &[u8],
     params: &Params,
     out_root: &mut GuardedKey32,
@@ -116,20 +170,20 @@ pub fn derive_two_keys(
         .map_err(|_| KdfError::Params)?,
     );
 
-    // Temporary heap buffer (NOT stack)
-    let mut tmp = Zeroizing::new(vec![0u8; 64]);
+    // Temporary locked heap buffer (NOT stack)
+    let mut tmp = GuardedVec::zeroed(64);
 
     argon
-        .hash_password_into(input, salt, &mut tmp)
+        .hash_password_into(input, salt, tmp.borrow_mut())
         .map_err(|_| KdfError::Derive)?;
 
     out_root
         .borrow_mut()
-        .copy_from_slice(&tmp[..32]);
+        .copy_from_slice(&tmp.borrow()[..32]);
 
     out_session
         .borrow_mut()
-        .copy_from_slice(&tmp[32..64]);
+        .copy_from_slice(&tmp.borrow()[32..64]);
 
     Ok(())
 }
@@ -138,7 +192,7 @@ pub fn derive_two_keys(
 
 #[inline(always)]
 fn validate_inputs(
-    input: &Zeroizing<Vec<u8>>,
+    input: &[u8],
     salt: &[u8],
 ) -> Result<(), KdfError> {
     if input.is_empty() || salt.is_empty() {
@@ -169,3 +223,31 @@ pub enum KdfError {
     Params,
     Derive,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibration_stops_at_target_and_respects_bounds() {
+        // Fake clock: 100 ms per pass
+        let per_pass = |p: &Params| Some(Duration::from_millis(100 * u64::from(p.time)));
+
+        let p = Params::calibrate_with(Duration::from_millis(350), 64 * 1024, per_pass);
+        assert_eq!((p.time, p.mem_kib), (4, 64 * 1024));
+
+        // Unreachable target => capped, with a bounded trial count
+        let mut trials = 0;
+        let p = Params::calibrate_with(Duration::from_secs(3600), u32::MAX, |p| {
+            trials += 1;
+            per_pass(p)
+        });
+        assert_eq!((p.time, p.mem_kib), (MAX_TIME, MAX_MEM_KIB));
+        assert!(trials <= (MAX_TIME - MIN_TIME) as usize);
+        assert!(validate_params(&p).is_ok());
+
+        // Failed trial stops at the cost reached so far
+        let p = Params::calibrate_with(Duration::from_secs(1), 0, |_| None);
+        assert_eq!((p.time, p.mem_kib), (MIN_TIME, MIN_MEM_KIB));
+    }
+}
@@ -0,0 +1,174 @@
+//! scrypt KDF — heap-only, misuse-resistant (constrained devices).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! Cheaper-but-still-memory-hard alternative to Argon2id for quick
+//! unlock on low-memory hardware. Mirrors `kdf_argon2`.
+//!
+//! FORMAL INVARIANTS (ENFORCED):
+//! - Inputs MUST be heap-backed
+//! - No stack-resident secret outputs
+//! - All outputs written into GuardedKey32
+//! - Bounded resource usage (memory = 128 · r · 2^log_n bytes)
+//! - Fail-closed
+
+use crate::crypto::kdf_argon2::KdfError;
+use crate::memory::{GuardedKey32, GuardedVec};
+use scrypt::Params as SParams;
+use zeroize::Zeroizing;
+
+/* ───────────── PARAMETERS ───────────── */
+
+/// scrypt parameters (non-secret).
+///
+/// SECURITY:
+/// - Bounds enforced internally
+/// - Caller cannot cause OOM or DoS
+#[derive(Clone, Copy)]
+pub struct Params {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            log_n: 15, // 32 MiB with r = 8
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+/* ───────────── LIMITS ───────────── */
+
+const MIN_LOG_N: u8 = 14;
+const MAX_LOG_N: u8 = 20;
+const MIN_R: u32 = 8;
+const MAX_R: u32 = 16;
+const MIN_P: u32 = 1;
+const MAX_P: u32 = 4;
+const MAX_MEM_BYTES: u64 = 512 * 1024 * 1024; // 512 MiB
+
+/* ───────────── PUBLIC API ───────────── */
+
+/// Derive a single 256-bit key using scrypt.
+///
+/// SECURITY:
+/// - Input MUST be heap-backed (`Zeroizing<Vec<u8>>`)
+/// - Salt MUST be explicit and non-empty
+/// - Output written directly into GuardedKey32
+pub fn derive_single_key(
+    input: &Zeroizing<Vec<u8>>,
+    salt: &[u8],
+    params: &Params,
+    out: &mut GuardedKey32,
+) -> Result<(), KdfError> {
+    validate_inputs(input, salt)?;
+    let sp = validate_params(params, 32)?;
+
+    scrypt::scrypt(input, salt, &sp, out.borrow_mut()).map_err(|_| KdfError::Derive)
+}
+
+/// Derive **two independent 256-bit keys** (e.g. recovery root + session).
+///
+/// Input is borrowed: callers hold it in `Zeroizing` or locked
+/// (`GuardedVec`) heap memory.
+pub fn derive_two_keys(
+    input: &[u8],
+    salt: &[u8],
+    params: &Params,
+    out_root: &mut GuardedKey32,
+    out_session: &mut GuardedKey32,
+) -> Result<(), KdfError> {
+    validate_inputs(input, salt)?;
+    let sp = validate_params(params, 64)?;
+
+    // Temporary locked heap buffer (NOT stack)
+    let mut tmp = GuardedVec::zeroed(64);
+
+    scrypt::scrypt(input, salt, &sp, tmp.borrow_mut()).map_err(|_| KdfError::Derive)?;
+
+    out_root.borrow_mut().copy_from_slice(&tmp.borrow()[..32]);
+    out_session.borrow_mut().copy_from_slice(&tmp.borrow()[32..64]);
+
+    Ok(())
+}
+
+/* ───────────── VALIDATION ───────────── */
+
+#[inline(always)]
+fn validate_inputs(
+    input: &[u8],
+    salt: &[u8],
+) -> Result<(), KdfError> {
+    if input.is_empty() || salt.is_empty() {
+        return Err(KdfError::InvalidInput);
+    }
+    Ok(())
+}
+
+#[inline(always)]
+fn validate_params(params: &Params, len: usize) -> Result<SParams, KdfError> {
+    if !(MIN_LOG_N..=MAX_LOG_N).contains(&params.log_n) {
+        return Err(KdfError::Params);
+    }
+    if !(MIN_R..=MAX_R).contains(&params.r) {
+        return Err(KdfError::Params);
+    }
+    if !(MIN_P..=MAX_P).contains(&params.p) {
+        return Err(KdfError::Params);
+    }
+
+    let mem = 128u64 * u64::from(params.r) * (1u64 << params.log_n);
+    if mem > MAX_MEM_BYTES {
+        return Err(KdfError::Params);
+    }
+
+    SParams::new(params.log_n, params.r, params.p, len).map_err(|_| KdfError::Params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 7914 §12, vector 3 (N = 16384, r = 8, p = 1).
+    #[test]
+    fn rfc7914_known_answer() {
+        const EXPECTED: [u8; 64] = [
+            0x70, 0x23, 0xbd, 0xcb, 0x3a, 0xfd, 0x73, 0x48, 0x46, 0x1c, 0x06, 0xcd, 0x81, 0xfd,
+            0x38, 0xeb, 0xfd, 0xa8, 0xfb, 0xba, 0x90, 0x4f, 0x8e, 0x3e, 0xa9, 0xb5, 0x43, 0xf6,
+            0x54, 0x5d, 0xa1, 0xf2, 0xd5, 0x43, 0x29, 0x55, 0x61, 0x3f, 0x0f, 0xcf, 0x62, 0xd4,
+            0x97, 0x05, 0x24, 0x2a, 0x9a, 0xf9, 0xe6, 0x1e, 0x85, 0xdc, 0x0d, 0x65, 0x1e, 0x40,
+            0xdf, 0xcf, 0x01, 0x7b, 0x45, 0x57, 0x58, 0x87,
+        ];
+
+        let input = Zeroizing::new(b"pleaseletmein".to_vec());
+        let params = Params { log_n: 14, r: 8, p: 1 };
+
+        let mut root = GuardedKey32::zeroed();
+        let mut session = GuardedKey32::zeroed();
+        assert!(derive_two_keys(&input, b"SodiumChloride", &params, &mut root, &mut session).is_ok());
+
+        assert_eq!(&root.borrow()[..], &EXPECTED[..32]);
+        assert_eq!(&session.borrow()[..], &EXPECTED[32..]);
+    }
+
+    #[test]
+    fn out_of_bounds_params_are_rejected() {
+        let input = Zeroizing::new(b"phrase".to_vec());
+        let mut out = GuardedKey32::zeroed();
+
+        for params in [
+            Params { log_n: 10, r: 8, p: 1 },
+            Params { log_n: 20, r: 16, p: 1 },
+            Params { log_n: 15, r: 8, p: 16 },
+        ] {
+            assert_eq!(
+                derive_single_key(&input, b"salt", &params, &mut out),
+                Err(KdfError::Params)
+            );
+        }
+    }
+}
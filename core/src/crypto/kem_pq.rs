@@ -0,0 +1,369 @@
+//! ML-KEM-768 + hybrid X25519/ML-KEM backup key encapsulation.
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! THREAT MODEL:
+//! - Harvest-now-decrypt-later: encapsulations recorded today
+//!   must stay confidential against a future quantum adversary
+//! - Hybrid mode stays secure if EITHER X25519 or ML-KEM survives
+//!
+//! FORMAL INVARIANTS (ENFORCED):
+//! - Shared secrets are wiped immediately after HKDF
+//! - Hybrid combiner (X-Wing style) binds both shared secrets AND
+//!   the transcript: ML-KEM ciphertext, ephemeral + recipient X25519
+//!   public keys; the IKM lives in locked, zeroized heap memory
+//! - `context` is at least `MIN_CONTEXT_LEN` bytes on BOTH sides
+//! - Output keys written only into GuardedKey32
+//! - Explicit, per-mode domain separation
+//! - Forbidden after global kill
+//! - Fail-closed
+
+#![deny(clippy::derive_debug)]
+
+use core::sync::atomic::Ordering;
+
+use hkdf::Hkdf;
+use ml_kem::kem::{Decapsulate, Encapsulate};
+use ml_kem::{Encoded, EncodedSizeUser, KemCore, MlKem768};
+use rand_core::OsRng;
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+use zeroize::Zeroize;
+
+use super::kem::KEMError;
+use crate::keystore::master::GLOBAL_KILLED;
+use crate::memory::{GuardedKey32, GuardedVec};
+
+/// HKDF domain-separation label, ML-KEM only (MUST NEVER CHANGE).
+const PQ_KEM_LABEL: &[u8] = b"rcxcloud:kem:backup:pq:v1";
+
+/// HKDF domain-separation label, hybrid (MUST NEVER CHANGE).
+///
+/// v2: transcript-binding combiner (v1 hashed the secrets only).
+const HYBRID_KEM_LABEL: &[u8] = b"rcxcloud:kem:backup:hybrid:v2";
+
+/// Shortest accepted HKDF `context` (encapsulate and decapsulate).
+pub const MIN_CONTEXT_LEN: usize = 32;
+
+/// Encoded ML-KEM-768 encapsulation (public) key length.
+pub const ML_KEM_768_PUBLIC_LEN: usize = 1184;
+
+/// ML-KEM-768 ciphertext length.
+pub const ML_KEM_768_CIPHERTEXT_LEN: usize = 1088;
+
+/// ML-KEM-768 decapsulation (secret) key.
+pub type PqSecret = <MlKem768 as KemCore>::DecapsulationKey;
+
+type PqPublic = <MlKem768 as KemCore>::EncapsulationKey;
+
+/* ───────────── KEY GENERATION ───────────── */
+
+/// Generate an ML-KEM-768 key pair.
+///
+/// RETURNS:
+/// - Boxed decapsulation key (never copied)
+/// - Encoded encapsulation key (public)
+pub fn generate_pq_keypair() -> Result<(Box<PqSecret>, Vec<u8>), KEMError> {
+    if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        return Err(KEMError::Killed);
+    }
+
+    let (dk, ek) = MlKem768::generate(&mut OsRng);
+
+    Ok((Box::new(dk), ek.as_bytes().to_vec()))
+}
+
+/* ───────────── ML-KEM ───────────── */
+
+/// Encapsulate a shared backup key to an ML-KEM-768 public key.
+///
+/// SECURITY:
+/// - Malformed public key => `Derive`
+/// - Shared secret wiped right after HKDF
+pub fn encapsulate_pq(
+    peer_pub: &[u8],
+    context: &[u8],
+) -> Result<(PqEncapsulation, GuardedKey32), KEMError> {
+    if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        return Err(KEMError::Killed);
+    }
+
+    if context.len() < MIN_CONTEXT_LEN {
+        return Err(KEMError::Derive);
+    }
+
+    let ek = parse_public(peer_pub)?;
+    let (ct, mut shared) = ek.encapsulate(&mut OsRng).map_err(|_| KEMError::Derive)?;
+
+    let mut out = GuardedKey32::zeroed();
+    let res = expand(PQ_KEM_LABEL, &shared, context, &mut out);
+    shared.as_mut_slice().zeroize();
+    res?;
+
+    Ok((
+        PqEncapsulation {
+            ciphertext: ct.to_vec(),
+        },
+        out,
+    ))
+}
+
+/// Decapsulate an ML-KEM-768 shared backup key.
+///
+/// SECURITY:
+/// - Malformed ciphertext => `Derive` (ML-KEM itself never fails:
+///   a tampered ciphertext yields an unrelated key)
+/// - Output key written in-place only
+pub fn decapsulate_pq(
+    our_secret: &PqSecret,
+    ciphertext: &[u8],
+    context: &[u8],
+    out: &mut GuardedKey32,
+) -> Result<(), KEMError> {
+    if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        return Err(KEMError::Killed);
+    }
+
+    if context.len() < MIN_CONTEXT_LEN {
+        return Err(KEMError::Derive);
+    }
+
+    let ct = ml_kem::Ciphertext::<MlKem768>::try_from(ciphertext)
+        .map_err(|_| KEMError::Derive)?;
+    let mut shared = our_secret.decapsulate(&ct).map_err(|_| KEMError::Derive)?;
+
+    let res = expand(PQ_KEM_LABEL, &shared, context, out);
+    shared.as_mut_slice().zeroize();
+    res
+}
+
+/* ───────────── HYBRID ───────────── */
+
+/// Encapsulate a hybrid X25519 + ML-KEM-768 shared backup key.
+///
+/// `key = HKDF(HYBRID_LABEL, ml_kem_ss || x25519_ss || ml_kem_ct
+///             || x25519_eph_pub || x25519_recipient_pub, context)`
+///
+/// SECURITY:
+/// - Combiner input built in locked heap memory, wiped after HKDF
+/// - Confidential as long as EITHER primitive is unbroken; the
+///   transcript binding keeps that true even if one component's
+///   ciphertext can be mauled or re-targeted
+/// - `context` shorter than `MIN_CONTEXT_LEN` => `Derive`
+pub fn encapsulate_hybrid(
+    peer_x25519: &[u8; 32],
+    peer_pq: &[u8],
+    context: &[u8],
+) -> Result<(HybridEncapsulation, GuardedKey32), KEMError> {
+    if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        return Err(KEMError::Killed);
+    }
+
+    if context.len() < MIN_CONTEXT_LEN {
+        return Err(KEMError::Derive);
+    }
+
+    let ek = parse_public(peer_pq)?;
+
+    let eph = EphemeralSecret::random_from_rng(OsRng);
+    let eph_pub = PublicKey::from(&eph);
+    let classical = eph.diffie_hellman(&PublicKey::from(*peer_x25519));
+
+    let (ct, mut pq) = ek.encapsulate(&mut OsRng).map_err(|_| KEMError::Derive)?;
+
+    let ikm = combiner_input(&pq, classical.as_bytes(), &ct, eph_pub.as_bytes(), peer_x25519);
+    pq.as_mut_slice().zeroize();
+
+    let mut out = GuardedKey32::zeroed();
+    expand(HYBRID_KEM_LABEL, ikm.borrow(), context, &mut out)?;
+
+    Ok((
+        HybridEncapsulation {
+            ephemeral_public: eph_pub.to_bytes(),
+            ciphertext: ct.to_vec(),
+        },
+        out,
+    ))
+}
+
+/// Decapsulate a hybrid X25519 + ML-KEM-768 shared backup key.
+pub fn decapsulate_hybrid(
+    our_x25519: &StaticSecret,
+    our_pq: &PqSecret,
+    encapsulation: &HybridEncapsulation,
+    context: &[u8],
+    out: &mut GuardedKey32,
+) -> Result<(), KEMError> {
+    if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        return Err(KEMError::Killed);
+    }
+
+    if context.len() < MIN_CONTEXT_LEN {
+        return Err(KEMError::Derive);
+    }
+
+    let ct = ml_kem::Ciphertext::<MlKem768>::try_from(encapsulation.ciphertext.as_slice())
+        .map_err(|_| KEMError::Derive)?;
+
+    let classical =
+        our_x25519.diffie_hellman(&PublicKey::from(encapsulation.ephemeral_public));
+    let mut pq = our_pq.decapsulate(&ct).map_err(|_| KEMError::Derive)?;
+
+    let ikm = combiner_input(
+        &pq,
+        classical.as_bytes(),
+        &encapsulation.ciphertext,
+        &encapsulation.ephemeral_public,
+        PublicKey::from(our_x25519).as_bytes(),
+    );
+    pq.as_mut_slice().zeroize();
+
+    expand(HYBRID_KEM_LABEL, ikm.borrow(), context, out)
+}
+
+/* ───────────── INTERNAL ───────────── */
+
+fn parse_public(bytes: &[u8]) -> Result<PqPublic, KEMError> {
+    let enc = Encoded::<PqPublic>::try_from(bytes).map_err(|_| KEMError::Derive)?;
+    Ok(PqPublic::from_bytes(&enc))
+}
+
+/// X-Wing style combiner input (locked heap):
+/// `ml_kem_ss || x25519_ss || ml_kem_ct || eph_pub || recipient_pub`.
+///
+/// Every field is fixed-width, so the concatenation is unambiguous.
+fn combiner_input(
+    pq: &[u8],
+    classical: &[u8],
+    pq_ciphertext: &[u8],
+    ephemeral_public: &[u8; 32],
+    recipient_public: &[u8; 32],
+) -> GuardedVec {
+    let parts = [pq, classical, pq_ciphertext, &ephemeral_public[..], &recipient_public[..]];
+    let len = parts.iter().map(|p| p.len()).sum();
+
+    GuardedVec::init_with(len, |v| {
+        let mut at = 0;
+        for part in parts {
+            v[at..at + part.len()].copy_from_slice(part);
+            at += part.len();
+        }
+    })
+}
+
+fn expand(
+    label: &[u8],
+    ikm: &[u8],
+    context: &[u8],
+    out: &mut GuardedKey32,
+) -> Result<(), KEMError> {
+    let hkdf = Hkdf::<Sha256>::new(Some(label), ikm);
+
+    hkdf.expand(context, out.borrow_mut())
+        .map_err(|_| KEMError::Derive)
+}
+
+/* ───────────── TYPES ───────────── */
+
+/// Public ML-KEM encapsulation output.
+pub struct PqEncapsulation {
+    pub ciphertext: Vec<u8>,
+}
+
+/// Public hybrid encapsulation output.
+pub struct HybridEncapsulation {
+    pub ephemeral_public: [u8; 32],
+    pub ciphertext: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTEXT: &[u8; 32] = b"rcxcloud:test:backup:context:v1!";
+
+    #[test]
+    fn pq_round_trip() -> Result<(), KEMError> {
+        let (dk, ek) = generate_pq_keypair()?;
+        assert_eq!(ek.len(), ML_KEM_768_PUBLIC_LEN);
+
+        let (enc, key) = encapsulate_pq(&ek, CONTEXT)?;
+        assert_eq!(enc.ciphertext.len(), ML_KEM_768_CIPHERTEXT_LEN);
+
+        let mut out = GuardedKey32::zeroed();
+        assert!(decapsulate_pq(&dk, &enc.ciphertext, CONTEXT, &mut out).is_ok());
+        assert_eq!(out.borrow(), key.borrow());
+        Ok(())
+    }
+
+    #[test]
+    fn hybrid_round_trip_and_domain_separation() -> Result<(), KEMError> {
+        let (dk, ek) = generate_pq_keypair()?;
+        let x = StaticSecret::random_from_rng(OsRng);
+        let x_pub = PublicKey::from(&x).to_bytes();
+
+        let (enc, key) = encapsulate_hybrid(&x_pub, &ek, CONTEXT)?;
+
+        let mut out = GuardedKey32::zeroed();
+        assert!(decapsulate_hybrid(&x, &dk, &enc, CONTEXT, &mut out).is_ok());
+        assert_eq!(out.borrow(), key.borrow());
+
+        // PQ-only decapsulation of the same ciphertext MUST differ
+        let mut pq_only = GuardedKey32::zeroed();
+        assert!(decapsulate_pq(&dk, &enc.ciphertext, CONTEXT, &mut pq_only).is_ok());
+        assert_ne!(pq_only.borrow(), key.borrow());
+        Ok(())
+    }
+
+    #[test]
+    fn hybrid_key_is_bound_to_the_recipient_x25519_key() -> Result<(), KEMError> {
+        let (dk, ek) = generate_pq_keypair()?;
+        let x = StaticSecret::random_from_rng(OsRng);
+        let x_pub = PublicKey::from(&x).to_bytes();
+
+        let (enc, key) = encapsulate_hybrid(&x_pub, &ek, CONTEXT)?;
+
+        // Another X25519 recipient with the same ML-KEM key
+        let other = StaticSecret::random_from_rng(OsRng);
+        let mut out = GuardedKey32::zeroed();
+        assert!(decapsulate_hybrid(&other, &dk, &enc, CONTEXT, &mut out).is_ok());
+        assert_ne!(out.borrow(), key.borrow());
+
+        // Re-targeted ephemeral key
+        let mut moved = HybridEncapsulation {
+            ephemeral_public: enc.ephemeral_public,
+            ciphertext: enc.ciphertext.clone(),
+        };
+        moved.ephemeral_public[0] ^= 0x01;
+        assert!(decapsulate_hybrid(&x, &dk, &moved, CONTEXT, &mut out).is_ok());
+        assert_ne!(out.borrow(), key.borrow());
+        Ok(())
+    }
+
+    #[test]
+    fn short_context_is_rejected_on_both_sides() -> Result<(), KEMError> {
+        let (_, ek) = generate_pq_keypair()?;
+        let x_pub = PublicKey::from(&StaticSecret::random_from_rng(OsRng)).to_bytes();
+        let short = &CONTEXT[..MIN_CONTEXT_LEN - 1];
+
+        assert!(matches!(encapsulate_pq(&ek, short), Err(KEMError::Derive)));
+        assert!(matches!(encapsulate_hybrid(&x_pub, &ek, short), Err(KEMError::Derive)));
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_inputs_are_rejected() -> Result<(), KEMError> {
+        assert!(matches!(
+            encapsulate_pq(&[0u8; 32], CONTEXT),
+            Err(KEMError::Derive)
+        ));
+
+        let (dk, _) = generate_pq_keypair()?;
+        let mut out = GuardedKey32::zeroed();
+        assert!(matches!(
+            decapsulate_pq(&dk, &[0u8; 16], CONTEXT, &mut out),
+            Err(KEMError::Derive)
+        ));
+        Ok(())
+    }
+}
@@ -5,26 +5,55 @@
 
 #![deny(clippy::derive_debug)]
 
-pub mod aad;
+// Always available (even without `std`): keyed, stateless, no I/O
 pub mod nonce;
 pub mod aes_gcm;
 pub mod derive;
+pub mod selftest;
+
+#[cfg(feature = "std")]
+pub mod rng;
+
+#[cfg(feature = "std")]
+pub mod aad;
+#[cfg(feature = "std")]
+pub mod file;
+#[cfg(feature = "std")]
+pub mod attest;
+#[cfg(feature = "std")]
+pub mod cipher;
+#[cfg(feature = "chacha")]
+pub mod chacha;
+#[cfg(feature = "std")]
 pub mod kdf_argon2;
+#[cfg(feature = "scrypt")]
+pub mod kdf_scrypt;
+#[cfg(feature = "std")]
 pub mod kem;
+#[cfg(feature = "kem-pq")]
+pub mod kem_pq;
 
 /* ───────────── EXPORT POLICY ───────────── */
 
-pub use aad::{Aad, AAD_VERSION_V1};
-
 pub use nonce::{derive_nonce, NONCE_LEN};
 
-pub use derive::{derive_key, Purpose};
+pub use derive::{derive_key, derive_key_with_domain, Purpose, MAX_DOMAIN_LEN};
+
+#[cfg(feature = "std")]
+pub use aad::{Aad, AAD_VERSION_V1, AAD_VERSION_V2};
+
+#[cfg(feature = "std")]
+pub use cipher::{Aead, CipherSuite};
 
+#[cfg(feature = "std")]
 pub use kdf_argon2::{Params, KdfError};
 
+#[cfg(feature = "std")]
 pub use kem::{
     csrng,
+    csrng_with,
     encapsulate,
+    encapsulate_with,
     decapsulate,
     Encapsulation,
     KEMError,
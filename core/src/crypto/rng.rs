@@ -0,0 +1,143 @@
+//! Randomness backends (Secure Core).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! PURPOSE:
+//! One injection point for every randomized path (`csrng`,
+//! `encapsulate`, bridge handle generation) so tests can replay
+//! them bit-for-bit.
+//!
+//! SECURITY:
+//! - Production code ONLY ever constructs `OsRngBackend`
+//! - `DeterministicRng` exists ONLY under `cfg(test)` or the
+//!   `test-rng` feature, and `test-rng` is a compile error in
+//!   release builds (see `lib.rs`)
+//! - Backends are `CryptoRng`: no `rand::thread_rng`-style fallbacks
+
+#![deny(clippy::derive_debug)]
+
+use rand_core::{CryptoRng, OsRng, RngCore};
+
+/// Cryptographically secure randomness source.
+pub trait SecureRng: RngCore + CryptoRng {}
+
+/* ───────────── PRODUCTION ───────────── */
+
+/// Operating-system CSPRNG (the ONLY production backend).
+#[derive(Clone, Copy, Default)]
+pub struct OsRngBackend;
+
+impl RngCore for OsRngBackend {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        OsRng.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        OsRng.next_u64()
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        OsRng.fill_bytes(dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        OsRng.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for OsRngBackend {}
+impl SecureRng for OsRngBackend {}
+
+/* ───────────── TEST ONLY ───────────── */
+
+/// Seedable, reproducible stream: `SHA-256(seed || counter_be)`.
+///
+/// ⚠️ NOT a production generator. Exists only for KATs and
+/// reproducible CI; unreachable from release builds.
+#[cfg(any(test, feature = "test-rng"))]
+pub struct DeterministicRng {
+    seed: [u8; 32],
+    counter: u64,
+    block: [u8; 32],
+    pos: usize,
+}
+
+#[cfg(any(test, feature = "test-rng"))]
+impl DeterministicRng {
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self {
+            seed,
+            counter: 0,
+            block: [0u8; 32],
+            pos: 32,
+        }
+    }
+
+    fn refill(&mut self) {
+        use sha2::{Digest, Sha256};
+
+        let mut h = Sha256::new();
+        h.update(self.seed);
+        h.update(self.counter.to_be_bytes());
+        self.block.copy_from_slice(&h.finalize());
+        self.counter = self.counter.wrapping_add(1);
+        self.pos = 0;
+    }
+}
+
+#[cfg(any(test, feature = "test-rng"))]
+impl RngCore for DeterministicRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest {
+            if self.pos == self.block.len() {
+                self.refill();
+            }
+            *byte = self.block[self.pos];
+            self.pos += 1;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(any(test, feature = "test-rng"))]
+impl CryptoRng for DeterministicRng {}
+#[cfg(any(test, feature = "test-rng"))]
+impl SecureRng for DeterministicRng {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_stream_replays_from_seed() {
+        let mut a = DeterministicRng::from_seed([7; 32]);
+        let mut b = DeterministicRng::from_seed([7; 32]);
+        let mut c = DeterministicRng::from_seed([8; 32]);
+
+        let (mut x, mut y, mut z) = ([0u8; 80], [0u8; 80], [0u8; 80]);
+        a.fill_bytes(&mut x);
+        b.fill_bytes(&mut y[..33]);
+        b.fill_bytes(&mut y[33..]);
+        c.fill_bytes(&mut z);
+
+        // Split reads see the same stream; other seeds diverge
+        assert_eq!(x, y);
+        assert_ne!(x, z);
+    }
+}
@@ -0,0 +1,126 @@
+//! Power-on cryptographic self-test (known-answer tests).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! FORMAL INVARIANTS (ENFORCED):
+//! - Fixed public vectors only: no key material, no I/O
+//! - Stateless: safe to run repeatedly
+//! - Any mismatch => `false` (caller fails closed)
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::crypto::aes_gcm::{self, NONCE_LEN, TAG_LEN};
+use crate::crypto::derive::{derive_key, Purpose};
+use crate::memory::GuardedKey32;
+
+/* ───────────── VECTORS ───────────── */
+
+/// NIST GCM spec test case 16 (AES-256, 96-bit IV, with AAD).
+const GCM_KEY: [u8; 32] = [
+    0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, 0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30, 0x83,
+    0x08, 0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, 0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30,
+    0x83, 0x08,
+];
+const GCM_IV: [u8; NONCE_LEN] = [
+    0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad, 0xde, 0xca, 0xf8, 0x88,
+];
+const GCM_AAD: [u8; 20] = [
+    0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad, 0xbe, 0xef, 0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad, 0xbe,
+    0xef, 0xab, 0xad, 0xda, 0xd2,
+];
+const GCM_PT: [u8; 60] = [
+    0xd9, 0x31, 0x32, 0x25, 0xf8, 0x84, 0x06, 0xe5, 0xa5, 0x59, 0x09, 0xc5, 0xaf, 0xf5, 0x26,
+    0x9a, 0x86, 0xa7, 0xa9, 0x53, 0x15, 0x34, 0xf7, 0xda, 0x2e, 0x4c, 0x30, 0x3d, 0x8a, 0x31,
+    0x8a, 0x72, 0x1c, 0x3c, 0x0c, 0x95, 0x95, 0x68, 0x09, 0x53, 0x2f, 0xcf, 0x0e, 0x24, 0x49,
+    0xa6, 0xb5, 0x25, 0xb1, 0x6a, 0xed, 0xf5, 0xaa, 0x0d, 0xe6, 0x57, 0xba, 0x63, 0x7b, 0x39,
+];
+const GCM_CT: [u8; 60] = [
+    0x52, 0x2d, 0xc1, 0xf0, 0x99, 0x56, 0x7d, 0x07, 0xf4, 0x7f, 0x37, 0xa3, 0x2a, 0x84, 0x42,
+    0x7d, 0x64, 0x3a, 0x8c, 0xdc, 0xbf, 0xe5, 0xc0, 0xc9, 0x75, 0x98, 0xa2, 0xbd, 0x25, 0x55,
+    0xd1, 0xaa, 0x8c, 0xb0, 0x8e, 0x48, 0x59, 0x0d, 0xbb, 0x3d, 0xa7, 0xb0, 0x8b, 0x10, 0x56,
+    0x82, 0x88, 0x38, 0xc5, 0xf6, 0x1e, 0x63, 0x93, 0xba, 0x7a, 0x0a, 0xbc, 0xc9, 0xf6, 0x62,
+];
+const GCM_TAG: [u8; TAG_LEN] = [
+    0x76, 0xfc, 0x6e, 0xce, 0x0f, 0x4e, 0x17, 0x68, 0xcd, 0xdf, 0x88, 0x53, 0xbb, 0x2d, 0x55,
+    0x1b,
+];
+
+/// RFC 4231 test case 2 (HMAC-SHA-256).
+const HMAC_KEY: &[u8] = b"Jefe";
+const HMAC_DATA: &[u8] = b"what do ya want for nothing?";
+const HMAC_TAG: [u8; 32] = [
+    0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95, 0x75,
+    0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9, 0x64, 0xec,
+    0x38, 0x43,
+];
+
+/* ───────────── ENTRY POINT ───────────── */
+
+/// Run every known-answer test; `true` iff all pass.
+pub fn run() -> bool {
+    aes_gcm_kat() && hmac_kat() && derive_kat()
+}
+
+/* ───────────── CHECKS ───────────── */
+
+fn aes_gcm_kat() -> bool {
+    let key = GuardedKey32::init_with(|k| k.copy_from_slice(&GCM_KEY));
+
+    let mut ct = [0u8; 60];
+    let mut tag = [0u8; TAG_LEN];
+    if aes_gcm::seal_detached(&key, &GCM_IV, &GCM_PT, &GCM_AAD, &mut ct, &mut tag).is_err() {
+        return false;
+    }
+    if ct != GCM_CT || tag != GCM_TAG {
+        return false;
+    }
+
+    let mut pt = [0u8; 60];
+    if !aes_gcm::open_detached(&key, &GCM_IV, &GCM_CT, &GCM_TAG, &GCM_AAD, &mut pt) || pt != GCM_PT
+    {
+        return false;
+    }
+
+    // Authentication MUST reject a flipped tag bit
+    let mut bad = GCM_TAG;
+    bad[0] ^= 0x01;
+    !aes_gcm::open_detached(&key, &GCM_IV, &GCM_CT, &bad, &GCM_AAD, &mut pt)
+}
+
+fn hmac_kat() -> bool {
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(HMAC_KEY) else {
+        return false;
+    };
+    mac.update(HMAC_DATA);
+    mac.finalize().into_bytes()[..] == HMAC_TAG
+}
+
+/// HKDF derivation: deterministic, and separated by purpose / context.
+fn derive_kat() -> bool {
+    let parent = GuardedKey32::init_with(|k| k.fill(0x5A));
+
+    let mut a = GuardedKey32::zeroed();
+    let mut b = GuardedKey32::zeroed();
+    let mut c = GuardedKey32::zeroed();
+    let mut d = GuardedKey32::zeroed();
+
+    derive_key(&parent, Purpose::Metadata, 1, &mut a).is_ok()
+        && derive_key(&parent, Purpose::Metadata, 1, &mut b).is_ok()
+        && derive_key(&parent, Purpose::Metadata, 2, &mut c).is_ok()
+        && derive_key(&parent, Purpose::Recovery, 1, &mut d).is_ok()
+        && a.borrow() == b.borrow()
+        && a.borrow() != c.borrow()
+        && a.borrow() != d.borrow()
+        && a.borrow() != parent.borrow()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn self_test_passes() {
+        assert!(run());
+    }
+}
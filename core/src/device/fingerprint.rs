@@ -5,7 +5,16 @@
 
 #![deny(clippy::derive_debug)]
 
-use crate::integrity::hash::hash_sha256;
+use crate::integrity::hash::{hash_sha256, HashOutput};
+
+/// Version of fingerprints built by `from_material` (single opaque
+/// material; also every identity persisted before versioning).
+pub const FINGERPRINT_VERSION_LEGACY: u8 = 0;
+
+/// Domain label for multi-source fingerprints.
+///
+/// ⚠️ MUST NEVER CHANGE.
+const SOURCES_LABEL: &[u8] = b"rcx:device:fp:sources";
 
 /// Canonical device fingerprint.
 ///
@@ -13,8 +22,12 @@ use crate::integrity::hash::hash_sha256;
 /// - Non-secret
 /// - Stable
 /// - Fixed-width
+/// - Carries the source-set version it was built under
 #[derive(Clone, Copy, PartialEq, Eq)]
-pub struct DeviceFingerprint(u64);
+pub struct DeviceFingerprint {
+    value: u64,
+    version: u8,
+}
 
 impl DeviceFingerprint {
     /// Create fingerprint from canonical device material.
@@ -24,23 +37,46 @@ impl DeviceFingerprint {
     /// - One-way hash
     /// - Stable across reboots
     pub fn from_material(material: &[u8]) -> Self {
-        let hash = hash_sha256(material);
+        Self {
+            value: truncate(&hash_sha256(material)),
+            version: FINGERPRINT_VERSION_LEGACY,
+        }
+    }
 
-        // Truncate SHA-256 → u64 (BE)
-        let mut buf = [0u8; 8];
-        buf.copy_from_slice(&hash.as_ref()[..8]);
+    /// Create fingerprint from several stable device sources
+    /// (e.g. hardware serial, install UUID, platform id).
+    ///
+    /// Encoding hashed:
+    /// `label || version (1) || count_be (8) || { len_be (8) || source }*`
+    ///
+    /// SECURITY:
+    /// - Deterministic, order-sensitive, unambiguous (length-prefixed)
+    /// - `version` is bound into the hash AND kept alongside it, so a
+    ///   future source set cannot silently collide with this one
+    /// - Legacy version (0) or no sources => `None`
+    pub fn from_sources(sources: &[&[u8]], version: u8) -> Option<Self> {
+        let encoded = encode_sources(sources, version)?;
 
-        Self(u64::from_be_bytes(buf))
+        Some(Self {
+            value: truncate(&hash_sha256(&encoded)),
+            version,
+        })
     }
 
-    /// Reconstruct fingerprint from stored value.
+    /// Reconstruct fingerprint from stored value and version.
     ///
     /// SECURITY:
     /// - Deterministic
     /// - No hashing
     /// - No ambiguity
-    pub(crate) fn from_u64(value: u64) -> Self {
-        Self(value)
+    pub(crate) fn from_stored(value: u64, version: u8) -> Self {
+        Self { value, version }
+    }
+
+    /// Source-set version this fingerprint was built under.
+    #[inline(always)]
+    pub fn fingerprint_version(self) -> u8 {
+        self.version
     }
 
     /// Big-endian bytes.
@@ -50,7 +86,7 @@ impl DeviceFingerprint {
     /// - AAD binding
     #[inline(always)]
     pub fn to_be_bytes(self) -> [u8; 8] {
-        self.0.to_be_bytes()
+        self.value.to_be_bytes()
     }
 
     /// Numeric fingerprint value.
@@ -59,12 +95,65 @@ impl DeviceFingerprint {
     /// - derive_key(context)
     #[inline(always)]
     pub fn as_u64(self) -> u64 {
-        self.0
+        self.value
     }
 }
 
+/// Canonical multi-source encoding (see `from_sources`).
+pub(crate) fn encode_sources(sources: &[&[u8]], version: u8) -> Option<Vec<u8>> {
+    if version == FINGERPRINT_VERSION_LEGACY || sources.is_empty() {
+        return None;
+    }
+
+    let total = sources.iter().map(|s| 8 + s.len()).sum::<usize>();
+    let mut buf = Vec::with_capacity(SOURCES_LABEL.len() + 1 + 8 + total);
+
+    buf.extend_from_slice(SOURCES_LABEL);
+    buf.push(version);
+    buf.extend_from_slice(&(sources.len() as u64).to_be_bytes());
+    for source in sources {
+        buf.extend_from_slice(&(source.len() as u64).to_be_bytes());
+        buf.extend_from_slice(source);
+    }
+
+    Some(buf)
+}
+
+/// Truncate SHA-256 → u64 (BE)
+#[inline(always)]
+fn truncate(hash: &HashOutput) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&hash.as_ref()[..8]);
+    u64::from_be_bytes(buf)
+}
+
 impl core::fmt::Debug for DeviceFingerprint {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.write_str("<DeviceFingerprint>")
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_are_length_prefixed_and_versioned() -> Result<(), ()> {
+        let a = DeviceFingerprint::from_sources(&[b"serial", b"uuid"], 1).ok_or(())?;
+
+        assert!(DeviceFingerprint::from_sources(&[b"serial", b"uuid"], 1) == Some(a));
+        assert_eq!(a.fingerprint_version(), 1);
+
+        // Moving bytes across the source boundary changes the value
+        let shifted = DeviceFingerprint::from_sources(&[b"serialu", b"uid"], 1).ok_or(())?;
+        assert!(shifted.as_u64() != a.as_u64());
+
+        // Same sources, new version => different fingerprint
+        let v2 = DeviceFingerprint::from_sources(&[b"serial", b"uuid"], 2).ok_or(())?;
+        assert!(v2.as_u64() != a.as_u64());
+
+        assert!(DeviceFingerprint::from_sources(&[b"serial"], FINGERPRINT_VERSION_LEGACY).is_none());
+        assert!(DeviceFingerprint::from_sources(&[], 1).is_none());
+        Ok(())
+    }
+}
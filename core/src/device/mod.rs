@@ -30,4 +30,4 @@ pub mod registry;
 pub use fingerprint::DeviceFingerprint;
 
 // Registry (stateful, persistent)
-pub use registry::{DeviceRegistry, RegistryError};
+pub use registry::{DeviceRegistry, PeerRecord, RegistryError, Revocations};
@@ -6,6 +6,8 @@
 //! - Persist stable device identity
 //! - Persist irreversible kill state
 //! - Provide deterministic device identifiers
+//! - Track peer devices (fleet metadata, `peers.bin`)
+//! - Track revoked devices (`revoked.bin`, append-only, irreversible)
 //!
 //! AUTHORITATIVE KILL SEMANTICS:
 //! - Device is killed IFF a kill record EXISTS
@@ -14,16 +16,27 @@
 //!
 //! SECURITY INVARIANTS:
 //! - No secrets stored
-//! - Identity is fixed-size and overwrite-only
+//! - Identity is fixed-size and overwrite-only; a legacy 40-byte
+//!   or unframed 41-byte identity is migrated ONCE to the versioned
+//!   layout (atomic
+//!   replace + read-back; fail-closed if it does not stick)
+//! - First-time identity creation has exactly one writer (the
+//!   complete file is hard-linked into place, never overwritten);
+//!   racing initializers adopt the winner's identity
 //! - Kill state is append-only and monotonic
 //! - No panics
 //! - Deterministic decoding
+//! - Peer records are fixed-size; device IDs are unique (a
+//!   duplicate in the log is ambiguity => `Corrupt`)
+//! - Revocation lookups scan the WHOLE list in constant time per
+//!   entry; any read / decode error => treated as revoked
 
 #![deny(clippy::derive_debug)]
 
-use crate::device::fingerprint::DeviceFingerprint;
+use crate::device::fingerprint::{self, DeviceFingerprint, FINGERPRINT_VERSION_LEGACY};
 use crate::integrity::hash::hash_sha256;
 use crate::logging::encrypted::EncryptedLog;
+use crate::memory::ct_eq;
 
 /* ───────────── TYPES ───────────── */
 
@@ -33,12 +46,45 @@ pub struct DeviceRegistry {
     fingerprint: DeviceFingerprint,
 }
 
+/// Current identity format (leading byte of the identity blob).
+pub const IDENTITY_FORMAT_V1: u8 = 1;
+
+/// Identity length:
+/// `format (1) || device_id (32) || fingerprint (8, BE) || fp_version (1)`.
+pub const IDENTITY_LEN: usize = 42;
+
+/// Unversioned identity: `device_id (32) || fingerprint (8, BE)`.
+const IDENTITY_LEN_LEGACY: usize = 40;
+
+/// Pre-format-byte identity:
+/// `device_id (32) || fingerprint (8, BE) || fp_version (1)`.
+const IDENTITY_LEN_UNFRAMED: usize = 41;
+
+/// Revocation record length: `device_id (32)`.
+pub const REVOKED_RECORD_LEN: usize = 32;
+
+/// Peer record length: `device_id (32) || fingerprint (8, BE)`.
+pub const PEER_RECORD_LEN: usize = 40;
+
+/// Registered peer device (non-secret).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct PeerRecord {
+    pub device_id: [u8; 32],
+    pub fingerprint: u64,
+}
+
 /* ───────────── ERRORS ───────────── */
 
 #[derive(Debug)]
 pub enum RegistryError {
     Storage,
     Corrupt,
+    /// Device ID already registered (or is this device)
+    Duplicate,
+    /// Fingerprint sources / version rejected
+    InvalidSources,
+    /// This device cannot revoke itself (its kill path must stay live)
+    SelfRevocation,
 }
 
 /* ───────────── IMPLEMENTATION ───────────── */
@@ -50,38 +96,58 @@ impl DeviceRegistry {
     ///
     /// SECURITY:
     /// - Must be called exactly once at startup
-    /// - Fixed-size identity (40 bytes)
+    /// - Fixed-size identity (`IDENTITY_LEN`)
     /// - Fails closed on corruption or IO error
+    /// - Concurrent first boots agree on ONE identity: it is
+    ///   published whole (atomic, no overwrite) or not at all
     pub fn load_or_init(
         device_material: &[u8],
     ) -> Result<Self, RegistryError> {
-        let mut id_log =
-            EncryptedLog::open_device_identity()
-                .map_err(|_| RegistryError::Storage)?;
+        Self::load_or_init_identity(
+            *hash_sha256(device_material).as_ref(),
+            DeviceFingerprint::from_material(device_material),
+        )
+    }
 
+    /// Load or initialize the registry from several device sources.
+    ///
+    /// SECURITY:
+    /// - Same guarantees as `load_or_init`
+    /// - An EXISTING identity is adopted as stored (with its own
+    ///   fingerprint version): a changed source never re-keys a device
+    /// - Legacy version / no sources => `InvalidSources`
+    pub fn load_or_init_with_sources(
+        sources: &[&[u8]],
+        version: u8,
+    ) -> Result<Self, RegistryError> {
+        let encoded = fingerprint::encode_sources(sources, version)
+            .ok_or(RegistryError::InvalidSources)?;
+        let fingerprint = DeviceFingerprint::from_sources(sources, version)
+            .ok_or(RegistryError::InvalidSources)?;
+
+        Self::load_or_init_identity(*hash_sha256(&encoded).as_ref(), fingerprint)
+    }
+
+    fn load_or_init_identity(
+        device_id: [u8; 32],
+        fingerprint: DeviceFingerprint,
+    ) -> Result<Self, RegistryError> {
         // ───── Try load existing identity ─────
-        if let Some(buf) =
-            id_log.read_fixed().map_err(|_| RegistryError::Storage)?
-        {
-            return Self::decode_identity(&buf);
+        if let Some(registry) = Self::load_existing()? {
+            return Ok(registry);
         }
 
-        // ───── First-time initialization ─────
-        let hash = hash_sha256(device_material);
-        let fingerprint = DeviceFingerprint::from_material(device_material);
+        // ───── First-time initialization (atomic publish) ─────
+        let registry = Self { device_id, fingerprint };
 
-        let mut buf = Vec::with_capacity(40);
-        buf.extend_from_slice(hash.as_ref());
-        buf.extend_from_slice(&fingerprint.as_u64().to_be_bytes());
-
-        id_log
-            .write_fixed(&buf)
-            .map_err(|_| RegistryError::Storage)?;
+        if EncryptedLog::publish_device_identity(&registry.encode_identity())
+            .map_err(|_| RegistryError::Storage)?
+        {
+            return Ok(registry);
+        }
 
-        Ok(Self {
-            device_id: *hash.as_ref(),
-            fingerprint,
-        })
+        // Lost the race: the winner's identity is already complete
+        Self::load_existing()?.ok_or(RegistryError::Storage)
     }
 
     /* ───────────── ACCESSORS ───────────── */
@@ -98,12 +164,19 @@ impl DeviceRegistry {
         self.fingerprint.as_u64()
     }
 
+    /// Source-set version the stored fingerprint was built under.
+    #[inline(always)]
+    pub fn fingerprint_version(&self) -> u8 {
+        self.fingerprint.fingerprint_version()
+    }
+
     /* ───────────── KILL STATE ───────────── */
 
     /// Check if device is permanently killed.
     ///
     /// SEMANTICS:
     /// - Killed iff ANY kill record exists
+    /// - Broken kill-log hash chain => killed (truncated evidence)
     /// - Fail-closed on any error
     pub fn is_killed(&self) -> bool {
         let mut log = match EncryptedLog::open_device_kill_log() {
@@ -111,6 +184,10 @@ impl DeviceRegistry {
             Err(_) => return true, // FAIL CLOSED
         };
 
+        if log.verify_chain().is_err() {
+            return true; // FAIL CLOSED
+        }
+
         match log.read_records() {
             Ok(records) => !records.is_empty(),
             Err(_) => true, // FAIL CLOSED
@@ -134,21 +211,167 @@ impl DeviceRegistry {
             .map_err(|_| RegistryError::Storage)
     }
 
+    /* ───────────── PEERS ───────────── */
+
+    /// Register a peer device.
+    ///
+    /// SECURITY:
+    /// - Append-only
+    /// - Duplicate device ID (or this device's own ID) => `Duplicate`
+    /// - Existing log MUST decode cleanly first (fail-closed)
+    pub fn register_peer(
+        &self,
+        device_id: [u8; 32],
+        fingerprint: u64,
+    ) -> Result<(), RegistryError> {
+        if device_id == self.device_id {
+            return Err(RegistryError::Duplicate);
+        }
+
+        let mut log =
+            EncryptedLog::open_peer_log()
+                .map_err(|_| RegistryError::Storage)?;
+
+        let peers = Self::read_peers(&mut log)?;
+        if peers.iter().any(|p| p.device_id == device_id) {
+            return Err(RegistryError::Duplicate);
+        }
+
+        log.append_record(&encode_peer(&PeerRecord { device_id, fingerprint }))
+            .map_err(|_| RegistryError::Storage)
+    }
+
+    /// All registered peers, in registration order.
+    pub fn list_peers(&self) -> Result<Vec<PeerRecord>, RegistryError> {
+        let mut log =
+            EncryptedLog::open_peer_log()
+                .map_err(|_| RegistryError::Storage)?;
+
+        Self::read_peers(&mut log)
+    }
+
+    /* ───────────── REVOCATION ───────────── */
+
+    /// Permanently revoke a (decommissioned) device ID.
+    ///
+    /// SECURITY:
+    /// - Append-only, irreversible (no un-revoke exists)
+    /// - Idempotent: an already-revoked ID is not appended twice
+    /// - This device's own ID => `SelfRevocation`
+    pub fn revoke(&self, device_id: [u8; 32]) -> Result<(), RegistryError> {
+        if device_id == self.device_id {
+            return Err(RegistryError::SelfRevocation);
+        }
+
+        let mut log =
+            EncryptedLog::open_revocation_log()
+                .map_err(|_| RegistryError::Storage)?;
+
+        let records = log.read_records().map_err(|_| RegistryError::Storage)?;
+        if contains_revoked(&records, &device_id)? {
+            return Ok(());
+        }
+
+        log.append_record(&device_id)
+            .map_err(|_| RegistryError::Storage)
+    }
+
+    /// Check whether `device_id` has been revoked.
+    ///
+    /// SEMANTICS:
+    /// - Constant-time over the whole list (no early exit)
+    /// - Fail-closed: any storage / decode error => revoked
+    pub fn is_revoked(&self, device_id: &[u8; 32]) -> bool {
+        self.revocations().contains(device_id)
+    }
+
+    /// Load the revocation list ONCE for in-memory lookups.
+    ///
+    /// SECURITY:
+    /// - All storage I/O happens here, never inside a lookup (keeps
+    ///   I/O timing out of constant-time decisions)
+    /// - Unreadable / malformed list => every lookup reports revoked
+    pub fn revocations(&self) -> Revocations {
+        let records = EncryptedLog::open_revocation_log()
+            .and_then(|mut log| log.read_records())
+            .ok();
+
+        Revocations { records }
+    }
+
+    fn read_peers(log: &mut EncryptedLog) -> Result<Vec<PeerRecord>, RegistryError> {
+        let records = log.read_records().map_err(|_| RegistryError::Storage)?;
+        decode_peers(&records)
+    }
+
     /* ───────────── INTERNAL ───────────── */
 
-    fn decode_identity(buf: &[u8]) -> Result<Self, RegistryError> {
-        if buf.len() != 40 {
-            return Err(RegistryError::Corrupt);
+    /// Load the published identity; `None` if there is none yet.
+    fn load_existing() -> Result<Option<Self>, RegistryError> {
+        let Some(mut id_log) = EncryptedLog::open_device_identity_read_only()
+            .map_err(|_| RegistryError::Storage)?
+        else {
+            return Ok(None);
+        };
+
+        match id_log.read_fixed().map_err(|_| RegistryError::Storage)? {
+            Some(buf) => Self::load_identity(&mut id_log, &buf).map(Some),
+            None => Ok(None),
         }
+    }
+
+    /// Decode a stored identity, migrating older layouts in place.
+    ///
+    /// SECURITY:
+    /// - Migration is an atomic whole-blob replace, then read back;
+    ///   anything but the exact new blob => `Storage` (fail-closed)
+    /// - A failed migration leaves the legacy blob intact (retried
+    ///   on next load), never a half-written identity
+    fn load_identity(id_log: &mut EncryptedLog, buf: &[u8]) -> Result<Self, RegistryError> {
+        let registry = Self::decode_identity(buf)?;
+        if buf.len() == IDENTITY_LEN {
+            return Ok(registry);
+        }
+
+        let migrated = registry.encode_identity();
+        id_log
+            .replace_fixed(&migrated)
+            .map_err(|_| RegistryError::Storage)?;
+
+        match id_log.read_fixed() {
+            Ok(Some(stored)) if stored[..] == migrated[..] => Ok(registry),
+            _ => Err(RegistryError::Storage),
+        }
+    }
+
+    fn encode_identity(&self) -> [u8; IDENTITY_LEN] {
+        let mut buf = [0u8; IDENTITY_LEN];
+        buf[0] = IDENTITY_FORMAT_V1;
+        buf[1..33].copy_from_slice(&self.device_id);
+        buf[33..41].copy_from_slice(&self.fingerprint.to_be_bytes());
+        buf[41] = self.fingerprint.fingerprint_version();
+        buf
+    }
+
+    /// Decode `IDENTITY_FORMAT_V1`, unframed (41-byte) or legacy
+    /// (fingerprint version 0) identities; any other length / format
+    /// => `Corrupt`.
+    fn decode_identity(buf: &[u8]) -> Result<Self, RegistryError> {
+        let (body, version) = match buf.len() {
+            IDENTITY_LEN if buf[0] == IDENTITY_FORMAT_V1 => (&buf[1..41], buf[41]),
+            IDENTITY_LEN_UNFRAMED => (&buf[..40], buf[40]),
+            IDENTITY_LEN_LEGACY => (buf, FINGERPRINT_VERSION_LEGACY),
+            _ => return Err(RegistryError::Corrupt),
+        };
 
         let mut id = [0u8; 32];
-        id.copy_from_slice(&buf[..32]);
+        id.copy_from_slice(&body[..32]);
 
         let mut fp = [0u8; 8];
-        fp.copy_from_slice(&buf[32..40]);
+        fp.copy_from_slice(&body[32..40]);
 
         let fingerprint =
-            DeviceFingerprint::from_u64(u64::from_be_bytes(fp));
+            DeviceFingerprint::from_stored(u64::from_be_bytes(fp), version);
 
         Ok(Self {
             device_id: id,
@@ -157,3 +380,304 @@ impl DeviceRegistry {
     }
 }
 
+/* ───────────── REVOCATION CODEC ───────────── */
+
+/// Snapshot of the revocation list (see `DeviceRegistry::revocations`).
+pub struct Revocations {
+    // `None` => unreadable (fail-closed); malformed is caught per lookup
+    records: Option<Vec<Vec<u8>>>,
+}
+
+impl Revocations {
+    /// Whether `device_id` is revoked: in memory, constant-time over
+    /// the whole list; a failed load => `true` (FAIL CLOSED).
+    pub fn contains(&self, device_id: &[u8; 32]) -> bool {
+        match &self.records {
+            Some(records) => contains_revoked(records, device_id).unwrap_or(true),
+            None => true,
+        }
+    }
+}
+
+/// Constant-time membership scan; wrong record length => `Corrupt`.
+fn contains_revoked(records: &[Vec<u8>], device_id: &[u8; 32]) -> Result<bool, RegistryError> {
+    let mut found = false;
+    let mut malformed = false;
+
+    for rec in records {
+        malformed |= rec.len() != REVOKED_RECORD_LEN;
+        found |= ct_eq(rec, device_id);
+    }
+
+    if malformed {
+        return Err(RegistryError::Corrupt);
+    }
+    Ok(found)
+}
+
+/* ───────────── PEER CODEC ───────────── */
+
+fn encode_peer(peer: &PeerRecord) -> [u8; PEER_RECORD_LEN] {
+    let mut rec = [0u8; PEER_RECORD_LEN];
+    rec[..32].copy_from_slice(&peer.device_id);
+    rec[32..].copy_from_slice(&peer.fingerprint.to_be_bytes());
+    rec
+}
+
+/// Decode every peer record; wrong length or repeated ID => `Corrupt`.
+fn decode_peers(records: &[Vec<u8>]) -> Result<Vec<PeerRecord>, RegistryError> {
+    let mut peers: Vec<PeerRecord> = Vec::with_capacity(records.len());
+
+    for rec in records {
+        if rec.len() != PEER_RECORD_LEN {
+            return Err(RegistryError::Corrupt);
+        }
+
+        let mut device_id = [0u8; 32];
+        device_id.copy_from_slice(&rec[..32]);
+
+        let mut fp = [0u8; 8];
+        fp.copy_from_slice(&rec[32..]);
+
+        if peers.iter().any(|p| p.device_id == device_id) {
+            return Err(RegistryError::Corrupt);
+        }
+
+        peers.push(PeerRecord {
+            device_id,
+            fingerprint: u64::from_be_bytes(fp),
+        });
+    }
+
+    Ok(peers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(b: u8, fingerprint: u64) -> PeerRecord {
+        PeerRecord {
+            device_id: [b; 32],
+            fingerprint,
+        }
+    }
+
+    #[test]
+    fn peers_decode_in_order() {
+        let records = vec![
+            encode_peer(&peer(1, 10)).to_vec(),
+            encode_peer(&peer(2, 20)).to_vec(),
+        ];
+
+        assert!(matches!(
+            decode_peers(&records).as_deref(),
+            Ok([a, b]) if *a == peer(1, 10) && *b == peer(2, 20)
+        ));
+    }
+
+    #[test]
+    fn ambiguous_peer_log_fails_closed() {
+        let dup = vec![
+            encode_peer(&peer(1, 10)).to_vec(),
+            encode_peer(&peer(1, 11)).to_vec(),
+        ];
+        assert!(matches!(decode_peers(&dup), Err(RegistryError::Corrupt)));
+
+        let short = vec![encode_peer(&peer(1, 10))[..PEER_RECORD_LEN - 1].to_vec()];
+        assert!(matches!(decode_peers(&short), Err(RegistryError::Corrupt)));
+    }
+
+    #[test]
+    fn revocation_scan_matches_and_fails_closed() {
+        let records = vec![[1u8; 32].to_vec(), [2u8; 32].to_vec()];
+
+        assert!(matches!(contains_revoked(&records, &[2; 32]), Ok(true)));
+        assert!(matches!(contains_revoked(&records, &[3; 32]), Ok(false)));
+        assert!(matches!(contains_revoked(&[], &[3; 32]), Ok(false)));
+
+        let torn = vec![[1u8; 32].to_vec(), vec![2u8; 31]];
+        assert!(matches!(contains_revoked(&torn, &[1; 32]), Err(RegistryError::Corrupt)));
+    }
+
+    #[test]
+    fn revocation_is_persistent_and_excludes_self() {
+        crate::logging::encrypted::init_test_log_root();
+
+        let registry = DeviceRegistry {
+            device_id: [0xEF; 32],
+            fingerprint: DeviceFingerprint::from_stored(1, FINGERPRINT_VERSION_LEGACY),
+        };
+
+        let mut id = [0u8; 32];
+        rand_core::RngCore::fill_bytes(&mut rand_core::OsRng, &mut id);
+
+        assert!(!registry.is_revoked(&id));
+        assert!(registry.revoke(id).is_ok());
+        assert!(registry.revoke(id).is_ok());
+        assert!(registry.is_revoked(&id));
+
+        // A snapshot answers from memory: later revocations need a reload
+        let mut other = [0u8; 32];
+        rand_core::RngCore::fill_bytes(&mut rand_core::OsRng, &mut other);
+        let snapshot = registry.revocations();
+        assert!(registry.revoke(other).is_ok());
+        assert!(snapshot.contains(&id) && !snapshot.contains(&other));
+        assert!(registry.revocations().contains(&other));
+
+        assert!(matches!(
+            registry.revoke([0xEF; 32]),
+            Err(RegistryError::SelfRevocation)
+        ));
+    }
+
+    fn legacy_identity(device_id: [u8; 32], fingerprint: u64) -> Vec<u8> {
+        let mut buf = device_id.to_vec();
+        buf.extend_from_slice(&fingerprint.to_be_bytes());
+        buf
+    }
+
+    #[test]
+    fn identity_round_trips() -> Result<(), RegistryError> {
+        let fingerprint = DeviceFingerprint::from_sources(&[b"serial", b"uuid"], 3)
+            .ok_or(RegistryError::InvalidSources)?;
+        let registry = DeviceRegistry { device_id: [0xAB; 32], fingerprint };
+
+        let buf = registry.encode_identity();
+        assert_eq!(buf[0], IDENTITY_FORMAT_V1);
+        assert!(matches!(
+            DeviceRegistry::decode_identity(&buf),
+            Ok(r) if r.device_id == [0xAB; 32]
+                && r.fingerprint == fingerprint
+                && r.fingerprint_version() == 3
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_identity_loads_and_migrates_once() -> Result<(), ()> {
+        let name = format!("identity-migrate-{}", rand_core::RngCore::next_u64(&mut rand_core::OsRng));
+        let mut log = crate::logging::encrypted::open_test_fixed(&name)?;
+
+        let legacy = legacy_identity([0xCD; 32], 77);
+        assert!(log.replace_fixed(&legacy).is_ok());
+
+        // Legacy load: decodes as fingerprint version 0 and rewrites
+        assert!(matches!(
+            DeviceRegistry::load_identity(&mut log, &legacy),
+            Ok(r) if r.device_id == [0xCD; 32]
+                && r.device_fingerprint() == 77
+                && r.fingerprint_version() == FINGERPRINT_VERSION_LEGACY
+        ));
+
+        // Migrated reload: versioned layout on disk, same identity
+        let stored = log.read_fixed()?.ok_or(())?;
+        assert_eq!(stored.len(), IDENTITY_LEN);
+        assert_eq!(stored[0], IDENTITY_FORMAT_V1);
+        assert!(matches!(
+            DeviceRegistry::load_identity(&mut log, &stored),
+            Ok(r) if r.device_id == [0xCD; 32] && r.device_fingerprint() == 77
+        ));
+        assert!(matches!(log.read_fixed(), Ok(Some(again)) if again == stored));
+        Ok(())
+    }
+
+    #[test]
+    fn unframed_identity_keeps_its_fingerprint_version_and_migrates() -> Result<(), ()> {
+        let name = format!("identity-unframed-{}", rand_core::RngCore::next_u64(&mut rand_core::OsRng));
+        let mut log = crate::logging::encrypted::open_test_fixed(&name)?;
+
+        // Written by builds that versioned the fingerprint but had no
+        // leading format byte
+        let mut unframed = legacy_identity([0xEE; 32], 99);
+        unframed.push(3);
+        assert_eq!(unframed.len(), IDENTITY_LEN_UNFRAMED);
+        assert!(log.replace_fixed(&unframed).is_ok());
+
+        assert!(matches!(
+            DeviceRegistry::load_identity(&mut log, &unframed),
+            Ok(r) if r.device_id == [0xEE; 32]
+                && r.device_fingerprint() == 99
+                && r.fingerprint_version() == 3
+        ));
+
+        let stored = log.read_fixed()?.ok_or(())?;
+        assert_eq!(stored.len(), IDENTITY_LEN);
+        assert_eq!(stored[0], IDENTITY_FORMAT_V1);
+        assert!(matches!(
+            DeviceRegistry::decode_identity(&stored),
+            Ok(r) if r.device_id == [0xEE; 32]
+                && r.device_fingerprint() == 99
+                && r.fingerprint_version() == 3
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn corrupt_identity_length_or_format_fails_closed() {
+        let legacy = legacy_identity([0xCD; 32], 77);
+
+        for len in [0, 39, IDENTITY_LEN + 1] {
+            let buf = vec![IDENTITY_FORMAT_V1; len];
+            assert!(matches!(
+                DeviceRegistry::decode_identity(&buf),
+                Err(RegistryError::Corrupt)
+            ));
+        }
+
+        // Right length, unknown format byte
+        let mut unknown = vec![0u8; IDENTITY_LEN];
+        unknown[0] = 0x7F;
+        unknown[1..41].copy_from_slice(&legacy);
+        assert!(matches!(
+            DeviceRegistry::decode_identity(&unknown),
+            Err(RegistryError::Corrupt)
+        ));
+    }
+
+    #[test]
+    fn concurrent_first_boot_agrees_on_one_identity() {
+        crate::logging::encrypted::init_test_log_root();
+
+        let spawn = |material: &'static [u8]| {
+            std::thread::spawn(move || {
+                DeviceRegistry::load_or_init(material).map(|r| r.device_id())
+            })
+        };
+
+        let a = spawn(b"device-material-a");
+        let b = spawn(b"device-material-b");
+
+        let (a, b) = (a.join(), b.join());
+        assert!(matches!((&a, &b), (Ok(Ok(x)), Ok(Ok(y))) if x == y));
+    }
+
+    #[test]
+    fn duplicate_registration_is_rejected() {
+        crate::logging::encrypted::init_test_log_root();
+
+        let registry = DeviceRegistry {
+            device_id: [0xEE; 32],
+            fingerprint: DeviceFingerprint::from_stored(1, FINGERPRINT_VERSION_LEGACY),
+        };
+
+        // Unique per run: the peer log is shared process-wide
+        let mut id = [0u8; 32];
+        rand_core::RngCore::fill_bytes(&mut rand_core::OsRng, &mut id);
+
+        assert!(registry.register_peer(id, 42).is_ok());
+        assert!(matches!(
+            registry.register_peer(id, 43),
+            Err(RegistryError::Duplicate)
+        ));
+        assert!(matches!(
+            registry.register_peer([0xEE; 32], 7),
+            Err(RegistryError::Duplicate)
+        ));
+
+        assert!(matches!(
+            registry.list_peers(),
+            Ok(peers) if peers.iter().filter(|p| p.device_id == id).count() == 1
+        ));
+    }
+}
@@ -0,0 +1,35 @@
+//! Embedded (no-std) public surface.
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! Replaces `bridge::*` for HSM-like targets: stateless crypto
+//! primitives and guarded memory only. There is no keystore, kill
+//! switch, or persistent log — the embedder owns all state.
+//!
+//! REQUIREMENTS:
+//! - A `#[global_allocator]` provided by the embedder
+//! - Memory locking is best effort: check `is_locked()`
+//!
+//! ❄️ SUBJECT TO SECURE CORE API FREEZE ❄️
+
+// ───── Key derivation ─────
+pub use crate::crypto::{derive_key, derive_key_with_domain, Purpose, MAX_DOMAIN_LEN};
+
+// ───── Nonces ─────
+pub use crate::crypto::{derive_nonce, NONCE_LEN};
+pub use crate::crypto::nonce::{derive_nonce_versioned, derive_nonce_with_epoch};
+
+// ───── AEAD (AES-256-GCM) ─────
+pub use crate::crypto::aes_gcm::{open, open_detached, seal, seal_detached, TAG_LEN};
+
+// ───── Guarded memory ─────
+pub use crate::memory::{
+    ct_eq,
+    wipe_bytes,
+    wipe_vec,
+    GuardedBox,
+    GuardedKey32,
+    GuardedVec,
+    Secret,
+    MAX_GUARDED_VEC_LEN,
+};
@@ -0,0 +1,22 @@
+//! Fuzz hooks (NOT A STABLE SURFACE).
+//!
+//! TRUST LEVEL: Test harness only
+//!
+//! Exposes pure, key-free parsers so `core/fuzz` targets can hit
+//! length / version edge cases without keys or registry state.
+//!
+//! RULES:
+//! - Feature `fuzzing` ONLY; never in shipped builds
+//! - No secrets, no I/O, no global state
+
+use crate::kill;
+
+/// Kill blob split: sealed body length, or `None` if too short.
+pub fn split_kill_blob(blob: &[u8]) -> Option<usize> {
+    kill::split_blob(blob).map(|(_, sealed)| sealed.len())
+}
+
+/// Kill plaintext parse: `true` iff the payload is well-formed.
+pub fn parse_kill_plaintext(buf: &[u8]) -> bool {
+    kill::parse_kill_plaintext(buf).is_some()
+}
@@ -0,0 +1,112 @@
+//! Metadata integrity tag (Secure Core).
+//!
+//! PURPOSE:
+//! Authenticate NON-SECRET file metadata (name, size, chunk count)
+//! stored in the cloud next to ciphertext but outside the chunk AAD.
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! SECURITY INVARIANTS (ENFORCED):
+//! - HMAC-SHA256 only
+//! - Key derived per file (`Purpose::Metadata`, MAC domain, file_id)
+//! - Derived MAC key lives in a GuardedKey32 (zeroized on drop)
+//! - Fixed, unambiguous message encoding
+//! - Constant-time verification
+//! - Fail-closed on any error
+//! - No panics
+
+use crate::crypto::derive::{derive_key_with_domain, Purpose};
+use crate::integrity::verify::IntegrityError;
+use crate::memory::{ct_eq, GuardedKey32};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Derivation domain separating MAC keys from other metadata keys.
+///
+/// ⚠️ MUST NEVER CHANGE.
+const METADATA_MAC_DOMAIN: &[u8] = b"metamac";
+
+/// Message label (METADATA TAG ONLY).
+///
+/// ⚠️ MUST NEVER CHANGE.
+const METADATA_MAC_LABEL: &[u8] = b"rcxcloud:integrity:metadata:v1";
+
+/// Metadata tag length.
+pub const METADATA_TAG_LEN: usize = 32;
+
+/// Compute the metadata tag for `file_id`.
+///
+/// Message encoding:
+/// `label || file_id_be (8) || metadata_len_be (8) || metadata`
+pub fn compute_metadata_tag(
+    session_key: &GuardedKey32,
+    file_id: u64,
+    metadata: &[u8],
+) -> Result<[u8; METADATA_TAG_LEN], IntegrityError> {
+    let mut mac_key = GuardedKey32::zeroed();
+    derive_key_with_domain(
+        session_key,
+        Purpose::Metadata,
+        METADATA_MAC_DOMAIN,
+        file_id,
+        &mut mac_key,
+    )
+    .map_err(|_| IntegrityError::Invalid)?;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(mac_key.borrow())
+        .map_err(|_| IntegrityError::Invalid)?;
+    drop(mac_key);
+
+    mac.update(METADATA_MAC_LABEL);
+    mac.update(&file_id.to_be_bytes());
+    mac.update(&(metadata.len() as u64).to_be_bytes());
+    mac.update(metadata);
+
+    let mut out = [0u8; METADATA_TAG_LEN];
+    out.copy_from_slice(&mac.finalize().into_bytes());
+    Ok(out)
+}
+
+/// Verify a stored metadata tag.
+///
+/// SECURITY:
+/// - Constant-time comparison
+/// - Wrong-length tag / derivation failure => Err
+pub fn verify_metadata_tag(
+    session_key: &GuardedKey32,
+    file_id: u64,
+    metadata: &[u8],
+    tag: &[u8],
+) -> Result<(), IntegrityError> {
+    let expected = compute_metadata_tag(session_key, file_id, metadata)?;
+
+    if ct_eq(tag, &expected) {
+        Ok(())
+    } else {
+        Err(IntegrityError::Invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> GuardedKey32 {
+        GuardedKey32::init_with(|k| k.fill(byte))
+    }
+
+    #[test]
+    fn tag_round_trips_and_binds_inputs() -> Result<(), ()> {
+        let k = key(7);
+        let tag = compute_metadata_tag(&k, 42, b"name=a.txt;size=9").map_err(|_| ())?;
+
+        assert!(verify_metadata_tag(&k, 42, b"name=a.txt;size=9", &tag).is_ok());
+
+        // Tampered metadata / other file / other key / truncated tag
+        assert!(verify_metadata_tag(&k, 42, b"name=a.txt;size=8", &tag).is_err());
+        assert!(verify_metadata_tag(&k, 43, b"name=a.txt;size=9", &tag).is_err());
+        assert!(verify_metadata_tag(&key(8), 42, b"name=a.txt;size=9", &tag).is_err());
+        assert!(verify_metadata_tag(&k, 42, b"name=a.txt;size=9", &tag[..31]).is_err());
+        Ok(())
+    }
+}
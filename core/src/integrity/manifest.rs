@@ -0,0 +1,269 @@
+//! File chunk manifest (Merkle root over chunk tags).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! PURPOSE:
+//! Commit to the ORDERED set of a file's ciphertext chunks so the
+//! cloud cannot reorder, drop or truncate them unnoticed, while a
+//! client can still verify ONE downloaded chunk on its own.
+//!
+//! CONSTRUCTION:
+//! - leaf  = SHA-256(0x00 || tag)
+//! - node  = SHA-256(0x01 || left || right)
+//! - An unpaired node is PROMOTED unchanged (never duplicated)
+//! - root  = SHA-256(0x02 || leaf_count_be32 || tree_root)
+//!
+//! SECURITY:
+//! - Leaf / node / root prefixes are distinct (no second-preimage
+//!   by presenting an internal node as a leaf)
+//! - Leaf count is bound into the root (truncation changes it)
+//! - Hashes are NOT authentication: the root itself MUST be
+//!   obtained over an authenticated channel (e.g. sealed metadata)
+
+use crate::crypto::aes_gcm::TAG_LEN;
+use crate::integrity::hash::{hash_sha256, HashOutput};
+use crate::integrity::verify::IntegrityError;
+
+/* ───────────── DOMAIN PREFIXES ───────────── */
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+const ROOT_PREFIX: u8 = 0x02;
+
+/* ───────────── HASHING ───────────── */
+
+#[inline]
+fn hash_leaf(tag: &[u8; TAG_LEN]) -> HashOutput {
+    let mut buf = [0u8; 1 + TAG_LEN];
+    buf[0] = LEAF_PREFIX;
+    buf[1..].copy_from_slice(tag);
+    hash_sha256(&buf)
+}
+
+#[inline]
+fn hash_node(left: &HashOutput, right: &HashOutput) -> HashOutput {
+    let mut buf = [0u8; 1 + 32 + 32];
+    buf[0] = NODE_PREFIX;
+    buf[1..33].copy_from_slice(left.as_ref());
+    buf[33..].copy_from_slice(right.as_ref());
+    hash_sha256(&buf)
+}
+
+#[inline]
+fn hash_root(leaf_count: u32, tree_root: &HashOutput) -> HashOutput {
+    let mut buf = [0u8; 1 + 4 + 32];
+    buf[0] = ROOT_PREFIX;
+    buf[1..5].copy_from_slice(&leaf_count.to_be_bytes());
+    buf[5..].copy_from_slice(tree_root.as_ref());
+    hash_sha256(&buf)
+}
+
+/* ───────────── MANIFEST ───────────── */
+
+/// Merkle manifest over a file's ordered chunk tags.
+///
+/// Keeps every tree level so inclusion proofs are cheap.
+pub struct Manifest {
+    leaf_count: u32,
+    /// `levels[0]` = leaves, last level = single tree root
+    levels: Vec<Vec<HashOutput>>,
+    root: HashOutput,
+}
+
+/// Inclusion proof for one chunk.
+///
+/// Siblings are ordered leaf → root; promoted levels contribute none.
+#[derive(Clone, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub leaf_count: u32,
+    pub siblings: Vec<HashOutput>,
+}
+
+impl Manifest {
+    /// Build a manifest over `tags` in chunk order.
+    ///
+    /// Fails closed on an empty file or more than `u32::MAX` chunks.
+    pub fn build(tags: &[[u8; TAG_LEN]]) -> Result<Self, IntegrityError> {
+        if tags.is_empty() {
+            return Err(IntegrityError::Invalid);
+        }
+        let leaf_count = u32::try_from(tags.len()).map_err(|_| IntegrityError::Invalid)?;
+
+        let mut levels = vec![tags.iter().map(hash_leaf).collect::<Vec<_>>()];
+
+        while let Some(level) = levels.last() {
+            if level.len() == 1 {
+                break;
+            }
+            let mut next = Vec::with_capacity(level.len().div_ceil(2));
+            for pair in level.chunks(2) {
+                next.push(match pair.get(1) {
+                    Some(right) => hash_node(&pair[0], right),
+                    None => pair[0],
+                });
+            }
+            levels.push(next);
+        }
+
+        let tree_root = levels
+            .last()
+            .and_then(|l| l.first())
+            .copied()
+            .ok_or(IntegrityError::Invalid)?;
+
+        Ok(Self {
+            leaf_count,
+            root: hash_root(leaf_count, &tree_root),
+            levels,
+        })
+    }
+
+    /// Committed root (store / seal this with the file).
+    #[inline(always)]
+    pub fn root(&self) -> HashOutput {
+        self.root
+    }
+
+    /// Number of chunks committed.
+    #[inline(always)]
+    pub fn leaf_count(&self) -> u32 {
+        self.leaf_count
+    }
+
+    /// Inclusion proof for `chunk_index` (`None` if out of range).
+    pub fn proof(&self, chunk_index: u32) -> Option<InclusionProof> {
+        if chunk_index >= self.leaf_count {
+            return None;
+        }
+
+        let mut idx = chunk_index as usize;
+        let mut siblings = Vec::new();
+
+        let (_, below_root) = self.levels.split_last()?;
+        for level in below_root {
+            let sibling = idx ^ 1;
+            if let Some(h) = level.get(sibling) {
+                siblings.push(*h);
+            }
+            idx /= 2;
+        }
+
+        Some(InclusionProof {
+            leaf_count: self.leaf_count,
+            siblings,
+        })
+    }
+}
+
+/* ───────────── VERIFICATION ───────────── */
+
+/// Verify that `tag` is chunk `chunk_index` of the file committed by `root`.
+///
+/// SECURITY:
+/// - Recomputes the path from the proof's leaf count; a forged
+///   count changes the root and fails
+/// - Extra / missing siblings => false
+/// - Never panics
+pub fn verify_inclusion(
+    root: &HashOutput,
+    chunk_index: u32,
+    tag: &[u8; TAG_LEN],
+    proof: &InclusionProof,
+) -> bool {
+    if proof.leaf_count == 0 || chunk_index >= proof.leaf_count {
+        return false;
+    }
+
+    let mut hash = hash_leaf(tag);
+    let mut idx = chunk_index;
+    let mut width = proof.leaf_count;
+    let mut siblings = proof.siblings.iter();
+
+    while width > 1 {
+        let sibling = idx ^ 1;
+        if sibling < width {
+            let Some(s) = siblings.next() else {
+                return false;
+            };
+            hash = if idx & 1 == 0 {
+                hash_node(&hash, s)
+            } else {
+                hash_node(s, &hash)
+            };
+        }
+        idx /= 2;
+        width = width.div_ceil(2);
+    }
+
+    if siblings.next().is_some() {
+        return false;
+    }
+
+    hash_root(proof.leaf_count, &hash) == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(n: usize) -> Vec<[u8; TAG_LEN]> {
+        (0..n).map(|i| [i as u8; TAG_LEN]).collect()
+    }
+
+    #[test]
+    fn every_chunk_proves_for_uneven_sizes() -> Result<(), ()> {
+        for n in [1usize, 2, 3, 5, 8, 13] {
+            let t = tags(n);
+            let m = Manifest::build(&t).map_err(|_| ())?;
+
+            for (i, tag) in t.iter().enumerate() {
+                let proof = m.proof(i as u32).ok_or(())?;
+                assert!(verify_inclusion(&m.root(), i as u32, tag, &proof));
+            }
+            assert!(m.proof(n as u32).is_none());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reorder_truncation_and_wrong_position_fail() -> Result<(), ()> {
+        let t = tags(5);
+        let m = Manifest::build(&t).map_err(|_| ())?;
+        let proof = m.proof(2).ok_or(())?;
+
+        // Wrong tag / wrong index
+        assert!(!verify_inclusion(&m.root(), 2, &t[3], &proof));
+        assert!(!verify_inclusion(&m.root(), 3, &t[2], &proof));
+
+        // Reordered and truncated files commit to different roots
+        let mut swapped = t.clone();
+        swapped.swap(0, 1);
+        let r = Manifest::build(&swapped).map_err(|_| ())?;
+        assert!(r.root() != m.root());
+
+        let short = Manifest::build(&t[..4]).map_err(|_| ())?;
+        assert!(short.root() != m.root());
+
+        // Forged leaf count
+        let mut forged = proof.clone();
+        forged.leaf_count = 4;
+        assert!(!verify_inclusion(&m.root(), 2, &t[2], &forged));
+
+        // Extra sibling
+        let mut extra = proof;
+        extra.siblings.push(m.root());
+        assert!(!verify_inclusion(&m.root(), 2, &t[2], &extra));
+        Ok(())
+    }
+
+    #[test]
+    fn leaf_and_node_hashes_are_domain_separated() {
+        // A two-leaf tree's internal node must not verify as a leaf
+        let t = tags(2);
+        let left = hash_leaf(&t[0]);
+        let right = hash_leaf(&t[1]);
+        assert!(hash_node(&left, &right) != hash_leaf(&t[0]));
+
+        assert!(Manifest::build(&[]).is_err());
+    }
+}
@@ -6,6 +6,8 @@
 //! PURPOSE:
 //! - Non-secret hashing
 //! - Cryptographic key integrity verification
+//! - Chunk-set commitments (Merkle manifest)
+//! - Non-secret metadata authentication (HMAC tag)
 //!
 //! This module defines what it means for data and keys
 //! to be *valid* before higher-level authorization or recovery.
@@ -13,7 +15,11 @@
 #![deny(clippy::derive_debug)]
 
 pub mod hash;
+pub mod mac;
+pub mod manifest;
 pub mod verify;
 
 pub use hash::{hash_sha256, HashOutput};
+pub use mac::{compute_metadata_tag, verify_metadata_tag, METADATA_TAG_LEN};
+pub use manifest::{verify_inclusion, InclusionProof, Manifest};
 pub use verify::{verify_key_integrity, IntegrityError};
\ No newline at end of file
@@ -15,15 +15,15 @@
 //! - No panics
 
 use crate::crypto::derive::{derive_key, Purpose};
-use crate::memory::GuardedKey32;
-use subtle::ConstantTimeEq;
+use crate::memory::{ct_eq, GuardedKey32};
 
 /// Fixed integrity derivation context.
 ///
 /// SECURITY:
 /// - MUST remain stable forever
 /// - Changing this breaks recovery compatibility
-const INTEGRITY_CONTEXT: u64 = 0x494E544547524954; // "INTEGRIT";
+/// - Shared with `keystore::recovery::shamir` (session derivation)
+pub(crate) const INTEGRITY_CONTEXT: u64 = 0x494E544547524954; // "INTEGRIT";
 
 /// Verify that `session` is correctly derived from `master`.
 ///
@@ -47,7 +47,7 @@ pub fn verify_key_integrity(
     .map_err(|_| IntegrityError::Invalid)?;
 
     // Constant-time comparison
-    if session.borrow().ct_eq(expected.borrow()).into() {
+    if ct_eq(session.borrow(), expected.borrow()) {
         Ok(())
     } else {
         Err(IntegrityError::Invalid)
@@ -0,0 +1,378 @@
+//! Sealed application file index (rollback-protected).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! The application's file index (names, sizes, chunk counts,
+//! manifest roots) is sealed as ONE blob under a key derived from
+//! the session key (`Purpose::Metadata`, `INDEX_CONTEXT`).
+//!
+//! BLOB FORMAT:
+//! `index_version (8, big-endian) || nonce (12) || ciphertext || tag (16)`
+//! - nonce: random (OS CSPRNG), never derived from the version
+//! - AAD:   `INDEX_AAD_LABEL || index_version`
+//!
+//! VERSION LOG RECORD (length-prefixed, `index_version.log`):
+//! `key_id (8) || index_version (8, big-endian) || tag (32)`
+//! - `key_id = HMAC(versions_key, INDEX_KEY_ID_LABEL)[..8]`
+//! - `tag = HMAC(versions_key, INDEX_VERSION_LABEL || index_version)`
+//! - `versions_key`: `derive_key(index_key, Purpose::Metadata, INDEX_VERSIONS_CONTEXT)`
+//!
+//! FORMAL INVARIANTS (ENFORCED):
+//! - Nonce uniqueness never depends on the version log: a lost or
+//!   rolled-back log cannot cause GCM nonce reuse
+//! - Every seal uses a fresh, persisted `index_version` (write-ahead)
+//! - `index_version` is authenticated (AAD) and monotonic: a blob
+//!   older than the highest version seen is refused (rollback)
+//! - Version records carrying OUR `key_id` MUST verify; records of
+//!   other key hierarchies (before a phrase change) are skipped
+//! - No log root, unreadable log, broken hash chain, or a record
+//!   that fails its MAC => refuse (fail-closed, never restart at 0)
+//! - Forbidden after global kill
+
+use core::sync::atomic::Ordering;
+
+use hmac::{Hmac, Mac};
+use rand_core::RngCore;
+use sha2::Sha256;
+
+use crate::crypto::aes_gcm::{self, NONCE_LEN, TAG_LEN};
+use crate::crypto::rng::OsRngBackend;
+use crate::keystore::master::GLOBAL_KILLED;
+use crate::logging::encrypted::EncryptedLog;
+use crate::memory::{ct_eq, GuardedKey32};
+
+/// Index key context under the session key (`Purpose::Metadata`).
+pub const INDEX_CONTEXT: u64 = 0x494E444558424C42; // "INDEXBLB"
+
+/// Largest accepted index plaintext.
+pub const MAX_INDEX_LEN: usize = 16 * 1024 * 1024;
+
+/// Version-log MAC key context under the index key (`Purpose::Metadata`).
+pub const INDEX_VERSIONS_CONTEXT: u64 = 0x494E444558564552; // "INDEXVER"
+
+/// Sealed blob overhead: version prefix + nonce + tag.
+pub const INDEX_OVERHEAD: usize = VERSION_LEN + NONCE_LEN + TAG_LEN;
+
+/// Index AAD domain label (MUST NEVER CHANGE).
+const INDEX_AAD_LABEL: &[u8] = b"rcxcloud:index:v1";
+
+/// Version record MAC domain label (MUST NEVER CHANGE).
+const INDEX_VERSION_LABEL: &[u8] = b"rcxcloud:index:version:v1";
+
+/// Version-log key id domain label (MUST NEVER CHANGE).
+const INDEX_KEY_ID_LABEL: &[u8] = b"rcxcloud:index:key-id:v1";
+
+const VERSION_LEN: usize = 8;
+const KEY_ID_LEN: usize = 8;
+const MAC_LEN: usize = 32;
+const RECORD_LEN: usize = KEY_ID_LEN + VERSION_LEN + MAC_LEN;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexError {
+    Killed,
+    InvalidInput,
+    /// Tampered blob, or version log missing / corrupt
+    Unverifiable,
+    /// Blob is older than the highest version seen
+    Rollback,
+    /// Version could not be persisted
+    Persist,
+    /// Version counter is used up
+    Exhausted,
+}
+
+/* ───────────── VERSION LOG ───────────── */
+
+/// Highest `index_version` sealed or opened on this device.
+pub struct IndexVersions<'k> {
+    // `None` only for in-memory test logs
+    log: Option<EncryptedLog>,
+    key: &'k GuardedKey32,
+    highest: u64,
+}
+
+impl<'k> IndexVersions<'k> {
+    /// Open and verify the persisted version log under `key`
+    /// (the versions key; 0 = nothing sealed under it yet).
+    ///
+    /// SECURITY:
+    /// - No log root / unreadable log / broken chain => `Unverifiable`
+    /// - Any record with our `key_id` that fails its MAC => `Unverifiable`
+    pub fn open(key: &'k GuardedKey32) -> Result<Self, IndexError> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(IndexError::Killed);
+        }
+
+        let mut log = EncryptedLog::open_index_versions().map_err(|_| IndexError::Unverifiable)?;
+        log.verify_chain().map_err(|_| IndexError::Unverifiable)?;
+        let records = log.read_records().map_err(|_| IndexError::Unverifiable)?;
+
+        let highest = replay(key, &records)?;
+
+        Ok(Self {
+            log: Some(log),
+            key,
+            highest,
+        })
+    }
+
+    #[cfg(test)]
+    pub(crate) fn in_memory(key: &'k GuardedKey32) -> Self {
+        Self {
+            log: None,
+            key,
+            highest: 0,
+        }
+    }
+
+    /// Highest version seen so far.
+    pub fn highest(&self) -> u64 {
+        self.highest
+    }
+
+    /// Reserve the next version for a seal.
+    ///
+    /// SECURITY:
+    /// - Persisted (flushed) BEFORE returning
+    pub fn reserve(&mut self) -> Result<u64, IndexError> {
+        let next = self.highest.checked_add(1).ok_or(IndexError::Exhausted)?;
+        self.advance(next)?;
+        Ok(next)
+    }
+
+    /// Accept an opened blob's version.
+    ///
+    /// Older than the highest seen => `Rollback`; newer (sealed on
+    /// another device) raises the floor.
+    pub fn accept(&mut self, version: u64) -> Result<(), IndexError> {
+        if version < self.highest {
+            return Err(IndexError::Rollback);
+        }
+
+        if version > self.highest {
+            self.advance(version)?;
+        }
+
+        Ok(())
+    }
+
+    fn advance(&mut self, version: u64) -> Result<(), IndexError> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(IndexError::Killed);
+        }
+
+        if let Some(log) = self.log.as_mut() {
+            let rec = encode_record(self.key, version)?;
+            log.append_record(&rec).map_err(|_| IndexError::Persist)?;
+        }
+
+        self.highest = version;
+        Ok(())
+    }
+}
+
+fn version_mac(key: &GuardedKey32, version: u64) -> Result<[u8; MAC_LEN], IndexError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.borrow())
+        .map_err(|_| IndexError::Unverifiable)?;
+    mac.update(INDEX_VERSION_LABEL);
+    mac.update(&version.to_be_bytes());
+
+    let mut out = [0u8; MAC_LEN];
+    out.copy_from_slice(&mac.finalize().into_bytes());
+    Ok(out)
+}
+
+fn key_id(key: &GuardedKey32) -> Result<[u8; KEY_ID_LEN], IndexError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.borrow())
+        .map_err(|_| IndexError::Unverifiable)?;
+    mac.update(INDEX_KEY_ID_LABEL);
+
+    let mut out = [0u8; KEY_ID_LEN];
+    out.copy_from_slice(&mac.finalize().into_bytes()[..KEY_ID_LEN]);
+    Ok(out)
+}
+
+fn encode_record(key: &GuardedKey32, version: u64) -> Result<[u8; RECORD_LEN], IndexError> {
+    let mut rec = [0u8; RECORD_LEN];
+    rec[..KEY_ID_LEN].copy_from_slice(&key_id(key)?);
+    rec[KEY_ID_LEN..KEY_ID_LEN + VERSION_LEN].copy_from_slice(&version.to_be_bytes());
+    rec[KEY_ID_LEN + VERSION_LEN..].copy_from_slice(&version_mac(key, version)?);
+    Ok(rec)
+}
+
+/// Highest authenticated version among our records.
+fn replay(key: &GuardedKey32, records: &[Vec<u8>]) -> Result<u64, IndexError> {
+    let id = key_id(key)?;
+    let mut highest = 0;
+
+    for rec in records {
+        if rec.len() != RECORD_LEN {
+            return Err(IndexError::Unverifiable);
+        }
+
+        // Another key hierarchy's record: not ours to judge
+        if !ct_eq(&rec[..KEY_ID_LEN], &id) {
+            continue;
+        }
+
+        let mut v = [0u8; VERSION_LEN];
+        v.copy_from_slice(&rec[KEY_ID_LEN..KEY_ID_LEN + VERSION_LEN]);
+        let version = u64::from_be_bytes(v);
+
+        if !ct_eq(&version_mac(key, version)?, &rec[KEY_ID_LEN + VERSION_LEN..]) {
+            return Err(IndexError::Unverifiable);
+        }
+
+        highest = highest.max(version);
+    }
+
+    Ok(highest)
+}
+
+/* ───────────── SEAL / OPEN ───────────── */
+
+/// Seal `plaintext` as index `version` under `key` (the index key).
+pub(crate) fn seal_index(
+    key: &GuardedKey32,
+    version: u64,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, IndexError> {
+    if plaintext.len() > MAX_INDEX_LEN {
+        return Err(IndexError::InvalidInput);
+    }
+
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRngBackend
+        .try_fill_bytes(&mut nonce)
+        .map_err(|_| IndexError::InvalidInput)?;
+
+    let mut blob = vec![0u8; INDEX_OVERHEAD + plaintext.len()];
+    blob[..VERSION_LEN].copy_from_slice(&version.to_be_bytes());
+    blob[VERSION_LEN..VERSION_LEN + NONCE_LEN].copy_from_slice(&nonce);
+
+    aes_gcm::seal(
+        key,
+        &nonce,
+        plaintext,
+        &index_aad(version),
+        &mut blob[VERSION_LEN + NONCE_LEN..],
+    )
+    .map_err(|_| IndexError::InvalidInput)?;
+
+    Ok(blob)
+}
+
+/// Authenticate and decrypt an index blob: `(version, plaintext)`.
+///
+/// The caller MUST still pass `version` to `IndexVersions::accept`.
+pub(crate) fn open_index(
+    key: &GuardedKey32,
+    blob: &[u8],
+) -> Result<(u64, Vec<u8>), IndexError> {
+    if blob.len() < INDEX_OVERHEAD || blob.len() > INDEX_OVERHEAD + MAX_INDEX_LEN {
+        return Err(IndexError::InvalidInput);
+    }
+
+    let (v, rest) = blob.split_at(VERSION_LEN);
+    let mut vb = [0u8; VERSION_LEN];
+    vb.copy_from_slice(v);
+    let version = u64::from_be_bytes(vb);
+
+    let (n, sealed) = rest.split_at(NONCE_LEN);
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce.copy_from_slice(n);
+
+    let mut plaintext = vec![0u8; sealed.len() - TAG_LEN];
+    if !aes_gcm::open(key, &nonce, sealed, &index_aad(version), &mut plaintext) {
+        return Err(IndexError::Unverifiable);
+    }
+
+    Ok((version, plaintext))
+}
+
+fn index_aad(version: u64) -> [u8; INDEX_AAD_LABEL.len() + VERSION_LEN] {
+    let mut aad = [0u8; INDEX_AAD_LABEL.len() + VERSION_LEN];
+    aad[..INDEX_AAD_LABEL.len()].copy_from_slice(INDEX_AAD_LABEL);
+    aad[INDEX_AAD_LABEL.len()..].copy_from_slice(&version.to_be_bytes());
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> GuardedKey32 {
+        GuardedKey32::init_with(|k| k.fill(0x33))
+    }
+
+    #[test]
+    fn sealed_index_round_trips() -> Result<(), IndexError> {
+        let blob = seal_index(&key(), 7, b"manifest roots")?;
+        assert_eq!(blob.len(), INDEX_OVERHEAD + 14);
+
+        assert_eq!(open_index(&key(), &blob), Ok((7, b"manifest roots".to_vec())));
+        assert_eq!(
+            open_index(&GuardedKey32::init_with(|k| k.fill(0x34)), &blob).err(),
+            Some(IndexError::Unverifiable)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn version_is_authenticated() -> Result<(), IndexError> {
+        let mut blob = seal_index(&key(), 4, b"index")?;
+
+        // Relabel as a newer version
+        blob[VERSION_LEN - 1] = 5;
+        assert_eq!(open_index(&key(), &blob).err(), Some(IndexError::Unverifiable));
+        Ok(())
+    }
+
+    #[test]
+    fn sealing_twice_never_reuses_a_nonce() -> Result<(), IndexError> {
+        // Same version (e.g. a lost version log): nonces still differ
+        let a = seal_index(&key(), 3, b"index")?;
+        let b = seal_index(&key(), 3, b"index")?;
+
+        assert!(a[VERSION_LEN..VERSION_LEN + NONCE_LEN] != b[VERSION_LEN..VERSION_LEN + NONCE_LEN]);
+        Ok(())
+    }
+
+    #[test]
+    fn version_records_are_authenticated() -> Result<(), IndexError> {
+        let mine = key();
+        let other = GuardedKey32::init_with(|k| k.fill(0x34));
+
+        let records = vec![
+            encode_record(&mine, 4)?.to_vec(),
+            encode_record(&other, 90)?.to_vec(),
+            encode_record(&mine, 6)?.to_vec(),
+        ];
+        // Other hierarchies are skipped, ours are replayed
+        assert_eq!(replay(&mine, &records), Ok(6));
+
+        // A forged version under our key id fails closed
+        let mut forged = encode_record(&mine, 6)?;
+        forged[KEY_ID_LEN + VERSION_LEN - 1] = 99;
+        assert_eq!(replay(&mine, &[forged.to_vec()]), Err(IndexError::Unverifiable));
+
+        // So does a malformed record
+        assert_eq!(replay(&mine, &[vec![0u8; 8]]), Err(IndexError::Unverifiable));
+        Ok(())
+    }
+
+    #[test]
+    fn versions_are_monotonic() {
+        let key = key();
+        let mut v = IndexVersions::in_memory(&key);
+
+        assert_eq!(v.reserve(), Ok(1));
+        assert_eq!(v.reserve(), Ok(2));
+        assert_eq!(v.accept(2), Ok(()));
+        assert_eq!(v.accept(1), Err(IndexError::Rollback));
+
+        // Newer blob from another device raises the floor
+        assert_eq!(v.accept(9), Ok(()));
+        assert_eq!(v.reserve(), Ok(10));
+    }
+}
@@ -0,0 +1,255 @@
+//! Device KEM static secret ring (rotation with grace period).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! DESIGN:
+//! - ONE current static secret (new backups are encapsulated to it)
+//! - A BOUNDED set of retired secrets, each with an expiry
+//! - Retired secrets are only used to import OLD backups
+//! - Time is supplied by the host (no threads, no clocks)
+//!
+//! SECURITY:
+//! - Secret bytes live in kill-wiped guarded buffers
+//!   (`memory::sensitive`): every kill cause zeroizes them, and
+//!   dropping the ring (Core lock) wipes them too
+//! - Expanded `StaticSecret`s exist only for one operation and are
+//!   zeroized on drop (`x25519-dalek/zeroize`)
+//! - Expired secrets are dropped (wiped) on `prune` / `rotate`
+//! - Oldest retired secret is evicted when the ring is full
+//! - A wrong secret does NOT fail X25519: callers MUST supply a
+//!   verifier for the derived key (e.g. backup MAC check)
+
+#![deny(clippy::derive_debug)]
+
+use core::sync::atomic::Ordering;
+
+use rand_core::OsRng;
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroizing;
+
+use crate::crypto::kem::{decapsulate, KEMError};
+use crate::keystore::master::GLOBAL_KILLED;
+use crate::memory::{sensitive, GuardedKey32, SensitiveBuffer};
+
+/// Maximum retired secrets kept for the grace period.
+pub const MAX_RETIRED_KEM_SECRETS: usize = 2;
+
+/// One static secret in a kill-wiped buffer.
+struct Slot {
+    bytes: SensitiveBuffer,
+}
+
+impl Slot {
+    fn generate() -> Result<Self, KEMError> {
+        let fresh = StaticSecret::random_from_rng(OsRng);
+        let bytes = sensitive::register(32);
+
+        bytes
+            .with_mut(|b| b.copy_from_slice(fresh.as_bytes()))
+            .ok_or(KEMError::Killed)?;
+
+        Ok(Self { bytes })
+    }
+
+    /// Expand the secret for one operation (`None` once killed).
+    fn secret(&self) -> Option<StaticSecret> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        self.bytes
+            .with(|b| <[u8; 32]>::try_from(b).ok().map(Zeroizing::new))
+            .flatten()
+            .map(|b| StaticSecret::from(*b))
+    }
+}
+
+struct Retired {
+    slot: Slot,
+    expires_at_ms: u64,
+}
+
+/// Current + retired device KEM secrets.
+pub struct KemKeyRing {
+    current: Slot,
+    retired: Vec<Retired>,
+}
+
+impl KemKeyRing {
+    /// Generate a fresh ring (no retired secrets).
+    pub fn generate() -> Result<Self, KEMError> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(KEMError::Killed);
+        }
+
+        Ok(Self {
+            current: Slot::generate()?,
+            retired: Vec::with_capacity(MAX_RETIRED_KEM_SECRETS),
+        })
+    }
+
+    /// Public key new backups MUST be encapsulated to.
+    pub fn public_key(&self) -> Result<[u8; 32], KEMError> {
+        let secret = self.current.secret().ok_or(KEMError::Killed)?;
+        Ok(PublicKey::from(&secret).to_bytes())
+    }
+
+    /// Number of retired secrets still held.
+    pub fn retired(&self) -> usize {
+        self.retired.len()
+    }
+
+    /// Replace the current secret; keep the old one until
+    /// `now_ms + grace_ms`.
+    ///
+    /// Returns the NEW public key.
+    pub fn rotate(&mut self, now_ms: u64, grace_ms: u64) -> Result<[u8; 32], KEMError> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(KEMError::Killed);
+        }
+
+        self.prune(now_ms);
+
+        let old = core::mem::replace(&mut self.current, Slot::generate()?);
+
+        if self.retired.len() >= MAX_RETIRED_KEM_SECRETS {
+            // Oldest first: dropping wipes it
+            self.retired.remove(0);
+        }
+
+        self.retired.push(Retired {
+            slot: old,
+            expires_at_ms: now_ms.saturating_add(grace_ms),
+        });
+
+        self.public_key()
+    }
+
+    /// Drop (wipe) every retired secret past its grace period.
+    pub fn prune(&mut self, now_ms: u64) {
+        self.retired.retain(|r| now_ms < r.expires_at_ms);
+    }
+
+    /// Drop (wipe) every retired secret immediately.
+    pub fn retire_all(&mut self) {
+        self.retired.clear();
+    }
+
+    /// Decapsulate a backup key, trying current then retired secrets.
+    ///
+    /// The first derived key accepted by `verify` is returned.
+    ///
+    /// SECURITY:
+    /// - Expired retired secrets are pruned BEFORE use
+    /// - Rejected candidate keys are dropped (zeroized)
+    /// - After kill => `Killed` (the secrets are already wiped)
+    pub fn decapsulate_verified(
+        &mut self,
+        peer_ephemeral: &[u8; 32],
+        context: &[u8],
+        now_ms: u64,
+        mut verify: impl FnMut(&GuardedKey32) -> bool,
+    ) -> Result<GuardedKey32, KEMError> {
+        self.prune(now_ms);
+
+        let candidates = core::iter::once(&self.current)
+            .chain(self.retired.iter().rev().map(|r| &r.slot));
+
+        for slot in candidates {
+            let secret = slot.secret().ok_or(KEMError::Killed)?;
+
+            let mut key = GuardedKey32::zeroed();
+            decapsulate(&secret, peer_ephemeral, context, &mut key)?;
+
+            if verify(&key) {
+                return Ok(key);
+            }
+        }
+
+        Err(KEMError::Derive)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::kem::encapsulate;
+
+    const CONTEXT: &[u8; 32] = b"rcxcloud:test:backup:context:v1!";
+
+    #[test]
+    fn backup_under_old_key_imports_until_grace_expires() -> Result<(), KEMError> {
+        let mut ring = KemKeyRing::generate()?;
+
+        let old_pub = ring.public_key()?;
+        let (encap, backup_key) = encapsulate(&old_pub, CONTEXT)?;
+
+        let new_pub = ring.rotate(1_000, 10_000);
+        assert!(matches!(new_pub, Ok(p) if p != old_pub));
+        assert_eq!(ring.retired(), 1);
+
+        // Within the grace period: old backup still imports
+        let imported = ring.decapsulate_verified(
+            &encap.ephemeral_public,
+            CONTEXT,
+            5_000,
+            |k| k.borrow() == backup_key.borrow(),
+        );
+        assert!(matches!(&imported, Ok(k) if k.borrow() == backup_key.borrow()));
+
+        // After the grace period the old secret is wiped
+        let imported = ring.decapsulate_verified(
+            &encap.ephemeral_public,
+            CONTEXT,
+            11_000,
+            |k| k.borrow() == backup_key.borrow(),
+        );
+        assert!(matches!(imported, Err(KEMError::Derive)));
+        assert_eq!(ring.retired(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn retired_set_is_bounded() -> Result<(), KEMError> {
+        let mut ring = KemKeyRing::generate()?;
+
+        for i in 0..5u64 {
+            assert!(ring.rotate(i, u64::MAX).is_ok());
+        }
+
+        assert_eq!(ring.retired(), MAX_RETIRED_KEM_SECRETS);
+        Ok(())
+    }
+
+    #[test]
+    fn kill_wipes_every_ring_secret() {
+        // Fresh process: the kill fuse is process-wide
+        assert!(crate::test_support::isolated(
+            "keystore::kem_ring::tests::kill_wipes_every_ring_secret",
+            || {
+                let ring = KemKeyRing::generate();
+                assert!(ring.is_ok());
+                let Ok(mut ring) = ring else { return };
+
+                let public = ring.public_key();
+                assert!(public.is_ok());
+                let Ok(public) = public else { return };
+                assert!(ring.rotate(0, u64::MAX).is_ok());
+
+                let encap = encapsulate(&public, CONTEXT);
+                assert!(encap.is_ok());
+                let Ok((encap, _)) = encap else { return };
+
+                crate::keystore::master::escalate(crate::keystore::master::KillCause::VerifiedKill);
+
+                assert!(ring.current.bytes.is_zeroed());
+                assert!(ring.retired.iter().all(|r| r.slot.bytes.is_zeroed()));
+                assert!(matches!(ring.public_key(), Err(KEMError::Killed)));
+                assert!(matches!(
+                    ring.decapsulate_verified(&encap.ephemeral_public, CONTEXT, 0, |_| true),
+                    Err(KEMError::Killed)
+                ));
+            },
+        ));
+    }
+}
@@ -4,11 +4,16 @@
 
 use crate::memory::GuardedKey32;
 
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::{Mutex, MutexGuard};
 
 /* ───────────── GLOBAL KILL FUSE ───────────── */
 
+// ⚠️ SOLE AUTHORITATIVE KILL FLAG.
+//
+// Every subsystem MUST import it from `crate::keystore::master`.
+// No other module may define, wrap, or re-export a kill flag:
+// a second static would silently split kill state.
 pub(crate) static GLOBAL_KILLED: AtomicBool = AtomicBool::new(false);
 
 #[inline(always)]
@@ -16,6 +21,64 @@ pub(crate) fn is_globally_killed() -> bool {
     GLOBAL_KILLED.load(Ordering::SeqCst)
 }
 
+/* ───────────── KILL CAUSE (DIAGNOSTIC) ───────────── */
+
+/// Why the kill fuse tripped (incident triage ONLY).
+///
+/// SECURITY:
+/// - Observability only: NEVER consulted by any kill check
+/// - First recorded cause wins; never reset
+/// - Killed with no recorded cause => `Unknown` (fail closed)
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KillCause {
+    None = 0,
+    VerifiedKill = 1,
+    Poison = 2,
+    LocalFail = 3,
+    Unknown = 0xFF,
+}
+
+impl KillCause {
+    /// Decode a raw cause as observed alongside the fuse.
+    const fn decode(raw: u8, killed: bool) -> Self {
+        match raw {
+            1 => Self::VerifiedKill,
+            2 => Self::Poison,
+            3 => Self::LocalFail,
+            _ if killed => Self::Unknown,
+            _ => Self::None,
+        }
+    }
+}
+
+static KILL_CAUSE: AtomicU8 = AtomicU8::new(KillCause::None as u8);
+
+/// Trip the kill fuse, recording `cause` first.
+///
+/// The cause is stored BEFORE the fuse so any reader that sees the
+/// fuse also sees a cause (or `Unknown`, never a stale `None`).
+///
+/// Every cause wipes host-held sensitive buffers: this thread's now,
+/// other threads' on their next access.
+#[inline(always)]
+pub(crate) fn escalate(cause: KillCause) {
+    let _ = KILL_CAUSE.compare_exchange(
+        KillCause::None as u8,
+        cause as u8,
+        Ordering::SeqCst,
+        Ordering::SeqCst,
+    );
+    GLOBAL_KILLED.store(true, Ordering::SeqCst);
+    crate::memory::sensitive::wipe_thread();
+}
+
+/// Current kill cause (`None` while alive).
+pub(crate) fn kill_cause() -> KillCause {
+    let killed = is_globally_killed();
+    KillCause::decode(KILL_CAUSE.load(Ordering::SeqCst), killed)
+}
+
 /* ───────────── ERRORS ───────────── */
 
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -88,7 +151,7 @@ impl MasterKeyStore {
     ///
     /// Caller MUST have already verified kill authorization.
     pub fn apply_verified_kill(&self) {
-        GLOBAL_KILLED.store(true, Ordering::SeqCst);
+        escalate(KillCause::VerifiedKill);
 
         if let Ok(mut guard) = self.state.lock() {
             *guard = KeyState::Wiped;
@@ -99,12 +162,29 @@ impl MasterKeyStore {
     where
         F: FnOnce(&[u8; 32]) -> R,
     {
-        if is_globally_killed() {
+        self.with_key_checked(is_globally_killed, f)
+    }
+
+    /// `with_key` with an injectable kill probe.
+    ///
+    /// The probe is consulted BEFORE and AFTER acquiring the lock
+    /// (same double check as `unlock`): a kill racing the lock
+    /// acquisition MUST NOT reach `f`.
+    fn with_key_checked<K, F, R>(&self, killed: K, f: F) -> Result<R, KeystoreError>
+    where
+        K: Fn() -> bool,
+        F: FnOnce(&[u8; 32]) -> R,
+    {
+        if killed() {
             return Err(KeystoreError::Wiped);
         }
 
         let guard = self.acquire_lock()?;
 
+        if killed() {
+            return Err(KeystoreError::Wiped);
+        }
+
         match &*guard {
             KeyState::Unlocked(key) => Ok(f(key.borrow())),
             KeyState::Locked => Err(KeystoreError::Locked),
@@ -114,8 +194,88 @@ impl MasterKeyStore {
 
     fn acquire_lock(&self) -> Result<MutexGuard<'_, KeyState>, KeystoreError> {
         self.state.lock().map_err(|_| {
-            GLOBAL_KILLED.store(true, Ordering::SeqCst);
+            escalate(KillCause::Poison);
             KeystoreError::Poisoned
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::Core;
+
+    #[test]
+    fn kill_cause_fails_closed_without_record() {
+        // Alive, nothing recorded
+        assert!(KillCause::decode(0, false) == KillCause::None);
+
+        // Fuse tripped with no recorded cause => Unknown, never None
+        assert!(KillCause::decode(0, true) == KillCause::Unknown);
+        assert!(KillCause::decode(0x7E, true) == KillCause::Unknown);
+
+        assert!(KillCause::decode(1, true) == KillCause::VerifiedKill);
+        assert!(KillCause::decode(2, true) == KillCause::Poison);
+        assert!(KillCause::decode(3, true) == KillCause::LocalFail);
+    }
+
+    #[test]
+    fn kill_flag_is_observed_consistently() {
+        use crate::crypto::aad::{Aad, AAD_VERSION_V1};
+        use crate::crypto::aes_gcm::TAG_LEN;
+        use crate::keystore::recovery::RecoveryAuthority;
+        use crate::keystore::session::{Session, SessionError, VerifyResult};
+        use crate::keystore::{KeyStore, KeyStoreError};
+
+        // Bridge, keystore and session all read the SAME fuse
+        assert!(crate::test_support::isolated(
+            "keystore::master::tests::kill_flag_is_observed_consistently",
+            || {
+                crate::logging::encrypted::init_test_log_root();
+
+                let core = Core::new();
+                let ks = KeyStore::new();
+                let key = GuardedKey32::init_with(|k| k.fill(0x42));
+                assert!(ks.unlock(RecoveryAuthority::from_session_key(key)).is_ok());
+                let mut session = Session::new(GuardedKey32::init_with(|k| k.fill(0x43)));
+
+                assert!(!core.is_killed() && !is_globally_killed());
+
+                escalate(KillCause::VerifiedKill);
+
+                assert!(core.is_killed());
+                assert!(is_globally_killed() && GLOBAL_KILLED.load(Ordering::SeqCst));
+                assert!(ks.with_session(|_| Ok(VerifyResult(true))) == Err(KeyStoreError::Killed));
+
+                let Some(aad) = Aad::new(7, 0, 1, AAD_VERSION_V1) else { return };
+                let mut ct = [0u8; 3 + TAG_LEN];
+                assert!(matches!(session.encrypt(b"abc", aad, &mut ct), Err(SessionError::Killed)));
+            },
+        ));
+    }
+
+    #[test]
+    fn kill_after_lock_acquisition_never_runs_closure() -> Result<(), ()> {
+        use core::cell::Cell;
+
+        let store = MasterKeyStore::new();
+        store
+            .unlock(GuardedKey32::init_with(|k| k.fill(0x11)))
+            .map_err(|_| ())?;
+
+        // Kill lands between the entry check and the lock
+        let probes = Cell::new(0u8);
+        let killed = || {
+            probes.set(probes.get() + 1);
+            probes.get() > 1
+        };
+
+        let ran = Cell::new(false);
+        let r = store.with_key_checked(killed, |_| ran.set(true));
+
+        assert!(r == Err(KeystoreError::Wiped));
+        assert!(!ran.get());
+        assert_eq!(probes.get(), 2);
+        Ok(())
+    }
+}
@@ -2,16 +2,32 @@
 
 #![deny(clippy::derive_debug)]
 
+pub mod master;
 pub mod session;
+#[cfg(feature = "kem")]
+pub mod kem_ring;
+pub mod stream;
+pub mod nonce_ledger;
+pub mod tombstone;
+pub mod index;
 pub mod recovery;
 
 use session::{Session, SessionError, SessionOutput};
 use recovery::RecoveryAuthority;
 
-use std::sync::Mutex;
-use core::sync::atomic::Ordering;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use core::cell::RefCell;
+use core::num::NonZeroU64;
+use core::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
 
-use crate::keystore::master::GLOBAL_KILLED;
+use crate::crypto::attest::{self, ATTESTATION_CONTEXT};
+use crate::crypto::cipher::CipherSuite;
+use crate::crypto::derive::{derive_key, Purpose};
+use crate::keystore::master::{escalate, KillCause, GLOBAL_KILLED};
+use crate::kill::audit;
+use crate::memory::{sensitive, GuardedKey32, SensitiveBuffer};
 
 /* ───────────── ERROR TYPES ───────────── */
 
@@ -21,6 +37,8 @@ pub enum KeyStoreError {
     AlreadyUnlocked,
     Killed,
     Poisoned,
+    /// Keystore re-entered from inside a `with_session` closure.
+    Reentrant,
     Session(SessionError),
 }
 
@@ -30,54 +48,241 @@ impl From<SessionError> for KeyStoreError {
     }
 }
 
+/* ───────────── RE-ENTRANCY GUARD ───────────── */
+
+// The state mutex is held for the whole `with_session` closure.
+// Re-entering the SAME keystore from that closure would deadlock;
+// instead it is detected and rejected (fail-closed). Other
+// keystores stay usable from the closure.
+thread_local! {
+    static IN_SESSION: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+// Process-unique keystore ids (keys the re-entrancy guard)
+static NEXT_KEYSTORE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// RAII marker for an active `with_session` closure (panic-safe).
+struct SessionScope(u64);
+
+impl SessionScope {
+    fn enter(keystore: u64) -> Result<Self, KeyStoreError> {
+        IN_SESSION.with(|f| {
+            let mut active = f.borrow_mut();
+            if active.contains(&keystore) {
+                Err(KeyStoreError::Reentrant)
+            } else {
+                active.push(keystore);
+                Ok(SessionScope(keystore))
+            }
+        })
+    }
+}
+
+impl Drop for SessionScope {
+    fn drop(&mut self) {
+        IN_SESSION.with(|f| f.borrow_mut().retain(|k| *k != self.0));
+    }
+}
+
+/* ───────────── SESSION IDS ───────────── */
+
+/// Maximum concurrently unlocked sessions (e.g. mounted vaults).
+pub const MAX_SESSIONS: usize = 8;
+
+/// Opaque handle to one unlocked session.
+///
+/// Values are never reused within a keystore's lifetime.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SessionId(NonZeroU64);
+
 /* ───────────── INTERNAL STATE ───────────── */
 
-enum State {
-    Locked,
-    Active(Session),
+struct State {
+    sessions: HashMap<SessionId, Session>,
+    next_id: u64,
+    /// Monotonic time of the last unlock / session operation
+    last_activity: Option<Instant>,
+    /// Session the attestation key was derived from
+    attest_source: Option<SessionId>,
+}
+
+impl State {
+    fn new() -> Self {
+        Self {
+            sessions: HashMap::new(),
+            next_id: 1,
+            last_activity: None,
+            attest_source: None,
+        }
+    }
+
+    /// Oldest live session ("the" session for convenience APIs).
+    fn first(&self) -> Option<SessionId> {
+        self.sessions.keys().copied().min_by_key(|id| id.0)
+    }
+
+    /// Zeroize every session key and forget all sessions.
+    fn kill_all(&mut self) {
+        for s in self.sessions.values_mut() {
+            s.kill();
+        }
+        self.sessions.clear();
+        self.attest_source = None;
+    }
 }
 
+/* ───────────── STATUS MIRROR ───────────── */
+
+// Lock-free copy of the lock state for cheap polling.
+// Written ONLY while the state mutex is held (or on kill);
+// never consulted by crypto operations.
+const STATUS_LOCKED: u8 = 0;
+const STATUS_UNLOCKED: u8 = 1;
+const STATUS_KILLED: u8 = 2;
+
 /* ───────────── KEYSTORE ───────────── */
 
 pub struct KeyStore {
+    // Re-entrancy guard key (never reused in-process)
+    id: u64,
     state: Mutex<State>,
+    status: AtomicU8,
+    // MAC-only, one-way derived from the session key.
+    // Deliberately RETAINED across kill so the killed
+    // state can still be attested honestly.
+    attestation: Mutex<Option<GuardedKey32>>,
+    // Inactivity auto-lock (`None` = disabled)
+    idle_limit: Option<Duration>,
+    // Set by an idle auto-lock until the owner reports it
+    idle_locked: AtomicBool,
 }
 
 impl KeyStore {
     pub fn new() -> Self {
         Self {
-            state: Mutex::new(State::Locked),
+            id: NEXT_KEYSTORE_ID.fetch_add(1, Ordering::Relaxed),
+            state: Mutex::new(State::new()),
+            status: AtomicU8::new(STATUS_LOCKED),
+            attestation: Mutex::new(None),
+            idle_limit: None,
+            idle_locked: AtomicBool::new(false),
         }
     }
 
-    /// Unlock keystore using a recovery authority.
+    /// Keystore that auto-locks after `secs` without a session operation
+    /// (`0` = disabled, same as `new`).
+    ///
+    /// No background thread: the NEXT operation after the idle window
+    /// zeroizes every session and fails with `Locked`
+    /// (`take_idle_lock` tells the owner it happened).
+    pub fn new_with_idle_limit(secs: u64) -> Self {
+        Self {
+            idle_limit: (secs > 0).then(|| Duration::from_secs(secs)),
+            ..Self::new()
+        }
+    }
+
+    /// Whether THIS keystore's state mutex is held by a session
+    /// closure on the current thread.
+    #[inline(always)]
+    fn in_session(&self) -> bool {
+        IN_SESSION.with(|f| f.borrow().contains(&self.id))
+    }
+
+    /// Unlock a new session using a recovery authority.
+    ///
+    /// Each call opens an INDEPENDENT session (separate vaults);
+    /// the returned id addresses it.
     ///
     /// SECURITY:
     /// - Forbidden after global kill
     /// - Authority is single-use
+    /// - At most `MAX_SESSIONS` => `AlreadyUnlocked`
+    /// - Attestation key is bound to the FIRST session (re-derived
+    ///   from the next unlock once its source session is locked)
     /// - Mutex poisoning FAILS CLOSED
-    pub fn unlock(&self, auth: RecoveryAuthority) -> Result<(), KeyStoreError> {
+    pub fn unlock(&self, auth: RecoveryAuthority) -> Result<SessionId, KeyStoreError> {
+        self.unlock_with_suite(auth, CipherSuite::default())
+    }
+
+    /// `unlock`, sealing the session's NEW chunks under `suite`.
+    ///
+    /// Decryption always follows the suite recorded in each AAD,
+    /// so data written under any compiled-in suite stays readable.
+    pub fn unlock_with_suite(
+        &self,
+        auth: RecoveryAuthority,
+        suite: CipherSuite,
+    ) -> Result<SessionId, KeyStoreError> {
         if GLOBAL_KILLED.load(Ordering::SeqCst) {
             return Err(KeyStoreError::Killed);
         }
 
-        let mut g = self.state.lock().map_err(|_| {
-            GLOBAL_KILLED.store(true, Ordering::SeqCst);
-            KeyStoreError::Poisoned
-        })?;
+        if self.in_session() {
+            return Err(KeyStoreError::Reentrant);
+        }
 
-        match *g {
-            State::Locked => {
-                let session_key = auth.consume();
-                *g = State::Active(Session::new(session_key));
-                Ok(())
-            }
-            State::Active(_) => Err(KeyStoreError::AlreadyUnlocked),
+        let mut g = self.acquire_state()?;
+
+        if g.sessions.len() >= MAX_SESSIONS {
+            return Err(KeyStoreError::AlreadyUnlocked);
+        }
+
+        let id = NonZeroU64::new(g.next_id)
+            .map(SessionId)
+            .ok_or(KeyStoreError::AlreadyUnlocked)?;
+
+        let session_key = auth.consume();
+
+        if g.attest_source.is_none() {
+            let mut attest_key = GuardedKey32::zeroed();
+            derive_key(
+                &session_key,
+                Purpose::Recovery,
+                ATTESTATION_CONTEXT,
+                &mut attest_key,
+            )
+            .map_err(|_| SessionError::CryptoFailure)?;
+
+            *self.acquire_attestation()? = Some(attest_key);
+            g.attest_source = Some(id);
         }
+
+        g.next_id = g.next_id.checked_add(1).unwrap_or(0);
+        g.sessions.insert(id, Session::with_suite(session_key, suite));
+        g.last_activity = Some(Instant::now());
+        self.status.store(STATUS_UNLOCKED, Ordering::SeqCst);
+        Ok(id)
     }
 
-    /// Execute a cryptographic operation within the active session.
+    /// Execute a cryptographic operation within the FIRST session.
+    ///
+    /// Single-session convenience; see `with_session_id`.
+    ///
+    /// SECURITY:
+    /// - Re-entry from `f` => `Reentrant` (never deadlocks)
     pub fn with_session<F, R>(&self, f: F) -> Result<R, KeyStoreError>
+    where
+        F: FnOnce(&mut Session) -> Result<R, SessionError>,
+        R: SessionOutput,
+    {
+        self.run_in_session(None, f)
+    }
+
+    /// Execute a cryptographic operation within session `id`.
+    ///
+    /// SECURITY:
+    /// - Unknown / locked id => `Locked`
+    /// - Re-entry from `f` => `Reentrant` (never deadlocks)
+    pub fn with_session_id<F, R>(&self, id: SessionId, f: F) -> Result<R, KeyStoreError>
+    where
+        F: FnOnce(&mut Session) -> Result<R, SessionError>,
+        R: SessionOutput,
+    {
+        self.run_in_session(Some(id), f)
+    }
+
+    fn run_in_session<F, R>(&self, id: Option<SessionId>, f: F) -> Result<R, KeyStoreError>
     where
         F: FnOnce(&mut Session) -> Result<R, SessionError>,
         R: SessionOutput,
@@ -86,38 +291,261 @@ impl KeyStore {
             return Err(KeyStoreError::Killed);
         }
 
-        let mut g = self.state.lock().map_err(|_| {
-            GLOBAL_KILLED.store(true, Ordering::SeqCst);
+        let _scope = SessionScope::enter(self.id)?;
+
+        let mut g = self.acquire_state()?;
+        self.check_idle(&mut g)?;
+
+        let id = id.or_else(|| g.first()).ok_or(KeyStoreError::Locked)?;
+
+        let result = match g.sessions.get_mut(&id) {
+            Some(s) => f(s).map_err(KeyStoreError::from),
+            None => Err(KeyStoreError::Locked),
+        };
+
+        // A kill applied from inside `f` could not take the mutex:
+        // finish it before the scope releases it
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            g.kill_all();
+        }
+
+        result
+    }
+
+    /// Idle window elapsed => lock everything (`Locked`);
+    /// otherwise record this operation as activity.
+    fn check_idle(&self, g: &mut State) -> Result<(), KeyStoreError> {
+        let now = Instant::now();
+
+        let expired = match (self.idle_limit, g.last_activity) {
+            (Some(limit), Some(last)) => now.saturating_duration_since(last) >= limit,
+            _ => false,
+        };
+
+        if expired && !g.sessions.is_empty() {
+            g.kill_all();
+            g.last_activity = None;
+            self.status.store(STATUS_LOCKED, Ordering::SeqCst);
+
+            if let Ok(mut a) = self.acquire_attestation() {
+                a.take();
+            }
+            self.idle_locked.store(true, Ordering::SeqCst);
+            return Err(KeyStoreError::Locked);
+        }
+
+        g.last_activity = Some(now);
+        Ok(())
+    }
+
+    /// Whether an idle auto-lock happened since the last call
+    /// (reported once).
+    ///
+    /// The keystore has no event sink: its owner (`Core`) polls this
+    /// on `Locked` to emit the lock transition.
+    pub(crate) fn take_idle_lock(&self) -> bool {
+        self.idle_locked.swap(false, Ordering::SeqCst)
+    }
+
+    /// Move the last recorded activity `by` into the past.
+    #[cfg(test)]
+    pub(crate) fn backdate_activity(&self, by: Duration) {
+        if let Ok(mut g) = self.state.lock() {
+            g.last_activity = g.last_activity.and_then(|t| t.checked_sub(by));
+        }
+    }
+
+    fn acquire_state(&self) -> Result<MutexGuard<'_, State>, KeyStoreError> {
+        self.state.lock().map_err(|_| {
+            escalate(KillCause::Poison);
+            self.status.store(STATUS_KILLED, Ordering::SeqCst);
             KeyStoreError::Poisoned
-        })?;
+        })
+    }
+
+    /// Lock ONE session (zeroizes only its key).
+    ///
+    /// SECURITY:
+    /// - Other sessions are untouched
+    /// - The attestation key's source session locked => key dropped
+    ///   (it never outlives the session it was derived from)
+    /// - No effect after global kill / from a session closure
+    pub fn lock_session(&self, id: SessionId) {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) || self.in_session() {
+            return;
+        }
+
+        let drop_attestation = match self.state.lock() {
+            Ok(mut g) => {
+                if let Some(mut s) = g.sessions.remove(&id) {
+                    s.kill();
+                }
+                if g.sessions.is_empty() {
+                    self.status.store(STATUS_LOCKED, Ordering::SeqCst);
+                }
 
-        match &mut *g {
-            State::Active(s) => f(s).map_err(KeyStoreError::from),
-            State::Locked => Err(KeyStoreError::Locked),
+                let source = g.attest_source == Some(id);
+                if source {
+                    g.attest_source = None;
+                }
+                source
+            }
+            Err(_) => {
+                escalate(KillCause::Poison);
+                self.status.store(STATUS_KILLED, Ordering::SeqCst);
+                return;
+            }
+        };
+
+        if drop_attestation {
+            if let Ok(mut a) = self.acquire_attestation() {
+                a.take();
+            }
         }
     }
 
     /// Local lock (user-initiated).
     ///
     /// SECURITY:
-    /// - Explicitly kills active session
+    /// - Explicitly kills ALL active sessions (fail-safe: a user
+    ///   "lock" never leaves another vault open)
     /// - No effect after global kill
+    /// - No effect when re-entered from a session closure
     pub fn lock(&self) {
-        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) || self.in_session() {
             return;
         }
 
         match self.state.lock() {
             Ok(mut g) => {
-                if let State::Active(ref mut s) = *g {
-                    s.kill();
-                    *g = State::Locked;
-                }
+                g.kill_all();
+                self.status.store(STATUS_LOCKED, Ordering::SeqCst);
             }
             Err(_) => {
-                GLOBAL_KILLED.store(true, Ordering::SeqCst);
+                escalate(KillCause::Poison);
+                self.status.store(STATUS_KILLED, Ordering::SeqCst);
             }
         }
+
+        if let Ok(mut a) = self.acquire_attestation() {
+            a.take();
+        }
+    }
+
+    /// MAC the current kill state for a remote verifier.
+    ///
+    /// SECURITY:
+    /// - Available after kill (reports killed = true)
+    /// - Locked (never unlocked) => `Locked`
+    pub fn attest_state(
+        &self,
+        fingerprint: u64,
+        nonce: &[u8],
+    ) -> Result<[u8; 32], KeyStoreError> {
+        let killed = GLOBAL_KILLED.load(Ordering::SeqCst);
+
+        let g = self.acquire_attestation()?;
+        let key = g.as_ref().ok_or(KeyStoreError::Locked)?;
+
+        attest::attest_state(key, killed, fingerprint, nonce)
+            .map_err(|_| KeyStoreError::Session(SessionError::InvalidInput))
+    }
+
+    /// Device-bound key of the primary session (`Session::device_key`).
+    ///
+    /// SECURITY:
+    /// - Killed / locked => error (fail-closed)
+    /// - Returned in guarded memory; the state lock is released
+    ///   before the caller uses it
+    pub(crate) fn device_key(&self, fingerprint: u64) -> Result<GuardedKey32, KeyStoreError> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(KeyStoreError::Killed);
+        }
+
+        let _scope = SessionScope::enter(self.id)?;
+
+        let mut g = self.acquire_state()?;
+        self.check_idle(&mut g)?;
+        let id = g.first().ok_or(KeyStoreError::Locked)?;
+
+        match g.sessions.get(&id) {
+            Some(s) => s.device_key(fingerprint).map_err(KeyStoreError::from),
+            None => Err(KeyStoreError::Locked),
+        }
+    }
+
+    /// MAC a kill-audit export body (`kill::audit` format).
+    ///
+    /// The audit key is derived from the retained attestation key
+    /// (`Purpose::Recovery`, `AUDIT_CONTEXT`), so it works after kill.
+    pub(crate) fn mac_kill_audit(&self, body: &[u8]) -> Result<[u8; 32], KeyStoreError> {
+        let g = self.acquire_attestation()?;
+        let attest_key = g.as_ref().ok_or(KeyStoreError::Locked)?;
+
+        let mut audit_key = GuardedKey32::zeroed();
+        derive_key(attest_key, Purpose::Recovery, audit::AUDIT_CONTEXT, &mut audit_key)
+            .map_err(|_| SessionError::CryptoFailure)?;
+
+        audit::mac_body(&audit_key, body)
+            .map_err(|_| KeyStoreError::Session(SessionError::CryptoFailure))
+    }
+
+    fn acquire_attestation(
+        &self,
+    ) -> Result<MutexGuard<'_, Option<GuardedKey32>>, KeyStoreError> {
+        self.attestation.lock().map_err(|_| {
+            escalate(KillCause::Poison);
+            KeyStoreError::Poisoned
+        })
+    }
+
+    /// Whether ANY session is currently active (non-secret state).
+    ///
+    /// Takes the state mutex briefly; never touches a session key.
+    ///
+    /// SECURITY:
+    /// - Boolean state only (timing depends on lock contention,
+    ///   never on key material)
+    /// - False after global kill
+    /// - Poisoned mutex => kill escalation, returns false
+    /// - From a session closure (mutex already held) the status
+    ///   mirror answers instead: never deadlocks
+    pub fn is_unlocked(&self) -> bool {
+        self.session_count() > 0
+    }
+
+    /// Number of active sessions (`0` when locked / killed / poisoned).
+    ///
+    /// Same locking and fail-closed rules as `is_unlocked`.
+    pub fn session_count(&self) -> usize {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return 0;
+        }
+
+        if self.in_session() {
+            // Inside `with_session`: at least the caller's session
+            return usize::from(self.status.load(Ordering::SeqCst) == STATUS_UNLOCKED);
+        }
+
+        match self.acquire_state() {
+            Ok(g) if !GLOBAL_KILLED.load(Ordering::SeqCst) => g.sessions.len(),
+            _ => 0,
+        }
+    }
+
+    /// Allocate a host-held buffer that is wiped on kill.
+    ///
+    /// SECURITY:
+    /// - Forbidden after global kill
+    pub fn register_sensitive(
+        &self,
+        len: usize,
+    ) -> Result<SensitiveBuffer, KeyStoreError> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(KeyStoreError::Killed);
+        }
+
+        Ok(sensitive::register(len))
     }
 
     /// 🔥 IRREVERSIBLE CLOUD KILL 🔥
@@ -128,15 +556,217 @@ impl KeyStore {
     ///
     /// This function performs **execution only**.
     pub(crate) fn apply_verified_kill(&self) {
-        GLOBAL_KILLED.store(true, Ordering::SeqCst);
+        escalate(KillCause::VerifiedKill);
+        self.status.store(STATUS_KILLED, Ordering::SeqCst);
 
-        if let Ok(mut g) = self.state.lock() {
-            if let State::Active(ref mut s) = *g {
-                s.kill();
+        // From inside a session closure the mutex is already held:
+        // the fuse above makes every further session op fail, and
+        // `run_in_session` zeroizes the sessions when the closure ends.
+        if !self.in_session() {
+            if let Ok(mut g) = self.state.lock() {
+                g.kill_all();
             }
-            *g = State::Locked;
         }
+
+        // Host-held buffers: wiped by `escalate` (every kill cause)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::GuardedKey32;
+    use session::VerifyResult;
+
+    #[test]
+    fn nested_with_session_is_rejected() {
+        let ks = KeyStore::new();
+        let key = GuardedKey32::init_with(|k| k.fill(0x42));
+        assert!(ks.unlock(RecoveryAuthority::from_session_key(key)).is_ok());
+
+        let mut nested = None;
+        let outer = ks.with_session(|_| {
+            nested = Some(ks.with_session(|_| Ok(VerifyResult(true))));
+            Ok(VerifyResult(true))
+        });
+
+        assert!(outer == Ok(VerifyResult(true)));
+        assert!(nested == Some(Err(KeyStoreError::Reentrant)));
+
+        // Guard is released once the outer closure returns
+        assert!(ks.with_session(|_| Ok(VerifyResult(true))).is_ok());
+    }
+
+    #[test]
+    fn other_keystore_is_usable_from_a_session_closure() {
+        let a = KeyStore::new();
+        let b = KeyStore::new();
+        assert!(a.unlock(RecoveryAuthority::from_session_key(GuardedKey32::init_with(|k| k.fill(0x01)))).is_ok());
+        assert!(b.unlock(RecoveryAuthority::from_session_key(GuardedKey32::init_with(|k| k.fill(0x02)))).is_ok());
+
+        let mut inner = None;
+        let outer = a.with_session(|_| {
+            inner = Some(b.with_session(|_| Ok(VerifyResult(true))));
+            Ok(VerifyResult(true))
+        });
+        assert!(outer == Ok(VerifyResult(true)));
+        assert!(inner == Some(Ok(VerifyResult(true))));
+
+        // Re-entering A from B's closure (nested in A's) is still refused
+        let mut back = None;
+        let outer = a.with_session(|_| {
+            b.with_session(|_| {
+                back = Some(a.with_session(|_| Ok(VerifyResult(true))));
+                Ok(VerifyResult(true))
+            })
+            .map_err(|_| SessionError::InvalidInput)
+        });
+        assert!(outer == Ok(VerifyResult(true)));
+        assert!(back == Some(Err(KeyStoreError::Reentrant)));
+    }
+
+    #[test]
+    fn verified_kill_inside_a_session_wipes_on_scope_exit() {
+        assert!(crate::test_support::isolated(
+            "keystore::tests::verified_kill_inside_a_session_wipes_on_scope_exit",
+            || {
+                let ks = KeyStore::new();
+                let key = GuardedKey32::init_with(|k| k.fill(0x42));
+                assert!(ks.unlock(RecoveryAuthority::from_session_key(key)).is_ok());
+
+                let r = ks.with_session(|_| {
+                    ks.apply_verified_kill();
+                    Ok(VerifyResult(true))
+                });
+                assert!(r == Ok(VerifyResult(true)));
+
+                // Zeroized when the closure ended, not merely fenced off
+                assert!(ks.state.lock().is_ok_and(|g| g.sessions.is_empty()));
+                assert!(ks.with_session(|_| Ok(VerifyResult(true))) == Err(KeyStoreError::Killed));
+            },
+        ));
+    }
+
+    #[test]
+    fn sessions_lock_independently() -> Result<(), KeyStoreError> {
+        let ks = KeyStore::new();
+        let a = ks.unlock(RecoveryAuthority::from_session_key(
+            GuardedKey32::init_with(|k| k.fill(0x01)),
+        ))?;
+        let b = ks.unlock(RecoveryAuthority::from_session_key(
+            GuardedKey32::init_with(|k| k.fill(0x02)),
+        ))?;
+        assert!(a != b);
+
+        let ok = |ks: &KeyStore, id| ks.with_session_id(id, |_| Ok(VerifyResult(true))).is_ok();
 
+        ks.lock_session(a);
+        assert!(!ok(&ks, a));
+        assert!(ok(&ks, b));
+        assert!(ks.is_unlocked());
+
+        // `a` was the attestation source: its key is gone with it
+        assert!(matches!(ks.attest_state(1, b"nonce"), Err(KeyStoreError::Locked)));
+
+        // Convenience API falls through to the oldest live session
+        assert!(ks.with_session(|_| Ok(VerifyResult(true))).is_ok());
+
+        ks.lock();
+        assert!(!ok(&ks, b));
+        assert!(!ks.is_unlocked());
+        Ok(())
+    }
+
+    #[test]
+    fn session_count_reflects_live_sessions() -> Result<(), KeyStoreError> {
+        let ks = KeyStore::new();
+        assert_eq!(ks.session_count(), 0);
+
+        let a = ks.unlock(RecoveryAuthority::from_session_key(
+            GuardedKey32::init_with(|k| k.fill(0x01)),
+        ))?;
+        ks.unlock(RecoveryAuthority::from_session_key(
+            GuardedKey32::init_with(|k| k.fill(0x02)),
+        ))?;
+        assert_eq!(ks.session_count(), 2);
+
+        ks.lock_session(a);
+        assert_eq!(ks.session_count(), 1);
+        assert!(ks.is_unlocked());
+
+        ks.lock();
+        assert_eq!(ks.session_count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn status_mirror_tracks_transitions() -> Result<(), KeyStoreError> {
+        let ks = KeyStore::new();
+        assert_eq!(ks.status.load(Ordering::SeqCst), STATUS_LOCKED);
+
+        let a = ks.unlock(RecoveryAuthority::from_session_key(
+            GuardedKey32::init_with(|k| k.fill(0x01)),
+        ))?;
+        let b = ks.unlock(RecoveryAuthority::from_session_key(
+            GuardedKey32::init_with(|k| k.fill(0x02)),
+        ))?;
+        assert_eq!(ks.status.load(Ordering::SeqCst), STATUS_UNLOCKED);
+
+        // Still one live session
+        ks.lock_session(a);
+        assert_eq!(ks.status.load(Ordering::SeqCst), STATUS_UNLOCKED);
+
+        ks.lock_session(b);
+        assert_eq!(ks.status.load(Ordering::SeqCst), STATUS_LOCKED);
+        assert!(!ks.is_unlocked());
+        Ok(())
+    }
+
+    #[test]
+    fn polling_does_not_block_on_held_state_mutex() {
+        crate::logging::encrypted::init_test_log_root();
+        let ks = KeyStore::new();
+        assert!(ks
+            .unlock(RecoveryAuthority::from_session_key(GuardedKey32::init_with(|k| k.fill(0x42))))
+            .is_ok());
+
+        // The state mutex is held for the whole closure (as during
+        // an encryption); a mutex-based status read would deadlock
+        let mut polled = false;
+        let res = ks.with_session(|s| {
+            polled = ks.is_unlocked();
+
+            let aad = crate::crypto::aad::Aad::new(1, 0, 1, crate::crypto::aad::AAD_VERSION_V1)
+                .ok_or(SessionError::InvalidInput)?;
+            let mut out = [0u8; 4 + crate::crypto::aes_gcm::TAG_LEN];
+            s.encrypt(b"data", aad, &mut out)
+        });
+
+        assert!(res.is_ok());
+        assert!(polled);
+    }
+
+    #[test]
+    fn idle_window_locks_on_the_late_call() {
+        let ks = KeyStore::new_with_idle_limit(60);
+        assert!(ks
+            .unlock(RecoveryAuthority::from_session_key(GuardedKey32::init_with(|k| k.fill(0x42))))
+            .is_ok());
+        assert!(ks.with_session(|_| Ok(VerifyResult(true))).is_ok());
+
+        // Mocked clock: last activity two minutes ago
+        ks.backdate_activity(Duration::from_secs(120));
+        assert!(!ks.take_idle_lock());
+
+        let late = ks.with_session(|_| Ok(VerifyResult(true)));
+        assert!(late == Err(KeyStoreError::Locked));
+        assert!(!ks.is_unlocked());
+        assert!(ks.take_idle_lock());
+        assert!(!ks.take_idle_lock());
+        assert!(ks.state.lock().map(|g| g.sessions.is_empty()).unwrap_or(false));
+
+        // Disabled by default
+        assert!(KeyStore::new().idle_limit.is_none());
+        assert!(KeyStore::new_with_idle_limit(0).idle_limit.is_none());
+    }
+}
@@ -0,0 +1,582 @@
+//! Persistent per-chunk write counters (nonce-reuse prevention).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! Chunks rewritten in place under the same file key MUST NOT reuse
+//! a nonce. `Session::encrypt_versioned` reserves a fresh `version`
+//! for the `(file_id, chunk)` here; the nonce is
+//! `derive_nonce_versioned(.., version)`.
+//!
+//! STORAGE (snapshot + tail), authenticated under the ledger key
+//! (`ledger_key`: session key → `Purpose::Metadata`, `NONCE_LEDGER_CONTEXT`):
+//! - `nonce_ledger.snap`: `generation (8) || record* || tag (32)`,
+//!   ONE record per chunk (its highest version);
+//!   `tag = HMAC(ledger_key, SNAPSHOT_LABEL || body)`
+//! - `nonce_ledger.log`: length-prefixed tail, first record is the
+//!   snapshot `generation (8)` it extends; every tail record is
+//!   `body || HMAC(ledger_key, RECORD_LABEL || body)`
+//! - Record: `file_id (8) || chunk (4) || version (4)` — big-endian
+//! - `create` writes the genesis (snapshot 0 + header 0) when a key
+//!   hierarchy is provisioned; a ledger without it is MISSING
+//! - Every `COMPACT_AFTER` tail records the map is folded into a new
+//!   snapshot and the tail restarts, so rewrites never grow the tail
+//!   past the log read bound
+//!
+//! COMPACTION ORDER (crash-safe):
+//! 1. Snapshot `g + 1` replaces snapshot `g` (atomic)
+//! 2. Tail restarts with header `g + 1` (atomic)
+//! A crash between 1 and 2 leaves a tail for `g`: every record in it
+//! is already in snapshot `g + 1`, so it is dropped on open.
+//!
+//! FORMAL INVARIANTS (ENFORCED):
+//! - Write-ahead: a version is persisted BEFORE it is handed out
+//! - Versions strictly increase per `(file_id, chunk)`
+//! - Missing (no genesis, no log root) / unreadable / forged /
+//!   non-monotonic ledger => refuse (fail-closed, never reopens fresh)
+//! - Tail not extending the snapshot (or its predecessor) => refuse
+//! - Counter exhaustion => refuse (never wraps)
+//! - Forbidden after global kill
+
+use core::sync::atomic::Ordering;
+use std::collections::HashMap;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::crypto::derive::{derive_key, Purpose};
+use crate::keystore::master::GLOBAL_KILLED;
+use crate::logging::encrypted::EncryptedLog;
+use crate::memory::{ct_eq, GuardedKey32};
+
+/// Ledger key context under the session key (`Purpose::Metadata`).
+pub const NONCE_LEDGER_CONTEXT: u64 = 0x4E4F4E43454C4447; // "NONCELDG"
+
+/// Tail record MAC domain label (MUST NEVER CHANGE).
+const RECORD_LABEL: &[u8] = b"rcxcloud:nonce-ledger:record:v1";
+
+/// Snapshot MAC domain label (MUST NEVER CHANGE).
+const SNAPSHOT_LABEL: &[u8] = b"rcxcloud:nonce-ledger:snapshot:v1";
+
+const RECORD_LEN: usize = 8 + 4 + 4;
+
+/// Tail header: snapshot generation the tail extends.
+const HEADER_LEN: usize = 8;
+
+const TAG_LEN: usize = 32;
+
+/// Tail records before the next compaction (~340 KiB framed,
+/// well under the log read bound).
+const COMPACT_AFTER: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerError {
+    Killed,
+    /// Persisted state missing, corrupt, or not monotonic
+    Unverifiable,
+    /// Write-ahead record could not be persisted
+    Persist,
+    /// Counter for this chunk is used up
+    Exhausted,
+}
+
+/// Persisted half of the ledger (absent for in-memory test ledgers).
+struct Store {
+    tail: EncryptedLog,
+    snapshot: EncryptedLog,
+    key: GuardedKey32,
+    // Generation of the snapshot the tail extends (0 = none yet)
+    generation: u64,
+    // Reservation records in the tail (header excluded)
+    tail_records: usize,
+}
+
+/// Highest reserved write version per `(file_id, chunk)`.
+pub struct NonceLedger {
+    store: Option<Store>,
+    highest: HashMap<(u64, u32), u32>,
+    // Set on any persistence failure: all further reservations refused
+    poisoned: bool,
+}
+
+/// Ledger key for the key hierarchy of `session_key`.
+pub(crate) fn ledger_key(session_key: &GuardedKey32) -> Result<GuardedKey32, LedgerError> {
+    let mut key = GuardedKey32::zeroed();
+    derive_key(session_key, Purpose::Metadata, NONCE_LEDGER_CONTEXT, &mut key)
+        .map_err(|_| LedgerError::Unverifiable)?;
+    Ok(key)
+}
+
+fn persisted_logs() -> Result<(EncryptedLog, EncryptedLog), LedgerError> {
+    if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        return Err(LedgerError::Killed);
+    }
+
+    let tail = EncryptedLog::open_nonce_ledger().map_err(|_| LedgerError::Unverifiable)?;
+    let snapshot = EncryptedLog::open_nonce_snapshot().map_err(|_| LedgerError::Unverifiable)?;
+    Ok((tail, snapshot))
+}
+
+#[cfg(test)]
+fn scratch_logs(name: &str) -> Result<(EncryptedLog, EncryptedLog), LedgerError> {
+    use crate::logging::encrypted::{open_test_append, open_test_fixed};
+
+    let tail = open_test_append(&format!("{name}.log")).map_err(|_| LedgerError::Unverifiable)?;
+    let snapshot =
+        open_test_fixed(&format!("{name}.snap")).map_err(|_| LedgerError::Unverifiable)?;
+    Ok((tail, snapshot))
+}
+
+impl NonceLedger {
+    /// Start an EMPTY ledger for a newly provisioned key hierarchy.
+    ///
+    /// ⚠️ Drops every earlier reservation. RESTRICTED: call ONLY
+    /// where a new session key is born (phrase provisioning / change),
+    /// BEFORE that key can seal anything.
+    pub fn create(key: &GuardedKey32) -> Result<(), LedgerError> {
+        let (tail, snapshot) = persisted_logs()?;
+        Store::genesis(tail, snapshot, key)
+    }
+
+    /// Open and replay the persisted ledger of `key`'s hierarchy.
+    ///
+    /// No genesis (never created, or deleted) => `Unverifiable`.
+    pub fn open(key: &GuardedKey32) -> Result<Self, LedgerError> {
+        let (tail, snapshot) = persisted_logs()?;
+        Self::load(tail, snapshot, key)
+    }
+
+    /// Create over scratch files `name.log` / `name.snap` (tests).
+    #[cfg(test)]
+    pub(crate) fn create_scratch(name: &str, key: &GuardedKey32) -> Result<(), LedgerError> {
+        let (tail, snapshot) = scratch_logs(name)?;
+        Store::genesis(tail, snapshot, key)
+    }
+
+    /// Ledger over scratch files `name.log` / `name.snap` (tests).
+    #[cfg(test)]
+    pub(crate) fn open_scratch(name: &str, key: &GuardedKey32) -> Result<Self, LedgerError> {
+        let (tail, snapshot) = scratch_logs(name)?;
+        Self::load(tail, snapshot, key)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn in_memory() -> Self {
+        Self {
+            store: None,
+            highest: HashMap::new(),
+            poisoned: false,
+        }
+    }
+
+    fn load(
+        mut tail: EncryptedLog,
+        mut snapshot: EncryptedLog,
+        key: &GuardedKey32,
+    ) -> Result<Self, LedgerError> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(LedgerError::Killed);
+        }
+
+        // Missing snapshot = missing ledger (the genesis writes one)
+        let (generation, mut highest) = match snapshot.read_fixed() {
+            Ok(Some(blob)) => parse_snapshot(key, &blob)?,
+            Ok(None) | Err(()) => return Err(LedgerError::Unverifiable),
+        };
+
+        tail.verify_chain().map_err(|_| LedgerError::Unverifiable)?;
+        let records = tail
+            .read_records()
+            .map_err(|_| LedgerError::Unverifiable)?
+            .iter()
+            .map(|rec| open_record(key, rec))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (tail_generation, body) = split_header(&records)?;
+
+        let mut store = Store {
+            tail,
+            snapshot,
+            key: GuardedKey32::init_with(|k| k.copy_from_slice(key.borrow())),
+            generation,
+            tail_records: body.len(),
+        };
+
+        if tail_generation == generation {
+            replay(body, &mut highest)?;
+        } else if tail_generation.checked_add(1) == Some(generation) {
+            // Crash mid-compaction: the tail is inside the snapshot
+            store.restart_tail()?;
+        } else {
+            return Err(LedgerError::Unverifiable);
+        }
+
+        Ok(Self {
+            store: Some(store),
+            highest,
+            poisoned: false,
+        })
+    }
+
+    /// Highest version reserved so far for a chunk.
+    pub fn highest(&self, file_id: u64, chunk: u32) -> Option<u32> {
+        self.highest.get(&(file_id, chunk)).copied()
+    }
+
+    /// Reserve the next write version for `(file_id, chunk)`.
+    ///
+    /// SECURITY:
+    /// - Persisted (flushed) BEFORE returning
+    /// - A full tail is compacted first (snapshot, then restart)
+    /// - Any failure poisons the ledger for this process
+    pub fn reserve(&mut self, file_id: u64, chunk: u32) -> Result<u32, LedgerError> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(LedgerError::Killed);
+        }
+
+        if self.poisoned {
+            return Err(LedgerError::Unverifiable);
+        }
+
+        let next = match self.highest(file_id, chunk) {
+            None => 0,
+            Some(v) => v.checked_add(1).ok_or(LedgerError::Exhausted)?,
+        };
+
+        if let Some(store) = self.store.as_mut() {
+            let persisted = if store.tail_records >= COMPACT_AFTER {
+                store.compact(&self.highest)
+            } else {
+                Ok(())
+            }
+            .and_then(|()| store.append(file_id, chunk, next));
+
+            if let Err(e) = persisted {
+                self.poisoned = true;
+                return Err(e);
+            }
+        }
+
+        self.highest.insert((file_id, chunk), next);
+        Ok(next)
+    }
+}
+
+impl Store {
+    /// Snapshot 0 (empty), then a tail holding only header 0.
+    fn genesis(
+        mut tail: EncryptedLog,
+        mut snapshot: EncryptedLog,
+        key: &GuardedKey32,
+    ) -> Result<(), LedgerError> {
+        snapshot
+            .replace_fixed(&seal_snapshot(key, 0, &HashMap::new())?)
+            .map_err(|_| LedgerError::Persist)?;
+
+        tail.restart_records(&seal_record(key, &0u64.to_be_bytes())?)
+            .map_err(|_| LedgerError::Persist)
+    }
+
+    fn append(&mut self, file_id: u64, chunk: u32, version: u32) -> Result<(), LedgerError> {
+        let rec = seal_record(&self.key, &encode(file_id, chunk, version))?;
+        self.tail
+            .append_record(&rec)
+            .map_err(|_| LedgerError::Persist)?;
+
+        self.tail_records += 1;
+        Ok(())
+    }
+
+    /// Fold `highest` into snapshot `generation + 1`, then restart
+    /// the tail on it (see COMPACTION ORDER).
+    fn compact(&mut self, highest: &HashMap<(u64, u32), u32>) -> Result<(), LedgerError> {
+        let generation = self.generation.checked_add(1).ok_or(LedgerError::Exhausted)?;
+        let blob = seal_snapshot(&self.key, generation, highest)?;
+
+        self.snapshot
+            .replace_fixed(&blob)
+            .map_err(|_| LedgerError::Persist)?;
+        self.generation = generation;
+
+        self.restart_tail()
+    }
+
+    fn restart_tail(&mut self) -> Result<(), LedgerError> {
+        let header = seal_record(&self.key, &self.generation.to_be_bytes())?;
+        self.tail
+            .restart_records(&header)
+            .map_err(|_| LedgerError::Persist)?;
+
+        self.tail_records = 0;
+        Ok(())
+    }
+}
+
+fn tag(key: &GuardedKey32, label: &[u8], body: &[u8]) -> Result<[u8; TAG_LEN], LedgerError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.borrow())
+        .map_err(|_| LedgerError::Unverifiable)?;
+    mac.update(label);
+    mac.update(body);
+
+    let mut out = [0u8; TAG_LEN];
+    out.copy_from_slice(&mac.finalize().into_bytes());
+    Ok(out)
+}
+
+/// `body || tag` (tail records and the tail header).
+fn seal_record(key: &GuardedKey32, body: &[u8]) -> Result<Vec<u8>, LedgerError> {
+    let mut rec = body.to_vec();
+    rec.extend_from_slice(&tag(key, RECORD_LABEL, body)?);
+    Ok(rec)
+}
+
+/// Verify a tail record and strip its tag.
+fn open_record(key: &GuardedKey32, rec: &[u8]) -> Result<Vec<u8>, LedgerError> {
+    let body_len = rec.len().checked_sub(TAG_LEN).ok_or(LedgerError::Unverifiable)?;
+    let (body, t) = rec.split_at(body_len);
+
+    if !ct_eq(&tag(key, RECORD_LABEL, body)?, t) {
+        return Err(LedgerError::Unverifiable);
+    }
+    Ok(body.to_vec())
+}
+
+fn seal_snapshot(
+    key: &GuardedKey32,
+    generation: u64,
+    highest: &HashMap<(u64, u32), u32>,
+) -> Result<Vec<u8>, LedgerError> {
+    let mut blob = Vec::with_capacity(HEADER_LEN + highest.len() * RECORD_LEN + TAG_LEN);
+    blob.extend_from_slice(&generation.to_be_bytes());
+    for (&(file_id, chunk), &version) in highest {
+        blob.extend_from_slice(&encode(file_id, chunk, version));
+    }
+    let t = tag(key, SNAPSHOT_LABEL, &blob)?;
+    blob.extend_from_slice(&t);
+    Ok(blob)
+}
+
+fn encode(file_id: u64, chunk: u32, version: u32) -> [u8; RECORD_LEN] {
+    let mut rec = [0u8; RECORD_LEN];
+    rec[..8].copy_from_slice(&file_id.to_be_bytes());
+    rec[8..12].copy_from_slice(&chunk.to_be_bytes());
+    rec[12..].copy_from_slice(&version.to_be_bytes());
+    rec
+}
+
+fn decode(rec: &[u8]) -> Result<((u64, u32), u32), LedgerError> {
+    if rec.len() != RECORD_LEN {
+        return Err(LedgerError::Unverifiable);
+    }
+
+    let mut f = [0u8; 8];
+    let mut c = [0u8; 4];
+    let mut v = [0u8; 4];
+    f.copy_from_slice(&rec[..8]);
+    c.copy_from_slice(&rec[8..12]);
+    v.copy_from_slice(&rec[12..]);
+
+    Ok(((u64::from_be_bytes(f), u32::from_be_bytes(c)), u32::from_be_bytes(v)))
+}
+
+/// Verify and parse a snapshot into `(generation, map)`.
+///
+/// Generation 0 is the (empty) genesis snapshot.
+fn parse_snapshot(
+    key: &GuardedKey32,
+    blob: &[u8],
+) -> Result<(u64, HashMap<(u64, u32), u32>), LedgerError> {
+    let body_len = blob
+        .len()
+        .checked_sub(TAG_LEN)
+        .filter(|n| *n >= HEADER_LEN && (*n - HEADER_LEN) % RECORD_LEN == 0)
+        .ok_or(LedgerError::Unverifiable)?;
+
+    let (body, t) = blob.split_at(body_len);
+    if !ct_eq(&tag(key, SNAPSHOT_LABEL, body)?, t) {
+        return Err(LedgerError::Unverifiable);
+    }
+
+    let mut g = [0u8; HEADER_LEN];
+    g.copy_from_slice(&body[..HEADER_LEN]);
+    let generation = u64::from_be_bytes(g);
+    if generation == 0 && body.len() != HEADER_LEN {
+        return Err(LedgerError::Unverifiable);
+    }
+
+    let mut highest = HashMap::new();
+    for rec in body[HEADER_LEN..].chunks_exact(RECORD_LEN) {
+        let (key, version) = decode(rec)?;
+
+        // One record per chunk
+        if highest.insert(key, version).is_some() {
+            return Err(LedgerError::Unverifiable);
+        }
+    }
+
+    Ok((generation, highest))
+}
+
+/// Split the tail into `(generation it extends, reservation records)`.
+///
+/// The header is mandatory: a tail without one is not a ledger.
+fn split_header(records: &[Vec<u8>]) -> Result<(u64, &[Vec<u8>]), LedgerError> {
+    match records.split_first() {
+        Some((first, rest)) if first.len() == HEADER_LEN => {
+            let mut g = [0u8; HEADER_LEN];
+            g.copy_from_slice(first);
+            Ok((u64::from_be_bytes(g), rest))
+        }
+        _ => Err(LedgerError::Unverifiable),
+    }
+}
+
+/// Apply tail records on top of `highest`, verifying monotonicity.
+fn replay(records: &[Vec<u8>], highest: &mut HashMap<(u64, u32), u32>) -> Result<(), LedgerError> {
+    for rec in records {
+        let (key, version) = decode(rec)?;
+
+        if let Some(prev) = highest.get(&key) {
+            if version <= *prev {
+                return Err(LedgerError::Unverifiable);
+            }
+        }
+
+        highest.insert(key, version);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(file_id: u64, chunk: u32, version: u32) -> Vec<u8> {
+        let mut r = file_id.to_be_bytes().to_vec();
+        r.extend_from_slice(&chunk.to_be_bytes());
+        r.extend_from_slice(&version.to_be_bytes());
+        r
+    }
+
+    #[test]
+    fn reservations_are_strictly_increasing_per_chunk() {
+        let mut ledger = NonceLedger::in_memory();
+
+        assert_eq!(ledger.reserve(1, 0), Ok(0));
+        assert_eq!(ledger.reserve(1, 0), Ok(1));
+        assert_eq!(ledger.reserve(1, 1), Ok(0));
+        assert_eq!(ledger.highest(1, 0), Some(1));
+
+        ledger.highest.insert((2, 0), u32::MAX);
+        assert_eq!(ledger.reserve(2, 0), Err(LedgerError::Exhausted));
+    }
+
+    #[test]
+    fn replay_rejects_non_monotonic_or_corrupt_ledger() {
+        let mut m = HashMap::new();
+        assert_eq!(replay(&[record(1, 0, 0), record(1, 0, 3), record(1, 1, 0)], &mut m), Ok(()));
+        assert_eq!(m.get(&(1, 0)), Some(&3));
+
+        // The tail must also move past the snapshot
+        assert_eq!(replay(&[record(1, 0, 3)], &mut m), Err(LedgerError::Unverifiable));
+        assert_eq!(
+            replay(&[vec![0u8; RECORD_LEN - 1]], &mut HashMap::new()),
+            Err(LedgerError::Unverifiable)
+        );
+    }
+
+    fn key() -> GuardedKey32 {
+        GuardedKey32::init_with(|k| k.fill(0x5A))
+    }
+
+    fn scratch(name: &str) -> Result<String, LedgerError> {
+        let name = format!("{name}-{}", rand_core::RngCore::next_u64(&mut rand_core::OsRng));
+        NonceLedger::create_scratch(&name, &key())?;
+        Ok(name)
+    }
+
+    #[test]
+    fn missing_or_forged_ledger_is_refused() -> Result<(), LedgerError> {
+        // Never created (or deleted): no genesis => refused, not fresh
+        let name = format!("ledger-missing-{}", rand_core::RngCore::next_u64(&mut rand_core::OsRng));
+        assert!(matches!(NonceLedger::open_scratch(&name, &key()), Err(LedgerError::Unverifiable)));
+
+        let name = scratch("ledger-forged")?;
+        let mut ledger = NonceLedger::open_scratch(&name, &key())?;
+        assert_eq!(ledger.reserve(1, 0), Ok(0));
+        drop(ledger);
+
+        // Another key cannot read (or extend) it
+        let other = GuardedKey32::init_with(|k| k.fill(0x5B));
+        assert!(matches!(NonceLedger::open_scratch(&name, &other), Err(LedgerError::Unverifiable)));
+
+        // An unauthenticated record (the old plain format) is refused
+        let mut tail = crate::logging::encrypted::open_test_append(&format!("{name}.log"))
+            .map_err(|_| LedgerError::Persist)?;
+        tail.append_record(&record(1, 0, 0)).map_err(|_| LedgerError::Persist)?;
+        drop(tail);
+        assert!(matches!(NonceLedger::open_scratch(&name, &key()), Err(LedgerError::Unverifiable)));
+        Ok(())
+    }
+
+    #[test]
+    fn rewrites_past_the_log_bound_still_reopen() -> Result<(), LedgerError> {
+        let name = scratch("ledger-compact")?;
+
+        // 84 framed bytes per record: well past the 1 MiB read bound
+        let rewrites = 3 * COMPACT_AFTER as u32 + 7;
+        let mut ledger = NonceLedger::open_scratch(&name, &key())?;
+        for v in 0..rewrites {
+            assert_eq!(ledger.reserve(9, 0), Ok(v));
+        }
+        assert_eq!(ledger.reserve(9, 1), Ok(0));
+        drop(ledger);
+
+        let mut ledger = NonceLedger::open_scratch(&name, &key())?;
+        assert_eq!(ledger.highest(9, 0), Some(rewrites - 1));
+        assert_eq!(ledger.highest(9, 1), Some(0));
+        assert_eq!(ledger.reserve(9, 0), Ok(rewrites));
+        Ok(())
+    }
+
+    #[test]
+    fn stale_tail_after_interrupted_compaction_is_dropped() -> Result<(), LedgerError> {
+        use crate::logging::encrypted::open_test_fixed;
+
+        let name = scratch("ledger-crash")?;
+        let full = COMPACT_AFTER as u32;
+
+        let mut ledger = NonceLedger::open_scratch(&name, &key())?;
+        for v in 0..full {
+            assert_eq!(ledger.reserve(4, 2), Ok(v));
+        }
+
+        // Crash between steps 1 and 2: snapshot 1 on disk, tail still
+        // the generation-0 records it already covers
+        let blob = seal_snapshot(&key(), 1, &HashMap::from([((4, 2), full - 1)]))?;
+
+        let mut snap = open_test_fixed(&format!("{name}.snap")).map_err(|_| LedgerError::Persist)?;
+        snap.replace_fixed(&blob).map_err(|_| LedgerError::Persist)?;
+        drop((snap, ledger));
+
+        let mut ledger = NonceLedger::open_scratch(&name, &key())?;
+        assert_eq!(ledger.highest(4, 2), Some(full - 1));
+        assert_eq!(ledger.reserve(4, 2), Ok(full));
+        drop(ledger);
+
+        // The open finished the compaction: the tail now extends 1
+        let ledger = NonceLedger::open_scratch(&name, &key())?;
+        assert_eq!(ledger.highest(4, 2), Some(full));
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_snapshot_is_refused() -> Result<(), LedgerError> {
+        let mut blob = seal_snapshot(&key(), 1, &HashMap::from([((1, 0), 5)]))?;
+
+        assert!(matches!(parse_snapshot(&key(), &blob), Ok((1, m)) if m.get(&(1, 0)) == Some(&5)));
+
+        blob[HEADER_LEN + RECORD_LEN - 1] = 4;
+        assert!(matches!(parse_snapshot(&key(), &blob), Err(LedgerError::Unverifiable)));
+        Ok(())
+    }
+}
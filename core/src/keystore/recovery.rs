@@ -12,26 +12,186 @@
 //! - Session key is guarded
 //! - Authority is single-use
 //! - No panics in cryptographic paths
+//!
+//! PHRASE VERIFIER:
+//! - `HMAC(root, VERIFIER_LABEL)`, persisted by the caller
+//! - Proves a phrase is THE provisioned phrase without storing
+//!   anything that derives a key
+//!
+//! WRAPPED VERIFIER (phrase rotation):
+//! - `verifier (32) || nonce (12) || AES-GCM(kek, session) (32 + 16)`
+//! - `kek` is derived from the NEW phrase's session key
+//!   (`Purpose::Recovery`, `WRAP_CONTEXT`); the verifier is the AAD
+//! - Lets a new phrase recover the SAME session key, so file keys
+//!   stay valid without re-encryption
+//!
+//! PERSISTED BINDING (`encode_binding` / `decode_binding`):
+//! - `verifier | wrapped verifier || RecoveryKdf (13)`
+//! - Carries the (possibly calibrated) KDF cost so re-derivation
+//!   uses exactly the cost the phrase was bound under
+//! - Legacy records (bare 32 / 92 bytes) imply `RecoveryKdf::default()`
 
 #![deny(clippy::derive_debug)]
 
+use crate::crypto::aes_gcm::{self, NONCE_LEN, TAG_LEN};
+use crate::crypto::derive::{derive_key, Purpose};
 use crate::crypto::kdf_argon2;
+#[cfg(feature = "scrypt")]
+use crate::crypto::kdf_scrypt;
 use crate::integrity::verify_key_integrity;
-use crate::memory::GuardedKey32;
+use crate::keystore::master::GLOBAL_KILLED;
+use crate::keystore::nonce_ledger::{ledger_key, LedgerError, NonceLedger};
+use crate::memory::{ct_eq, GuardedKey32, GuardedVec};
+use hmac::{Hmac, Mac};
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+use core::sync::atomic::Ordering;
+use std::time::Duration;
 use zeroize::Zeroizing;
 
+pub mod mnemonic;
+pub mod shamir;
+
+/// Maximum accepted recovery phrase length (bytes).
+///
+/// Bounds KDF input and FFI ingress (a 24-word BIP39 phrase is
+/// well under 256 bytes).
+pub const MAX_PHRASE_LEN: usize = 1024;
+
+/// Serialized phrase verifier length.
+pub const VERIFIER_LEN: usize = 32;
+
+/// Serialized wrapped verifier length (see module docs).
+pub const WRAPPED_VERIFIER_LEN: usize = VERIFIER_LEN + NONCE_LEN + 32 + TAG_LEN;
+
+/// Session-key wrapping context under `Purpose::Recovery`.
+///
+/// ⚠️ MUST NEVER CHANGE.
+const WRAP_CONTEXT: u64 = 0x5752_4150_5345_5331; // "WRAPSES1"
+
+/// Verifier domain label (MUST NEVER CHANGE).
+const VERIFIER_LABEL: &[u8] = b"rcxcloud:recovery:verifier:v1";
+
+/// KDF salt for phrase derivation (MUST NEVER CHANGE).
+const PHRASE_SALT: &[u8] = b"rcxcloud-recovery-v1";
+
 /* ───────────── CONFIG ───────────── */
 
+/// Recovery KDF selection (non-secret).
+///
+/// The SAME selection MUST be used to re-derive from a phrase:
+/// persist it with `to_bytes` and restore it with `from_bytes`.
 #[derive(Clone, Copy)]
-pub struct RecoveryConfig {
-    pub kdf: kdf_argon2::Params,
+pub enum RecoveryKdf {
+    Argon2id(kdf_argon2::Params),
+    /// Memory-constrained devices
+    #[cfg(feature = "scrypt")]
+    Scrypt(kdf_scrypt::Params),
 }
 
-impl Default for RecoveryConfig {
+/// Encoded `RecoveryKdf` length: `id (1) || 3 × u32 (BE)`.
+pub const RECOVERY_KDF_LEN: usize = 13;
+
+const KDF_ID_ARGON2ID: u8 = 1;
+#[cfg(feature = "scrypt")]
+const KDF_ID_SCRYPT: u8 = 2;
+
+impl Default for RecoveryKdf {
     fn default() -> Self {
+        RecoveryKdf::Argon2id(kdf_argon2::Params::default())
+    }
+}
+
+impl RecoveryKdf {
+    /// Stable encoding of the selection and its parameters.
+    pub fn to_bytes(&self) -> [u8; RECOVERY_KDF_LEN] {
+        let (id, a, b, c) = match self {
+            RecoveryKdf::Argon2id(p) => (KDF_ID_ARGON2ID, p.mem_kib, p.time, p.lanes),
+            #[cfg(feature = "scrypt")]
+            RecoveryKdf::Scrypt(p) => (KDF_ID_SCRYPT, u32::from(p.log_n), p.r, p.p),
+        };
+
+        let mut out = [0u8; RECOVERY_KDF_LEN];
+        out[0] = id;
+        out[1..5].copy_from_slice(&a.to_be_bytes());
+        out[5..9].copy_from_slice(&b.to_be_bytes());
+        out[9..13].copy_from_slice(&c.to_be_bytes());
+        out
+    }
+
+    /// Restore a persisted selection.
+    ///
+    /// Unknown / not-compiled KDF id => `None` (parameter bounds are
+    /// enforced again by the KDF itself).
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != RECOVERY_KDF_LEN {
+            return None;
+        }
+
+        let word = |i: usize| {
+            u32::from_be_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]])
+        };
+        let (a, b, c) = (word(1), word(5), word(9));
+
+        match bytes[0] {
+            KDF_ID_ARGON2ID => Some(RecoveryKdf::Argon2id(kdf_argon2::Params {
+                mem_kib: a,
+                time: b,
+                lanes: c,
+            })),
+            #[cfg(feature = "scrypt")]
+            KDF_ID_SCRYPT => Some(RecoveryKdf::Scrypt(kdf_scrypt::Params {
+                log_n: u8::try_from(a).ok()?,
+                r: b,
+                p: c,
+            })),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct RecoveryConfig {
+    pub kdf: RecoveryKdf,
+}
+
+impl RecoveryConfig {
+    /// Argon2id at the default memory cost, `time` calibrated to
+    /// `target` unlock latency on this device (bounded; see
+    /// `kdf_argon2::Params::calibrate`).
+    pub fn calibrated(target: Duration) -> Self {
+        let mem_kib = kdf_argon2::Params::default().mem_kib;
         Self {
-            kdf: kdf_argon2::Params::default(),
+            kdf: RecoveryKdf::Argon2id(kdf_argon2::Params::calibrate(target, mem_kib)),
+        }
+    }
+}
+
+/* ───────────── PERSISTED BINDING ───────────── */
+
+/// Persist a (wrapped) verifier together with the KDF it was bound under.
+pub fn encode_binding(verifier: &[u8], cfg: &RecoveryConfig) -> Vec<u8> {
+    let mut out = Vec::with_capacity(verifier.len() + RECOVERY_KDF_LEN);
+    out.extend_from_slice(verifier);
+    out.extend_from_slice(&cfg.kdf.to_bytes());
+    out
+}
+
+/// Split a persisted binding into its KDF selection and verifier.
+///
+/// Legacy bare verifiers => default KDF. Unknown length / KDF =>
+/// `None` (fail-closed: NEVER guess a cost).
+pub fn decode_binding(stored: &[u8]) -> Option<(RecoveryConfig, &[u8])> {
+    match stored.len() {
+        VERIFIER_LEN | WRAPPED_VERIFIER_LEN => Some((RecoveryConfig::default(), stored)),
+        n if n == VERIFIER_LEN + RECOVERY_KDF_LEN
+            || n == WRAPPED_VERIFIER_LEN + RECOVERY_KDF_LEN =>
+        {
+            let (verifier, kdf) = stored.split_at(n - RECOVERY_KDF_LEN);
+            let kdf = RecoveryKdf::from_bytes(kdf)?;
+            Some((RecoveryConfig { kdf }, verifier))
         }
+        _ => None,
     }
 }
 
@@ -42,6 +202,16 @@ pub enum RecoveryError {
     InvalidInput,
     KdfFailure,
     IntegrityFailure,
+    /// Mnemonic is not 12 / 24 words
+    InvalidWordCount,
+    /// Mnemonic word outside the BIP39 wordlist
+    UnknownWord,
+    /// Mnemonic checksum bits do not match
+    BadChecksum,
+    /// Fewer than `k` distinct Shamir shares
+    InsufficientShares,
+    /// Shamir share index supplied twice
+    DuplicateShare,
 }
 
 /* ───────────── AUTHORITY ───────────── */
@@ -61,9 +231,21 @@ impl RecoveryAuthority {
     pub(crate) fn consume(self) -> GuardedKey32 {
         self.session
     }
+
+    /// Write the empty nonce ledger of this key hierarchy
+    /// (`NonceLedger::create`). Provisioning / phrase change ONLY.
+    pub(crate) fn start_nonce_ledger(&self) -> Result<(), LedgerError> {
+        NonceLedger::create(&ledger_key(&self.session)?)
+    }
+
+    /// Test-only authority over a fixed session key.
+    #[cfg(test)]
+    pub(crate) fn from_session_key(session: GuardedKey32) -> Self {
+        Self { session }
+    }
 }
 
-/* ───────────── ENTRY POINT ───────────── */
+/* ───────────── ENTRY POINTS ───────────── */
 
 /// Recover a session authority from a recovery phrase.
 ///
@@ -75,7 +257,154 @@ pub fn recover_from_phrase(
     phrase: Zeroizing<Vec<u8>>,
     cfg: &RecoveryConfig,
 ) -> Result<RecoveryAuthority, RecoveryError> {
-    if phrase.is_empty() {
+    recover_from_bytes(&phrase, cfg)
+}
+
+/// Recover from a phrase held in LOCKED memory.
+///
+/// Same checks as `recover_with_verifier` (when `verifier` is
+/// `Some`) or `recover_from_phrase`, without ever copying the
+/// phrase out of its `GuardedVec`.
+pub fn recover_from_guarded(
+    phrase: &GuardedVec,
+    cfg: &RecoveryConfig,
+    verifier: Option<&[u8]>,
+) -> Result<RecoveryAuthority, RecoveryError> {
+    match verifier {
+        Some(v) => recover_bytes_with_verifier(phrase.borrow(), cfg, v),
+        None => recover_from_bytes(phrase.borrow(), cfg),
+    }
+}
+
+fn recover_from_bytes(
+    phrase: &[u8],
+    cfg: &RecoveryConfig,
+) -> Result<RecoveryAuthority, RecoveryError> {
+    let (root, session) = derive_phrase_keys(phrase, cfg)?;
+
+    // Cryptographic binding check
+    verify_key_integrity(&root, &session)
+        .map_err(|_| RecoveryError::IntegrityFailure)?;
+
+    // Root is dropped here; session becomes authority
+    Ok(RecoveryAuthority { session })
+}
+
+/// Provision a phrase: derive its authority AND its verifier.
+///
+/// SECURITY:
+/// - Verifier is one-way from the root (no key material)
+/// - Root is dropped (zeroized) before returning
+pub fn provision_phrase(
+    phrase: Zeroizing<Vec<u8>>,
+    cfg: &RecoveryConfig,
+) -> Result<(RecoveryAuthority, [u8; VERIFIER_LEN]), RecoveryError> {
+    let (root, session) = derive_phrase_keys(&phrase, cfg)?;
+    let verifier = phrase_verifier(&root)?;
+
+    Ok((RecoveryAuthority { session }, verifier))
+}
+
+/// Re-bind an EXISTING session authority to a new phrase.
+///
+/// Returns the (unchanged) authority and a wrapped verifier for
+/// `new_phrase`; persisting it replaces the old binding.
+///
+/// SECURITY:
+/// - Session key is never re-derived: file keys stay valid
+/// - Fresh random nonce per wrap (a phrase may be reused)
+/// - The wrap is opened again before returning (fail-closed)
+/// - Forbidden after global kill
+pub fn rewrap_phrase(
+    authority: RecoveryAuthority,
+    new_phrase: Zeroizing<Vec<u8>>,
+    cfg: &RecoveryConfig,
+) -> Result<(RecoveryAuthority, [u8; WRAPPED_VERIFIER_LEN]), RecoveryError> {
+    if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        return Err(RecoveryError::IntegrityFailure);
+    }
+
+    let (root, phrase_session) = derive_phrase_keys(&new_phrase, cfg)?;
+    let verifier = phrase_verifier(&root)?;
+    drop(root);
+
+    let kek = wrap_key(&phrase_session)?;
+    drop(phrase_session);
+
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng
+        .try_fill_bytes(&mut nonce)
+        .map_err(|_| RecoveryError::KdfFailure)?;
+
+    let mut out = [0u8; WRAPPED_VERIFIER_LEN];
+    out[..VERIFIER_LEN].copy_from_slice(&verifier);
+    out[VERIFIER_LEN..VERIFIER_LEN + NONCE_LEN].copy_from_slice(&nonce);
+
+    let session = authority.consume();
+    aes_gcm::seal(
+        &kek,
+        &nonce,
+        session.borrow(),
+        &verifier,
+        &mut out[VERIFIER_LEN + NONCE_LEN..],
+    )
+    .map_err(|_| RecoveryError::KdfFailure)?;
+
+    // Round-trip before the caller commits anything
+    let reopened = unwrap_session(&kek, &out).ok_or(RecoveryError::IntegrityFailure)?;
+    if !ct_eq(reopened.borrow(), session.borrow()) {
+        return Err(RecoveryError::IntegrityFailure);
+    }
+
+    Ok((RecoveryAuthority { session }, out))
+}
+
+/// Recover a session authority from a phrase checked against a
+/// provisioned (plain or wrapped) verifier.
+///
+/// SECURITY:
+/// - Constant-time comparison
+/// - Wrapped: session key comes ONLY from the authenticated wrap
+/// - Wrong phrase / malformed verifier => `IntegrityFailure`
+pub fn recover_with_verifier(
+    phrase: Zeroizing<Vec<u8>>,
+    cfg: &RecoveryConfig,
+    verifier: &[u8],
+) -> Result<RecoveryAuthority, RecoveryError> {
+    recover_bytes_with_verifier(&phrase, cfg, verifier)
+}
+
+fn recover_bytes_with_verifier(
+    phrase: &[u8],
+    cfg: &RecoveryConfig,
+    verifier: &[u8],
+) -> Result<RecoveryAuthority, RecoveryError> {
+    if verifier.len() != VERIFIER_LEN && verifier.len() != WRAPPED_VERIFIER_LEN {
+        return Err(RecoveryError::IntegrityFailure);
+    }
+
+    let (root, session) = derive_phrase_keys(phrase, cfg)?;
+
+    if !ct_eq(&phrase_verifier(&root)?, &verifier[..VERIFIER_LEN]) {
+        return Err(RecoveryError::IntegrityFailure);
+    }
+
+    if verifier.len() == VERIFIER_LEN {
+        return Ok(RecoveryAuthority { session });
+    }
+
+    let kek = wrap_key(&session)?;
+    let session = unwrap_session(&kek, verifier).ok_or(RecoveryError::IntegrityFailure)?;
+
+    Ok(RecoveryAuthority { session })
+}
+
+/// Deterministic phrase KDF (no RNG) into guarded `(root, session)`.
+fn derive_phrase_keys(
+    phrase: &[u8],
+    cfg: &RecoveryConfig,
+) -> Result<(GuardedKey32, GuardedKey32), RecoveryError> {
+    if phrase.is_empty() || phrase.len() > MAX_PHRASE_LEN {
         return Err(RecoveryError::InvalidInput);
     }
 
@@ -83,22 +412,189 @@ pub fn recover_from_phrase(
     let mut root = GuardedKey32::zeroed();
     let mut session = GuardedKey32::zeroed();
 
-    // Deterministic KDF (no RNG)
-    kdf_argon2::derive_two_keys(
-        &phrase,
-        b"rcxcloud-recovery-v1",
-        &cfg.kdf,
-        &mut root,
-        &mut session,
-    )
+    match &cfg.kdf {
+        RecoveryKdf::Argon2id(p) => {
+            kdf_argon2::derive_two_keys(phrase, PHRASE_SALT, p, &mut root, &mut session)
+        }
+        #[cfg(feature = "scrypt")]
+        RecoveryKdf::Scrypt(p) => {
+            kdf_scrypt::derive_two_keys(phrase, PHRASE_SALT, p, &mut root, &mut session)
+        }
+    }
     .map_err(|_| RecoveryError::KdfFailure)?;
 
-    // Cryptographic binding check
-    verify_key_integrity(&root, &session)
-        .map_err(|_| RecoveryError::IntegrityFailure)?;
+    Ok((root, session))
+}
 
-    // Root is dropped here; session becomes authority
-    Ok(RecoveryAuthority { session })
+/// Key-encryption key for a wrapped verifier.
+fn wrap_key(phrase_session: &GuardedKey32) -> Result<GuardedKey32, RecoveryError> {
+    let mut kek = GuardedKey32::zeroed();
+    derive_key(phrase_session, Purpose::Recovery, WRAP_CONTEXT, &mut kek)
+        .map_err(|_| RecoveryError::KdfFailure)?;
+    Ok(kek)
+}
+
+/// Open the wrapped session key (`None` on any auth failure).
+fn unwrap_session(kek: &GuardedKey32, wrapped: &[u8]) -> Option<GuardedKey32> {
+    if wrapped.len() != WRAPPED_VERIFIER_LEN {
+        return None;
+    }
+
+    let (verifier, rest) = wrapped.split_at(VERIFIER_LEN);
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let nonce: &[u8; NONCE_LEN] = nonce.try_into().ok()?;
+
+    let mut session = GuardedKey32::zeroed();
+    if !aes_gcm::open(kek, nonce, sealed, verifier, session.borrow_mut()) {
+        return None;
+    }
+    Some(session)
 }
 
+fn phrase_verifier(root: &GuardedKey32) -> Result<[u8; VERIFIER_LEN], RecoveryError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(root.borrow())
+        .map_err(|_| RecoveryError::KdfFailure)?;
+    mac.update(VERIFIER_LABEL);
+
+    let mut out = [0u8; VERIFIER_LEN];
+    out.copy_from_slice(&mac.finalize().into_bytes());
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kdf_selection_round_trips_through_encoding() {
+        let argon = RecoveryKdf::default();
+        let bytes = argon.to_bytes();
+        assert!(matches!(
+            RecoveryKdf::from_bytes(&bytes),
+            Some(RecoveryKdf::Argon2id(p)) if p.mem_kib == 64 * 1024 && p.time == 3
+        ));
+
+        #[cfg(feature = "scrypt")]
+        {
+            let bytes = RecoveryKdf::Scrypt(kdf_scrypt::Params::default()).to_bytes();
+            assert!(matches!(
+                RecoveryKdf::from_bytes(&bytes),
+                Some(RecoveryKdf::Scrypt(p)) if p.log_n == 15 && p.r == 8
+            ));
+        }
+
+        assert!(RecoveryKdf::from_bytes(&[0xFF; RECOVERY_KDF_LEN]).is_none());
+        assert!(RecoveryKdf::from_bytes(&bytes[..4]).is_none());
+    }
+
+    #[test]
+    fn verifier_accepts_only_the_provisioned_phrase() -> Result<(), RecoveryError> {
+        let cfg = RecoveryConfig {
+            kdf: RecoveryKdf::Argon2id(kdf_argon2::Params {
+                mem_kib: 8 * 1024,
+                time: 1,
+                lanes: 1,
+            }),
+        };
+        let phrase = || Zeroizing::new(b"correct horse battery staple".to_vec());
+
+        let (auth, verifier) = provision_phrase(phrase(), &cfg)?;
+        let again = recover_with_verifier(phrase(), &cfg, &verifier)?;
+        let guarded = GuardedVec::init_with(phrase().len(), |b| b.copy_from_slice(&phrase()));
+        let locked = recover_from_guarded(&guarded, &cfg, Some(&verifier))?;
+        let again = again.consume();
+        assert_eq!(locked.consume().borrow(), again.borrow());
+        assert_eq!(auth.consume().borrow(), again.borrow());
 
+        assert!(matches!(
+            recover_with_verifier(Zeroizing::new(b"wrong".to_vec()), &cfg, &verifier),
+            Err(RecoveryError::IntegrityFailure)
+        ));
+        assert!(matches!(
+            recover_with_verifier(phrase(), &cfg, &verifier[..8]),
+            Err(RecoveryError::IntegrityFailure)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn rewrapped_phrase_recovers_the_same_session_key() -> Result<(), RecoveryError> {
+        let cfg = RecoveryConfig {
+            kdf: RecoveryKdf::Argon2id(kdf_argon2::Params {
+                mem_kib: 8 * 1024,
+                time: 1,
+                lanes: 1,
+            }),
+        };
+        let old = || Zeroizing::new(b"old phrase".to_vec());
+        let new = || Zeroizing::new(b"new phrase".to_vec());
+
+        let (auth, _) = provision_phrase(old(), &cfg)?;
+        let original = GuardedKey32::init_with(|k| k.copy_from_slice(auth_bytes(&auth)));
+
+        let (_, wrapped) = rewrap_phrase(auth, new(), &cfg)?;
+
+        let again = recover_with_verifier(new(), &cfg, &wrapped)?;
+        assert_eq!(again.consume().borrow(), original.borrow());
+
+        // Old binding no longer matches
+        assert!(matches!(
+            recover_with_verifier(old(), &cfg, &wrapped),
+            Err(RecoveryError::IntegrityFailure)
+        ));
+
+        // Tampered wrap fails closed
+        let mut tampered = wrapped;
+        tampered[WRAPPED_VERIFIER_LEN - 1] ^= 1;
+        assert!(matches!(
+            recover_with_verifier(new(), &cfg, &tampered),
+            Err(RecoveryError::IntegrityFailure)
+        ));
+        Ok(())
+    }
+
+    fn auth_bytes(auth: &RecoveryAuthority) -> &[u8; 32] {
+        auth.session.borrow()
+    }
+
+    #[test]
+    fn binding_carries_kdf_cost_and_accepts_legacy() {
+        let cfg = RecoveryConfig {
+            kdf: RecoveryKdf::Argon2id(kdf_argon2::Params {
+                mem_kib: 16 * 1024,
+                time: 7,
+                lanes: 1,
+            }),
+        };
+        let verifier = [0xAB; VERIFIER_LEN];
+
+        let stored = encode_binding(&verifier, &cfg);
+        assert!(matches!(
+            decode_binding(&stored),
+            Some((RecoveryConfig { kdf: RecoveryKdf::Argon2id(p) }, v))
+                if p.time == 7 && p.mem_kib == 16 * 1024 && v == verifier
+        ));
+
+        // Legacy bare verifier => default cost
+        assert!(matches!(
+            decode_binding(&verifier),
+            Some((RecoveryConfig { kdf: RecoveryKdf::Argon2id(p) }, _)) if p.time == 3
+        ));
+
+        // Truncated / unknown KDF => rejected
+        assert!(decode_binding(&stored[..stored.len() - 1]).is_none());
+        let mut unknown = stored;
+        unknown[VERIFIER_LEN] = 0xFF;
+        assert!(decode_binding(&unknown).is_none());
+    }
+
+    #[test]
+    fn over_cap_phrase_is_rejected_before_kdf() {
+        let phrase = Zeroizing::new(vec![b'a'; MAX_PHRASE_LEN + 1]);
+
+        assert!(matches!(
+            recover_from_phrase(phrase, &RecoveryConfig::default()),
+            Err(RecoveryError::InvalidInput)
+        ));
+    }
+}
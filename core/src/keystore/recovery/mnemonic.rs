@@ -0,0 +1,217 @@
+//! BIP39 mnemonic ↔ entropy (English wordlist).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! PURPOSE:
+//! - Turn a 12 / 24-word BIP39 mnemonic into the entropy fed to
+//!   `recover_from_phrase` (and back, for phrase generation)
+//!
+//! FORMAL INVARIANTS:
+//! - Only 12 (128-bit) and 24 (256-bit) word mnemonics
+//! - Checksum bits are verified (constant-time compare)
+//! - Word lookup scans the FULL wordlist (no early exit)
+//! - All intermediate buffers are zeroized
+//! - No panics
+
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+use super::RecoveryError;
+
+/// BIP39 English wordlist (2048 words, sorted).
+///
+/// SHA-256: 2f5eed53a4727b4bf8880d8f3f199efc90e58503646d9ff8eff3a2ed3b24dbda
+const WORDLIST: &str = include_str!("english.txt");
+
+const WORD_COUNT: usize = 2048;
+const BITS_PER_WORD: usize = 11;
+
+/// Largest `entropy || checksum` buffer (24 words = 264 bits).
+const MAX_PACKED_LEN: usize = 33;
+
+/* ───────────── LOOKUP ───────────── */
+
+/// Wordlist index of `word` (full scan, no early exit).
+fn word_index(word: &str) -> Option<u16> {
+    let mut found: u16 = 0;
+    let mut hit = 0u8;
+
+    for (i, cand) in WORDLIST.lines().enumerate() {
+        let eq = cand.as_bytes().ct_eq(word.as_bytes()).unwrap_u8();
+        found |= (i as u16) * u16::from(eq);
+        hit |= eq;
+    }
+
+    (hit == 1).then_some(found)
+}
+
+/// Word at `index` (full scan, no early exit).
+fn word_at(index: u16) -> Option<&'static str> {
+    let mut out = None;
+
+    for (i, cand) in WORDLIST.lines().enumerate() {
+        if i == usize::from(index) {
+            out = Some(cand);
+        }
+    }
+
+    out
+}
+
+/// Entropy length (bytes) for a mnemonic of `words` words.
+#[inline(always)]
+fn entropy_len(words: usize) -> Option<usize> {
+    match words {
+        12 => Some(16),
+        24 => Some(32),
+        _ => None,
+    }
+}
+
+/// First `entropy.len() / 4` bits of SHA-256(entropy), left-aligned.
+#[inline(always)]
+fn checksum_byte(entropy: &[u8]) -> u8 {
+    let cs_bits = entropy.len() / 4;
+    let digest = Sha256::digest(entropy);
+    digest[0] & (0xFFu8 << (8 - cs_bits))
+}
+
+/* ───────────── PHRASE → ENTROPY ───────────── */
+
+/// Validate a BIP39 mnemonic and return its entropy.
+///
+/// ERRORS:
+/// - `InvalidWordCount` — not 12 / 24 words
+/// - `UnknownWord` — word outside the English wordlist
+/// - `BadChecksum` — checksum bits do not match
+pub fn phrase_to_entropy(words: &[&str]) -> Result<Zeroizing<Vec<u8>>, RecoveryError> {
+    let ent_len = entropy_len(words.len()).ok_or(RecoveryError::InvalidWordCount)?;
+
+    let mut packed = Zeroizing::new([0u8; MAX_PACKED_LEN]);
+
+    for (w, word) in words.iter().enumerate() {
+        let index = word_index(word).ok_or(RecoveryError::UnknownWord)?;
+
+        for b in 0..BITS_PER_WORD {
+            let bit = ((index >> (BITS_PER_WORD - 1 - b)) & 1) as u8;
+            let pos = w * BITS_PER_WORD + b;
+            packed[pos / 8] |= bit << (7 - pos % 8);
+        }
+    }
+
+    let entropy = Zeroizing::new(packed[..ent_len].to_vec());
+
+    let cs_mask = 0xFFu8 << (8 - ent_len / 4);
+    let got = packed[ent_len] & cs_mask;
+
+    if bool::from(got.ct_eq(&checksum_byte(&entropy))) {
+        Ok(entropy)
+    } else {
+        Err(RecoveryError::BadChecksum)
+    }
+}
+
+/* ───────────── ENTROPY → PHRASE ───────────── */
+
+/// Encode 16 / 32 bytes of entropy as a 12 / 24-word mnemonic.
+///
+/// Words are separated by single ASCII spaces.
+pub fn entropy_to_phrase(entropy: &[u8]) -> Result<Zeroizing<String>, RecoveryError> {
+    let words = match entropy.len() {
+        16 => 12,
+        32 => 24,
+        _ => return Err(RecoveryError::InvalidInput),
+    };
+
+    let mut packed = Zeroizing::new([0u8; MAX_PACKED_LEN]);
+    packed[..entropy.len()].copy_from_slice(entropy);
+    packed[entropy.len()] = checksum_byte(entropy);
+
+    let mut phrase = Zeroizing::new(String::with_capacity(words * 9));
+
+    for w in 0..words {
+        let mut index: u16 = 0;
+
+        for b in 0..BITS_PER_WORD {
+            let pos = w * BITS_PER_WORD + b;
+            let bit = (packed[pos / 8] >> (7 - pos % 8)) & 1;
+            index = (index << 1) | u16::from(bit);
+        }
+
+        let word = word_at(index).ok_or(RecoveryError::InvalidInput)?;
+
+        if w > 0 {
+            phrase.push(' ');
+        }
+        phrase.push_str(word);
+    }
+
+    Ok(phrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(phrase: &str) -> Vec<&str> {
+        phrase.split(' ').collect()
+    }
+
+    #[test]
+    fn wordlist_is_complete() {
+        assert_eq!(WORDLIST.lines().count(), WORD_COUNT);
+        assert_eq!(word_index("abandon"), Some(0));
+        assert_eq!(word_index("zoo"), Some(2047));
+    }
+
+    #[test]
+    fn known_vectors_encode() {
+        let p = entropy_to_phrase(&[0u8; 16]);
+        assert!(matches!(&p, Ok(s) if s.as_str()
+            == "abandon abandon abandon abandon abandon abandon \
+                abandon abandon abandon abandon abandon about"));
+
+        let p = entropy_to_phrase(&[0xFFu8; 16]);
+        assert!(matches!(&p, Ok(s) if s.as_str()
+            == "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong"));
+
+        let p = entropy_to_phrase(&[0u8; 32]);
+        assert!(matches!(&p, Ok(s) if s.ends_with("abandon art")));
+    }
+
+    #[test]
+    fn phrase_round_trips() {
+        for len in [16usize, 32] {
+            let entropy: Vec<u8> = (0..len as u8).map(|b| b.wrapping_mul(37)).collect();
+
+            let phrase = entropy_to_phrase(&entropy);
+            assert!(phrase.is_ok());
+            let phrase = phrase.unwrap_or_default();
+
+            let back = phrase_to_entropy(&split(&phrase));
+            assert!(matches!(back, Ok(e) if e.as_slice() == entropy.as_slice()));
+        }
+    }
+
+    #[test]
+    fn invalid_mnemonics_are_distinguished() {
+        let twelve = ["abandon"; 12];
+        assert_eq!(
+            phrase_to_entropy(&twelve).err(),
+            Some(RecoveryError::BadChecksum)
+        );
+
+        assert_eq!(
+            phrase_to_entropy(&["abandon"; 11]).err(),
+            Some(RecoveryError::InvalidWordCount)
+        );
+
+        let mut unknown = ["abandon"; 12];
+        unknown[3] = "notaword";
+        assert_eq!(
+            phrase_to_entropy(&unknown).err(),
+            Some(RecoveryError::UnknownWord)
+        );
+    }
+}
@@ -0,0 +1,291 @@
+//! k-of-n Shamir secret sharing of the recovery root (GF(256)).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! SHARE FORMAT (fixed, 50 bytes):
+//! `index (1) || threshold k (1) || commitment (16) || y (32)`
+//!
+//! FORMAL INVARIANTS:
+//! - GF(2^8) arithmetic is branch-free and table-free
+//! - Fewer than `k` distinct shares => `InsufficientShares`
+//! - Repeated share index => `DuplicateShare`
+//! - Reconstructed root MUST match the share commitment and the
+//!   recovery integrity binding before any authority is produced
+//! - Shares, coefficients and the root live in zeroized heap memory
+
+use hmac::{Hmac, Mac};
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+
+use super::{RecoveryAuthority, RecoveryError};
+use crate::crypto::derive::{derive_key, Purpose};
+use crate::integrity::verify::{verify_key_integrity, INTEGRITY_CONTEXT};
+use crate::memory::{ct_eq, GuardedKey32, Secret};
+
+/// Serialized share length.
+pub const SHARE_LEN: usize = 1 + 1 + COMMIT_LEN + 32;
+
+const COMMIT_LEN: usize = 16;
+
+/// Commitment domain label (MUST NEVER CHANGE).
+const COMMIT_LABEL: &[u8] = b"rcxcloud:recovery:shamir:commit:v1";
+
+/* ───────────── GF(256) ───────────── */
+
+/// Multiply in GF(2^8) mod x^8 + x^4 + x^3 + x + 1 (constant-time).
+#[inline(always)]
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut p = 0u8;
+    for _ in 0..8 {
+        p ^= a & 0u8.wrapping_sub(b & 1);
+        let hi = a >> 7;
+        a = (a << 1) ^ (0x1B & 0u8.wrapping_sub(hi));
+        b >>= 1;
+    }
+    p
+}
+
+/// Inverse in GF(2^8) as `a^254` (fixed exponent, constant-time).
+#[inline(always)]
+fn gf_inv(a: u8) -> u8 {
+    let a2 = gf_mul(a, a);
+    let a4 = gf_mul(a2, a2);
+    let a8 = gf_mul(a4, a4);
+    let a16 = gf_mul(a8, a8);
+    let a32 = gf_mul(a16, a16);
+    let a64 = gf_mul(a32, a32);
+    let a128 = gf_mul(a64, a64);
+
+    // 254 = 128 + 64 + 32 + 16 + 8 + 4 + 2
+    let mut r = gf_mul(a128, a64);
+    r = gf_mul(r, a32);
+    r = gf_mul(r, a16);
+    r = gf_mul(r, a8);
+    r = gf_mul(r, a4);
+    gf_mul(r, a2)
+}
+
+/* ───────────── COMMITMENT ───────────── */
+
+fn commitment(root: &GuardedKey32) -> Result<[u8; COMMIT_LEN], RecoveryError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(root.borrow())
+        .map_err(|_| RecoveryError::KdfFailure)?;
+    mac.update(COMMIT_LABEL);
+
+    let mut out = [0u8; COMMIT_LEN];
+    out.copy_from_slice(&mac.finalize().into_bytes()[..COMMIT_LEN]);
+    Ok(out)
+}
+
+/* ───────────── SPLIT ───────────── */
+
+/// Split `root` into `n` shares, any `k` of which reconstruct it.
+///
+/// SECURITY:
+/// - Requires `2 <= k <= n`
+/// - Polynomial coefficients from the OS CSPRNG, zeroized after use
+pub fn split_root(
+    root: &GuardedKey32,
+    k: u8,
+    n: u8,
+) -> Result<Vec<Secret<Vec<u8>>>, RecoveryError> {
+    if k < 2 || n < k {
+        return Err(RecoveryError::InvalidInput);
+    }
+
+    let commit = commitment(root)?;
+
+    // coeffs[j * 32 + b] = coefficient of x^(j+1) for byte b
+    let mut coeffs = Secret::init_with(|v: &mut Vec<u8>| {
+        v.resize((usize::from(k) - 1) * 32, 0);
+    });
+    OsRng
+        .try_fill_bytes(coeffs.borrow_mut())
+        .map_err(|_| RecoveryError::KdfFailure)?;
+
+    let mut shares = Vec::with_capacity(usize::from(n));
+
+    for x in 1..=n {
+        let share = Secret::init_with(|v: &mut Vec<u8>| {
+            v.resize(SHARE_LEN, 0);
+            v[0] = x;
+            v[1] = k;
+            v[2..2 + COMMIT_LEN].copy_from_slice(&commit);
+
+            for b in 0..32 {
+                // Horner: highest coefficient first
+                let mut y = 0u8;
+                for j in (0..usize::from(k) - 1).rev() {
+                    y = gf_mul(y, x) ^ coeffs.borrow()[j * 32 + b];
+                }
+                y = gf_mul(y, x) ^ root.borrow()[b];
+
+                v[2 + COMMIT_LEN + b] = y;
+            }
+        });
+
+        shares.push(share);
+    }
+
+    Ok(shares)
+}
+
+/* ───────────── COMBINE ───────────── */
+
+/// Reconstruct the root from at least `k` distinct shares.
+///
+/// SECURITY:
+/// - All shares MUST agree on `k` and the commitment
+/// - Reconstructed root is checked against the commitment
+///   (wrong / mixed shares => `IntegrityFailure`)
+pub fn combine_shares(shares: &[Secret<Vec<u8>>]) -> Result<GuardedKey32, RecoveryError> {
+    let first = shares.first().ok_or(RecoveryError::InsufficientShares)?.borrow();
+    if first.len() != SHARE_LEN {
+        return Err(RecoveryError::InvalidInput);
+    }
+
+    let k = first[1];
+    let commit = &first[2..2 + COMMIT_LEN];
+
+    let mut xs: Vec<u8> = Vec::with_capacity(shares.len());
+
+    for s in shares {
+        let s = s.borrow();
+
+        if s.len() != SHARE_LEN || s[0] == 0 || s[1] != k || k < 2 {
+            return Err(RecoveryError::InvalidInput);
+        }
+        if !ct_eq(&s[2..2 + COMMIT_LEN], commit) {
+            return Err(RecoveryError::IntegrityFailure);
+        }
+        if xs.contains(&s[0]) {
+            return Err(RecoveryError::DuplicateShare);
+        }
+        xs.push(s[0]);
+    }
+
+    if xs.len() < usize::from(k) {
+        return Err(RecoveryError::InsufficientShares);
+    }
+
+    let used = &shares[..usize::from(k)];
+    let xs = &xs[..usize::from(k)];
+
+    // Lagrange basis at x = 0 (subtraction == XOR in GF(2^8))
+    let mut basis = Vec::with_capacity(xs.len());
+    for (i, xi) in xs.iter().enumerate() {
+        let mut num = 1u8;
+        let mut den = 1u8;
+        for (j, xj) in xs.iter().enumerate() {
+            if i != j {
+                num = gf_mul(num, *xj);
+                den = gf_mul(den, xi ^ xj);
+            }
+        }
+        basis.push(gf_mul(num, gf_inv(den)));
+    }
+
+    let root = GuardedKey32::init_with(|r| {
+        for b in 0..32 {
+            let mut acc = 0u8;
+            for (share, l) in used.iter().zip(&basis) {
+                acc ^= gf_mul(share.borrow()[2 + COMMIT_LEN + b], *l);
+            }
+            r[b] = acc;
+        }
+    });
+
+    if !ct_eq(&commitment(&root)?, commit) {
+        return Err(RecoveryError::IntegrityFailure);
+    }
+
+    Ok(root)
+}
+
+/// Reconstruct the root and produce a session authority.
+///
+/// SECURITY:
+/// - Session key derived from the root, then checked against the
+///   recovery integrity binding BEFORE the authority exists
+/// - Root is dropped (zeroized) before returning
+pub fn recover_from_shares(
+    shares: &[Secret<Vec<u8>>],
+) -> Result<RecoveryAuthority, RecoveryError> {
+    let root = combine_shares(shares)?;
+
+    let mut session = GuardedKey32::zeroed();
+    derive_key(&root, Purpose::Recovery, INTEGRITY_CONTEXT, &mut session)
+        .map_err(|_| RecoveryError::KdfFailure)?;
+
+    verify_key_integrity(&root, &session)
+        .map_err(|_| RecoveryError::IntegrityFailure)?;
+
+    Ok(RecoveryAuthority { session })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root() -> GuardedKey32 {
+        GuardedKey32::init_with(|k| {
+            for (i, b) in k.iter_mut().enumerate() {
+                *b = (i as u8).wrapping_mul(29) ^ 0x5A;
+            }
+        })
+    }
+
+    fn copy(s: &Secret<Vec<u8>>) -> Secret<Vec<u8>> {
+        Secret::new(s.borrow().clone())
+    }
+
+    #[test]
+    fn gf_inverse_is_correct() {
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn any_k_shares_reconstruct_root() {
+        let r = root();
+        let shares = split_root(&r, 3, 5).unwrap_or_default();
+        assert_eq!(shares.len(), 5);
+
+        for pick in [[0usize, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset: Vec<_> = pick.iter().map(|i| copy(&shares[*i])).collect();
+            let got = combine_shares(&subset);
+            assert!(matches!(got, Ok(g) if g.borrow() == r.borrow()));
+        }
+
+        assert!(recover_from_shares(&shares[..3]).is_ok());
+    }
+
+    #[test]
+    fn too_few_or_repeated_shares_fail_closed() {
+        let shares = split_root(&root(), 3, 5).unwrap_or_default();
+
+        assert!(matches!(
+            combine_shares(&shares[..2]),
+            Err(RecoveryError::InsufficientShares)
+        ));
+
+        let repeated = [copy(&shares[0]), copy(&shares[0]), copy(&shares[1])];
+        assert!(matches!(
+            combine_shares(&repeated),
+            Err(RecoveryError::DuplicateShare)
+        ));
+    }
+
+    #[test]
+    fn tampered_share_fails_integrity() {
+        let shares = split_root(&root(), 2, 3).unwrap_or_default();
+        let mut bad = copy(&shares[1]);
+        bad.borrow_mut()[SHARE_LEN - 1] ^= 0x01;
+
+        assert!(matches!(
+            combine_shares(&[copy(&shares[0]), bad]),
+            Err(RecoveryError::IntegrityFailure)
+        ));
+    }
+}
@@ -14,13 +14,22 @@
 #![deny(clippy::derive_debug)]
 
 use crate::crypto::{
-    aad::Aad,
+    aad::{Aad, SerializedAad, AAD_VERSION_STREAM, AAD_VERSION_V1, AAD_VERSION_V2},
+    attest::ATTESTATION_CONTEXT,
     aes_gcm,
-    derive::{derive_key, Purpose},
-    nonce::derive_nonce,
+    cipher::{self, CipherSuite, COMMIT_LEN},
+    derive::{derive_key, derive_key_with_domain, Purpose},
+    nonce::{
+        derive_nonce, derive_nonce_stream, derive_nonce_versioned, derive_nonce_with_epoch,
+        NONCE_LEN,
+    },
 };
+use crate::keystore::index::{self, IndexError, IndexVersions, INDEX_CONTEXT, INDEX_VERSIONS_CONTEXT};
 use crate::keystore::master::GLOBAL_KILLED;
-use crate::memory::GuardedKey32;
+use crate::keystore::nonce_ledger::{self, LedgerError, NonceLedger};
+use crate::keystore::stream::{StreamingDecryptor, StreamingEncryptor};
+use crate::keystore::tombstone::{self, TombstoneError, TombstoneLog, TOMBSTONE_CONTEXT};
+use crate::memory::{GuardedKey32, GuardedVec};
 
 use core::marker::PhantomData;
 use core::sync::atomic::Ordering;
@@ -38,6 +47,19 @@ pub trait SessionOutput: sealed::Sealed {}
 #[derive(Clone, Copy)]
 pub struct EncryptResult {
     pub total_len: usize,
+    /// AAD version byte the chunk was sealed under (store with it)
+    pub aad_version: u8,
+    /// Ratchet generation the chunk was sealed under (store with it)
+    pub generation: u32,
+    /// Exact associated data the chunk was sealed with; storing it
+    /// lets `file::decrypt_chunk` pick the layout from the AAD itself
+    pub aad: SerializedAad,
+    /// Write version reserved by `encrypt_versioned` (store with it;
+    /// `decrypt_verify_versioned` requires it)
+    pub write_version: Option<u32>,
+    /// Key commitment of the chunk (store with it;
+    /// `file::decrypt_chunk` checks it before the AEAD tag)
+    pub commitment: [u8; COMMIT_LEN],
 }
 impl sealed::Sealed for EncryptResult {}
 impl SessionOutput for EncryptResult {}
@@ -47,6 +69,59 @@ pub struct VerifyResult(pub bool);
 impl sealed::Sealed for VerifyResult {}
 impl SessionOutput for VerifyResult {}
 
+/// Whole batch sealed (`Session::encrypt_file_chunks`).
+#[derive(Clone, Copy)]
+pub struct BatchEncryptResult {
+    /// Chunks sealed (`chunks[i]` => chunk index `i`)
+    pub chunks: usize,
+    /// AAD version byte shared by every chunk (store with them)
+    pub aad_version: u8,
+    /// Ratchet generation shared by every chunk (store with them)
+    pub generation: u32,
+}
+impl sealed::Sealed for BatchEncryptResult {}
+impl SessionOutput for BatchEncryptResult {}
+
+/// File-key root ratcheted forward (`Session::rekey`).
+#[derive(Clone, Copy)]
+pub struct Rekeyed {
+    /// New current generation (checkpoint it)
+    pub generation: u32,
+}
+impl sealed::Sealed for Rekeyed {}
+impl SessionOutput for Rekeyed {}
+
+/// File key derived and cached (see `Session::prewarm`).
+#[derive(Clone, Copy)]
+pub struct Prewarmed;
+impl sealed::Sealed for Prewarmed {}
+impl SessionOutput for Prewarmed {}
+
+/// Sealed application index blob (format: `keystore::index`).
+pub struct SealedIndex(pub Vec<u8>);
+impl sealed::Sealed for SealedIndex {}
+impl SessionOutput for SealedIndex {}
+
+/// Opened index: `(index_version, plaintext)`, or `None` if the
+/// blob failed authentication.
+pub struct OpenedIndex(pub Option<(u64, Vec<u8>)>);
+impl sealed::Sealed for OpenedIndex {}
+impl SessionOutput for OpenedIndex {}
+
+/// File permanently revoked (`Session::revoke_file`).
+#[derive(Clone, Copy)]
+pub struct Revoked;
+impl sealed::Sealed for Revoked {}
+impl SessionOutput for Revoked {}
+
+/// Revocation status of one file.
+#[derive(Clone, Copy)]
+pub struct FileStatus {
+    pub revoked: bool,
+}
+impl sealed::Sealed for FileStatus {}
+impl SessionOutput for FileStatus {}
+
 /* ───────────── ERRORS ───────────── */
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -58,10 +133,164 @@ pub enum SessionError {
     CryptoFailure,
 }
 
+fn map_tombstone_error(err: TombstoneError) -> SessionError {
+    match err {
+        TombstoneError::Killed => SessionError::Killed,
+        TombstoneError::Unverifiable | TombstoneError::Persist => SessionError::CryptoFailure,
+    }
+}
+
+fn map_ledger_error(err: LedgerError) -> SessionError {
+    match err {
+        LedgerError::Killed => SessionError::Killed,
+        LedgerError::Unverifiable | LedgerError::Persist | LedgerError::Exhausted => {
+            SessionError::CryptoFailure
+        }
+    }
+}
+
+fn map_index_error(err: IndexError) -> SessionError {
+    match err {
+        IndexError::Killed => SessionError::Killed,
+        IndexError::InvalidInput => SessionError::InvalidInput,
+        IndexError::Unverifiable
+        | IndexError::Rollback
+        | IndexError::Persist
+        | IndexError::Exhausted => SessionError::CryptoFailure,
+    }
+}
+
+/* ───────────── NONCE SELECTION ───────────── */
+
+/// Epoch-bound chunks (AAD V2) MUST use the epoch-bound nonce;
+/// stream chunks MUST use the stream nonce; rewritable chunks
+/// MUST use their reserved write version.
+#[inline(always)]
+fn chunk_nonce(key: &GuardedKey32, aad: &Aad, version: Option<u32>) -> [u8; NONCE_LEN] {
+    match version {
+        Some(v) => derive_nonce_versioned(key, aad.file_id(), aad.chunk(), v),
+        None if aad.format_version() == AAD_VERSION_V2 => {
+            derive_nonce_with_epoch(key, aad.file_id(), aad.chunk(), aad.epoch())
+        }
+        None if aad.format_version() == AAD_VERSION_STREAM => {
+            derive_nonce_stream(key, aad.file_id(), aad.chunk())
+        }
+        None => derive_nonce(key, aad.file_id(), aad.chunk()),
+    }
+}
+
+/* ───────────── PLAINTEXT STAGING ───────────── */
+
+/// Copy `plaintext` into `out` (`plaintext.len() + TAG_LEN`) for
+/// in-place sealing; wrong size => wiped + `OutputTooSmall`.
+#[inline(always)]
+fn stage_plaintext(plaintext: &[u8], out: &mut [u8]) -> Result<(), SessionError> {
+    if out.len() != plaintext.len() + aes_gcm::TAG_LEN {
+        out.fill(0);
+        return Err(SessionError::OutputTooSmall);
+    }
+
+    out[..plaintext.len()].copy_from_slice(plaintext);
+    Ok(())
+}
+
+/* ───────────── RATCHET ───────────── */
+
+/// HKDF domain of the file-key ratchet (`Purpose::Recovery`).
+const RATCHET_DOMAIN: &[u8] = b"ratchet";
+
+/// HKDF domain of the control-plane device parent (`Purpose::Recovery`).
+const CONTROL_DOMAIN: &[u8] = b"control";
+
+/// HKDF domain of the host-lent device key (`Purpose::Recovery`).
+///
+/// Separates it from the kill key, which uses the bare fingerprint
+/// as context under the same purpose.
+const DEVICE_KEY_DOMAIN: &[u8] = b"device-attest";
+
+/// Max generations a chunk may be AHEAD of the session (bounds the
+/// forward derivation a hostile AAD can trigger).
+pub const MAX_RATCHET_AHEAD: u32 = 1024;
+
+/// One ratchet step: `root_{g} = HKDF(root_{g-1}, "ratchet", g)`.
+fn ratchet(prev: &GuardedKey32, generation: u32) -> Result<GuardedKey32, SessionError> {
+    let mut next = GuardedKey32::zeroed();
+    derive_key_with_domain(prev, Purpose::Recovery, RATCHET_DOMAIN, u64::from(generation), &mut next)
+        .map_err(|_| SessionError::CryptoFailure)?;
+    Ok(next)
+}
+
+/* ───────────── FILE KEY CACHE ───────────── */
+
+/// Most recently derived file key (single entry).
+struct CachedFileKey {
+    file_id: u64,
+    suite: CipherSuite,
+    generation: u32,
+    key: GuardedKey32,
+}
+
+/* ───────────── CONTROL-PLANE KEYS ───────────── */
+
+/// Keys that never ratchet, derived once from the session key.
+///
+/// Lets the session key itself leave memory: nothing that can
+/// re-derive an older file-key generation stays resident.
+struct ControlKeys {
+    /// `derive_key(session, Purpose::Metadata, INDEX_CONTEXT)`
+    index: GuardedKey32,
+    /// index → `INDEX_VERSIONS_CONTEXT` (version-log MAC key)
+    index_versions: GuardedKey32,
+    /// `derive_key(session, Purpose::Metadata, NONCE_LEDGER_CONTEXT)`
+    ledger: GuardedKey32,
+    /// session → attestation → `TOMBSTONE_CONTEXT` (`Purpose::Recovery`)
+    tombstone: GuardedKey32,
+    /// Parent of the per-fingerprint device key
+    device: GuardedKey32,
+}
+
+impl ControlKeys {
+    fn derive(session_key: &GuardedKey32) -> Option<Self> {
+        let mut index = GuardedKey32::zeroed();
+        derive_key(session_key, Purpose::Metadata, INDEX_CONTEXT, &mut index).ok()?;
+
+        let mut index_versions = GuardedKey32::zeroed();
+        derive_key(&index, Purpose::Metadata, INDEX_VERSIONS_CONTEXT, &mut index_versions).ok()?;
+
+        let ledger = nonce_ledger::ledger_key(session_key).ok()?;
+
+        let mut attest_key = GuardedKey32::zeroed();
+        derive_key(session_key, Purpose::Recovery, ATTESTATION_CONTEXT, &mut attest_key).ok()?;
+
+        let mut tombstone = GuardedKey32::zeroed();
+        derive_key(&attest_key, Purpose::Recovery, TOMBSTONE_CONTEXT, &mut tombstone).ok()?;
+
+        let mut device = GuardedKey32::zeroed();
+        derive_key_with_domain(session_key, Purpose::Recovery, CONTROL_DOMAIN, 0, &mut device).ok()?;
+
+        Some(Self { index, index_versions, ledger, tombstone, device })
+    }
+}
+
 /* ───────────── SESSION TYPE ───────────── */
 
 pub struct Session {
-    session_key: Option<GuardedKey32>,
+    // Control-plane keys (never ratcheted)
+    control: Option<ControlKeys>,
+    // AEAD suite for NEW chunks (decrypt follows the AAD)
+    suite: CipherSuite,
+    // Wiped together with the file root
+    file_key: Option<CachedFileKey>,
+    // File-key root of the CURRENT generation (generation 0 = the
+    // session key); replaced + zeroized by `rekey`
+    file_root: Option<GuardedKey32>,
+    generation: u32,
+    // Revoked files (persisted; replayed on the first file operation)
+    revoked: Option<TombstoneLog>,
+    // Write-version ledger (opened on the first versioned write)
+    ledger: Option<NonceLedger>,
+    #[cfg(test)]
+    derivations: u32,
     _no_send_sync: PhantomData<*const ()>,
 }
 
@@ -69,25 +298,303 @@ impl Session {
     /* ───────────── CONSTRUCTION ───────────── */
 
     pub(crate) fn new(session_key: GuardedKey32) -> Self {
+        Self::with_suite(session_key, CipherSuite::default())
+    }
+
+    /// Session sealing new chunks under `suite`.
+    ///
+    /// The session key becomes the generation-0 file root; control
+    /// keys are split off first. A failed derivation yields a dead
+    /// (locked) session.
+    pub(crate) fn with_suite(session_key: GuardedKey32, suite: CipherSuite) -> Self {
+        let control = ControlKeys::derive(&session_key);
+        let file_root = control.as_ref().map(|_| session_key);
+
         Self {
-            session_key: Some(session_key),
+            control,
+            suite,
+            file_key: None,
+            file_root,
+            generation: 0,
+            revoked: None,
+            ledger: None,
+            #[cfg(test)]
+            derivations: 0,
             _no_send_sync: PhantomData,
         }
     }
 
+    /// Cipher suite used for NEW chunks.
+    #[inline(always)]
+    pub fn suite(&self) -> CipherSuite {
+        self.suite
+    }
+
     /* ───────────── INTERNAL GUARDS ───────────── */
 
+    /// Current file-key root (liveness check for every operation).
     #[inline(always)]
     fn require_alive(&self) -> Result<&GuardedKey32, SessionError> {
         if GLOBAL_KILLED.load(Ordering::SeqCst) {
             return Err(SessionError::Killed);
         }
 
-        self.session_key
+        self.file_root
             .as_ref()
             .ok_or(SessionError::Locked)
     }
 
+    #[inline(always)]
+    fn require_control(&self) -> Result<&ControlKeys, SessionError> {
+        self.require_alive()?;
+
+        self.control
+            .as_ref()
+            .ok_or(SessionError::Locked)
+    }
+
+    /// Current ratchet generation (0 until the first `rekey`).
+    #[inline(always)]
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Rotate the file-key root one generation (post-compromise
+    /// key rotation).
+    ///
+    /// New chunks are sealed under V3 AAD carrying the generation;
+    /// the app checkpoints `Rekeyed::generation` and replays `rekey`
+    /// after the next unlock to reach it again.
+    ///
+    /// SECURITY:
+    /// - In this session, the previous root (the session key itself
+    ///   at generation 0) is zeroized and chunks of OLDER generations
+    ///   fail authentication
+    /// - Cached file key dropped
+    /// - No resident key can re-derive an older generation
+    ///   (control-plane keys are split off at construction)
+    /// - NOT forward secrecy: every generation is re-derivable from
+    ///   the session key, so whoever recovers it (e.g. via the
+    ///   recovery authority) can still open all older chunks
+    pub fn rekey(&mut self) -> Result<Rekeyed, SessionError> {
+        let current = self.require_alive()?;
+        let next = self.generation.checked_add(1).ok_or(SessionError::InvalidInput)?;
+
+        let root = ratchet(current, next)?;
+
+        // Replacing drops (zeroizes) the previous root
+        self.file_root = Some(root);
+        self.file_key.take();
+        self.generation = next;
+
+        Ok(Rekeyed { generation: next })
+    }
+
+    /// File key for `(suite, file_id, generation)`, derived on cache miss.
+    ///
+    /// SECURITY:
+    /// - Liveness is checked on EVERY call (hit or miss)
+    /// - A miss replaces (zeroizes) the previous cached key
+    /// - `generation` below the current one => `InvalidInput`;
+    ///   above it => derived forward (bounded, not adopted)
+    fn file_key(
+        &mut self,
+        suite: CipherSuite,
+        file_id: u64,
+        generation: u32,
+    ) -> Result<&GuardedKey32, SessionError> {
+        self.require_live_file(file_id)?;
+        let current = self.require_alive()?;
+
+        if generation < self.generation
+            || generation - self.generation > MAX_RATCHET_AHEAD
+        {
+            return Err(SessionError::InvalidInput);
+        }
+
+        let hit = matches!(
+            &self.file_key,
+            Some(c) if c.file_id == file_id && c.suite == suite && c.generation == generation
+        );
+
+        if !hit {
+            let mut ahead: Option<GuardedKey32> = None;
+            for g in self.generation + 1..=generation {
+                ahead = Some(ratchet(ahead.as_ref().unwrap_or(current), g)?);
+            }
+
+            let mut key = GuardedKey32::zeroed();
+
+            derive_key(ahead.as_ref().unwrap_or(current), suite.file_key_purpose(), file_id, &mut key)
+                .map_err(|_| SessionError::CryptoFailure)?;
+
+            #[cfg(test)]
+            {
+                self.derivations += 1;
+            }
+
+            self.file_key = Some(CachedFileKey { file_id, suite, generation, key });
+        }
+
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            self.file_key.take();
+            return Err(SessionError::Killed);
+        }
+
+        self.file_key
+            .as_ref()
+            .map(|c| &c.key)
+            .ok_or(SessionError::CryptoFailure)
+    }
+
+    /* ───────────── PER-FILE REVOCATION ───────────── */
+
+    /// Permanently revoke `file_id` (idempotent).
+    ///
+    /// SECURITY:
+    /// - Authenticated record persisted BEFORE it takes effect
+    /// - Irreversible: there is no API to lift a revocation
+    /// - Cached file key is wiped if it belongs to `file_id`
+    /// - Does NOT touch `GLOBAL_KILLED`
+    pub fn revoke_file(&mut self, file_id: u64) -> Result<Revoked, SessionError> {
+        let tag = tombstone::mac_file_id(self.tombstone_key()?, file_id)
+            .map_err(map_tombstone_error)?;
+
+        self.revocations()?
+            .insert(file_id, tag)
+            .map_err(map_tombstone_error)?;
+
+        if matches!(&self.file_key, Some(c) if c.file_id == file_id) {
+            self.file_key.take();
+        }
+
+        Ok(Revoked)
+    }
+
+    /// Whether `file_id` is revoked (replays persisted revocations).
+    pub fn file_status(&mut self, file_id: u64) -> Result<FileStatus, SessionError> {
+        let revoked = self.revocations()?.contains(file_id);
+        Ok(FileStatus { revoked })
+    }
+
+    /// Refuse a revoked file.
+    ///
+    /// SECURITY:
+    /// - Persisted revocations are replayed on first use, so a
+    ///   restart never forgets one
+    /// - Unreadable / unverifiable log => error (fail-closed)
+    pub(crate) fn require_live_file(&mut self, file_id: u64) -> Result<(), SessionError> {
+        if self.revocations()?.contains(file_id) {
+            Err(SessionError::Killed)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Revocation set, replayed from the log on first use (and
+    /// attached to it as soon as a log root exists).
+    fn revocations(&mut self) -> Result<&mut TombstoneLog, SessionError> {
+        let log = match self.revoked.take() {
+            Some(mut log) => {
+                let key = self.tombstone_key()?;
+                log.attach(|id| tombstone::mac_file_id(key, id))
+                    .map_err(map_tombstone_error)?;
+                log
+            }
+            None => {
+                let key = self.tombstone_key()?;
+                TombstoneLog::open(|id| tombstone::mac_file_id(key, id))
+                    .map_err(map_tombstone_error)?
+            }
+        };
+
+        Ok(self.revoked.insert(log))
+    }
+
+    /// Record MAC key: session → attestation → `TOMBSTONE_CONTEXT`
+    /// (both `Purpose::Recovery`).
+    fn tombstone_key(&self) -> Result<&GuardedKey32, SessionError> {
+        self.require_control().map(|c| &c.tombstone)
+    }
+
+    /* ───────────── DEVICE-BOUND KEY ───────────── */
+
+    /// Device-bound key
+    /// `derive_key_with_domain(device, Purpose::Recovery, DEVICE_KEY_DOMAIN, fingerprint)`
+    /// under the control-plane device parent.
+    ///
+    /// SECURITY:
+    /// - Own HKDF domain: never equals the per-device kill key
+    ///   (`derive_key(root, Purpose::Recovery, fingerprint)`) nor any
+    ///   fixed `Purpose::Recovery` context (wrap, integrity, attestation)
+    /// - Zero (unbound) fingerprint => `InvalidInput`
+    pub(crate) fn device_key(&self, fingerprint: u64) -> Result<GuardedKey32, SessionError> {
+        if fingerprint == 0 {
+            return Err(SessionError::InvalidInput);
+        }
+
+        let parent = &self.require_control()?.device;
+
+        let mut key = GuardedKey32::zeroed();
+        derive_key_with_domain(parent, Purpose::Recovery, DEVICE_KEY_DOMAIN, fingerprint, &mut key)
+            .map_err(|_| SessionError::CryptoFailure)?;
+
+        Ok(key)
+    }
+
+    /* ───────────── APPLICATION INDEX ───────────── */
+
+    /// Seal the application index under a freshly reserved version.
+    ///
+    /// SECURITY:
+    /// - The version is reserved (persisted, MAC-authenticated)
+    ///   BEFORE sealing; a missing / unverifiable version log
+    ///   refuses the seal instead of restarting at 0
+    pub fn seal_index(&mut self, plaintext: &[u8]) -> Result<SealedIndex, SessionError> {
+        if plaintext.len() > index::MAX_INDEX_LEN {
+            return Err(SessionError::InvalidInput);
+        }
+
+        let control = self.require_control()?;
+
+        let version = IndexVersions::open(&control.index_versions)
+            .and_then(|mut v| v.reserve())
+            .map_err(map_index_error)?;
+
+        index::seal_index(&control.index, version, plaintext)
+            .map(SealedIndex)
+            .map_err(map_index_error)
+    }
+
+    /// Authenticate and decrypt an application index blob.
+    ///
+    /// Tampered, or older than the highest version seen (rollback)
+    /// => `OpenedIndex(None)`; version log unusable => `Err`.
+    pub fn open_index(&mut self, blob: &[u8]) -> Result<OpenedIndex, SessionError> {
+        let control = self.require_control()?;
+
+        let (version, plaintext) = match index::open_index(&control.index, blob) {
+            Ok(opened) => opened,
+            Err(IndexError::Unverifiable) => return Ok(OpenedIndex(None)),
+            Err(e) => return Err(map_index_error(e)),
+        };
+
+        match IndexVersions::open(&control.index_versions).and_then(|mut v| v.accept(version)) {
+            Ok(()) => Ok(OpenedIndex(Some((version, plaintext)))),
+            Err(IndexError::Rollback) => Ok(OpenedIndex(None)),
+            Err(e) => Err(map_index_error(e)),
+        }
+    }
+
+    /* ───────────── PREWARM ───────────── */
+
+    /// Derive and cache the file key for `aad`'s file ahead of
+    /// the first chunk operation (latency only, no output).
+    pub fn prewarm(&mut self, aad: &Aad) -> Result<Prewarmed, SessionError> {
+        let (suite, generation) = (self.suite, self.generation);
+        self.file_key(suite, aad.file_id(), generation).map(|_| Prewarmed)
+    }
+
     /* ───────────── ENCRYPT ───────────── */
 
     /// Encrypt plaintext using derived file key.
@@ -100,51 +607,201 @@ impl Session {
         aad: Aad,
         out: &mut [u8],
     ) -> Result<EncryptResult, SessionError> {
-        let session_key = self.require_alive()?;
+        stage_plaintext(plaintext, out)?;
+        self.encrypt_inner(aad, None, out)
+    }
 
-        let required = plaintext.len() + aes_gcm::TAG_LEN;
-        if out.len() != required {
-            out.fill(0);
+    /// Encrypt without a separate plaintext buffer.
+    ///
+    /// `buf` layout: `[ plaintext | TAG_LEN spare ]` in,
+    /// `[ ciphertext | tag ]` out (identical to `encrypt`).
+    ///
+    /// SECURITY:
+    /// - `buf` is wiped on ALL failures (plaintext included)
+    pub fn encrypt_in_place(
+        &mut self,
+        aad: Aad,
+        buf: &mut [u8],
+    ) -> Result<EncryptResult, SessionError> {
+        if buf.len() < aes_gcm::TAG_LEN {
+            buf.fill(0);
             return Err(SessionError::OutputTooSmall);
         }
 
-        let mut enc_key = GuardedKey32::zeroed();
+        self.encrypt_inner(aad, None, buf)
+    }
 
-        derive_key(
-            session_key,
-            Purpose::FileEncryption,
-            aad.file_id(),
-            &mut enc_key,
-        )
-        .map_err(|_| {
-            out.fill(0);
-            SessionError::CryptoFailure
-        })?;
+    /// Encrypt a chunk that may be rewritten in place.
+    ///
+    /// SECURITY:
+    /// - The write version is reserved HERE from the session's
+    ///   authenticated `NonceLedger` (persisted before sealing);
+    ///   callers never choose it
+    /// - Missing / unverifiable ledger => refused (fail-closed)
+    /// - Store `write_version` with the chunk; decrypt requires it
+    /// - V1 AAD only (epoch-bound chunks already separate rewrites)
+    pub fn encrypt_versioned(
+        &mut self,
+        plaintext: &[u8],
+        aad: Aad,
+        out: &mut [u8],
+    ) -> Result<EncryptResult, SessionError> {
+        stage_plaintext(plaintext, out)?;
 
-        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        if aad.format_version() != AAD_VERSION_V1 {
             out.fill(0);
-            return Err(SessionError::Killed);
+            return Err(SessionError::InvalidInput);
         }
 
-        let nonce = derive_nonce(&enc_key, aad.file_id(), aad.chunk());
+        let version = match self
+            .nonce_ledger()
+            .and_then(|l| l.reserve(aad.file_id(), aad.chunk()).map_err(map_ledger_error))
+        {
+            Ok(v) => v,
+            Err(e) => {
+                out.fill(0);
+                return Err(e);
+            }
+        };
 
-        aes_gcm::seal(
-            &enc_key,
-            &nonce,
-            plaintext,
-            &aad.serialize(),
-            out,
-        )
-        .map_err(|_| {
-            out.fill(0);
-            SessionError::CryptoFailure
-        })?;
+        self.encrypt_inner(aad, Some(version), out)
+    }
+
+    /// The session's write-version ledger, opened on first use.
+    fn nonce_ledger(&mut self) -> Result<&mut NonceLedger, SessionError> {
+        if self.ledger.is_none() {
+            let ledger = NonceLedger::open(&self.require_control()?.ledger).map_err(map_ledger_error)?;
+            self.ledger = Some(ledger);
+        }
+
+        self.require_alive()?;
+        self.ledger.as_mut().ok_or(SessionError::CryptoFailure)
+    }
+
+    /// Seal `buf` (`[ plaintext | TAG_LEN spare ]`) in place.
+    ///
+    /// `buf` is wiped on ALL failures.
+    fn encrypt_inner(
+        &mut self,
+        aad: Aad,
+        version: Option<u32>,
+        buf: &mut [u8],
+    ) -> Result<EncryptResult, SessionError> {
+        let result = self.seal_staged(aad, version, buf);
+        if result.is_err() {
+            buf.fill(0);
+        }
+        result
+    }
+
+    fn seal_staged(
+        &mut self,
+        aad: Aad,
+        version: Option<u32>,
+        buf: &mut [u8],
+    ) -> Result<EncryptResult, SessionError> {
+        let suite = self.suite;
+        self.require_alive()?;
+
+        // Epoch-bound chunks predate the ratchet (no V2 + generation)
+        let generation = self.generation;
+        if generation > 0 && aad.format_version() == AAD_VERSION_V2 {
+            return Err(SessionError::InvalidInput);
+        }
+
+        let aad = aad.with_cipher(suite).with_generation(generation);
+
+        let enc_key = self.file_key(suite, aad.file_id(), generation)?;
+
+        let nonce = chunk_nonce(enc_key, &aad, version);
+
+        let commitment = cipher::commitment(enc_key, &nonce)
+            .map_err(|_| SessionError::CryptoFailure)?;
+
+        suite
+            .seal_in_place(enc_key, &nonce, &aad.serialize(), buf)
+            .map_err(|_| SessionError::CryptoFailure)?;
 
         Ok(EncryptResult {
-            total_len: required,
+            total_len: buf.len(),
+            aad_version: aad.version(),
+            generation,
+            aad: aad.serialize(),
+            write_version: version,
+            commitment,
         })
     }
 
+    /// Seal every chunk of one file with a SINGLE key derivation.
+    ///
+    /// `chunks[i]` is sealed as chunk index `i` into `out[i]`
+    /// (length MUST be `chunks[i].len() + TAG_LEN`); output is
+    /// identical to per-chunk `encrypt` at the current generation.
+    ///
+    /// SECURITY:
+    /// - All lengths validated before anything is sealed
+    /// - Kill / lock re-checked before EACH chunk
+    /// - File key is local and wiped when the batch ends (any outcome)
+    /// - Any failure wipes EVERY output buffer
+    pub fn encrypt_file_chunks(
+        &mut self,
+        file_id: u64,
+        cloud_id: u16,
+        chunks: &[&[u8]],
+        out: &mut [&mut [u8]],
+    ) -> Result<BatchEncryptResult, SessionError> {
+        let result = self.encrypt_batch_inner(file_id, cloud_id, chunks, out);
+
+        if result.is_err() {
+            out.iter_mut().for_each(|o| o.fill(0));
+        }
+        result
+    }
+
+    fn encrypt_batch_inner(
+        &mut self,
+        file_id: u64,
+        cloud_id: u16,
+        chunks: &[&[u8]],
+        out: &mut [&mut [u8]],
+    ) -> Result<BatchEncryptResult, SessionError> {
+        let suite = self.suite;
+        self.require_live_file(file_id)?;
+
+        if chunks.len() != out.len() || u32::try_from(chunks.len()).is_err() {
+            return Err(SessionError::InvalidInput);
+        }
+        if chunks.iter().zip(out.iter()).any(|(c, o)| o.len() != c.len() + aes_gcm::TAG_LEN) {
+            return Err(SessionError::OutputTooSmall);
+        }
+
+        // Local key: dropped (zeroized) on every return path
+        let generation = self.generation;
+        let root = self.require_alive()?;
+        let mut key = GuardedKey32::zeroed();
+        derive_key(root, suite.file_key_purpose(), file_id, &mut key)
+            .map_err(|_| SessionError::CryptoFailure)?;
+
+        let mut aad_version = AAD_VERSION_V1;
+
+        for (index, (chunk, dst)) in (0u32..).zip(chunks.iter().zip(out.iter_mut())) {
+            self.require_alive()?;
+
+            let aad = Aad::new(file_id, index, cloud_id, AAD_VERSION_V1)
+                .ok_or(SessionError::InvalidInput)?
+                .with_cipher(suite)
+                .with_generation(generation);
+            aad_version = aad.version();
+
+            let nonce = chunk_nonce(&key, &aad, None);
+            suite
+                .seal(&key, &nonce, chunk, &aad.serialize(), dst)
+                .map_err(|_| SessionError::CryptoFailure)?;
+        }
+
+        Ok(BatchEncryptResult { chunks: chunks.len(), aad_version, generation })
+    }
+
     /* ───────────── DECRYPT + VERIFY ───────────── */
 
     /// Authenticate and decrypt ciphertext.
@@ -156,7 +813,78 @@ impl Session {
         aad: Aad,
         out: &mut [u8],
     ) -> Result<VerifyResult, SessionError> {
-        let session_key = self.require_alive()?;
+        self.decrypt_inner(input, aad, None, None, out)
+    }
+
+    /// Authenticate and decrypt ciphertext after checking its stored
+    /// key commitment (`EncryptResult::commitment`).
+    ///
+    /// SECURITY:
+    /// - Commitment checked (constant-time) BEFORE the AEAD tag:
+    ///   a chunk crafted to open under another file's key is
+    ///   refused even if its tag would verify
+    /// - Mismatch => `VerifyResult(false)`, `out` wiped
+    pub fn decrypt_verify_committed(
+        &mut self,
+        input: &[u8],
+        aad: Aad,
+        commitment: &[u8; COMMIT_LEN],
+        out: &mut [u8],
+    ) -> Result<VerifyResult, SessionError> {
+        self.decrypt_inner(input, aad, None, Some(commitment), out)
+    }
+
+    /// Authenticate and decrypt a chunk sealed by `encrypt_versioned`.
+    ///
+    /// A wrong `version` yields `VerifyResult(false)`.
+    pub fn decrypt_verify_versioned(
+        &mut self,
+        input: &[u8],
+        aad: Aad,
+        version: u32,
+        out: &mut [u8],
+    ) -> Result<VerifyResult, SessionError> {
+        if aad.format_version() == AAD_VERSION_V2 {
+            out.fill(0);
+            return Err(SessionError::InvalidInput);
+        }
+
+        self.decrypt_inner(input, aad, Some(version), None, out)
+    }
+
+    /// Authenticate ciphertext WITHOUT releasing plaintext.
+    ///
+    /// Decrypts into a locked scratch buffer that is wiped before
+    /// returning (integrity audits never hold a plaintext buffer).
+    pub fn verify_only(
+        &mut self,
+        input: &[u8],
+        aad: Aad,
+    ) -> Result<VerifyResult, SessionError> {
+        self.require_alive()?;
+
+        let ct_len = input
+            .len()
+            .checked_sub(aes_gcm::TAG_LEN)
+            .ok_or(SessionError::InvalidInput)?;
+
+        let mut scratch =
+            GuardedVec::try_zeroed(ct_len).ok_or(SessionError::InvalidInput)?;
+
+        let result = self.decrypt_inner(input, aad, None, None, scratch.borrow_mut());
+        scratch.borrow_mut().fill(0);
+        result
+    }
+
+    fn decrypt_inner(
+        &mut self,
+        input: &[u8],
+        aad: Aad,
+        version: Option<u32>,
+        commitment: Option<&[u8; COMMIT_LEN]>,
+        out: &mut [u8],
+    ) -> Result<VerifyResult, SessionError> {
+        self.require_alive()?;
 
         if input.len() < aes_gcm::TAG_LEN {
             out.fill(0);
@@ -169,28 +897,46 @@ impl Session {
             return Err(SessionError::OutputTooSmall);
         }
 
-        let mut enc_key = GuardedKey32::zeroed();
+        // Backend is selected by the AAD header, not the session.
+        //
+        // The header is UNAUTHENTICATED until the tag verifies, so the
+        // selection only picks a candidate: each suite has its own file
+        // key and the same header bytes are re-checked by the AEAD.
+        // A tampered / unknown `aead_id` is therefore indistinguishable
+        // from any other authentication failure.
+        let suite = match CipherSuite::from_aad_version(aad.version()) {
+            Some(s) => s,
+            None => {
+                out.fill(0);
+                return Ok(VerifyResult(false));
+            }
+        };
 
-        derive_key(
-            session_key,
-            Purpose::FileEncryption,
-            aad.file_id(),
-            &mut enc_key,
-        )
-        .map_err(|_| {
+        // Older generation (rotated out) or too far ahead: the header
+        // is unauthenticated, so this is just another auth failure
+        let generation = aad.generation();
+        if generation < self.generation || generation - self.generation > MAX_RATCHET_AHEAD {
             out.fill(0);
-            SessionError::CryptoFailure
-        })?;
+            return Ok(VerifyResult(false));
+        }
 
-        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        let enc_key = match self.file_key(suite, aad.file_id(), generation) {
+            Ok(k) => k,
+            Err(e) => {
+                out.fill(0);
+                return Err(e);
+            }
+        };
+
+        let nonce = chunk_nonce(enc_key, &aad, version);
+
+        if commitment.is_some_and(|c| !cipher::commitment_matches(enc_key, &nonce, c)) {
             out.fill(0);
-            return Err(SessionError::Killed);
+            return Ok(VerifyResult(false));
         }
 
-        let nonce = derive_nonce(&enc_key, aad.file_id(), aad.chunk());
-
-        let ok = aes_gcm::open(
-            &enc_key,
+        let ok = suite.open(
+            enc_key,
             &nonce,
             input,
             &aad.serialize(),
@@ -204,19 +950,283 @@ impl Session {
         Ok(VerifyResult(ok))
     }
 
+    /* ───────────── STREAMING ───────────── */
+
+    /// Begin a streaming encryption of one file.
+    ///
+    /// The one-shot API stays authoritative for chunked uploads;
+    /// streams use their own internal chunking (see `stream`).
+    pub fn begin_stream(&mut self, file_id: u64, cloud_id: u16) -> StreamingEncryptor<'_> {
+        StreamingEncryptor::new(self, file_id, cloud_id)
+    }
+
+    /// Begin a streaming verify-then-decrypt of one file.
+    pub fn open_stream(&mut self, file_id: u64, cloud_id: u16) -> StreamingDecryptor<'_> {
+        StreamingDecryptor::new(self, file_id, cloud_id)
+    }
+
     /* ───────────── TERMINATION ───────────── */
 
     /// Kill this session explicitly.
     ///
     /// SECURITY:
-    /// - Zeroizes and drops session key
+    /// - Zeroizes and drops file root, control keys and cached file key
     pub(crate) fn kill(&mut self) {
-        self.session_key.take();
+        self.file_key.take();
+        self.file_root.take();
+        self.revoked.take();
+        self.control.take();
     }
 }
 
 impl Drop for Session {
     fn drop(&mut self) {
-        self.session_key.take();
+        self.file_key.take();
+        self.file_root.take();
+        self.control.take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        // Revocations are replayed from the log before any file op
+        crate::logging::encrypted::init_test_log_root();
+        Session::new(GuardedKey32::init_with(|k| k.fill(0x42)))
+    }
+
+    #[test]
+    fn prewarm_is_reused_by_first_encrypt() -> Result<(), ()> {
+        let mut s = session();
+        let aad = Aad::new(7, 0, 1, AAD_VERSION_V1).ok_or(())?;
+
+        assert!(s.prewarm(&aad).is_ok());
+        assert_eq!(s.derivations, 1);
+
+        let mut out = [0u8; 3 + aes_gcm::TAG_LEN];
+        assert!(s.encrypt(b"abc", aad, &mut out).is_ok());
+        assert_eq!(s.derivations, 1);
+
+        // Different file => fresh derivation, old key replaced
+        let other = Aad::new(8, 0, 1, AAD_VERSION_V1).ok_or(())?;
+        assert!(s.encrypt(b"abc", other, &mut out).is_ok());
+        assert_eq!(s.derivations, 2);
+        Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn rewritten_chunk_never_reuses_nonce() {
+        // Fresh process: the ledger files are process-wide
+        assert!(crate::test_support::isolated(
+            "keystore::session::tests::rewritten_chunk_never_reuses_nonce",
+            || {
+                let session_key = GuardedKey32::init_with(|k| k.fill(0x42));
+                let mut s = session();
+                let aad = Aad::new(7, 3, 1, AAD_VERSION_V1);
+                assert!(aad.is_some());
+                let Some(aad) = aad else { return };
+
+                let mut first = [0u8; 3 + aes_gcm::TAG_LEN];
+                let mut second = [0u8; 3 + aes_gcm::TAG_LEN];
+
+                // No ledger genesis yet => refused, never a fresh counter
+                assert!(s.encrypt_versioned(b"abc", aad, &mut first).err() == Some(SessionError::CryptoFailure));
+                assert!(first.iter().all(|b| *b == 0));
+
+                let ledger_key = nonce_ledger::ledger_key(&session_key).ok();
+                assert!(ledger_key.as_ref().is_some_and(|k| NonceLedger::create(k).is_ok()));
+
+                let mut s = session();
+                let v0 = s.encrypt_versioned(b"abc", aad, &mut first).ok().and_then(|r| r.write_version);
+                let v1 = s.encrypt_versioned(b"abc", aad, &mut second).ok().and_then(|r| r.write_version);
+                assert_eq!((v0, v1), (Some(0), Some(1)));
+
+                // Same plaintext, same chunk: distinct nonces => distinct output
+                assert_ne!(first, second);
+
+                let mut pt = [0u8; 3];
+                assert!(s.decrypt_verify_versioned(&second, aad, 1, &mut pt) == Ok(VerifyResult(true)));
+                assert!(s.decrypt_verify_versioned(&second, aad, 0, &mut pt) == Ok(VerifyResult(false)));
+
+                // A new session resumes from the persisted ledger
+                let mut s = session();
+                let v2 = s.encrypt_versioned(b"abc", aad, &mut first).ok().and_then(|r| r.write_version);
+                assert_eq!(v2, Some(2));
+            },
+        ));
+    }
+
+    #[test]
+    fn kill_wipes_cached_file_key() -> Result<(), ()> {
+        let mut s = session();
+        let aad = Aad::new(7, 0, 1, AAD_VERSION_V1).ok_or(())?;
+
+        assert!(s.prewarm(&aad).is_ok());
+        s.kill();
+
+        assert!(s.file_key.is_none());
+        assert!(matches!(s.prewarm(&aad), Err(SessionError::Locked)));
+        Ok(())
+    }
+
+    #[test]
+    fn revoked_file_is_refused_without_global_kill() -> Result<(), ()> {
+        let mut s = session();
+        s.revoked = Some(TombstoneLog::in_memory());
+
+        let aad = Aad::new(7, 0, 1, AAD_VERSION_V1).ok_or(())?;
+        let other = Aad::new(8, 0, 1, AAD_VERSION_V1).ok_or(())?;
+
+        let mut ct = [0u8; 3 + aes_gcm::TAG_LEN];
+        assert!(s.encrypt(b"abc", aad, &mut ct).is_ok());
+
+        assert!(s.revoke_file(7).is_ok());
+        assert!(s.revoke_file(7).is_ok());
+        assert!(s.file_key.is_none());
+        assert!(matches!(s.file_status(7), Ok(FileStatus { revoked: true })));
+
+        let mut out = [0u8; 3 + aes_gcm::TAG_LEN];
+        let mut pt = [0u8; 3];
+        assert!(matches!(s.encrypt(b"abc", aad, &mut out), Err(SessionError::Killed)));
+        assert!(matches!(s.decrypt_verify(&ct, aad, &mut pt), Err(SessionError::Killed)));
+        assert!(matches!(s.prewarm(&aad), Err(SessionError::Killed)));
+
+        // Per-file only: the session and other files stay usable
+        assert!(!GLOBAL_KILLED.load(Ordering::SeqCst));
+        assert!(s.encrypt(b"abc", other, &mut out).is_ok());
+        assert!(matches!(s.file_status(8), Ok(FileStatus { revoked: false })));
+        Ok(())
+    }
+
+    #[test]
+    fn persisted_revocation_survives_a_new_session() -> Result<(), ()> {
+        crate::logging::encrypted::init_test_log_root();
+        // Own key: its tombstone MACs never match other tests' records
+        let key = || GuardedKey32::init_with(|k| k.fill(0x5D));
+        let aad = Aad::new(0x5D00_0000_0000_0007, 0, 1, AAD_VERSION_V1).ok_or(())?;
+
+        let mut ct = [0u8; 3 + aes_gcm::TAG_LEN];
+        let mut first = Session::new(key());
+        assert!(first.encrypt(b"abc", aad, &mut ct).is_ok());
+        assert!(first.revoke_file(aad.file_id()).is_ok());
+        drop(first);
+
+        // No `revoke_file` / `file_status` call before the chunk op
+        let mut second = Session::new(key());
+        let mut pt = [0xAAu8; 3];
+        assert!(matches!(second.decrypt_verify(&ct, aad, &mut pt), Err(SessionError::Killed)));
+        assert!(pt.iter().all(|b| *b == 0));
+        Ok(())
+    }
+
+    #[test]
+    fn verify_only_authenticates_without_plaintext() -> Result<(), ()> {
+        let mut s = session();
+        let aad = Aad::new(7, 0, 1, AAD_VERSION_V1).ok_or(())?;
+        let other = Aad::new(7, 1, 1, AAD_VERSION_V1).ok_or(())?;
+
+        let mut ct = [0u8; 3 + aes_gcm::TAG_LEN];
+        assert!(s.encrypt(b"abc", aad, &mut ct).is_ok());
+
+        assert!(s.verify_only(&ct, aad) == Ok(VerifyResult(true)));
+        assert!(s.verify_only(&ct, other) == Ok(VerifyResult(false)));
+
+        ct[0] ^= 1;
+        assert!(s.verify_only(&ct, aad) == Ok(VerifyResult(false)));
+        assert!(matches!(s.verify_only(&ct[..4], aad), Err(SessionError::InvalidInput)));
+        Ok(())
+    }
+
+    #[test]
+    fn batch_encrypt_matches_per_chunk_with_one_derivation() -> Result<(), ()> {
+        let mut s = session();
+
+        let chunks: [&[u8]; 3] = [b"abc", b"", b"defgh"];
+        let mut a = [0u8; 3 + aes_gcm::TAG_LEN];
+        let mut b = [0u8; aes_gcm::TAG_LEN];
+        let mut c = [0u8; 5 + aes_gcm::TAG_LEN];
+
+        {
+            let mut out: [&mut [u8]; 3] = [&mut a, &mut b, &mut c];
+            let r = s.encrypt_file_chunks(7, 1, &chunks, &mut out).map_err(|_| ())?;
+            assert_eq!(r.chunks, 3);
+        }
+        // Batch never touches the single-entry cache
+        assert_eq!(s.derivations, 0);
+
+        for (i, (chunk, sealed)) in chunks.iter().zip([&a[..], &b[..], &c[..]]).enumerate() {
+            let aad = Aad::new(7, i as u32, 1, AAD_VERSION_V1).ok_or(())?;
+            let mut single = vec![0u8; chunk.len() + aes_gcm::TAG_LEN];
+            assert!(s.encrypt(chunk, aad, &mut single).is_ok());
+            assert_eq!(&single[..], sealed);
+        }
+
+        // Bad length: nothing sealed, every output wiped
+        let mut short = [0xAAu8; 2];
+        let mut out: [&mut [u8]; 2] = [&mut a, &mut short];
+        let r = s.encrypt_file_chunks(7, 1, &chunks[..2], &mut out);
+        assert!(matches!(r, Err(SessionError::OutputTooSmall)));
+        assert!(a.iter().chain(short.iter()).all(|x| *x == 0));
+        Ok(())
+    }
+
+    #[test]
+    fn rekey_ratchets_forward_only() -> Result<(), ()> {
+        let mut s = session();
+        let aad = Aad::new(7, 0, 1, AAD_VERSION_V1).ok_or(())?;
+
+        let mut gen0 = [0u8; 3 + aes_gcm::TAG_LEN];
+        let mut gen1 = [0u8; 3 + aes_gcm::TAG_LEN];
+        assert!(matches!(s.encrypt(b"abc", aad, &mut gen0), Ok(EncryptResult { generation: 0, .. })));
+
+        assert!(matches!(s.rekey(), Ok(Rekeyed { generation: 1 })));
+        assert_eq!(s.generation(), 1);
+
+        let r = s.encrypt(b"abc", aad, &mut gen1).map_err(|_| ())?;
+        assert_eq!(r.generation, 1);
+        assert_eq!(r.aad_version, crate::crypto::aad::AAD_VERSION_V3);
+        assert_ne!(gen0, gen1);
+
+        let mut pt = [0u8; 3];
+        let at1 = aad.with_generation(1);
+        assert!(s.decrypt_verify(&gen1, at1, &mut pt) == Ok(VerifyResult(true)));
+
+        // Generation 0 is behind the ratchet: refused in this session
+        assert!(s.decrypt_verify(&gen0, aad, &mut pt) == Ok(VerifyResult(false)));
+
+        // A fresh session (same key) replays the ratchet to reach gen 1
+        let mut fresh = session();
+        assert!(fresh.decrypt_verify(&gen0, aad, &mut pt) == Ok(VerifyResult(true)));
+        assert!(fresh.decrypt_verify(&gen1, at1, &mut pt) == Ok(VerifyResult(true)));
+        assert!(fresh.rekey().is_ok());
+        assert!(fresh.decrypt_verify(&gen0, aad, &mut pt) == Ok(VerifyResult(false)));
+        Ok(())
+    }
+
+    #[test]
+    fn rekey_leaves_no_generation_zero_key_resident() {
+        let session_key = GuardedKey32::init_with(|k| k.fill(0x42));
+        let mut s = session();
+
+        let before = s.device_key(0xF00D).map(|k| *k.borrow());
+        assert!(before.is_ok());
+        let sealed = s.seal_index(b"index").map(|b| b.0).unwrap_or_default();
+        assert!(!sealed.is_empty());
+
+        assert!(s.rekey().is_ok());
+
+        // The only file-capable key is the ratcheted root
+        assert!(matches!(&s.file_root, Some(r) if r.borrow() != session_key.borrow()));
+        assert!(s.control.as_ref().is_some_and(|c| {
+            c.index.borrow() != session_key.borrow()
+                && c.tombstone.borrow() != session_key.borrow()
+                && c.device.borrow() != session_key.borrow()
+        }));
+
+        // Control plane is unaffected by the ratchet
+        assert!(s.device_key(0xF00D).map(|k| *k.borrow()) == before);
+        assert!(matches!(s.open_index(&sealed), Ok(OpenedIndex(Some(_)))));
+    }
+}
@@ -0,0 +1,476 @@
+//! Streaming AEAD over a session (large files).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! Splits an arbitrary-length stream into fixed-size internal
+//! chunks, each sealed with `Session::encrypt` under the stream
+//! AAD layout (`AAD_VERSION_STREAM`) and its own nonce domain
+//! (`derive_nonce_stream`), independently authenticated.
+//!
+//! WIRE FORMAT:
+//! `chunk_0 || chunk_1 || ... || chunk_last`
+//! where every chunk is `[ ciphertext | tag ]` and all chunks but
+//! the last carry exactly `STREAM_CHUNK_SIZE` plaintext bytes.
+//!
+//! FORMAL INVARIANTS (ENFORCED):
+//! - The LAST chunk is sealed with `STREAM_FINAL_FLAG` set in its
+//!   chunk index => truncation / extension fails authentication
+//! - Plaintext is buffered in guarded memory only
+//! - Any error (incl. kill) poisons the stream permanently
+//! - Output buffers are wiped on failure
+//! - !Send / !Sync (like `Session`)
+
+#![deny(clippy::derive_debug)]
+
+use core::marker::PhantomData;
+use core::sync::atomic::Ordering;
+
+use crate::crypto::aad::Aad;
+use crate::crypto::aes_gcm::TAG_LEN;
+use crate::keystore::master::GLOBAL_KILLED;
+use crate::keystore::session::{Session, SessionError};
+use crate::memory::GuardedVec;
+
+/// Plaintext bytes per internal chunk.
+///
+/// ⚠️ Part of the wire format: MUST NEVER CHANGE.
+pub const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Sealed chunk size (all chunks except the last).
+pub const STREAM_SEALED_CHUNK_SIZE: usize = STREAM_CHUNK_SIZE + TAG_LEN;
+
+/// Chunk-index bit marking the final chunk.
+///
+/// Streams are therefore limited to `2^31` chunks.
+pub const STREAM_FINAL_FLAG: u32 = 1 << 31;
+
+/// Highest stream chunk index; reserved for the FINAL chunk.
+///
+/// A non-final chunk here could never be followed by a final one.
+pub const MAX_STREAM_CHUNK_INDEX: u32 = STREAM_FINAL_FLAG - 1;
+
+/* ───────────── SHARED ───────────── */
+
+#[inline(always)]
+fn require_alive() -> Result<(), SessionError> {
+    if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        Err(SessionError::Killed)
+    } else {
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn stream_aad(
+    file_id: u64,
+    cloud_id: u16,
+    index: u32,
+    last: bool,
+) -> Result<Aad, SessionError> {
+    if index & STREAM_FINAL_FLAG != 0 {
+        return Err(SessionError::InvalidInput);
+    }
+
+    let chunk = if last { index | STREAM_FINAL_FLAG } else { index };
+
+    Ok(Aad::stream(file_id, chunk, cloud_id))
+}
+
+/* ───────────── ENCRYPTOR ───────────── */
+
+/// Incremental encryptor (obtain via `Session::begin_stream`).
+pub struct StreamingEncryptor<'s> {
+    session: &'s mut Session,
+    file_id: u64,
+    cloud_id: u16,
+    next_index: u32,
+    buf: GuardedVec,
+    buffered: usize,
+    poisoned: bool,
+    _no_send_sync: PhantomData<*const ()>,
+}
+
+impl<'s> StreamingEncryptor<'s> {
+    pub(crate) fn new(session: &'s mut Session, file_id: u64, cloud_id: u16) -> Self {
+        Self {
+            session,
+            file_id,
+            cloud_id,
+            next_index: 0,
+            buf: GuardedVec::zeroed(STREAM_CHUNK_SIZE),
+            buffered: 0,
+            poisoned: false,
+            _no_send_sync: PhantomData,
+        }
+    }
+
+    /// Exact number of bytes the next `update(input)` will emit.
+    pub fn update_output_len(&self, input_len: usize) -> usize {
+        ((self.buffered + input_len) / STREAM_CHUNK_SIZE) * STREAM_SEALED_CHUNK_SIZE
+    }
+
+    /// Exact number of bytes `finalize` will emit.
+    pub fn finalize_output_len(&self) -> usize {
+        self.buffered + TAG_LEN
+    }
+
+    /// Feed plaintext; emits every completed chunk into `out`.
+    ///
+    /// Returns the number of bytes written. `out` MUST hold at
+    /// least `update_output_len(input.len())` bytes.
+    pub fn update(&mut self, input: &[u8], out: &mut [u8]) -> Result<usize, SessionError> {
+        let res = self.update_inner(input, out);
+        self.fail_closed(res, out)
+    }
+
+    /// Seal the final (possibly empty) chunk into `out`.
+    ///
+    /// `out` MUST hold at least `finalize_output_len()` bytes.
+    pub fn finalize(mut self, out: &mut [u8]) -> Result<usize, SessionError> {
+        let res = self.finalize_inner(out);
+        self.fail_closed(res, out)
+    }
+
+    fn update_inner(&mut self, mut input: &[u8], out: &mut [u8]) -> Result<usize, SessionError> {
+        if self.poisoned {
+            return Err(SessionError::InvalidInput);
+        }
+        require_alive()?;
+
+        if out.len() < self.update_output_len(input.len()) {
+            return Err(SessionError::OutputTooSmall);
+        }
+
+        let mut written = 0usize;
+
+        while !input.is_empty() {
+            let take = (STREAM_CHUNK_SIZE - self.buffered).min(input.len());
+            self.buf.borrow_mut()[self.buffered..self.buffered + take]
+                .copy_from_slice(&input[..take]);
+            self.buffered += take;
+            input = &input[take..];
+
+            // Full chunks are sealed eagerly; `finalize` always emits
+            // a (possibly empty) final chunk
+            if self.buffered == STREAM_CHUNK_SIZE {
+                let dst = &mut out[written..written + STREAM_SEALED_CHUNK_SIZE];
+                self.seal_buffered(false, dst)?;
+                written += STREAM_SEALED_CHUNK_SIZE;
+            }
+        }
+
+        Ok(written)
+    }
+
+    fn finalize_inner(&mut self, out: &mut [u8]) -> Result<usize, SessionError> {
+        if self.poisoned {
+            return Err(SessionError::InvalidInput);
+        }
+        require_alive()?;
+
+        let len = self.finalize_output_len();
+        if out.len() < len {
+            return Err(SessionError::OutputTooSmall);
+        }
+
+        self.seal_buffered(true, &mut out[..len])?;
+        Ok(len)
+    }
+
+    fn seal_buffered(&mut self, last: bool, dst: &mut [u8]) -> Result<(), SessionError> {
+        if !last && self.next_index >= MAX_STREAM_CHUNK_INDEX {
+            return Err(SessionError::InvalidInput);
+        }
+
+        let aad = stream_aad(self.file_id, self.cloud_id, self.next_index, last)?;
+
+        self.session
+            .encrypt(&self.buf.borrow()[..self.buffered], aad, dst)?;
+
+        self.buf.borrow_mut()[..self.buffered].fill(0);
+        self.buffered = 0;
+        self.next_index = self
+            .next_index
+            .checked_add(1)
+            .ok_or(SessionError::InvalidInput)?;
+        Ok(())
+    }
+
+    fn fail_closed(
+        &mut self,
+        res: Result<usize, SessionError>,
+        out: &mut [u8],
+    ) -> Result<usize, SessionError> {
+        if res.is_err() {
+            out.fill(0);
+            self.buf.borrow_mut().fill(0);
+            self.buffered = 0;
+            self.poisoned = true;
+        }
+        res
+    }
+}
+
+/* ───────────── DECRYPTOR ───────────── */
+
+/// Incremental verify-then-release decryptor.
+///
+/// SECURITY:
+/// - Plaintext is released one AUTHENTICATED chunk at a time
+/// - A complete chunk is held back until more input arrives,
+///   so the final chunk is only accepted by `finalize`
+/// - Authentication failure => `CryptoFailure`, stream poisoned
+pub struct StreamingDecryptor<'s> {
+    session: &'s mut Session,
+    file_id: u64,
+    cloud_id: u16,
+    next_index: u32,
+    buf: Vec<u8>,
+    poisoned: bool,
+    _no_send_sync: PhantomData<*const ()>,
+}
+
+impl<'s> StreamingDecryptor<'s> {
+    pub(crate) fn new(session: &'s mut Session, file_id: u64, cloud_id: u16) -> Self {
+        Self {
+            session,
+            file_id,
+            cloud_id,
+            next_index: 0,
+            buf: Vec::with_capacity(STREAM_SEALED_CHUNK_SIZE),
+            poisoned: false,
+            _no_send_sync: PhantomData,
+        }
+    }
+
+    /// Upper bound on bytes the next `update(input)` will emit.
+    pub fn update_output_len(&self, input_len: usize) -> usize {
+        // Strictly more than a full sealed chunk is needed to release one
+        let total = self.buf.len() + input_len;
+        (total.saturating_sub(1) / STREAM_SEALED_CHUNK_SIZE) * STREAM_CHUNK_SIZE
+    }
+
+    /// Exact number of bytes `finalize` will emit (if authentic).
+    pub fn finalize_output_len(&self) -> usize {
+        self.buf.len().saturating_sub(TAG_LEN)
+    }
+
+    /// Feed ciphertext; emits every authenticated non-final chunk.
+    pub fn update(&mut self, input: &[u8], out: &mut [u8]) -> Result<usize, SessionError> {
+        let res = self.update_inner(input, out);
+        self.fail_closed(res, out)
+    }
+
+    /// Authenticate the final chunk and emit its plaintext.
+    pub fn finalize(mut self, out: &mut [u8]) -> Result<usize, SessionError> {
+        let res = self.finalize_inner(out);
+        self.fail_closed(res, out)
+    }
+
+    fn update_inner(&mut self, mut input: &[u8], out: &mut [u8]) -> Result<usize, SessionError> {
+        if self.poisoned {
+            return Err(SessionError::InvalidInput);
+        }
+        require_alive()?;
+
+        if out.len() < self.update_output_len(input.len()) {
+            return Err(SessionError::OutputTooSmall);
+        }
+
+        let mut written = 0usize;
+
+        while !input.is_empty() {
+            // Buffer is full and more input exists => it is NOT final
+            if self.buf.len() == STREAM_SEALED_CHUNK_SIZE {
+                let dst = &mut out[written..written + STREAM_CHUNK_SIZE];
+                self.open_buffered(false, dst)?;
+                written += STREAM_CHUNK_SIZE;
+            }
+
+            let take = (STREAM_SEALED_CHUNK_SIZE - self.buf.len()).min(input.len());
+            self.buf.extend_from_slice(&input[..take]);
+            input = &input[take..];
+        }
+
+        Ok(written)
+    }
+
+    fn finalize_inner(&mut self, out: &mut [u8]) -> Result<usize, SessionError> {
+        if self.poisoned {
+            return Err(SessionError::InvalidInput);
+        }
+        require_alive()?;
+
+        if self.buf.len() < TAG_LEN {
+            return Err(SessionError::InvalidInput);
+        }
+
+        let len = self.finalize_output_len();
+        if out.len() < len {
+            return Err(SessionError::OutputTooSmall);
+        }
+
+        self.open_buffered(true, &mut out[..len])?;
+        Ok(len)
+    }
+
+    fn open_buffered(&mut self, last: bool, dst: &mut [u8]) -> Result<(), SessionError> {
+        if !last && self.next_index >= MAX_STREAM_CHUNK_INDEX {
+            return Err(SessionError::InvalidInput);
+        }
+
+        let aad = stream_aad(self.file_id, self.cloud_id, self.next_index, last)?
+            .with_cipher(self.session.suite())
+            .with_generation(self.session.generation());
+
+        let verified = self.session.decrypt_verify(&self.buf, aad, dst)?;
+        if !verified.0 {
+            return Err(SessionError::CryptoFailure);
+        }
+
+        self.buf.clear();
+        self.next_index = self
+            .next_index
+            .checked_add(1)
+            .ok_or(SessionError::InvalidInput)?;
+        Ok(())
+    }
+
+    fn fail_closed(
+        &mut self,
+        res: Result<usize, SessionError>,
+        out: &mut [u8],
+    ) -> Result<usize, SessionError> {
+        if res.is_err() {
+            out.fill(0);
+            self.buf.clear();
+            self.poisoned = true;
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::GuardedKey32;
+
+    fn session() -> Session {
+        // Revocations are replayed from the log before any file op
+        crate::logging::encrypted::init_test_log_root();
+        Session::new(GuardedKey32::init_with(|k| k.fill(0x42)))
+    }
+
+    fn encrypt_all(s: &mut Session, data: &[u8], step: usize) -> Result<Vec<u8>, SessionError> {
+        let mut enc = s.begin_stream(3, 1);
+        let mut sealed = Vec::new();
+
+        for part in data.chunks(step.max(1)) {
+            let mut out = vec![0u8; enc.update_output_len(part.len())];
+            let n = enc.update(part, &mut out)?;
+            sealed.extend_from_slice(&out[..n]);
+        }
+
+        let mut out = vec![0u8; enc.finalize_output_len()];
+        let n = enc.finalize(&mut out)?;
+        sealed.extend_from_slice(&out[..n]);
+        Ok(sealed)
+    }
+
+    fn decrypt_all(s: &mut Session, sealed: &[u8], step: usize) -> Result<Vec<u8>, SessionError> {
+        let mut dec = s.open_stream(3, 1);
+        let mut plain = Vec::new();
+
+        for part in sealed.chunks(step.max(1)) {
+            let mut out = vec![0u8; dec.update_output_len(part.len())];
+            let n = dec.update(part, &mut out)?;
+            plain.extend_from_slice(&out[..n]);
+        }
+
+        let mut out = vec![0u8; dec.finalize_output_len()];
+        let n = dec.finalize(&mut out)?;
+        plain.extend_from_slice(&out[..n]);
+        Ok(plain)
+    }
+
+    #[test]
+    fn stream_round_trips_across_chunk_boundaries() {
+        let mut s = session();
+        let data: Vec<u8> = (0..(2 * STREAM_CHUNK_SIZE + 123)).map(|i| i as u8).collect();
+
+        let sealed = encrypt_all(&mut s, &data, 10_000);
+        assert!(matches!(&sealed, Ok(c) if c.len() == data.len() + 3 * TAG_LEN));
+
+        let sealed = sealed.unwrap_or_default();
+        assert!(decrypt_all(&mut s, &sealed, 7_777) == Ok(data));
+    }
+
+    #[test]
+    fn stream_chunk_is_not_a_one_shot_chunk() -> Result<(), ()> {
+        let mut s = session();
+        let data = [7u8; 32];
+
+        let sealed = encrypt_all(&mut s, &data, 32).map_err(|_| ())?;
+
+        // Same key, file and chunk index: only the stream domain differs
+        let one_shot = Aad::new(3, STREAM_FINAL_FLAG, 1, crate::crypto::aad::AAD_VERSION_V1).ok_or(())?;
+        let mut sealed_one_shot = [0u8; 32 + TAG_LEN];
+        s.encrypt(&data, one_shot, &mut sealed_one_shot).map_err(|_| ())?;
+        assert!(sealed[..] != sealed_one_shot[..]);
+
+        let mut out = [0u8; 32];
+        let verified = s.decrypt_verify(&sealed, one_shot, &mut out).map_err(|_| ())?;
+        assert!(!verified.0);
+        Ok(())
+    }
+
+    #[test]
+    fn encryptor_refuses_to_advance_past_max_index() {
+        let mut s = session();
+        let chunk = vec![0u8; STREAM_CHUNK_SIZE];
+        let mut out = vec![0u8; STREAM_SEALED_CHUNK_SIZE];
+
+        let mut enc = s.begin_stream(3, 1);
+        enc.next_index = MAX_STREAM_CHUNK_INDEX;
+        assert!(enc.update(&chunk, &mut out) == Err(SessionError::InvalidInput));
+        assert!(out.iter().all(|b| *b == 0));
+
+        // The final chunk may still use the last index
+        let mut enc = s.begin_stream(3, 1);
+        enc.next_index = MAX_STREAM_CHUNK_INDEX;
+        let mut fin = vec![0u8; enc.finalize_output_len()];
+        assert!(enc.finalize(&mut fin) == Ok(TAG_LEN));
+    }
+
+    #[test]
+    fn truncated_stream_fails_authentication() {
+        let mut s = session();
+        let data = vec![0x5Au8; 2 * STREAM_CHUNK_SIZE];
+
+        let sealed = encrypt_all(&mut s, &data, STREAM_CHUNK_SIZE).unwrap_or_default();
+
+        // Drop the final (empty) chunk: last full chunk is NOT final
+        let truncated = &sealed[..2 * STREAM_SEALED_CHUNK_SIZE];
+        assert!(decrypt_all(&mut s, truncated, 4096) == Err(SessionError::CryptoFailure));
+    }
+
+    #[cfg(feature = "chacha")]
+    #[test]
+    fn chacha_stream_round_trips() -> Result<(), ()> {
+        use crate::crypto::cipher::CipherSuite;
+
+        let mut aes = session();
+        let mut chacha = Session::with_suite(
+            GuardedKey32::init_with(|k| k.fill(0x42)),
+            CipherSuite::ChaCha20Poly1305,
+        );
+        let data: Vec<u8> = (0..(STREAM_CHUNK_SIZE + 77)).map(|i| (i * 7) as u8).collect();
+
+        let sealed = encrypt_all(&mut chacha, &data, 5_000).map_err(|_| ())?;
+        assert!(decrypt_all(&mut chacha, &sealed, 3_333) == Ok(data));
+
+        // Same key, other suite: the chunks do not open
+        assert!(decrypt_all(&mut aes, &sealed, 3_333) == Err(SessionError::CryptoFailure));
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+//! Persistent per-file tombstones (crypto-shredding).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! A tombstoned `file_id` can never again be encrypted or decrypted
+//! by any Session holding the set (`Session::revoke_file`), even
+//! though its key stays derivable from the session key.
+//!
+//! ONE store: `Core::tombstone_file` is `Session::revoke_file` on the
+//! Core's session, and the Core's `Denied` refusal reads the same
+//! set. The set lives with the session (dropped on lock, replayed on
+//! the next unlock), keyed by attestation key → `TOMBSTONE_CONTEXT`,
+//! so tombstones written through either API verify under the other.
+//!
+//! RECORD FORMAT (length-prefixed, `file_tombstones.log`):
+//! `file_id (8, big-endian) || tag (32)`
+//! `tag = HMAC(tombstone_key, TOMBSTONE_LABEL || file_id)`
+//!
+//! FORMAL INVARIANTS (ENFORCED):
+//! - Write-ahead: a tombstone is persisted BEFORE it takes effect
+//!   in memory, and is never removed (monotonic)
+//! - Records are authenticated under a key derived from the
+//!   attestation key; records that do not verify are not ours
+//!   and grant / revoke nothing
+//! - Unreadable / malformed log => refuse (fail-closed)
+//! - No log root (WASM, or before the bridge's `init_log_root`) =>
+//!   nothing was ever recorded: the set starts empty and is attached
+//!   to the log once a root exists; until then `insert` refuses
+//! - The log is MAC-only, NOT record-sealed (`with_log_key`):
+//!   records from other key hierarchies (other vaults, before a
+//!   phrase change) MUST stay skippable
+//! - Forbidden after global kill
+
+use core::sync::atomic::Ordering;
+use std::collections::HashSet;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::keystore::master::GLOBAL_KILLED;
+use crate::logging::encrypted::{has_log_root, EncryptedLog};
+use crate::memory::{ct_eq, GuardedKey32};
+
+/// Tombstone key context under the attestation key (`Purpose::Recovery`).
+pub const TOMBSTONE_CONTEXT: u64 = 0x544F4D4253544F4E; // "TOMBSTON"
+
+/// Record MAC domain label (MUST NEVER CHANGE).
+const TOMBSTONE_LABEL: &[u8] = b"rcxcloud:file:tombstone:v1";
+
+const TAG_LEN: usize = 32;
+const RECORD_LEN: usize = 8 + TAG_LEN;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TombstoneError {
+    Killed,
+    /// Persisted state missing or malformed
+    Unverifiable,
+    /// Tombstone record could not be persisted
+    Persist,
+}
+
+/// Set of tombstoned files for the current key hierarchy.
+pub struct TombstoneLog {
+    // `None` until a log root exists (always for in-memory test logs)
+    log: Option<EncryptedLog>,
+    // Backed by `file_tombstones.log` (false only in tests)
+    persistent: bool,
+    files: HashSet<u64>,
+}
+
+impl TombstoneLog {
+    /// Open and replay the persisted tombstones.
+    ///
+    /// `mac` computes the expected tag for a `file_id` under the
+    /// current key hierarchy.
+    pub fn open(
+        mac: impl Fn(u64) -> Result<[u8; TAG_LEN], TombstoneError>,
+    ) -> Result<Self, TombstoneError> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(TombstoneError::Killed);
+        }
+
+        let mut set = Self {
+            log: None,
+            persistent: true,
+            files: HashSet::new(),
+        };
+        set.attach(mac)?;
+        Ok(set)
+    }
+
+    /// Replay and adopt the persisted log once a log root exists
+    /// (no-op when already attached or still without a root).
+    ///
+    /// Records found are ADDED: nothing in memory is ever dropped.
+    pub fn attach(
+        &mut self,
+        mac: impl Fn(u64) -> Result<[u8; TAG_LEN], TombstoneError>,
+    ) -> Result<(), TombstoneError> {
+        if !self.persistent || self.log.is_some() || !has_log_root() {
+            return Ok(());
+        }
+
+        let mut log = EncryptedLog::open_tombstone_log().map_err(|_| TombstoneError::Unverifiable)?;
+        let records = log.read_records().map_err(|_| TombstoneError::Unverifiable)?;
+
+        self.files.extend(replay(&records, mac)?);
+        self.log = Some(log);
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub(crate) fn in_memory() -> Self {
+        Self {
+            log: None,
+            persistent: false,
+            files: HashSet::new(),
+        }
+    }
+
+    /// Whether `file_id` has been tombstoned.
+    pub fn contains(&self, file_id: u64) -> bool {
+        self.files.contains(&file_id)
+    }
+
+    /// Tombstone `file_id` (idempotent).
+    ///
+    /// SECURITY:
+    /// - Persisted (flushed) BEFORE the in-memory set changes
+    /// - No log root yet => `Persist` (a revocation that could not
+    ///   survive restart is refused, not silently kept in memory)
+    pub fn insert(&mut self, file_id: u64, tag: [u8; TAG_LEN]) -> Result<(), TombstoneError> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(TombstoneError::Killed);
+        }
+
+        if self.contains(file_id) {
+            return Ok(());
+        }
+
+        match self.log.as_mut() {
+            Some(log) => log
+                .append_record(&encode_record(file_id, &tag))
+                .map_err(|_| TombstoneError::Persist)?,
+            None if self.persistent => return Err(TombstoneError::Persist),
+            None => {}
+        }
+
+        self.files.insert(file_id);
+        Ok(())
+    }
+}
+
+/// Tag a `file_id` under the tombstone key.
+pub(crate) fn mac_file_id(
+    key: &GuardedKey32,
+    file_id: u64,
+) -> Result<[u8; TAG_LEN], TombstoneError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.borrow())
+        .map_err(|_| TombstoneError::Unverifiable)?;
+    mac.update(TOMBSTONE_LABEL);
+    mac.update(&file_id.to_be_bytes());
+
+    let mut out = [0u8; TAG_LEN];
+    out.copy_from_slice(&mac.finalize().into_bytes());
+    Ok(out)
+}
+
+fn encode_record(file_id: u64, tag: &[u8; TAG_LEN]) -> [u8; RECORD_LEN] {
+    let mut rec = [0u8; RECORD_LEN];
+    rec[..8].copy_from_slice(&file_id.to_be_bytes());
+    rec[8..].copy_from_slice(tag);
+    rec
+}
+
+/// Rebuild the tombstone set from authenticated records.
+fn replay(
+    records: &[Vec<u8>],
+    mac: impl Fn(u64) -> Result<[u8; TAG_LEN], TombstoneError>,
+) -> Result<HashSet<u64>, TombstoneError> {
+    let mut files = HashSet::new();
+
+    for rec in records {
+        if rec.len() != RECORD_LEN {
+            return Err(TombstoneError::Unverifiable);
+        }
+
+        let mut f = [0u8; 8];
+        f.copy_from_slice(&rec[..8]);
+        let file_id = u64::from_be_bytes(f);
+
+        if ct_eq(&mac(file_id)?, &rec[8..]) {
+            files.insert(file_id);
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(b: u8) -> GuardedKey32 {
+        GuardedKey32::init_with(|k| k.fill(b))
+    }
+
+    fn record(k: &GuardedKey32, file_id: u64) -> Vec<u8> {
+        encode_record(file_id, &mac_file_id(k, file_id).unwrap_or_default()).to_vec()
+    }
+
+    #[test]
+    fn insert_is_monotonic_and_idempotent() {
+        let k = key(0x11);
+        let mut log = TombstoneLog::in_memory();
+
+        assert!(!log.contains(7));
+        assert_eq!(log.insert(7, mac_file_id(&k, 7).unwrap_or_default()), Ok(()));
+        assert_eq!(log.insert(7, mac_file_id(&k, 7).unwrap_or_default()), Ok(()));
+        assert!(log.contains(7));
+        assert!(!log.contains(8));
+    }
+
+    #[test]
+    fn replay_keeps_only_authenticated_records() {
+        let ours = key(0x11);
+        let theirs = key(0x22);
+
+        let mut forged = record(&ours, 3);
+        forged[RECORD_LEN - 1] ^= 0x01;
+
+        let files = replay(
+            &[record(&ours, 1), record(&theirs, 2), forged],
+            |f| mac_file_id(&ours, f),
+        );
+        assert!(matches!(&files, Ok(s) if s.len() == 1 && s.contains(&1)));
+
+        assert_eq!(
+            replay(&[vec![0u8; RECORD_LEN - 1]], |f| mac_file_id(&ours, f)).err(),
+            Some(TombstoneError::Unverifiable)
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Kill acknowledgment (target → admin, out-of-band).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! After the replay token is committed (the kill is now certain),
+//! the target seals a short receipt into `kill_ack.bin` for an
+//! out-of-band collector to ship back to the admin.
+//!
+//! BLOB FORMAT:
+//! `nonce (12) || AEAD(device_id (32) || replay (8) || timestamp_ms (8)) || tag (16)`
+//! - key: `derive_key(kill_key, Purpose::Recovery, KILL_ACK_CONTEXT)`,
+//!   `kill_key` = the per-device key the kill blob was sealed under
+//! - AAD: `ACK_AAD_LABEL || device_fingerprint`
+//!
+//! SECURITY:
+//! - Best effort: an ack failure NEVER blocks or delays the kill
+//! - Written AFTER the kill fuse and keystore wipe (the only log
+//!   write allowed then, `EncryptedLog::publish_kill_ack`)
+//! - Proves possession of the kill key, not honest execution
+
+use rand_core::{OsRng, RngCore};
+
+use crate::crypto::aes_gcm::{self, NONCE_LEN, TAG_LEN};
+use crate::crypto::derive::{derive_key, Purpose};
+use crate::device::registry::DeviceRegistry;
+use crate::kill::replay::ReplayToken;
+use crate::logging::encrypted::EncryptedLog;
+use crate::memory::GuardedKey32;
+
+/// Ack key context under the per-device kill key (`Purpose::Recovery`).
+pub const KILL_ACK_CONTEXT: u64 = 0x4B494C4C41434B31; // "KILLACK1"
+
+/// Ack AAD domain label (MUST NEVER CHANGE).
+const ACK_AAD_LABEL: &[u8; 16] = b"rcxcloud-killack";
+
+const ACK_PLAINTEXT_LEN: usize = 32 + 8 + 8;
+
+/// Sealed ack length.
+pub const KILL_ACK_LEN: usize = NONCE_LEN + ACK_PLAINTEXT_LEN + TAG_LEN;
+
+/// Authenticated kill acknowledgment (NON-SECRET).
+#[cfg(any(test, feature = "kill-admin"))]
+pub struct KillAck {
+    pub device_id: [u8; 32],
+    pub replay: u64,
+    /// Target host clock at execution, ms since the Unix epoch
+    pub timestamp_ms: u64,
+}
+
+/* ───────────── TARGET ───────────── */
+
+/// Seal and persist the ack (best effort, errors are swallowed).
+pub(crate) fn write_kill_ack(
+    root_key: &GuardedKey32,
+    registry: &DeviceRegistry,
+    replay: ReplayToken,
+    now_ms: u64,
+) {
+    let Some(key) = ack_key(root_key, registry.device_fingerprint()) else {
+        return;
+    };
+
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let Some(blob) = seal_ack(
+        &key,
+        &nonce,
+        registry.device_fingerprint(),
+        &registry.device_id(),
+        replay.value(),
+        now_ms,
+    ) else {
+        return;
+    };
+
+    let _ = EncryptedLog::publish_kill_ack(&blob);
+}
+
+/* ───────────── ADMIN ───────────── */
+
+/// Verify an ack collected from the target described by `registry`.
+///
+/// `None` on any failure. The caller MUST still compare
+/// `device_id` / `replay` with the kill it issued.
+#[cfg(feature = "kill-admin")]
+pub fn verify_kill_ack(
+    root_key: &GuardedKey32,
+    registry: &DeviceRegistry,
+    blob: &[u8],
+) -> Option<KillAck> {
+    let key = ack_key(root_key, registry.device_fingerprint())?;
+    open_ack(&key, registry.device_fingerprint(), blob)
+}
+
+/* ───────────── INTERNAL ───────────── */
+
+fn ack_key(root_key: &GuardedKey32, fingerprint: u64) -> Option<GuardedKey32> {
+    let mut kill_key = GuardedKey32::zeroed();
+    derive_key(root_key, Purpose::Recovery, fingerprint, &mut kill_key).ok()?;
+
+    let mut key = GuardedKey32::zeroed();
+    derive_key(&kill_key, Purpose::Recovery, KILL_ACK_CONTEXT, &mut key).ok()?;
+    Some(key)
+}
+
+fn ack_aad(fingerprint: u64) -> [u8; 24] {
+    let mut aad = [0u8; 24];
+    aad[..16].copy_from_slice(ACK_AAD_LABEL);
+    aad[16..].copy_from_slice(&fingerprint.to_be_bytes());
+    aad
+}
+
+fn seal_ack(
+    key: &GuardedKey32,
+    nonce: &[u8; NONCE_LEN],
+    fingerprint: u64,
+    device_id: &[u8; 32],
+    replay: u64,
+    timestamp_ms: u64,
+) -> Option<Vec<u8>> {
+    let mut plaintext = [0u8; ACK_PLAINTEXT_LEN];
+    plaintext[..32].copy_from_slice(device_id);
+    plaintext[32..40].copy_from_slice(&replay.to_be_bytes());
+    plaintext[40..].copy_from_slice(&timestamp_ms.to_be_bytes());
+
+    let mut blob = vec![0u8; KILL_ACK_LEN];
+    blob[..NONCE_LEN].copy_from_slice(nonce);
+
+    aes_gcm::seal(key, nonce, &plaintext, &ack_aad(fingerprint), &mut blob[NONCE_LEN..]).ok()?;
+    Some(blob)
+}
+
+#[cfg(any(test, feature = "kill-admin"))]
+fn open_ack(key: &GuardedKey32, fingerprint: u64, blob: &[u8]) -> Option<KillAck> {
+    if blob.len() != KILL_ACK_LEN {
+        return None;
+    }
+
+    let nonce: &[u8; NONCE_LEN] = blob[..NONCE_LEN].try_into().ok()?;
+
+    let mut plaintext = [0u8; ACK_PLAINTEXT_LEN];
+    if !aes_gcm::open(key, nonce, &blob[NONCE_LEN..], &ack_aad(fingerprint), &mut plaintext) {
+        return None;
+    }
+
+    let mut device_id = [0u8; 32];
+    device_id.copy_from_slice(&plaintext[..32]);
+
+    Some(KillAck {
+        device_id,
+        replay: u64::from_be_bytes(plaintext[32..40].try_into().ok()?),
+        timestamp_ms: u64::from_be_bytes(plaintext[40..].try_into().ok()?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ack_round_trips_and_is_device_bound() -> Result<(), ()> {
+        let root = GuardedKey32::init_with(|k| k.fill(0x42));
+        let key = ack_key(&root, 0xF00D).ok_or(())?;
+
+        let blob = seal_ack(&key, &[7u8; NONCE_LEN], 0xF00D, &[0xD1; 32], 9, 1_700_000_000_000)
+            .ok_or(())?;
+        assert_eq!(blob.len(), KILL_ACK_LEN);
+
+        let ack = open_ack(&key, 0xF00D, &blob).ok_or(())?;
+        assert_eq!(ack.device_id, [0xD1; 32]);
+        assert_eq!(ack.replay, 9);
+        assert_eq!(ack.timestamp_ms, 1_700_000_000_000);
+
+        // Other device / tampered blob => rejected
+        assert!(open_ack(&key, 0xBEEF, &blob).is_none());
+        let other = ack_key(&root, 0xBEEF).ok_or(())?;
+        assert!(open_ack(&other, 0xF00D, &blob).is_none());
+
+        let mut bad = blob.clone();
+        bad[NONCE_LEN] ^= 0x01;
+        assert!(open_ack(&key, 0xF00D, &bad).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn ack_is_written_after_the_fuse() {
+        // Fresh process: the kill fuse is process-wide
+        assert!(crate::test_support::isolated(
+            "kill::ack::tests::ack_is_written_after_the_fuse",
+            || {
+                crate::logging::encrypted::init_test_log_root();
+
+                let registry = DeviceRegistry::load_or_init(b"ack-test-device");
+                assert!(registry.is_ok());
+                let Ok(registry) = registry else { return };
+
+                let replay = ReplayToken::from_bytes(&9u64.to_be_bytes());
+                assert!(replay.is_some());
+                let Some(replay) = replay else { return };
+
+                let root = GuardedKey32::init_with(|k| k.fill(0x42));
+
+                crate::keystore::master::escalate(crate::keystore::master::KillCause::VerifiedKill);
+                write_kill_ack(&root, &registry, replay, 1_700_000_000_000);
+
+                let path = std::env::temp_dir()
+                    .join(format!("rcxcore-test-{}", std::process::id()))
+                    .join("kill_ack.bin");
+                let blob = std::fs::read(path).unwrap_or_default();
+
+                let key = ack_key(&root, registry.device_fingerprint());
+                let ack = key.and_then(|k| open_ack(&k, registry.device_fingerprint(), &blob));
+                assert!(ack.is_some_and(|a| a.replay == 9 && a.device_id == registry.device_id()));
+            },
+        ));
+    }
+}
@@ -0,0 +1,137 @@
+//! Authenticated kill-history export (compliance audit).
+//!
+//! TRUST LEVEL: Secure Core
+//!
+//! FORMAT (v1, all integers big-endian):
+//! ```text
+//! magic "RCXA" (4) || version (1) || killed (1)
+//! || n_records (4) || { len (4) || record }*
+//! || n_tokens (4)  || { token (8) }*
+//! || mac (32)
+//! ```
+//! `mac = HMAC-SHA256(audit_key, AUDIT_LABEL || everything before mac)`
+//!
+//! FORMAL INVARIANTS (ENFORCED):
+//! - Exported data is NON-SECRET (kill markers, replay counters)
+//! - Audit key is purpose-bound (`Purpose::Recovery`, `AUDIT_CONTEXT`)
+//! - Verification is constant-time
+//! - Read-only: export never writes any log
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::memory::{ct_eq, GuardedKey32};
+
+/// Export magic.
+const AUDIT_MAGIC: &[u8; 4] = b"RCXA";
+
+/// Current export format version.
+pub const KILL_AUDIT_VERSION: u8 = 1;
+
+/// Audit key derivation context (`derive_key`, `Purpose::Recovery`).
+///
+/// ⚠️ MUST NEVER CHANGE.
+pub const AUDIT_CONTEXT: u64 = 0x4b49_4c4c_4155_0001; // "KILLAU" v1
+
+/// MAC domain separation label.
+///
+/// ⚠️ MUST NEVER CHANGE.
+const AUDIT_LABEL: &[u8] = b"rcxcloud:kill:audit:v1";
+
+const MAC_LEN: usize = 32;
+
+/// Serialize the unauthenticated export body.
+pub(crate) fn encode_body(
+    killed: bool,
+    records: &[Vec<u8>],
+    tokens: &[u64],
+) -> Result<Vec<u8>, ()> {
+    let n_records = u32::try_from(records.len()).map_err(|_| ())?;
+    let n_tokens = u32::try_from(tokens.len()).map_err(|_| ())?;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(AUDIT_MAGIC);
+    out.push(KILL_AUDIT_VERSION);
+    out.push(killed as u8);
+
+    out.extend_from_slice(&n_records.to_be_bytes());
+    for r in records {
+        let len = u32::try_from(r.len()).map_err(|_| ())?;
+        out.extend_from_slice(&len.to_be_bytes());
+        out.extend_from_slice(r);
+    }
+
+    out.extend_from_slice(&n_tokens.to_be_bytes());
+    for t in tokens {
+        out.extend_from_slice(&t.to_be_bytes());
+    }
+
+    Ok(out)
+}
+
+/// MAC over an export body.
+pub(crate) fn mac_body(key: &GuardedKey32, body: &[u8]) -> Result<[u8; MAC_LEN], ()> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.borrow()).map_err(|_| ())?;
+    mac.update(AUDIT_LABEL);
+    mac.update(body);
+
+    let mut out = [0u8; MAC_LEN];
+    out.copy_from_slice(&mac.finalize().into_bytes());
+    Ok(out)
+}
+
+/// Verify an exported blob against the audit key (auditor side).
+///
+/// SECURITY:
+/// - Constant-time tag comparison
+/// - Unknown magic / version => false
+pub fn verify_kill_audit(audit_key: &GuardedKey32, blob: &[u8]) -> bool {
+    if blob.len() < AUDIT_MAGIC.len() + 2 + MAC_LEN {
+        return false;
+    }
+
+    let (body, tag) = blob.split_at(blob.len() - MAC_LEN);
+
+    if &body[..4] != AUDIT_MAGIC || body[4] != KILL_AUDIT_VERSION {
+        return false;
+    }
+
+    match mac_body(audit_key, body) {
+        Ok(expected) => ct_eq(&expected, tag),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(key: &GuardedKey32) -> Vec<u8> {
+        let mut blob = encode_body(true, &[b"KILLED".to_vec()], &[7, 9]).unwrap_or_default();
+        let tag = mac_body(key, &blob).unwrap_or([0u8; MAC_LEN]);
+        blob.extend_from_slice(&tag);
+        blob
+    }
+
+    #[test]
+    fn export_with_kill_record_verifies() {
+        let key = GuardedKey32::init_with(|k| k.fill(0x3C));
+        let blob = export(&key);
+
+        assert!(verify_kill_audit(&key, &blob));
+        assert!(blob.windows(6).any(|w| w == b"KILLED"));
+    }
+
+    #[test]
+    fn forged_export_is_rejected() {
+        let key = GuardedKey32::init_with(|k| k.fill(0x3C));
+        let mut blob = export(&key);
+
+        // Flip the `killed` byte
+        blob[5] ^= 0x01;
+        assert!(!verify_kill_audit(&key, &blob));
+
+        let other = GuardedKey32::init_with(|k| k.fill(0x3D));
+        assert!(!verify_kill_audit(&other, &export(&key)));
+    }
+}
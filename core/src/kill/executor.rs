@@ -1,12 +1,12 @@
 //! Terminal kill executor (Secure Core).
 
-use core::sync::atomic::Ordering;
-
-use crate::keystore::master::GLOBAL_KILLED;
+use crate::keystore::master::{escalate, KillCause};
 use crate::keystore::KeyStore;
 use crate::device::registry::DeviceRegistry;
 use crate::plugins;
+use crate::kill::ack::write_kill_ack;
 use crate::kill::replay::{ReplayToken, check_and_commit};
+use crate::memory::GuardedKey32;
 
 #[derive(Debug)]
 pub enum KillError {
@@ -17,10 +17,15 @@ pub enum KillError {
 ///
 /// Returns Err if rejected (replay).
 /// Never returns on success.
+///
+/// `root_key` is the key the kill blob was verified under; it seals
+/// the acknowledgment (`kill::ack`). `now_ms` is the host clock.
 pub fn execute_kill(
     keystore: &KeyStore,
     registry: &DeviceRegistry,
+    root_key: &GuardedKey32,
     replay: ReplayToken,
+    now_ms: u64,
 ) -> Result<!, KillError> {
     // 1️⃣ Replay protection (FAIL CLOSED)
     if !check_and_commit(replay) {
@@ -28,7 +33,7 @@ pub fn execute_kill(
     }
 
     // 2️⃣ GLOBAL KILL FUSE — FIRST (memory barrier)
-    GLOBAL_KILLED.store(true, Ordering::SeqCst);
+    escalate(KillCause::VerifiedKill);
 
     // 3️⃣ Disable all plugins immediately
     plugins::disable_all();
@@ -37,6 +42,9 @@ pub fn execute_kill(
     // This permanently transitions keystore into KILLED state.
     keystore.wipe();
 
+    // 4️⃣½ Acknowledgment (best effort; nothing above waits on it)
+    write_kill_ack(root_key, registry, replay, now_ms);
+
     // 5️⃣ Persist device kill marker (best effort)
     let _ = registry.mark_this_device_killed();
 
@@ -44,4 +52,18 @@ pub fn execute_kill(
     loop {
         core::hint::spin_loop();
     }
-}
\ No newline at end of file
+}
+
+/// Local (policy-initiated) kill: no blob, no ack, no return.
+///
+/// Same fuse as a verified kill (`escalate` also wipes host-held
+/// sensitive buffers); `reason` is for the caller's audit trail only.
+pub(crate) fn execute(reason: &str) -> ! {
+    let _ = reason;
+
+    escalate(KillCause::LocalFail);
+
+    loop {
+        core::hint::spin_loop();
+    }
+}
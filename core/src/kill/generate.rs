@@ -8,6 +8,7 @@ use crate::crypto::{
     derive::{derive_key, Purpose},
 };
 use crate::device::registry::DeviceRegistry;
+use crate::kill::strategy::{KillReason, KILL_VERSION_V2, PLAINTEXT_LEN_V2};
 use crate::kill::build_kill_aad;
 use crate::memory::{GuardedKey32, Secret};
 
@@ -17,15 +18,16 @@ use rand_core::{OsRng, RngCore};
 
 pub const NONCE_LEN: usize = 12;
 pub const TAG_LEN: usize = 16;
-pub const PLAINTEXT_LEN: usize = 1 + 32 + 8;
-
-const KILL_VERSION_V1: u8 = 1;
+pub const PLAINTEXT_LEN: usize = PLAINTEXT_LEN_V2;
 
 /* ───────────── TYPES ───────────── */
 
 pub struct KillRequest {
     pub target_device_id: [u8; 32],
     pub replay: u64,
+    pub reason: KillReason,
+    /// Issuance time, ms since the Unix epoch
+    pub issued_at: u64,
 }
 
 /* ───────────── API ───────────── */
@@ -47,9 +49,11 @@ pub fn generate_kill_blob(
 
     let plaintext = Secret::<Vec<u8>>::init_with(|buf| {
         *buf = vec![0u8; PLAINTEXT_LEN];
-        buf[0] = KILL_VERSION_V1;
+        buf[0] = KILL_VERSION_V2;
         buf[1..33].copy_from_slice(&req.target_device_id);
         buf[33..41].copy_from_slice(&req.replay.to_be_bytes());
+        buf[41] = req.reason.code();
+        buf[42..50].copy_from_slice(&req.issued_at.to_be_bytes());
     });
 
     let mut nonce = [0u8; NONCE_LEN];
@@ -17,19 +17,30 @@ mod strategy;
 mod replay;
 mod executor;
 mod protocol;
+mod ack;
+pub mod audit;
 
 /* ───────────── CURATED EXPORTS ───────────── */
 
 // Kill protocol (shared AAD definition)
-pub(crate) use protocol::build_kill_aad;
+pub(crate) use protocol::{build_kill_aad, kill_aad_for};
 
 // Target-side API
-pub use strategy::{verify_kill_blob, KillDecision};
+pub use strategy::{verify_kill_blob, verify_kill_blob_with, KillDecision, KillReason};
 pub use executor::{execute_kill, KillError};
+pub(crate) use executor::execute;
+pub use ack::KILL_ACK_LEN;
+
+// Key-free parser entry points (fuzz harness only)
+#[cfg(feature = "fuzzing")]
+pub(crate) use strategy::{parse_kill_plaintext, split_blob};
 
 // Admin-only generator (MUST NOT ship to targets)
 #[cfg(feature = "kill-admin")]
 mod generate;
 
 #[cfg(feature = "kill-admin")]
-pub(crate) use generate::generate_kill_blob;
\ No newline at end of file
+pub(crate) use generate::generate_kill_blob;
+
+#[cfg(feature = "kill-admin")]
+pub use ack::{verify_kill_ack, KillAck};
\ No newline at end of file
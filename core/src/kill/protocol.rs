@@ -17,15 +17,19 @@ use crate::device::registry::DeviceRegistry;
 /// - NOT encrypted (AAD)
 #[inline(always)]
 pub fn build_kill_aad(registry: &DeviceRegistry) -> [u8; 24] {
+    kill_aad_for(registry.device_fingerprint())
+}
+
+/// `build_kill_aad` for an explicit fingerprint (no registry I/O).
+#[inline(always)]
+pub fn kill_aad_for(fingerprint: u64) -> [u8; 24] {
     let mut aad = [0u8; 24];
 
     // Protocol label (16 bytes, fixed)
     aad[..16].copy_from_slice(b"rcxcloud-kill-v1");
 
     // Device fingerprint (u64, BE)
-    aad[16..24].copy_from_slice(
-        &registry.device_fingerprint().to_be_bytes(),
-    );
+    aad[16..24].copy_from_slice(&fingerprint.to_be_bytes());
 
     aad
 }
\ No newline at end of file
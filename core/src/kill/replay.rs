@@ -2,7 +2,7 @@
 //!
 //! SECURITY:
 //! - Persistent
-//! - Monotonic
+//! - Monotonic (optionally windowed, see `REPLAY_WINDOW`)
 //! - Append-only
 //! - Fail-closed
 
@@ -25,29 +25,124 @@ impl ReplayToken {
     }
 }
 
+/// Out-of-order acceptance window below the highest token seen.
+///
+/// `0` = strict: every token MUST exceed all previous ones.
+/// `W > 0` also accepts unconsumed tokens in `[highest - W, highest)`
+/// (batched blobs delivered out of order). Exact replays are always
+/// rejected.
+pub const REPLAY_WINDOW: u64 = 0;
+
 /// Check and persist replay token.
 ///
 /// Semantics:
-/// - Reads last committed token (if any)
-/// - Rejects non-increasing values
+/// - Reads every committed token (the consumed set), including
+///   legacy tokens left in `device_kill.log` by older builds
+/// - Rejects consumed values, and values at or below the window
 /// - Appends new token (never overwrites)
 ///
 /// FAIL-CLOSED on any error.
 pub fn check_and_commit(token: ReplayToken) -> bool {
-    let mut log = match EncryptedLog::open_kill_log() {
+    let mut log = match EncryptedLog::open_replay_log() {
         Ok(l) => l,
         Err(_) => return false,
     };
 
-    let last = match log.read_last_u64() {
-        Ok(Some(v)) => v,
-        Ok(None) => 0,
+    let mut consumed = match log.read_all_u64() {
+        Ok(v) => v,
         Err(_) => return false,
     };
 
-    if token.value() <= last {
+    match legacy_tokens() {
+        Ok(legacy) => consumed.extend(legacy),
+        Err(()) => return false,
+    }
+
+    if !acceptable(&consumed, token.value(), REPLAY_WINDOW) {
         return false;
     }
 
     log.append_u64(token.value()).is_ok()
-}
\ No newline at end of file
+}
+
+/// Tokens older builds committed as raw u64s into `device_kill.log`.
+///
+/// A log in the current record format (hash chain verifies) holds
+/// kill markers only => none. Anything else MUST parse as raw u64s;
+/// unreadable => Err (fail-closed: a token may hide in it).
+fn legacy_tokens() -> Result<Vec<u64>, ()> {
+    let Some(mut log) = EncryptedLog::open_device_kill_log_read_only()? else {
+        return Ok(Vec::new());
+    };
+
+    if log.verify_chain().and_then(|()| log.read_records()).is_ok() {
+        return Ok(Vec::new());
+    }
+
+    log.read_all_u64()
+}
+
+/// Whether `token` may be consumed given the committed tokens.
+fn acceptable(consumed: &[u64], token: u64, window: u64) -> bool {
+    let highest = consumed.iter().copied().max().unwrap_or(0);
+
+    if token > highest {
+        return true;
+    }
+
+    // Zero is never a valid token; below the window => too old
+    if token == 0 || token < highest.saturating_sub(window) {
+        return false;
+    }
+
+    window > 0 && !consumed.contains(&token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strict_window_requires_increasing_tokens() {
+        assert!(acceptable(&[], 1, 0));
+        assert!(!acceptable(&[], 0, 0));
+        assert!(acceptable(&[3, 5], 6, 0));
+        assert!(!acceptable(&[3, 5], 5, 0));
+        assert!(!acceptable(&[3, 5], 4, 0));
+    }
+
+    #[test]
+    fn legacy_kill_log_tokens_are_consumed() {
+        // Fresh process: the log root and its files are process-wide
+        assert!(crate::test_support::isolated(
+            "kill::replay::tests::legacy_kill_log_tokens_are_consumed",
+            || {
+                crate::logging::encrypted::init_test_log_root();
+
+                // Older builds: raw u64 tokens in the kill log
+                let legacy = EncryptedLog::open_device_kill_log().and_then(|mut l| l.append_u64(7));
+                assert!(legacy.is_ok());
+
+                let token = |v: u64| ReplayToken::from_bytes(&v.to_be_bytes());
+                assert!(token(7).is_some_and(|t| !check_and_commit(t)));
+                assert!(token(5).is_some_and(|t| !check_and_commit(t)));
+                assert!(token(8).is_some_and(check_and_commit));
+                assert!(token(8).is_some_and(|t| !check_and_commit(t)));
+            },
+        ));
+    }
+
+    #[test]
+    fn window_accepts_unconsumed_out_of_order_tokens() {
+        let consumed = [10, 12];
+
+        assert!(acceptable(&consumed, 11, 4));
+        assert!(acceptable(&consumed, 8, 4));
+        assert!(acceptable(&consumed, 13, 4));
+
+        // Exact replays and tokens below the window
+        assert!(!acceptable(&consumed, 10, 4));
+        assert!(!acceptable(&consumed, 12, 4));
+        assert!(!acceptable(&consumed, 7, 4));
+    }
+}
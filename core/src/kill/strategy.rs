@@ -9,18 +9,21 @@
 //! - Fail-closed on all errors
 //! - No stack-resident secrets
 //! - Constant-time device binding
+//! - Revoked devices never authorize a kill (constant-time scan)
+//! - No size oracle: every blob runs the same fixed-size AEAD +
+//!   parse path; all failures meet in ONE constant-time decision
 
 #![deny(clippy::derive_debug)]
 
-use subtle::ConstantTimeEq;
-
 use crate::crypto::{
     aes_gcm,
     derive::{derive_key, Purpose},
 };
 use crate::device::registry::DeviceRegistry;
-use crate::kill::{build_kill_aad, replay::ReplayToken};
-use crate::memory::{wipe_vec, GuardedKey32, Secret};
+use crate::kill::{kill_aad_for, replay::ReplayToken};
+use crate::memory::{ct_eq, GuardedKey32, ScratchGuard, ScratchPool};
+
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater};
 
 /* ───────────── CONSTANTS ───────────── */
 
@@ -30,18 +33,70 @@ const NONCE_LEN: usize = 12;
 /// AES-GCM authentication tag length
 const TAG_LEN: usize = 16;
 
-/// Kill protocol version
+/// Kill protocol versions
 const KILL_VERSION_V1: u8 = 1;
+pub(crate) const KILL_VERSION_V2: u8 = 2;
 
-/// Plaintext layout:
+/// V1 plaintext layout:
 /// [ version (1) | device_id (32) | replay (8) ]
-const PLAINTEXT_LEN: usize = 1 + 32 + 8;
+const PLAINTEXT_LEN_V1: usize = 1 + 32 + 8;
+
+/// V2 plaintext layout:
+/// [ version (1) | device_id (32) | replay (8) | reason (1) | issued_at (8, BE) ]
+pub(crate) const PLAINTEXT_LEN_V2: usize = PLAINTEXT_LEN_V1 + 1 + 8;
+
+/// Exact blob lengths: [ nonce | plaintext | tag ]
+const BLOB_LEN_V1: usize = NONCE_LEN + PLAINTEXT_LEN_V1 + TAG_LEN;
+const BLOB_LEN_V2: usize = NONCE_LEN + PLAINTEXT_LEN_V2 + TAG_LEN;
+
+/// Fixed working size: every blob is zero-padded / truncated to it.
+const PADDED_BLOB_LEN: usize = BLOB_LEN_V2;
+
+/// Plaintext scratch for kill blobs (wiped + pooled on drop).
+static KILL_SCRATCH: ScratchPool = ScratchPool::new(2, 2 * PLAINTEXT_LEN_V2);
 
 /* ───────────── PUBLIC TYPES ───────────── */
 
+/// Operator-declared kill reason (V2 blobs).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KillReason {
+    Stolen,
+    Compromised,
+    Decommission,
+    Admin,
+}
+
+impl KillReason {
+    /// Wire code (MUST NEVER CHANGE).
+    pub fn code(self) -> u8 {
+        match self {
+            KillReason::Stolen => 1,
+            KillReason::Compromised => 2,
+            KillReason::Decommission => 3,
+            KillReason::Admin => 4,
+        }
+    }
+
+    /// Unknown code => `None` (blob rejected).
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(KillReason::Stolen),
+            2 => Some(KillReason::Compromised),
+            3 => Some(KillReason::Decommission),
+            4 => Some(KillReason::Admin),
+            _ => None,
+        }
+    }
+}
+
 /// Authenticated kill decision (NON-SECRET).
+///
+/// `reason` / `issued_at` are `None` for V1 blobs.
 pub struct KillDecision {
     pub replay: ReplayToken,
+    pub reason: Option<KillReason>,
+    /// Issuer clock, ms since the Unix epoch (NOT checked against ours)
+    pub issued_at: Option<u64>,
 }
 
 /* ───────────── ENTRY POINT ───────────── */
@@ -50,122 +105,405 @@ pub struct KillDecision {
 ///
 /// Returns `Some(KillDecision)` iff:
 /// - AEAD authentication succeeds
-/// - Protocol version matches
+/// - Protocol version is known (V1, V2) and matches the length
 /// - Device binding matches (constant-time)
+/// - Bound device is NOT revoked (constant-time, fail-closed)
 /// - Replay token parses correctly
 ///
 /// FAIL-CLOSED on all errors.
+///
+/// SIZE-ORACLE RESISTANCE:
+/// Too short, wrong key and wrong payload length are
+/// indistinguishable: the blob is padded to a fixed size, the AEAD
+/// and payload checks always run, and ONE constant-time accept /
+/// reject is taken at the end.
 pub fn verify_kill_blob(
     registry: &DeviceRegistry,
     root_key: &GuardedKey32,
     blob: &[u8],
+) -> Option<KillDecision> {
+    // Loaded BEFORE verification: the decision below is in-memory only
+    let revocations = registry.revocations();
+
+    verify_bound(
+        registry.device_fingerprint(),
+        &registry.device_id(),
+        root_key,
+        blob,
+        |id| revocations.contains(id),
+    )
+}
+
+/// Verify a kill blob against an EXPLICIT identity (no registry).
+///
+/// For recovery / forensic use where the root key and the target's
+/// fingerprint + device id are known but no registry is loadable.
+/// Byte-identical to `verify_kill_blob` for matching inputs, EXCEPT
+/// that no revocation list is consulted (there is none offline).
+pub fn verify_kill_blob_with(
+    fingerprint: u64,
+    device_id: [u8; 32],
+    root_key: &GuardedKey32,
+    blob: &[u8],
+) -> Option<KillDecision> {
+    verify_bound(fingerprint, &device_id, root_key, blob, |_| false)
+}
+
+/// Shared verifier: identity and (in-memory) revocation probe
+/// are injected.
+fn verify_bound(
+    fingerprint: u64,
+    expected_id: &[u8; 32],
+    root_key: &GuardedKey32,
+    blob: &[u8],
+    is_revoked: impl FnOnce(&[u8; 32]) -> bool,
 ) -> Option<KillDecision> {
     /* ───── Derive per-device kill key ───── */
 
     let mut kill_key = GuardedKey32::zeroed();
 
-    derive_key(
-        root_key,
-        Purpose::Recovery,
-        registry.device_fingerprint(),
-        &mut kill_key,
-    )
-    .ok()?;
+    derive_key(root_key, Purpose::Recovery, fingerprint, &mut kill_key).ok()?;
 
     /* ───── Build authenticated associated data ───── */
 
-    let aad = build_kill_aad(registry);
+    let aad = kill_aad_for(fingerprint);
 
-    /* ───── Decrypt + authenticate blob ───── */
+    /* ───── Decrypt + authenticate blob (fixed size) ───── */
 
-    let plaintext = decrypt_blob(&kill_key, blob, &aad)?;
+    let class = classify_len(blob.len());
+    let (plaintext, authentic) = decrypt_blob(&kill_key, blob, &aad, &class);
 
-    /* ───── Parse payload ───── */
+    /* ───── Payload shape (constant-time) ───── */
 
-    let parsed = parse_payload(plaintext.borrow())?;
+    let well_formed = payload_ok(&plaintext, &class);
 
     /* ───── Constant-time device binding ───── */
 
-    if parsed
-        .device_id
-        .ct_eq(&registry.device_id())
-        .into()
-        == false
-    {
+    let mut device_id = [0u8; 32];
+    device_id.copy_from_slice(&plaintext[1..33]);
+
+    let bound = Choice::from(u8::from(ct_eq(&device_id, expected_id)));
+
+    /* ───── Revoked issuer binding (always scanned) ───── */
+
+    let revoked = Choice::from(u8::from(is_revoked(&device_id)));
+
+    /* ───── Single decision ───── */
+
+    let accept = class.known() & authentic & well_formed & bound & !revoked;
+    if !bool::from(accept) {
         return None;
     }
 
+    let parsed = parse_kill_plaintext(&plaintext[..class.plaintext_len()])?;
+
     Some(KillDecision {
         replay: parsed.replay,
+        reason: parsed.reason,
+        issued_at: parsed.issued_at,
     })
 }
 
 /* ───────────── INTERNAL TYPES ───────────── */
 
-struct ParsedKill {
-    device_id: [u8; 32],
-    replay: ReplayToken,
+pub(crate) struct ParsedKill {
+    pub(crate) device_id: [u8; 32],
+    pub(crate) replay: ReplayToken,
+    pub(crate) reason: Option<KillReason>,
+    pub(crate) issued_at: Option<u64>,
+}
+
+/// Constant-time classification of a blob length.
+struct LengthClass {
+    is_v1: Choice,
+    is_v2: Choice,
+}
+
+impl LengthClass {
+    /// Length matches SOME known version.
+    fn known(&self) -> Choice {
+        self.is_v1 | self.is_v2
+    }
+
+    /// Plaintext length the AEAD runs over (V2 unless exactly V1).
+    fn plaintext_len(&self) -> usize {
+        u64::conditional_select(
+            &(PLAINTEXT_LEN_V2 as u64),
+            &(PLAINTEXT_LEN_V1 as u64),
+            self.is_v1,
+        ) as usize
+    }
 }
 
 /* ───────────── INTERNAL HELPERS ───────────── */
 
-/// Decrypt and authenticate kill blob.
+fn classify_len(len: usize) -> LengthClass {
+    let len = len as u64;
+    LengthClass {
+        is_v1: len.ct_eq(&(BLOB_LEN_V1 as u64)),
+        is_v2: len.ct_eq(&(BLOB_LEN_V2 as u64)),
+    }
+}
+
+/// Split a kill blob into nonce and sealed body (NO crypto).
 ///
 /// Expected format:
 /// [ nonce (12) | ciphertext | tag (16) ]
-fn decrypt_blob(
-    key: &GuardedKey32,
-    blob: &[u8],
-    aad: &[u8],
-) -> Option<Secret<Vec<u8>>> {
+///
+/// Pure and key-free (fuzz entry point); too short => `None`.
+/// Verification itself never branches on it (see `decrypt_blob`).
+#[cfg(any(test, feature = "fuzzing"))]
+pub(crate) fn split_blob(blob: &[u8]) -> Option<(&[u8; NONCE_LEN], &[u8])> {
     if blob.len() < NONCE_LEN + TAG_LEN {
         return None;
     }
 
-    let nonce: &[u8; NONCE_LEN] = blob[..NONCE_LEN].try_into().ok()?;
-    let ciphertext = &blob[NONCE_LEN..];
-
-    let mut plaintext = vec![0u8; ciphertext.len() - TAG_LEN];
+    let (nonce, sealed) = blob.split_at(NONCE_LEN);
+    Some((nonce.try_into().ok()?, sealed))
+}
 
-    let ok = aes_gcm::open(
+/// Decrypt and authenticate a kill blob on a FIXED-SIZE buffer.
+///
+/// The blob is zero-padded / truncated to `PADDED_BLOB_LEN` and the
+/// AEAD always runs; a malformed length simply fails the tag.
+/// Returns the `PLAINTEXT_LEN_V2` scratch (zero on failure) and the
+/// authentication result, never an early exit.
+fn decrypt_blob(
+    key: &GuardedKey32,
+    blob: &[u8],
+    aad: &[u8],
+    class: &LengthClass,
+) -> (ScratchGuard<'static>, Choice) {
+    // Ciphertext only (public): a stack copy is fine
+    let mut padded = [0u8; PADDED_BLOB_LEN];
+    let n = blob.len().min(PADDED_BLOB_LEN);
+    padded[..n].copy_from_slice(&blob[..n]);
+
+    let pt_len = class.plaintext_len();
+    let (nonce, body) = padded.split_at(NONCE_LEN);
+    let (ciphertext, rest) = body.split_at(pt_len);
+
+    let mut nonce_arr = [0u8; NONCE_LEN];
+    nonce_arr.copy_from_slice(nonce);
+    let mut tag = [0u8; TAG_LEN];
+    tag.copy_from_slice(&rest[..TAG_LEN]);
+
+    // Wiped on every exit path by the guard's Drop
+    let mut plaintext = KILL_SCRATCH.acquire(PLAINTEXT_LEN_V2);
+
+    let ok = aes_gcm::open_detached(
         key,
-        nonce,
+        &nonce_arr,
         ciphertext,
+        &tag,
         aad,
-        &mut plaintext,
+        &mut plaintext[..pt_len],
     );
 
-    if !ok {
-        wipe_vec(&mut plaintext);
-        return None;
-    }
+    (plaintext, Choice::from(u8::from(ok)))
+}
 
-    if plaintext.len() != PLAINTEXT_LEN {
-        wipe_vec(&mut plaintext);
-        return None;
-    }
+/// Constant-time payload shape check on the padded plaintext.
+///
+/// Version MUST match the length class; V2 reason code MUST be known.
+fn payload_ok(buf: &[u8], class: &LengthClass) -> Choice {
+    let version = buf[0];
+    let code = buf[41];
 
-    if plaintext[0] != KILL_VERSION_V1 {
-        wipe_vec(&mut plaintext);
-        return None;
-    }
+    let v1 = class.is_v1 & version.ct_eq(&KILL_VERSION_V1);
+    let reason_known = code.ct_gt(&0) & !code.ct_gt(&4);
+    let v2 = class.is_v2 & version.ct_eq(&KILL_VERSION_V2) & reason_known;
 
-    Some(Secret::new(plaintext))
+    v1 | v2
 }
 
-/// Parse authenticated kill payload.
+/// Parse a kill payload.
+///
+/// Pure and key-free (fuzz entry point): safe on ARBITRARY bytes.
+/// Authenticity is the caller's job (`verify_kill_blob` only calls
+/// this after AEAD authentication succeeded).
 ///
-/// ASSUMES:
-/// - AEAD authentication already succeeded
-/// - Length already verified
-fn parse_payload(buf: &[u8]) -> Option<ParsedKill> {
+/// SECURITY:
+/// - Version byte selects the (exact) layout length
+/// - Unknown version / wrong length / unknown reason => `None`
+pub(crate) fn parse_kill_plaintext(buf: &[u8]) -> Option<ParsedKill> {
+    let known = match buf.first() {
+        Some(&KILL_VERSION_V1) => buf.len() == PLAINTEXT_LEN_V1,
+        Some(&KILL_VERSION_V2) => buf.len() == PLAINTEXT_LEN_V2,
+        _ => false,
+    };
+
+    if !known {
+        return None;
+    }
+
     let mut device_id = [0u8; 32];
     device_id.copy_from_slice(&buf[1..33]);
 
     let replay = ReplayToken::from_bytes(&buf[33..41])?;
 
+    let (reason, issued_at) = if buf[0] == KILL_VERSION_V2 {
+        let reason = KillReason::from_code(buf[41])?;
+        let issued_at = u64::from_be_bytes(buf[42..50].try_into().ok()?);
+        (Some(reason), Some(issued_at))
+    } else {
+        (None, None)
+    };
+
     Some(ParsedKill {
         device_id,
         replay,
+        reason,
+        issued_at,
     })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(version: u8, len: usize) -> Vec<u8> {
+        let mut buf = vec![0u8; len];
+        buf[0] = version;
+        buf[1..33].fill(0xD1);
+        buf[33..41].copy_from_slice(&9u64.to_be_bytes());
+        buf
+    }
+
+    #[test]
+    fn v1_payload_has_no_reason() {
+        let parsed = parse_kill_plaintext(&payload(KILL_VERSION_V1, PLAINTEXT_LEN_V1));
+        assert!(matches!(
+            parsed,
+            Some(ParsedKill { reason: None, issued_at: None, .. })
+        ));
+    }
+
+    #[test]
+    fn v2_payload_carries_reason_and_time() -> Result<(), ()> {
+        let mut buf = payload(KILL_VERSION_V2, PLAINTEXT_LEN_V2);
+        buf[41] = KillReason::Stolen.code();
+        buf[42..50].copy_from_slice(&1_700_000_000_000u64.to_be_bytes());
+
+        let parsed = parse_kill_plaintext(&buf).ok_or(())?;
+        assert!(parsed.reason == Some(KillReason::Stolen));
+        assert_eq!(parsed.issued_at, Some(1_700_000_000_000));
+        assert_eq!(parsed.replay.value(), 9);
+
+        // Unknown reason code => rejected
+        buf[41] = 0xEE;
+        assert!(parse_kill_plaintext(&buf).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_lengths_never_parse() {
+        for version in [0, KILL_VERSION_V1, KILL_VERSION_V2, 0xFF] {
+            for len in 0..=PLAINTEXT_LEN_V2 + 1 {
+                let mut buf = vec![0u8; len];
+                if let Some(b) = buf.first_mut() {
+                    *b = version;
+                }
+
+                let exact = (version == KILL_VERSION_V1 && len == PLAINTEXT_LEN_V1)
+                    || (version == KILL_VERSION_V2 && len == PLAINTEXT_LEN_V2);
+                if !exact {
+                    assert!(parse_kill_plaintext(&buf).is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn split_blob_requires_nonce_and_tag() {
+        assert!(split_blob(&[0u8; NONCE_LEN + TAG_LEN - 1]).is_none());
+
+        let blob = [7u8; NONCE_LEN + TAG_LEN + 3];
+        assert!(matches!(
+            split_blob(&blob),
+            Some((nonce, sealed)) if nonce == &[7u8; NONCE_LEN] && sealed.len() == TAG_LEN + 3
+        ));
+    }
+
+    #[test]
+    fn length_class_and_payload_check_agree_with_parser() {
+        for len in 0..=BLOB_LEN_V2 + 1 {
+            let class = classify_len(len);
+            let known = len == BLOB_LEN_V1 || len == BLOB_LEN_V2;
+            assert_eq!(bool::from(class.known()), known);
+        }
+        assert_eq!(classify_len(BLOB_LEN_V1).plaintext_len(), PLAINTEXT_LEN_V1);
+        // Anything else runs the full V2-sized path
+        assert_eq!(classify_len(3).plaintext_len(), PLAINTEXT_LEN_V2);
+
+        let v1 = classify_len(BLOB_LEN_V1);
+        let v2 = classify_len(BLOB_LEN_V2);
+
+        let mut buf = payload(KILL_VERSION_V2, PLAINTEXT_LEN_V2);
+        buf[41] = KillReason::Admin.code();
+        assert!(bool::from(payload_ok(&buf, &v2)));
+        // Version / length class mismatch
+        assert!(!bool::from(payload_ok(&buf, &v1)));
+
+        for bad in [0u8, 5, 0xFF] {
+            buf[41] = bad;
+            assert!(!bool::from(payload_ok(&buf, &v2)));
+        }
+
+        let mut buf = payload(KILL_VERSION_V1, PLAINTEXT_LEN_V2);
+        buf[41] = 0xEE; // padding, ignored for V1
+        assert!(bool::from(payload_ok(&buf, &v1)));
+        assert!(!bool::from(payload_ok(&buf, &v2)));
+    }
+
+    /// Seal a V2 kill payload for a synthetic identity.
+    fn seal_for(root: &GuardedKey32, fingerprint: u64, device_id: [u8; 32]) -> Vec<u8> {
+        let mut key = GuardedKey32::zeroed();
+        assert!(derive_key(root, Purpose::Recovery, fingerprint, &mut key).is_ok());
+
+        let mut pt = payload(KILL_VERSION_V2, PLAINTEXT_LEN_V2);
+        pt[1..33].copy_from_slice(&device_id);
+        pt[41] = KillReason::Decommission.code();
+
+        let nonce = [0x4E; NONCE_LEN];
+        let mut blob = vec![0u8; BLOB_LEN_V2];
+        blob[..NONCE_LEN].copy_from_slice(&nonce);
+        let sealed = aes_gcm::seal(&key, &nonce, &pt, &kill_aad_for(fingerprint), &mut blob[NONCE_LEN..]);
+        assert!(sealed.is_ok());
+        blob
+    }
+
+    #[test]
+    fn explicit_identity_verifies_without_registry() -> Result<(), ()> {
+        let root = GuardedKey32::from_slice_into(&[0x21; 32]).map_err(|_| ())?;
+        let (fp, id) = (0xF00D_u64, [0xD1; 32]);
+        let blob = seal_for(&root, fp, id);
+
+        let decision = verify_kill_blob_with(fp, id, &root, &blob);
+        assert!(matches!(
+            decision,
+            Some(ref d) if d.replay.value() == 9 && d.reason == Some(KillReason::Decommission)
+        ));
+
+        // Other fingerprint (key + AAD) or other device => rejected
+        assert!(verify_kill_blob_with(fp + 1, id, &root, &blob).is_none());
+        assert!(verify_kill_blob_with(fp, [0xD2; 32], &root, &blob).is_none());
+
+        // Revocation probe still gates the shared path
+        assert!(verify_bound(fp, &id, &root, &blob, |_| true).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn reason_codes_round_trip() {
+        for r in [
+            KillReason::Stolen,
+            KillReason::Compromised,
+            KillReason::Decommission,
+            KillReason::Admin,
+        ] {
+            assert!(KillReason::from_code(r.code()) == Some(r));
+        }
+        assert!(KillReason::from_code(0).is_none());
+    }
 }
\ No newline at end of file
@@ -11,6 +11,7 @@
 //!
 //! PUBLIC SURFACE RULE:
 //! - ONLY `bridge::*` is public to the outside world
+//!   (`embedded::*` replaces it without the `std` feature)
 //! - All other modules are INTERNAL and MUST NOT be re-exported
 //!
 //! ❄️ SUBJECT TO SECURE CORE API FREEZE ❄️
@@ -19,6 +20,11 @@
 // SECURITY & SAFETY LINTS (GLOBAL)
 // ─────────────────────────────────────────────
 
+// Embedded builds (`--no-default-features`): stateless crypto +
+// guarded memory only.
+// The test harness always links `std`; the library never does.
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
+
 // Unsafe code is forbidden by default.
 // Modules that REQUIRE unsafe (FFI / memory locking)
 // MUST explicitly opt-in with `#![allow(unsafe_code)]`.
@@ -36,13 +42,24 @@
 // These modules form the Secure Core trust anchor.
 // They MUST NOT be publicly re-exported.
 
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
 mod crypto;
+mod memory;
+
+// Stateful modules: filesystem logs, std sync, OS time
+#[cfg(feature = "std")]
 mod device;
+#[cfg(feature = "std")]
 mod integrity;
+#[cfg(feature = "std")]
 mod keystore;
+#[cfg(feature = "std")]
 mod kill;
+#[cfg(feature = "std")]
 mod logging;
-mod memory;
+#[cfg(feature = "std")]
 mod policy;
 
 // ─────────────────────────────────────────────
@@ -67,8 +84,39 @@ mod media;
 // The bridge module defines the ONLY stable integration surface
 // for JNI / C / WASM / Plugins.
 
+#[cfg(feature = "std")]
 pub mod bridge;
 
+// ─────────────────────────────────────────────
+// EMBEDDED SURFACE (no-std)
+// ─────────────────────────────────────────────
+//
+// HSM-like targets with an embedder-provided global allocator.
+
+#[cfg(not(feature = "std"))]
+pub mod embedded;
+
+// ─────────────────────────────────────────────
+// FUZZ HOOKS (NOT A STABLE SURFACE)
+// ─────────────────────────────────────────────
+//
+// Key-free parser entry points for the `core/fuzz` harness.
+// MUST NEVER be enabled in shipped builds.
+
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing;
+
+// ─────────────────────────────────────────────
+// TEST SUPPORT
+// ─────────────────────────────────────────────
+//
+// The kill fuse is process-wide and irreversible: tests that trip
+// it run in a child process (`test_support::isolated`).
+
+#[cfg(all(test, feature = "std"))]
+mod test_support;
+
 // ─────────────────────────────────────────────
 // COMPILATION SAFETY CHECKS
 // ─────────────────────────────────────────────
@@ -86,10 +134,16 @@ compile_error!(
     "desktop-media feature is FORBIDDEN on Android targets"
 );
 
+// The deterministic RNG backend MUST NEVER reach a release build.
+#[cfg(all(feature = "test-rng", not(debug_assertions)))]
+compile_error!(
+    "test-rng feature is FORBIDDEN in release builds"
+);
+
 // Load JNI bindings ONLY when explicitly requested.
 #[cfg(feature = "android")]
 use bridge::jni;
 
 // Load WASM bindings ONLY for wasm32 targets.
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", feature = "std"))]
 use bridge::wasm;
\ No newline at end of file
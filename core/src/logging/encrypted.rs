@@ -6,13 +6,46 @@
 //! - No `unsafe` blocks
 //! - Fail-closed
 //! - Bounded memory usage
-//! - GLOBAL_KILLED checked on ALL writes
+//! - Bounded disk usage per segment: kill / replay logs rotate
+//!   to `name.1`, `name.2`, ... and segments are NEVER deleted
+//! - GLOBAL_KILLED checked on ALL writes, except the kill
+//!   acknowledgment (`publish_kill_ack`, written after the fuse)
+//!
+//! RECORD ENCRYPTION (opt-in, keyed handles, `with_log_key`):
+//! - Record i on disk: `len (4) || nonce (12) || ciphertext || tag`
+//! - AAD: `label || log name || i` (no reordering, no splicing
+//!   between logs); the first failing record ends every read
+//! - NO production log is keyed today; their records are stored
+//!   in the clear and authenticated (or not) by their owners:
+//!   - kill marker, replay tokens, device registry: read before
+//!     unlock and after key wipe (existence is the signal)
+//!   - tombstones, index versions, nonce ledger: HMAC-tagged per
+//!     key hierarchy; records of other hierarchies (other vaults,
+//!     before a phrase change) MUST stay skippable, which a keyed
+//!     read (stop at the first failing record) cannot do
+//!   - None of them carries file plaintext or secret keys: ids,
+//!     public fingerprints, counters and MAC tags only
+//!
+//! HASH CHAIN (every `append_record` record, keyed or not):
+//! - Stored record: `body || link (32)`,
+//!   `link_i = SHA-256(link_{i-1} || body_i)`, `link_{-1} = 0^32`
+//! - The chain continues across rotated segments
+//! - The tail link is mirrored into `name.head` (atomic replace),
+//!   so dropping whole records from the end is detected too
+//! - `verify_chain` is the audit check; it is NOT authentication
+//!   (anyone with write access to both files can rebuild it)
+//! - Raw u64 logs (replay tokens) are not chained
 
+use crate::crypto::aes_gcm::{self, NONCE_LEN, TAG_LEN};
+use crate::crypto::derive::{derive_key, Purpose};
+use crate::crypto::nonce::derive_nonce_log;
+use crate::integrity::hash_sha256;
 use crate::keystore::master::GLOBAL_KILLED;
-use core::sync::atomic::Ordering;
+use crate::memory::GuardedKey32;
+use core::sync::atomic::{AtomicU64, Ordering};
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Seek, SeekFrom, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 static LOG_ROOT: OnceLock<PathBuf> = OnceLock::new();
@@ -23,13 +56,137 @@ pub fn init_log_root(path: PathBuf) {
     let _ = LOG_ROOT.set(path);
 }
 
+/// Point the log root at a per-process temp directory (tests).
+#[cfg(test)]
+pub(crate) fn init_test_log_root() {
+    init_log_root(std::env::temp_dir().join(format!("rcxcore-test-{}", std::process::id())));
+}
+
+/// Scratch overwrite-mode blob (tests of fixed-blob formats).
+#[cfg(test)]
+pub(crate) fn open_test_fixed(name: &str) -> Result<EncryptedLog, ()> {
+    init_test_log_root();
+    EncryptedLog::open_overwrite(name)
+}
+
+/// Scratch append-mode log (tests of record formats).
+#[cfg(test)]
+pub(crate) fn open_test_append(name: &str) -> Result<EncryptedLog, ()> {
+    init_test_log_root();
+    EncryptedLog::open_append(name)
+}
+
 fn log_root() -> Result<PathBuf, ()> {
     LOG_ROOT.get().cloned().ok_or(())
 }
 
+/// Whether `init_log_root` has run (never true on WASM hosts
+/// without a filesystem).
+pub(crate) fn has_log_root() -> bool {
+    LOG_ROOT.get().is_some()
+}
+
+/// Every log file managed by this module (non-secret names).
+pub const LOG_FILES: [&str; 11] = [
+    "device_identity.bin",
+    "device_kill.log",
+    "kill_replay.log",
+    "nonce_ledger.log",
+    "nonce_ledger.snap",
+    "file_tombstones.log",
+    "phrase_verifier.bin",
+    "index_version.log",
+    "peers.bin",
+    "revoked.bin",
+    "kill_ack.bin",
+];
+
+/// Sizes of all managed log files, for diagnostics.
+///
+/// SECURITY:
+/// - Metadata only: never opens, creates, or reads contents
+/// - `None` when the root is unset or the file is absent
+pub fn log_file_sizes() -> [(&'static str, Option<u64>); 11] {
+    let root = log_root().ok();
+
+    LOG_FILES.map(|name| {
+        let size = root
+            .as_ref()
+            .and_then(|r| std::fs::metadata(r.join(name)).ok())
+            .map(|m| m.len());
+        (name, size)
+    })
+}
+
+/// Whether the log root accepts writes (health check).
+///
+/// Writes, syncs and removes a uniquely named scratch file; never
+/// opens a real log.
+pub fn probe_log_root() -> bool {
+    static PROBES: AtomicU64 = AtomicU64::new(0);
+
+    let Ok(root) = log_root() else { return false };
+    if std::fs::create_dir_all(&root).is_err() {
+        return false;
+    }
+
+    let n = PROBES.fetch_add(1, Ordering::Relaxed);
+    let path = root.join(format!(".health-probe.{}.{}", std::process::id(), n));
+
+    let written = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .and_then(|mut f| {
+            f.write_all(b"rcx")?;
+            f.sync_all()
+        })
+        .is_ok();
+
+    let removed = std::fs::remove_file(&path).is_ok();
+    written && removed
+}
+
+/// Maximum bytes read back from any log (bounded memory).
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Maximum single length-prefixed record.
+const MAX_RECORD_LEN: usize = 64 * 1024;
+
+/// Maximum rotated segments per log (`name.1` ..= `name.N`).
+///
+/// A full log refuses further appends: kill evidence is never
+/// deleted to make room.
+const MAX_SEGMENTS: u32 = 64;
+
+/// Active-segment cap for the kill and replay logs.
+const KILL_LOG_SEGMENT_BYTES: u64 = 64 * 1024;
+
+/// Log key context under `Purpose::Metadata`.
+pub const LOG_KEY_CONTEXT: u64 = 0x4C4F475245434F52; // "LOGRECOR"
+
+/// Hash chain link length (SHA-256).
+const CHAIN_LEN: usize = 32;
+
+/// Link preceding the first record.
+const CHAIN_GENESIS: [u8; CHAIN_LEN] = [0u8; CHAIN_LEN];
+
+/// Record AAD domain label (MUST NEVER CHANGE).
+const LOG_AAD_LABEL: &[u8] = b"rcxcloud:log:record:v1";
+
 /// Persistent log handle.
 pub struct EncryptedLog {
     file: File,
+    // Active segment path; reads span `path.1` ..= `path.N` first
+    path: Option<PathBuf>,
+    // Rotation threshold for the active segment (append handles only)
+    max_bytes: Option<u64>,
+    // Record key (keyed handles only): records are AEAD-sealed
+    key: Option<GuardedKey32>,
+    // Index of the next appended record (keyed; counted lazily)
+    next_index: Option<u64>,
+    // Link of the last appended record (read lazily)
+    chain_tail: Option<[u8; CHAIN_LEN]>,
 }
 
 impl EncryptedLog {
@@ -40,18 +197,156 @@ impl EncryptedLog {
         Self::open_overwrite("device_identity.bin")
     }
 
-    /// Open Kill Flag Log (Mode: Append).
+    /// Open Kill Flag Log (Mode: Append, rotating).
     pub fn open_device_kill_log() -> Result<Self, ()> {
-        Self::open_append("device_kill.log")
+        Self::open_append_bounded("device_kill.log", KILL_LOG_SEGMENT_BYTES)
     }
 
-    /// Open Replay Token Log (Mode: Append).
+    /// Open Replay Token Log (Mode: Append, rotating).
     pub fn open_replay_log() -> Result<Self, ()> {
-        Self::open_append("kill_replay.log")
+        Self::open_append_bounded("kill_replay.log", KILL_LOG_SEGMENT_BYTES)
+    }
+
+    /// Open Identity READ-ONLY (`Ok(None)` if never published).
+    pub fn open_device_identity_read_only() -> Result<Option<Self>, ()> {
+        Self::open_read_only("device_identity.bin")
+    }
+
+    /// Publish the first-time identity blob.
+    ///
+    /// `Ok(true)` for exactly ONE caller ever; everyone else gets
+    /// `Ok(false)` and re-reads the winner's identity.
+    ///
+    /// SECURITY:
+    /// - Written + synced to a unique temp file, then hard-linked
+    ///   into place: atomic, never overwrites, and the identity
+    ///   exists whole or not at all (a crash leaves nothing to undo)
+    /// - A zero-length identity (left by builds that created the
+    ///   file before writing it) is never adopted: it is removed
+    ///   and the claim retried once
+    pub fn publish_device_identity(data: &[u8]) -> Result<bool, ()> {
+        publish_new("device_identity.bin", data)
+    }
+
+    /// Open Peer Device Log (Mode: Append).
+    pub fn open_peer_log() -> Result<Self, ()> {
+        Self::open_append("peers.bin")
+    }
+
+    /// Open Revoked Device Log (Mode: Append).
+    pub fn open_revocation_log() -> Result<Self, ()> {
+        Self::open_append("revoked.bin")
+    }
+
+    /// Open Nonce Version Ledger (Mode: Append).
+    pub fn open_nonce_ledger() -> Result<Self, ()> {
+        Self::open_append("nonce_ledger.log")
+    }
+
+    /// Open Nonce Ledger Snapshot (Mode: Overwrite, atomic replace).
+    pub fn open_nonce_snapshot() -> Result<Self, ()> {
+        Self::open_overwrite("nonce_ledger.snap")
+    }
+
+    /// Open File Tombstone Log (Mode: Append).
+    pub fn open_tombstone_log() -> Result<Self, ()> {
+        Self::open_append("file_tombstones.log")
+    }
+
+    /// Open Index Version Log (Mode: Append, MAC-authenticated records).
+    pub fn open_index_versions() -> Result<Self, ()> {
+        Self::open_append("index_version.log")
+    }
+
+    /// Open Recovery Phrase Verifier (Mode: Overwrite, atomic replace).
+    pub fn open_phrase_verifier() -> Result<Self, ()> {
+        Self::open_overwrite("phrase_verifier.bin")
+    }
+
+    /// Write the Kill Acknowledgment (atomic replace).
+    ///
+    /// SECURITY:
+    /// - The ONE write allowed after the kill fuse: the ack is
+    ///   sealed once the kill has executed (`kill::ack`)
+    /// - Fixed name, whole-blob replace: nothing else is reachable
+    pub fn publish_kill_ack(data: &[u8]) -> Result<(), ()> {
+        let root = log_root()?;
+        std::fs::create_dir_all(&root).map_err(|_| ())?;
+        replace_file(&root.join("kill_ack.bin"), data)
+    }
+
+    /// Open Phrase Verifier READ-ONLY (health check; never created here).
+    pub fn open_phrase_verifier_read_only() -> Result<Option<Self>, ()> {
+        Self::open_read_only("phrase_verifier.bin")
+    }
+
+    /// Open Kill Flag Log READ-ONLY (audit export; allowed after kill).
+    ///
+    /// `Ok(None)` if the log does not exist (never created here).
+    pub fn open_device_kill_log_read_only() -> Result<Option<Self>, ()> {
+        Self::open_read_only("device_kill.log")
+    }
+
+    /// Open Replay Token Log READ-ONLY (audit export; allowed after kill).
+    pub fn open_replay_log_read_only() -> Result<Option<Self>, ()> {
+        Self::open_read_only("kill_replay.log")
+    }
+
+    /// Open an append-only log that rotates once the active segment
+    /// would exceed `max_bytes`.
+    ///
+    /// SECURITY:
+    /// - Append-only: the active file is renamed to the next free
+    ///   `name.N`, never truncated or overwritten
+    /// - Reads transparently span all segments, oldest first
+    /// - Rotation that cannot complete => append refused (fail-closed)
+    pub fn open_append_bounded(name: &str, max_bytes: u64) -> Result<Self, ()> {
+        if max_bytes == 0 || max_bytes > MAX_LOG_BYTES {
+            return Err(());
+        }
+
+        let mut log = Self::open_append(name)?;
+        log.max_bytes = Some(max_bytes);
+        Ok(log)
+    }
+
+    /// Seal every length-prefixed record under a log key derived
+    /// from `master` (`Purpose::Metadata`, `LOG_KEY_CONTEXT`).
+    ///
+    /// SECURITY:
+    /// - Derived key lives in guarded memory, dropped with the handle
+    /// - Applies to `append_record` / `read_records` only
+    pub fn with_log_key(mut self, master: &GuardedKey32) -> Result<Self, ()> {
+        let mut key = GuardedKey32::zeroed();
+        derive_key(master, Purpose::Metadata, LOG_KEY_CONTEXT, &mut key).map_err(|_| ())?;
+
+        self.key = Some(key);
+        Ok(self)
     }
 
     /* ───────────── INTERNAL HELPERS (STRICT MODES) ───────────── */
 
+    fn open_read_only(name: &str) -> Result<Option<Self>, ()> {
+        let mut path = log_root()?;
+        path.push(name);
+
+        // A rotated-away active file still leaves its segments
+        let has_segments = segment_path(&path, 1).exists();
+
+        match OpenOptions::new().read(true).open(&path) {
+            Ok(file) => Ok(Some(Self {
+                file,
+                path: Some(path),
+                max_bytes: None,
+                key: None,
+                next_index: None,
+                chain_tail: None,
+            })),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !has_segments => Ok(None),
+            Err(_) => Err(()),
+        }
+    }
+
     fn open_append(name: &str) -> Result<Self, ()> {
         if GLOBAL_KILLED.load(Ordering::SeqCst) {
             return Err(());
@@ -76,31 +371,311 @@ impl EncryptedLog {
             .read(true)
             .write(true)
             .append(append)
+            .open(&path)
+            .map_err(|_| ())?;
+
+        Ok(Self {
+            file,
+            path: Some(path),
+            max_bytes: None,
+            key: None,
+            next_index: None,
+            chain_tail: None,
+        })
+    }
+
+    /* ───────────── ROTATION ───────────── */
+
+    /// Rotate the active segment if `incoming` more bytes would
+    /// push it past `max_bytes`.
+    ///
+    /// Order: flush → rename to `name.N` (atomic, no clobber) →
+    /// create a fresh active file. Any failure => `Err`, and every
+    /// later append fails too (the handle no longer matches `path`).
+    fn rotate_if_needed(&mut self, incoming: u64) -> Result<(), ()> {
+        let (Some(path), Some(max_bytes)) = (self.path.as_ref(), self.max_bytes) else {
+            return Ok(());
+        };
+
+        let len = self.file.metadata().map_err(|_| ())?.len();
+        if len == 0 || len.saturating_add(incoming) <= max_bytes {
+            return Ok(());
+        }
+
+        let next = segment_count(path) + 1;
+        if next > MAX_SEGMENTS {
+            return Err(());
+        }
+
+        let target = segment_path(path, next);
+        if target.exists() {
+            return Err(());
+        }
+
+        self.file.sync_all().map_err(|_| ())?;
+        std::fs::rename(path, &target).map_err(|_| ())?;
+
+        self.file = OpenOptions::new()
+            .create_new(true)
+            .read(true)
+            .append(true)
             .open(path)
             .map_err(|_| ())?;
 
-        Ok(Self { file })
+        Ok(())
+    }
+
+    /// Rotated segments (oldest first); empty for non-rotating logs.
+    fn segment_paths(&self) -> Vec<PathBuf> {
+        match self.path.as_ref() {
+            Some(path) => (1..=segment_count(path))
+                .map(|n| segment_path(path, n))
+                .collect(),
+            None => Vec::new(),
+        }
     }
 
     /* ───────────── STANDARD LOG (Length-Prefixed) ───────────── */
 
     /// Append a length-prefixed binary record.
     /// Used for: Kill Flags, Audit.
+    ///
+    /// Keyed handles write `nonce || AES-GCM(record)` instead of
+    /// the plaintext.
     pub fn append_record(&mut self, data: &[u8]) -> Result<(), ()> {
         if GLOBAL_KILLED.load(Ordering::SeqCst) {
             return Err(());
         }
 
-        let len = (data.len() as u32).to_be_bytes();
+        let sealed;
+        let body = if self.key.is_some() {
+            sealed = self.seal_record(data)?;
+            &sealed[..]
+        } else {
+            data
+        };
+
+        // Never write what `read_records` would refuse
+        let stored_len = body.len() + CHAIN_LEN;
+        if stored_len > MAX_RECORD_LEN {
+            return Err(());
+        }
+
+        let link = chain_link(&self.chain_tail()?, body);
+
+        self.rotate_if_needed(4 + stored_len as u64)?;
+
+        let len = (stored_len as u32).to_be_bytes();
         self.file.write_all(&len).map_err(|_| ())?;
-        self.file.write_all(data).map_err(|_| ())?;
+        self.file.write_all(body).map_err(|_| ())?;
+        self.file.write_all(&link).map_err(|_| ())?;
         self.file.flush().map_err(|_| ())?;
+
+        self.chain_tail = Some(link);
+        replace_file(&head_path(self.path.as_ref().ok_or(())?), &link)?;
+
+        if let Some(i) = self.next_index.as_mut() {
+            *i += 1;
+        }
         Ok(())
     }
 
+    /// Atomically restart the log with `first` as its only record.
+    ///
+    /// ⚠️ NOT APPEND-ONLY. Drops every earlier record.
+    /// RESTRICTED: Use ONLY for Nonce Ledger compaction, after the
+    /// dropped records are durable in the snapshot.
+    ///
+    /// SECURITY:
+    /// - temp file → rename: a crash leaves the old or the new log
+    /// - Chain restarts from genesis; `name.head` follows
+    /// - Refused for keyed or rotating logs
+    pub fn restart_records(&mut self, first: &[u8]) -> Result<(), ()> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(());
+        }
+
+        if self.key.is_some() || self.max_bytes.is_some() {
+            return Err(());
+        }
+
+        let stored_len = first.len() + CHAIN_LEN;
+        if stored_len > MAX_RECORD_LEN {
+            return Err(());
+        }
+
+        let link = chain_link(&CHAIN_GENESIS, first);
+        let path = self.path.clone().ok_or(())?;
+
+        let mut framed = Vec::with_capacity(4 + stored_len);
+        framed.extend_from_slice(&(stored_len as u32).to_be_bytes());
+        framed.extend_from_slice(first);
+        framed.extend_from_slice(&link);
+        replace_file(&path, &framed)?;
+
+        self.file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&path)
+            .map_err(|_| ())?;
+
+        self.chain_tail = Some(link);
+        replace_file(&head_path(&path), &link)
+    }
+
+    /// Walk every record (all segments) and check the hash chain.
+    ///
+    /// Broken link, record too short to carry one, or a tail that
+    /// does not match `name.head` => Err (fail-closed).
+    pub fn verify_chain(&mut self) -> Result<(), ()> {
+        let mut link = CHAIN_GENESIS;
+
+        for rec in self.stored_records()? {
+            let (body, stored) = split_link(&rec)?;
+            link = chain_link(&link, body);
+
+            if stored != link {
+                return Err(());
+            }
+        }
+
+        let head = match std::fs::read(head_path(self.path.as_ref().ok_or(())?)) {
+            Ok(head) => head,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => CHAIN_GENESIS.to_vec(),
+            Err(_) => return Err(()),
+        };
+
+        if head != link {
+            return Err(());
+        }
+
+        Ok(())
+    }
+
+    fn chain_tail(&mut self) -> Result<[u8; CHAIN_LEN], ()> {
+        if let Some(tail) = self.chain_tail {
+            return Ok(tail);
+        }
+
+        let tail = match self.stored_records()?.last() {
+            Some(rec) => split_link(rec)?.1,
+            None => CHAIN_GENESIS,
+        };
+
+        self.chain_tail = Some(tail);
+        Ok(tail)
+    }
+
+    fn seal_record(&mut self, data: &[u8]) -> Result<Vec<u8>, ()> {
+        let index = match self.next_index {
+            Some(i) => i,
+            None => self.raw_records()?.len() as u64,
+        };
+        self.next_index = Some(index);
+
+        let aad = self.record_aad(index);
+        let key = self.key.as_ref().ok_or(())?;
+        let nonce = derive_nonce_log(key, index, data);
+
+        let mut out = vec![0u8; NONCE_LEN + data.len() + TAG_LEN];
+        out[..NONCE_LEN].copy_from_slice(&nonce);
+        aes_gcm::seal(key, &nonce, data, &aad, &mut out[NONCE_LEN..])?;
+
+        Ok(out)
+    }
+
+    fn record_aad(&self, index: u64) -> Vec<u8> {
+        let name = self
+            .path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let mut aad = Vec::with_capacity(LOG_AAD_LABEL.len() + 1 + name.len() + 8);
+        aad.extend_from_slice(LOG_AAD_LABEL);
+        aad.push(name.len() as u8);
+        aad.extend_from_slice(name.as_bytes());
+        aad.extend_from_slice(&index.to_be_bytes());
+        aad
+    }
+
+    /// Read every length-prefixed record (bounded), across all
+    /// rotated segments, oldest first.
+    ///
+    /// Truncated tail, oversized record, or (keyed) a record that
+    /// fails authentication => Err (fail-closed).
+    pub fn read_records(&mut self) -> Result<Vec<Vec<u8>>, ()> {
+        self.iter_records()?.collect()
+    }
+
+    /// Iterate records in order, decrypting + authenticating
+    /// each one on keyed handles.
+    ///
+    /// The first failing record is yielded as `Err` and ends the
+    /// iteration; nothing after it is ever returned.
+    pub fn iter_records(&mut self) -> Result<Records<'_>, ()> {
+        let raw = self.raw_records()?;
+
+        Ok(Records {
+            raw: raw.into_iter(),
+            index: 0,
+            log: self,
+            done: false,
+        })
+    }
+
+    /// Record bodies as stored on disk, chain links stripped.
+    fn raw_records(&mut self) -> Result<Vec<Vec<u8>>, ()> {
+        let mut records = self.stored_records()?;
+
+        for rec in records.iter_mut() {
+            let body_len = split_link(rec)?.0.len();
+            rec.truncate(body_len);
+        }
+
+        Ok(records)
+    }
+
+    /// Framed records exactly as stored (`body || link`).
+    fn stored_records(&mut self) -> Result<Vec<Vec<u8>>, ()> {
+        let mut records = Vec::new();
+
+        for seg in self.segment_paths() {
+            let mut file = File::open(seg).map_err(|_| ())?;
+            parse_records(&read_bounded(&mut file)?, &mut records)?;
+        }
+
+        parse_records(&read_bounded(&mut self.file)?, &mut records)?;
+        Ok(records)
+    }
+
+    /// Read every raw u64 record (replay log format), across all
+    /// rotated segments, oldest first.
+    pub fn read_all_u64(&mut self) -> Result<Vec<u64>, ()> {
+        let mut values = Vec::new();
+
+        for seg in self.segment_paths() {
+            let mut file = File::open(seg).map_err(|_| ())?;
+            parse_u64s(&read_bounded(&mut file)?, &mut values)?;
+        }
+
+        parse_u64s(&read_bounded(&mut self.file)?, &mut values)?;
+        Ok(values)
+    }
+
     /// Check if the log contains ANY data.
     /// Used for: Kill switch detection (Existence-based).
     pub fn has_any_content(&self) -> bool {
+        // Segments only ever come from non-empty active files
+        if let Some(path) = self.path.as_ref() {
+            match std::fs::metadata(segment_path(path, 1)) {
+                Ok(_) => return true,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(_) => return true, // Fail closed
+            }
+        }
+
         match self.file.metadata() {
             Ok(m) => m.len() > 0,
             Err(_) => true, // Fail closed: Assume content exists (e.g. killed) on error
@@ -117,6 +692,8 @@ impl EncryptedLog {
             return Err(());
         }
 
+        self.rotate_if_needed(8)?;
+
         self.file.seek(SeekFrom::End(0)).map_err(|_| ())?;
         self.file.write_all(&value.to_be_bytes()).map_err(|_| ())?;
         self.file.flush().map_err(|_| ())?;
@@ -125,25 +702,20 @@ impl EncryptedLog {
 
     /// Read last u64 record.
     /// ASSUMES: File consists ONLY of raw 8-byte records.
+    ///
+    /// A freshly rotated (empty) active segment falls back to the
+    /// newest rotated segment.
     pub fn read_last_u64(&mut self) -> Result<Option<u64>, ()> {
         let len = self.file.metadata().map_err(|_| ())?.len();
 
-        // Must have at least one u64 (8 bytes)
-        if len < 8 {
-            return Ok(None);
+        if len == 0 {
+            if let Some(newest) = self.segment_paths().pop() {
+                let mut file = File::open(newest).map_err(|_| ())?;
+                return last_u64(&mut file);
+            }
         }
 
-        // Integrity check: File size must be multiple of 8
-        if len % 8 != 0 {
-            return Err(()); // Corrupt tail
-        }
-
-        self.file.seek(SeekFrom::End(-8)).map_err(|_| ())?;
-
-        let mut buf = [0u8; 8];
-        self.file.read_exact(&mut buf).map_err(|_| ())?;
-        
-        Ok(Some(u64::from_be_bytes(buf)))
+        last_u64(&mut self.file)
     }
 
     /* ───────────── IDENTITY (Fixed) ───────────── */
@@ -163,6 +735,26 @@ impl EncryptedLog {
         Ok(())
     }
 
+    /// Atomically replace the fixed-size blob (temp file → rename).
+    /// A crash leaves either the old or the new blob, never a mix.
+    /// RESTRICTED: Use ONLY for the Phrase Verifier and first-time
+    /// Device Identity.
+    pub fn replace_fixed(&mut self, data: &[u8]) -> Result<(), ()> {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(());
+        }
+
+        let path = self.path.clone().ok_or(())?;
+        replace_file(&path, data)?;
+
+        self.file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .map_err(|_| ())?;
+        Ok(())
+    }
+
     /// Read fixed-size blob.
     pub fn read_fixed(&mut self) -> Result<Option<Vec<u8>>, ()> {
         self.file.seek(SeekFrom::Start(0)).map_err(|_| ())?;
@@ -175,3 +767,457 @@ impl EncryptedLog {
         }
     }
 }
+
+/* ───────────── RECORD ITERATOR ───────────── */
+
+/// Iterator over the records of one log (see `iter_records`).
+pub struct Records<'a> {
+    raw: std::vec::IntoIter<Vec<u8>>,
+    index: u64,
+    log: &'a EncryptedLog,
+    done: bool,
+}
+
+impl Iterator for Records<'_> {
+    type Item = Result<Vec<u8>, ()>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let body = self.raw.next()?;
+        let index = self.index;
+        self.index += 1;
+
+        let Some(key) = self.log.key.as_ref() else {
+            return Some(Ok(body));
+        };
+
+        let opened = open_record(key, &self.log.record_aad(index), &body);
+        self.done = opened.is_err();
+        Some(opened)
+    }
+}
+
+fn open_record(key: &GuardedKey32, aad: &[u8], body: &[u8]) -> Result<Vec<u8>, ()> {
+    if body.len() < NONCE_LEN + TAG_LEN {
+        return Err(());
+    }
+
+    let (nonce, sealed) = body.split_at(NONCE_LEN);
+    let nonce: &[u8; NONCE_LEN] = nonce.try_into().map_err(|_| ())?;
+
+    let mut out = vec![0u8; sealed.len() - TAG_LEN];
+    if !aes_gcm::open(key, nonce, sealed, aad, &mut out) {
+        return Err(());
+    }
+
+    Ok(out)
+}
+
+/* ───────────── HASH CHAIN ───────────── */
+
+fn chain_link(prev: &[u8; CHAIN_LEN], body: &[u8]) -> [u8; CHAIN_LEN] {
+    let mut buf = Vec::with_capacity(CHAIN_LEN + body.len());
+    buf.extend_from_slice(prev);
+    buf.extend_from_slice(body);
+
+    *hash_sha256(&buf).as_ref()
+}
+
+/// Split a stored record into `(body, link)`.
+fn split_link(rec: &[u8]) -> Result<(&[u8], [u8; CHAIN_LEN]), ()> {
+    let split = rec.len().checked_sub(CHAIN_LEN).ok_or(())?;
+    let (body, link) = rec.split_at(split);
+    Ok((body, link.try_into().map_err(|_| ())?))
+}
+
+fn head_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".head");
+    PathBuf::from(name)
+}
+
+/// Replace `path` with `data` via temp file → rename (atomic).
+fn replace_file(path: &Path, data: &[u8]) -> Result<(), ()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&tmp)
+        .map_err(|_| ())?;
+    file.write_all(data).map_err(|_| ())?;
+    file.sync_all().map_err(|_| ())?;
+    drop(file);
+
+    std::fs::rename(&tmp, path).map_err(|_| ())
+}
+
+/// Publish `name` as a whole new file (see `publish_device_identity`).
+fn publish_new(name: &str, data: &[u8]) -> Result<bool, ()> {
+    static CLAIMS: AtomicU64 = AtomicU64::new(0);
+
+    if GLOBAL_KILLED.load(Ordering::SeqCst) {
+        return Err(());
+    }
+
+    let root = log_root()?;
+    std::fs::create_dir_all(&root).map_err(|_| ())?;
+    let path = root.join(name);
+
+    let n = CLAIMS.fetch_add(1, Ordering::Relaxed);
+    let tmp = root.join(format!(".{name}.{}.{}.tmp", std::process::id(), n));
+
+    let written = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&tmp)
+        .and_then(|mut f| {
+            f.write_all(data)?;
+            f.sync_all()
+        });
+
+    let claimed = written.map_err(|_| ()).and_then(|()| {
+        let mut linked = link_new(&tmp, &path);
+        if linked == Ok(false) && std::fs::metadata(&path).map_err(|_| ())?.len() == 0 {
+            std::fs::remove_file(&path).map_err(|_| ())?;
+            linked = link_new(&tmp, &path);
+        }
+        linked
+    });
+
+    // The temp name is ours alone; the published link keeps the data
+    let _ = std::fs::remove_file(&tmp);
+    claimed
+}
+
+/// Hard-link `src` to `dst` unless `dst` exists (atomic, no clobber).
+///
+/// `Ok(false)` if `dst` already exists.
+fn link_new(src: &Path, dst: &Path) -> Result<bool, ()> {
+    match std::fs::hard_link(src, dst) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+        Err(_) => Err(()),
+    }
+}
+
+/* ───────────── SEGMENT / PARSE HELPERS ───────────── */
+
+fn segment_path(path: &Path, n: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+/// Number of contiguous rotated segments (`path.1` ..= `path.N`).
+fn segment_count(path: &Path) -> u32 {
+    let mut n = 0;
+    while n < MAX_SEGMENTS && segment_path(path, n + 1).exists() {
+        n += 1;
+    }
+    n
+}
+
+/// Read a whole segment; oversized => Err (bounded memory).
+fn read_bounded(file: &mut File) -> Result<Vec<u8>, ()> {
+    file.seek(SeekFrom::Start(0)).map_err(|_| ())?;
+
+    let mut buf = Vec::new();
+    Read::by_ref(file)
+        .take(MAX_LOG_BYTES + 1)
+        .read_to_end(&mut buf)
+        .map_err(|_| ())?;
+
+    if buf.len() as u64 > MAX_LOG_BYTES {
+        return Err(());
+    }
+
+    Ok(buf)
+}
+
+fn parse_records(buf: &[u8], records: &mut Vec<Vec<u8>>) -> Result<(), ()> {
+    let mut rest = buf;
+
+    while !rest.is_empty() {
+        if rest.len() < 4 {
+            return Err(());
+        }
+        let (len, body) = rest.split_at(4);
+        let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+
+        if len > MAX_RECORD_LEN || body.len() < len {
+            return Err(());
+        }
+
+        records.push(body[..len].to_vec());
+        rest = &body[len..];
+    }
+
+    Ok(())
+}
+
+fn parse_u64s(buf: &[u8], values: &mut Vec<u64>) -> Result<(), ()> {
+    if buf.len() % 8 != 0 {
+        return Err(());
+    }
+
+    values.extend(
+        buf.chunks_exact(8)
+            .map(|c| u64::from_be_bytes([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7]])),
+    );
+    Ok(())
+}
+
+fn last_u64(file: &mut File) -> Result<Option<u64>, ()> {
+    let len = file.metadata().map_err(|_| ())?.len();
+
+    // Must have at least one u64 (8 bytes)
+    if len < 8 {
+        return Ok(None);
+    }
+
+    // Integrity check: File size must be multiple of 8
+    if len % 8 != 0 {
+        return Err(()); // Corrupt tail
+    }
+
+    file.seek(SeekFrom::End(-8)).map_err(|_| ())?;
+
+    let mut buf = [0u8; 8];
+    file.read_exact(&mut buf).map_err(|_| ())?;
+
+    Ok(Some(u64::from_be_bytes(buf)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fresh(name: &str) -> String {
+        init_test_log_root();
+
+        let name = format!("{name}-{}", rand_core::RngCore::next_u64(&mut rand_core::OsRng));
+        if let Ok(root) = log_root() {
+            let _ = std::fs::create_dir_all(root);
+        }
+        name
+    }
+
+    #[test]
+    fn bounded_log_rotates_and_reads_span_segments() -> Result<(), ()> {
+        let name = fresh("rotate");
+
+        let mut log = EncryptedLog::open_append_bounded(&name, 24)?;
+        for v in 0..10u64 {
+            assert!(log.append_u64(v).is_ok());
+        }
+
+        let root = log_root()?;
+        let base = root.join(&name);
+        assert!(segment_path(&base, 1).exists());
+        assert!(std::fs::metadata(&base).map_err(|_| ())?.len() <= 24);
+
+        assert_eq!(log.read_all_u64(), Ok((0..10).collect::<Vec<_>>()));
+        assert_eq!(log.read_last_u64(), Ok(Some(9)));
+        assert!(log.has_any_content());
+        Ok(())
+    }
+
+    #[test]
+    fn rotated_records_survive_reopen() -> Result<(), ()> {
+        let name = fresh("records");
+
+        let mut log = EncryptedLog::open_append_bounded(&name, 16)?;
+        for r in [b"first".as_slice(), b"second", b"third"] {
+            assert!(log.append_record(r).is_ok());
+        }
+        drop(log);
+
+        let mut ro = EncryptedLog::open_read_only(&name)?.ok_or(())?;
+        assert_eq!(
+            ro.read_records(),
+            Ok(vec![b"first".to_vec(), b"second".to_vec(), b"third".to_vec()])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_active_segment_falls_back_to_newest_segment() -> Result<(), ()> {
+        let name = fresh("fallback");
+
+        let mut log = EncryptedLog::open_append_bounded(&name, 8)?;
+        assert!(log.append_u64(41).is_ok());
+        assert!(log.append_u64(42).is_ok());
+
+        drop(log);
+
+        // State right after a rotation: empty active, value in `name.2`
+        let root = log_root()?;
+        let base = root.join(&name);
+        assert!(std::fs::rename(&base, segment_path(&base, 2)).is_ok());
+        assert!(File::create(&base).is_ok());
+
+        let mut ro = EncryptedLog::open_read_only(&name)?.ok_or(())?;
+        assert_eq!(ro.read_last_u64(), Ok(Some(42)));
+        assert_eq!(ro.read_all_u64(), Ok(vec![41, 42]));
+        Ok(())
+    }
+
+    fn master() -> GuardedKey32 {
+        GuardedKey32::init_with(|k| k.fill(0x42))
+    }
+
+    #[test]
+    fn keyed_records_are_encrypted_on_disk() -> Result<(), ()> {
+        let name = fresh("sealed");
+
+        let log = EncryptedLog::open_append(&name)?;
+        let mut log = log.with_log_key(&master())?;
+        assert!(log.append_record(b"KILLED").is_ok());
+        assert!(log.append_record(b"AUDIT").is_ok());
+        drop(log);
+
+        let root = log_root()?;
+        let disk = std::fs::read(root.join(&name)).map_err(|_| ())?;
+        assert!(!disk.windows(6).any(|w| w == b"KILLED"));
+
+        // Reopen: the record index continues from the persisted count
+        let log = EncryptedLog::open_append(&name)?;
+        let mut log = log.with_log_key(&master())?;
+        assert!(log.append_record(b"THIRD").is_ok());
+        assert_eq!(
+            log.read_records(),
+            Ok(vec![b"KILLED".to_vec(), b"AUDIT".to_vec(), b"THIRD".to_vec()])
+        );
+
+        // Wrong master key => authentication failure
+        let other = EncryptedLog::open_read_only(&name)?.ok_or(())?;
+        let mut other = other.with_log_key(&GuardedKey32::init_with(|k| k.fill(0x43)))?;
+        assert!(other.read_records().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_record_stops_iteration() -> Result<(), ()> {
+        let name = fresh("tamper");
+
+        let log = EncryptedLog::open_append(&name)?;
+        let mut log = log.with_log_key(&master())?;
+        for r in [b"one".as_slice(), b"two", b"three"] {
+            assert!(log.append_record(r).is_ok());
+        }
+        drop(log);
+
+        // Flip a byte inside the second record's ciphertext
+        let root = log_root()?;
+        let path = root.join(&name);
+        let mut disk = std::fs::read(&path).map_err(|_| ())?;
+        let first = 4 + NONCE_LEN + 3 + TAG_LEN + CHAIN_LEN;
+        disk[first + 4 + NONCE_LEN] ^= 0x01;
+        assert!(std::fs::write(&path, &disk).is_ok());
+
+        let ro = EncryptedLog::open_read_only(&name)?.ok_or(())?;
+        let mut ro = ro.with_log_key(&master())?;
+        let iter = ro.iter_records()?;
+        let got: Vec<_> = iter.collect();
+
+        assert_eq!(got.len(), 2);
+        assert_eq!(got[0], Ok(b"one".to_vec()));
+        assert_eq!(got[1], Err(()));
+        assert!(ro.read_records().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn hash_chain_detects_edits_and_truncation() -> Result<(), ()> {
+        let name = fresh("chain");
+
+        let mut log = EncryptedLog::open_append_bounded(&name, 64)?;
+        assert_eq!(log.verify_chain(), Ok(()));
+        for r in [b"one".as_slice(), b"two", b"three", b"four"] {
+            assert!(log.append_record(r).is_ok());
+        }
+        assert_eq!(log.verify_chain(), Ok(()));
+        drop(log);
+
+        // Chain spans rotated segments and survives reopen
+        let root = log_root()?;
+        let base = root.join(&name);
+        assert!(segment_path(&base, 1).exists());
+
+        let mut log = EncryptedLog::open_append_bounded(&name, 64)?;
+        assert!(log.append_record(b"five").is_ok());
+        assert_eq!(log.verify_chain(), Ok(()));
+        let records = log.read_records()?;
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[4], b"five".to_vec());
+
+        // Drop the last record: every remaining link is intact,
+        // but the tail no longer matches the head
+        let active = std::fs::read(&base).map_err(|_| ())?;
+        let last = 4 + 4 + CHAIN_LEN;
+        assert!(std::fs::write(&base, &active[..active.len() - last]).is_ok());
+        let mut log = EncryptedLog::open_append_bounded(&name, 64)?;
+        assert!(log.verify_chain().is_err());
+
+        // Edit a body in place
+        assert!(std::fs::write(&base, &active).is_ok());
+        assert_eq!(log.verify_chain(), Ok(()));
+        let mut edited = active.clone();
+        edited[4] ^= 0x01;
+        assert!(std::fs::write(&base, &edited).is_ok());
+        assert!(log.verify_chain().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn replace_fixed_swaps_whole_blob() -> Result<(), ()> {
+        let name = fresh("fixed");
+
+        let mut log = EncryptedLog::open_overwrite(&name)?;
+        assert_eq!(log.read_fixed(), Ok(None));
+        assert!(log.replace_fixed(b"old-verifier").is_ok());
+        assert!(log.replace_fixed(b"new").is_ok());
+        assert_eq!(log.read_fixed(), Ok(Some(b"new".to_vec())));
+
+        let mut again = EncryptedLog::open_overwrite(&name)?;
+        assert_eq!(again.read_fixed(), Ok(Some(b"new".to_vec())));
+        Ok(())
+    }
+
+    #[test]
+    fn out_of_range_bound_is_rejected() {
+        let name = fresh("range");
+
+        assert!(EncryptedLog::open_append_bounded(&name, 0).is_err());
+        assert!(EncryptedLog::open_append_bounded(&name, MAX_LOG_BYTES + 1).is_err());
+    }
+
+    #[test]
+    fn publish_is_whole_once_and_leaves_no_claim_behind() -> Result<(), ()> {
+        let name = fresh("publish");
+        let root = log_root()?;
+
+        // Zero-length leftover of a pre-atomic build: never adopted
+        std::fs::write(root.join(&name), b"").map_err(|_| ())?;
+
+        assert_eq!(publish_new(&name, b"winner"), Ok(true));
+        assert_eq!(publish_new(&name, b"loser"), Ok(false));
+        assert_eq!(std::fs::read(root.join(&name)).map_err(|_| ())?, b"winner");
+
+        // Nothing but the identity itself: no marker, no temp files
+        let leftovers = std::fs::read_dir(&root)
+            .map_err(|_| ())?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(name.as_str()))
+            .count();
+        assert_eq!(leftovers, 1);
+        Ok(())
+    }
+}
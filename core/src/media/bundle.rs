@@ -0,0 +1,311 @@
+//! Sanitized media bundle (transport container)
+//!
+//! Deterministic, uncompressed, metadata-free serialization of
+//! sanitized video / image output (frames + subtitle cues), so hosts
+//! never hand-roll their own.
+//!
+//! LAYOUT (big-endian):
+//! - magic(4) = "RCXB" || version(1) || kind(1)
+//! - width(4) || height(4) || frame_count(4)
+//! - frame_count × [ len(4) || RGBA bytes ]
+//! - cue_count(4) || cue_count × [ start_ms(8) || end_ms(8) || len(4) || UTF-8 ]
+//!
+//! SECURITY (`from_bundle`):
+//! - Bundle itself is HOSTILE input: every length is checked against
+//!   `media::limits` AND the bytes actually remaining BEFORE allocating
+//! - Frames are capped at `width × height × 4`
+//! - Cue text is capped in total at `MAX_SUBTITLE_BYTES`, UTF-8 only
+//! - Truncated input, trailing bytes, unknown version / kind => error
+//! - No panics; no metadata, warnings or tag counts are carried
+
+use crate::media::errors::MediaError;
+use crate::media::limits::{
+    MAX_HEIGHT, MAX_MEDIA_BYTES, MAX_SUBTITLE_BYTES, MAX_VIDEO_FRAMES, MAX_WIDTH,
+};
+use crate::media::output::{SanitizedImage, SanitizedMedia, SanitizedVideo};
+use crate::media::subtitles::SubtitleCue;
+
+/* ───────────── FORMAT ───────────── */
+
+const BUNDLE_MAGIC: [u8; 4] = *b"RCXB";
+const BUNDLE_VERSION: u8 = 1;
+
+const KIND_VIDEO: u8 = 1;
+const KIND_IMAGE: u8 = 2;
+
+/// magic + version + kind + width + height + frame_count
+const HEADER_LEN: usize = 4 + 1 + 1 + 4 + 4 + 4;
+/// start_ms + end_ms + text len
+const CUE_HEADER_LEN: usize = 8 + 8 + 4;
+
+/// Max bytes of one RGBA frame at `width × height`.
+fn frame_cap(width: u32, height: u32) -> Option<usize> {
+    if width == 0 || height == 0 || width > MAX_WIDTH || height > MAX_HEIGHT {
+        return None;
+    }
+    (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|n| n.checked_mul(4))
+}
+
+/* ───────────── SERIALIZE ───────────── */
+
+impl SanitizedMedia {
+    /// Serialize into a transport bundle (see module layout).
+    ///
+    /// Fails closed with `UnsupportedFormat` for audio, and with
+    /// `SanitizationFailed` if the output exceeds what `from_bundle`
+    /// would accept (a bundle we write MUST parse back).
+    pub fn to_bundle(&self) -> Result<Vec<u8>, MediaError> {
+        match self {
+            SanitizedMedia::Video(v) => {
+                let frames: Vec<&[u8]> = v.frames.iter().map(Vec::as_slice).collect();
+                encode(KIND_VIDEO, v.width, v.height, &frames, &v.subtitles)
+            }
+            SanitizedMedia::Image(i) => encode(KIND_IMAGE, i.width, i.height, &[&i.pixels], &[]),
+            SanitizedMedia::Audio(_) => Err(MediaError::UnsupportedFormat),
+        }
+    }
+
+    /// Parse a bundle produced by `to_bundle` (strictly bounded).
+    pub fn from_bundle(input: &[u8]) -> Result<SanitizedMedia, MediaError> {
+        decode(input)
+    }
+}
+
+fn encode(
+    kind: u8,
+    width: u32,
+    height: u32,
+    frames: &[&[u8]],
+    cues: &[SubtitleCue],
+) -> Result<Vec<u8>, MediaError> {
+    let cap = frame_cap(width, height).ok_or(MediaError::SanitizationFailed)?;
+
+    if frames.len() > MAX_VIDEO_FRAMES || frames.iter().any(|f| f.len() > cap) {
+        return Err(MediaError::SanitizationFailed);
+    }
+    let text_total: usize = cues.iter().map(|c| c.text.len()).sum();
+    if text_total > MAX_SUBTITLE_BYTES {
+        return Err(MediaError::SanitizationFailed);
+    }
+
+    // Exact size up front: no reallocation leaves stray pixel copies
+    let total = frames
+        .iter()
+        .try_fold(HEADER_LEN + 4, |acc, f| acc.checked_add(4 + f.len()))
+        .and_then(|acc| acc.checked_add(cues.len().checked_mul(CUE_HEADER_LEN)?))
+        .and_then(|acc| acc.checked_add(text_total))
+        .filter(|t| *t <= MAX_MEDIA_BYTES)
+        .ok_or(MediaError::InputTooLarge)?;
+
+    let mut out = Vec::with_capacity(total);
+    out.extend_from_slice(&BUNDLE_MAGIC);
+    out.push(BUNDLE_VERSION);
+    out.push(kind);
+    out.extend_from_slice(&width.to_be_bytes());
+    out.extend_from_slice(&height.to_be_bytes());
+    out.extend_from_slice(&(frames.len() as u32).to_be_bytes());
+
+    for frame in frames {
+        out.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+        out.extend_from_slice(frame);
+    }
+
+    out.extend_from_slice(&(cues.len() as u32).to_be_bytes());
+    for cue in cues {
+        out.extend_from_slice(&cue.start_ms.to_be_bytes());
+        out.extend_from_slice(&cue.end_ms.to_be_bytes());
+        out.extend_from_slice(&(cue.text.len() as u32).to_be_bytes());
+        out.extend_from_slice(cue.text.as_bytes());
+    }
+
+    Ok(out)
+}
+
+/* ───────────── PARSE ───────────── */
+
+/// Bounded cursor; every read checks the remaining length first.
+struct Reader<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], MediaError> {
+        if n > self.rest.len() {
+            return Err(MediaError::DecodeFailed);
+        }
+        let (head, tail) = self.rest.split_at(n);
+        self.rest = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, MediaError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, MediaError> {
+        let mut b = [0u8; 4];
+        b.copy_from_slice(self.take(4)?);
+        Ok(u32::from_be_bytes(b))
+    }
+
+    fn u64(&mut self) -> Result<u64, MediaError> {
+        let mut b = [0u8; 8];
+        b.copy_from_slice(self.take(8)?);
+        Ok(u64::from_be_bytes(b))
+    }
+}
+
+fn decode(input: &[u8]) -> Result<SanitizedMedia, MediaError> {
+    if input.len() > MAX_MEDIA_BYTES {
+        return Err(MediaError::InputTooLarge);
+    }
+
+    let mut r = Reader { rest: input };
+
+    if r.take(4)? != BUNDLE_MAGIC || r.u8()? != BUNDLE_VERSION {
+        return Err(MediaError::UnsupportedFormat);
+    }
+    let kind = r.u8()?;
+
+    let width = r.u32()?;
+    let height = r.u32()?;
+    let cap = frame_cap(width, height).ok_or(MediaError::DecodeFailed)?;
+
+    let frame_count = r.u32()? as usize;
+    let frames_ok = match kind {
+        KIND_VIDEO => frame_count <= MAX_VIDEO_FRAMES,
+        KIND_IMAGE => frame_count == 1,
+        _ => return Err(MediaError::UnsupportedFormat),
+    };
+    // Each frame costs at least its length prefix
+    if !frames_ok || frame_count > r.rest.len() / 4 {
+        return Err(MediaError::DecodeFailed);
+    }
+
+    let mut frames = Vec::with_capacity(frame_count);
+    for _ in 0..frame_count {
+        let len = r.u32()? as usize;
+        if len > cap {
+            return Err(MediaError::DecodeFailed);
+        }
+        frames.push(r.take(len)?.to_vec());
+    }
+
+    let cue_count = r.u32()? as usize;
+    if cue_count > r.rest.len() / CUE_HEADER_LEN {
+        return Err(MediaError::DecodeFailed);
+    }
+
+    let mut cues = Vec::with_capacity(cue_count);
+    let mut text_total = 0usize;
+    for _ in 0..cue_count {
+        let start_ms = r.u64()?;
+        let end_ms = r.u64()?;
+        let len = r.u32()? as usize;
+
+        text_total = text_total
+            .checked_add(len)
+            .filter(|t| *t <= MAX_SUBTITLE_BYTES)
+            .ok_or(MediaError::DecodeFailed)?;
+
+        let text = core::str::from_utf8(r.take(len)?).map_err(|_| MediaError::DecodeFailed)?;
+        cues.push(SubtitleCue {
+            start_ms,
+            end_ms,
+            text: text.into(),
+        });
+    }
+
+    if !r.rest.is_empty() {
+        return Err(MediaError::DecodeFailed);
+    }
+
+    match kind {
+        KIND_IMAGE => {
+            if !cues.is_empty() {
+                return Err(MediaError::DecodeFailed);
+            }
+            let pixels = frames.pop().ok_or(MediaError::DecodeFailed)?;
+            if pixels.len() != cap {
+                return Err(MediaError::DecodeFailed);
+            }
+            Ok(SanitizedMedia::Image(SanitizedImage {
+                pixels,
+                width,
+                height,
+                stripped_tags: 0,
+            }))
+        }
+        _ => Ok(SanitizedMedia::Video(SanitizedVideo {
+            frames,
+            width,
+            height,
+            subtitles: cues,
+            stripped_tags: 0,
+            warnings: Vec::new(),
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video() -> SanitizedMedia {
+        SanitizedMedia::Video(SanitizedVideo {
+            frames: vec![vec![1; 2 * 2 * 4], vec![2; 2 * 2 * 4]],
+            width: 2,
+            height: 2,
+            subtitles: vec![SubtitleCue { start_ms: 0, end_ms: 900, text: "hi".into() }],
+            stripped_tags: 3,
+            warnings: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn video_bundle_round_trips_deterministically() -> Result<(), MediaError> {
+        let bundle = video().to_bundle()?;
+        assert_eq!(video().to_bundle(), Ok(bundle.clone()));
+
+        let SanitizedMedia::Video(v) = SanitizedMedia::from_bundle(&bundle)? else {
+            return Err(MediaError::UnsupportedFormat);
+        };
+        assert_eq!((v.width, v.height, v.frames.len()), (2, 2, 2));
+        assert_eq!(v.frames[1], vec![2; 16]);
+        assert_eq!(v.subtitles, vec![SubtitleCue { start_ms: 0, end_ms: 900, text: "hi".into() }]);
+        // No metadata survives transport
+        assert_eq!(v.stripped_tags, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn hostile_lengths_fail_closed() -> Result<(), MediaError> {
+        let bundle = video().to_bundle()?;
+
+        // Truncation anywhere / trailing garbage
+        for cut in [0, HEADER_LEN - 1, HEADER_LEN + 3, bundle.len() - 1] {
+            assert!(SanitizedMedia::from_bundle(&bundle[..cut]).is_err());
+        }
+        let mut trailing = bundle.clone();
+        trailing.push(0);
+        assert!(SanitizedMedia::from_bundle(&trailing).is_err());
+
+        // Frame count far beyond the bytes present
+        let mut many = bundle.clone();
+        many[14..18].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(SanitizedMedia::from_bundle(&many).err(), Some(MediaError::DecodeFailed));
+
+        // Frame longer than width × height × 4
+        let mut big = bundle.clone();
+        big[18..22].copy_from_slice(&17u32.to_be_bytes());
+        assert_eq!(SanitizedMedia::from_bundle(&big).err(), Some(MediaError::DecodeFailed));
+
+        // Oversized dimensions
+        let mut wide = bundle;
+        wide[6..10].copy_from_slice(&(MAX_WIDTH + 1).to_be_bytes());
+        assert_eq!(SanitizedMedia::from_bundle(&wide).err(), Some(MediaError::DecodeFailed));
+        Ok(())
+    }
+}
@@ -1,25 +1,71 @@
+use crate::media::container::metadata::{self, Metadata};
+use crate::media::decode::Deadline;
 use crate::media::errors::MediaError;
+use crate::media::limits::MAX_SUBTITLE_BYTES;
+use crate::media::subtitles::{write_frame, FRAME_HEADER_LEN};
 use crate::keystore::master::GLOBAL_KILLED;
 
 use ffmpeg_next as ffmpeg;
+use ffmpeg::codec;
 use ffmpeg::media::Type;
+use ffmpeg::Rational;
 use core::sync::atomic::Ordering;
 
 const MAX_AUDIO_BYTES: usize = 64 * 1024 * 1024;
 const MAX_VIDEO_BYTES: usize = 128 * 1024 * 1024;
-const MAX_SUBTITLE_BYTES: usize = 4 * 1024 * 1024;
 
 const MAX_AUDIO_PACKETS: usize = 100_000;
 const MAX_VIDEO_PACKETS: usize = 200_000;
 const MAX_SUBTITLE_PACKETS: usize = 50_000;
 
+/// Text subtitle codecs (bitmap codecs such as PGS / DVB are refused).
+const TEXT_SUBTITLE_CODECS: &[codec::Id] = &[
+    codec::Id::SUBRIP,
+    codec::Id::SRT,
+    codec::Id::WEBVTT,
+    codec::Id::ASS,
+    codec::Id::SSA,
+    codec::Id::MOV_TEXT,
+    codec::Id::TEXT,
+];
+
 pub struct DemuxedStreams {
     pub audio: Vec<u8>,
     pub video: Vec<u8>,
+    /// Framed per packet (see `subtitles` framing), never concatenated
     pub subtitles: Vec<u8>,
+    /// Quarantined tags (wiped + counted by the sanitize stage)
+    pub metadata: Metadata,
 }
 
-pub fn demux(input: &[u8]) -> Result<DemuxedStreams, MediaError> {
+/// Reject non-text subtitle codecs.
+fn check_subtitle_codec(id: codec::Id) -> Result<(), MediaError> {
+    if TEXT_SUBTITLE_CODECS.contains(&id) {
+        Ok(())
+    } else {
+        Err(MediaError::UnsupportedCodec)
+    }
+}
+
+/// Stream timestamp → milliseconds (saturating, negative => 0).
+fn ts_to_ms(ts: i64, tb: Rational) -> u64 {
+    let den = i128::from(tb.denominator());
+    if ts <= 0 || den <= 0 {
+        return 0;
+    }
+
+    let ms = i128::from(ts) * i128::from(tb.numerator()) * 1000 / den;
+    u64::try_from(ms).unwrap_or(u64::MAX)
+}
+
+/// Open hostile input as a container (shared by demux + probe).
+///
+/// SECURITY:
+/// - Kill-aware
+/// - Empty input rejected
+pub(crate) fn open_input(
+    input: &[u8],
+) -> Result<ffmpeg::format::context::Input, MediaError> {
     if GLOBAL_KILLED.load(Ordering::SeqCst) {
         return Err(MediaError::DemuxFailed);
     }
@@ -31,9 +77,19 @@ pub fn demux(input: &[u8]) -> Result<DemuxedStreams, MediaError> {
     ffmpeg::init().map_err(|_| MediaError::DemuxFailed)?;
 
     let mut cursor = std::io::Cursor::new(input);
-    let mut ictx =
-        ffmpeg::format::input(&mut cursor)
-            .map_err(|_| MediaError::DemuxFailed)?;
+    ffmpeg::format::input(&mut cursor)
+        .map_err(|_| MediaError::DemuxFailed)
+}
+
+pub fn demux(
+    input: &[u8],
+    deadline: &Deadline,
+) -> Result<DemuxedStreams, MediaError> {
+    deadline.check()?;
+    let mut ictx = open_input(input)?;
+
+    // Oversized metadata is refused, not silently ignored
+    let metadata = metadata::collect(&ictx)?;
 
     let mut audio = Vec::new();
     let mut video = Vec::new();
@@ -47,38 +103,119 @@ pub fn demux(input: &[u8]) -> Result<DemuxedStreams, MediaError> {
         if GLOBAL_KILLED.load(Ordering::SeqCst) {
             return Err(MediaError::DemuxFailed);
         }
+        deadline.check()?;
 
         let data = packet.data();
 
         match stream.parameters().medium() {
             Type::Audio => {
                 a_pk += 1;
-                if a_pk > MAX_AUDIO_PACKETS || audio.len() + data.len() > MAX_AUDIO_BYTES {
-                    return Err(MediaError::DemuxFailed);
+                if a_pk > MAX_AUDIO_PACKETS {
+                    return Err(MediaError::PacketLimitExceeded);
+                }
+                if audio.len() + data.len() > MAX_AUDIO_BYTES {
+                    return Err(MediaError::ByteLimitExceeded);
                 }
                 audio.extend_from_slice(data);
             }
             Type::Video => {
                 v_pk += 1;
-                if v_pk > MAX_VIDEO_PACKETS || video.len() + data.len() > MAX_VIDEO_BYTES {
-                    return Err(MediaError::DemuxFailed);
+                if v_pk > MAX_VIDEO_PACKETS {
+                    return Err(MediaError::PacketLimitExceeded);
+                }
+                if video.len() + data.len() > MAX_VIDEO_BYTES {
+                    return Err(MediaError::ByteLimitExceeded);
                 }
                 video.extend_from_slice(data);
             }
             Type::Subtitle => {
+                check_subtitle_codec(stream.parameters().id())?;
+
                 s_pk += 1;
-                if s_pk > MAX_SUBTITLE_PACKETS || subtitles.len() + data.len() > MAX_SUBTITLE_BYTES {
-                    return Err(MediaError::DemuxFailed);
+                if s_pk > MAX_SUBTITLE_PACKETS {
+                    return Err(MediaError::PacketLimitExceeded);
+                }
+                if subtitles.len() + FRAME_HEADER_LEN + data.len() > MAX_SUBTITLE_BYTES {
+                    return Err(MediaError::ByteLimitExceeded);
                 }
-                subtitles.extend_from_slice(data);
+
+                let tb = stream.time_base();
+                let start = packet.pts().unwrap_or(0);
+                let end = start.saturating_add(packet.duration());
+
+                write_frame(&mut subtitles, ts_to_ms(start, tb), ts_to_ms(end, tb), data)
+                    .map_err(|_| MediaError::DemuxFailed)?;
             }
             _ => {}
         }
     }
 
     if audio.is_empty() && video.is_empty() && subtitles.is_empty() {
-        return Err(MediaError::DemuxFailed);
+        return Err(MediaError::NoStreamsFound);
     }
 
-    Ok(DemuxedStreams { audio, video, subtitles })
+    Ok(DemuxedStreams { audio, video, subtitles, metadata })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_subtitle_codec_is_accepted() {
+        assert_eq!(check_subtitle_codec(codec::Id::SUBRIP), Ok(()));
+        assert_eq!(check_subtitle_codec(codec::Id::WEBVTT), Ok(()));
+    }
+
+    #[test]
+    fn bitmap_subtitle_codec_is_rejected() {
+        assert_eq!(
+            check_subtitle_codec(codec::Id::HDMV_PGS_SUBTITLE),
+            Err(MediaError::UnsupportedCodec)
+        );
+        assert_eq!(
+            check_subtitle_codec(codec::Id::DVB_SUBTITLE),
+            Err(MediaError::UnsupportedCodec)
+        );
+    }
+
+    #[test]
+    fn muxed_text_subtitles_demux_into_decodable_frames() {
+        use crate::media::subtitles::{decode::decode_subtitles, SubtitleCue};
+
+        let mkv = crate::media::mkv_subtitles(
+            "S_TEXT/UTF8",
+            &[(0, 1_500, b"Hello"), (2_000, 1_000, b"world")],
+        );
+
+        let out = demux(&mkv, &Deadline::after(std::time::Duration::from_secs(60)));
+        assert!(out.is_ok());
+        let Ok(streams) = out else { return };
+        assert!(streams.audio.is_empty() && streams.video.is_empty());
+
+        // One frame per packet, timestamps from the container
+        assert_eq!(
+            decode_subtitles(&streams.subtitles),
+            Ok(vec![
+                SubtitleCue { start_ms: 0, end_ms: 1_500, text: "Hello".into() },
+                SubtitleCue { start_ms: 2_000, end_ms: 3_000, text: "world".into() },
+            ])
+        );
+    }
+
+    #[test]
+    fn muxed_bitmap_subtitles_are_refused() {
+        let mkv = crate::media::mkv_subtitles("S_HDMV/PGS", &[(0, 1_000, &[0x80, 0x00, 0x00])]);
+
+        assert!(matches!(
+            demux(&mkv, &Deadline::after(std::time::Duration::from_secs(60))),
+            Err(MediaError::UnsupportedCodec)
+        ));
+    }
+
+    #[test]
+    fn timestamps_convert_to_ms() {
+        assert_eq!(ts_to_ms(90_000, Rational::new(1, 90_000)), 1_000);
+        assert_eq!(ts_to_ms(-5, Rational::new(1, 1000)), 0);
+    }
 }
@@ -0,0 +1,91 @@
+//! Container / stream metadata quarantine
+//!
+//! SECURITY:
+//! - Tags (EXIF, XMP, ID3, container tags) are collected only so the
+//!   sanitize stage can COUNT and WIPE them; they never reach output
+//! - Total tag bytes are capped (oversized metadata => reject)
+//! - Buffers are zeroized on drop (tags may carry GPS / identity)
+
+use crate::media::errors::MediaError;
+use crate::media::limits::MAX_METADATA_BYTES;
+
+use ffmpeg_next as ffmpeg;
+use zeroize::Zeroize;
+
+/// Metadata surfaced by the demuxer, pending strip.
+#[derive(Default)]
+pub struct Metadata {
+    /// `key || value` per tag (content is never interpreted)
+    tags: Vec<Vec<u8>>,
+    bytes: usize,
+}
+
+impl Metadata {
+    /// Quarantine one tag; fails once the byte cap is exceeded.
+    pub(crate) fn push(&mut self, key: &str, value: &str) -> Result<(), MediaError> {
+        let len = key.len().saturating_add(value.len());
+        self.bytes = self.bytes.saturating_add(len);
+        if self.bytes > MAX_METADATA_BYTES {
+            return Err(MediaError::DemuxFailed);
+        }
+
+        let mut tag = Vec::with_capacity(len);
+        tag.extend_from_slice(key.as_bytes());
+        tag.extend_from_slice(value.as_bytes());
+        self.tags.push(tag);
+        Ok(())
+    }
+
+    /// Wipe every tag; returns how many were dropped.
+    pub(crate) fn strip(mut self) -> u32 {
+        let count = u32::try_from(self.tags.len()).unwrap_or(u32::MAX);
+        self.tags.zeroize();
+        self.bytes = 0;
+        count
+    }
+}
+
+impl Drop for Metadata {
+    fn drop(&mut self) {
+        self.tags.zeroize();
+    }
+}
+
+/// Collect container + per-stream tags from an opened input.
+pub(crate) fn collect(
+    ictx: &ffmpeg::format::context::Input,
+) -> Result<Metadata, MediaError> {
+    let mut meta = Metadata::default();
+
+    for (k, v) in ictx.metadata().iter() {
+        meta.push(k, v)?;
+    }
+
+    for stream in ictx.streams() {
+        for (k, v) in stream.metadata().iter() {
+            meta.push(k, v)?;
+        }
+    }
+
+    Ok(meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_counted_on_strip() {
+        let mut meta = Metadata::default();
+        assert_eq!(meta.push("GPSLatitude", "48.8584"), Ok(()));
+        assert_eq!(meta.push("artist", "someone"), Ok(()));
+        assert_eq!(meta.strip(), 2);
+    }
+
+    #[test]
+    fn oversized_metadata_is_rejected() {
+        let mut meta = Metadata::default();
+        let big = "x".repeat(MAX_METADATA_BYTES);
+        assert_eq!(meta.push("xmp", &big), Err(MediaError::DemuxFailed));
+    }
+}
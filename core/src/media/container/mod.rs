@@ -1 +1,2 @@
 pub mod demux;
+pub mod metadata;
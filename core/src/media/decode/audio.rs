@@ -1,3 +1,4 @@
+use crate::media::decode::{Corruption, DecodeMode, Deadline};
 use crate::media::errors::MediaError;
 use crate::media::limits::MAX_AUDIO_SAMPLES;
 use crate::keystore::master::GLOBAL_KILLED;
@@ -10,12 +11,26 @@ pub(crate) struct DecodedAudio {
     pub pcm: Vec<i16>,
     pub sample_rate: u32,
     pub channels: u8,
+    /// Corrupt units dropped (lenient mode only)
+    pub skipped: u32,
 }
 
-pub fn decode_audio(input: &[u8]) -> Result<DecodedAudio, MediaError> {
+#[cfg(feature = "wipe-media")]
+impl Drop for DecodedAudio {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.pcm);
+    }
+}
+
+pub fn decode_audio(
+    input: &[u8],
+    mode: DecodeMode,
+    deadline: &Deadline,
+) -> Result<DecodedAudio, MediaError> {
     if GLOBAL_KILLED.load(Ordering::SeqCst) || input.is_empty() {
         return Err(MediaError::DecodeFailed);
     }
+    deadline.check()?;
 
     ffmpeg::init().map_err(|_| MediaError::DecodeFailed)?;
 
@@ -26,7 +41,7 @@ pub fn decode_audio(input: &[u8]) -> Result<DecodedAudio, MediaError> {
 
     let stream = ictx.streams()
         .best(media::Type::Audio)
-        .ok_or(MediaError::DecodeFailed)?;
+        .ok_or(MediaError::NoStreamsFound)?;
 
     let ctx =
         codec::context::Context::from_parameters(stream.parameters())
@@ -37,25 +52,34 @@ pub fn decode_audio(input: &[u8]) -> Result<DecodedAudio, MediaError> {
             .map_err(|_| MediaError::DecodeFailed)?;
 
     let mut pcm = Vec::<i16>::new();
+    let mut corruption = Corruption::new(mode);
 
     for (_, packet) in ictx.packets() {
         if GLOBAL_KILLED.load(Ordering::SeqCst) {
             return Err(MediaError::DecodeFailed);
         }
+        deadline.tick()?;
 
-        decoder.send_packet(&packet).ok();
+        // Strict keeps ignoring send errors (decoder resyncs);
+        // lenient counts them against the corruption budget
+        if decoder.send_packet(&packet).is_err() && corruption.is_lenient() {
+            corruption.skip()?;
+            continue;
+        }
 
         let mut frame = frame::Audio::empty();
         while decoder.receive_frame(&mut frame).is_ok() {
+            deadline.tick()?;
             let data = frame.data(0);
 
             if data.len() % 2 != 0 {
-                return Err(MediaError::DecodeFailed);
+                corruption.skip()?;
+                continue;
             }
 
             for chunk in data.chunks_exact(2) {
                 if pcm.len() >= MAX_AUDIO_SAMPLES {
-                    return Err(MediaError::DecodeFailed);
+                    return Err(MediaError::ByteLimitExceeded);
                 }
                 pcm.push(i16::from_le_bytes([chunk[0], chunk[1]]));
             }
@@ -75,5 +99,6 @@ pub fn decode_audio(input: &[u8]) -> Result<DecodedAudio, MediaError> {
         pcm,
         sample_rate: rate,
         channels: channels as u8,
+        skipped: corruption.skipped(),
     })
 }
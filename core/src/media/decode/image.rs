@@ -0,0 +1,183 @@
+use crate::media::container::demux::open_input;
+use crate::media::container::metadata::{self, Metadata};
+use crate::media::decode::Deadline;
+use crate::media::errors::MediaError;
+use crate::media::limits::{MAX_HEIGHT, MAX_WIDTH};
+use crate::keystore::master::GLOBAL_KILLED;
+
+use ffmpeg_next as ffmpeg;
+use ffmpeg::{codec, frame, media, software::scaling};
+use core::sync::atomic::Ordering;
+
+/// Still-image codecs accepted on the image path.
+pub(crate) const IMAGE_CODECS: &[codec::Id] = &[
+    codec::Id::MJPEG,
+    codec::Id::PNG,
+    codec::Id::WEBP,
+];
+
+/// Max packets read for one still (a still is a single packet;
+/// the slack only lets a trailing empty packet through).
+const MAX_IMAGE_PACKETS: usize = 2;
+
+/// Tightly packed RGBA still (no stride padding, no metadata).
+pub(crate) struct DecodedImage {
+    pub pixels: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    /// EXIF / XMP / ICC tags, quarantined for the sanitize stage
+    pub metadata: Metadata,
+}
+
+#[cfg(feature = "wipe-media")]
+impl Drop for DecodedImage {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.pixels);
+    }
+}
+
+/// Decode exactly ONE still from the original container bytes.
+///
+/// SECURITY:
+/// - Codec allowlist (JPEG / PNG / WebP); anything else => `UnsupportedCodec`
+/// - More than one frame (animation) => `DecodeFailed`
+/// - Only pixels leave as content; tags are quarantined for stripping
+pub fn decode_image(
+    input: &[u8],
+    deadline: &Deadline,
+) -> Result<DecodedImage, MediaError> {
+    if GLOBAL_KILLED.load(Ordering::SeqCst) || input.is_empty() {
+        return Err(MediaError::DecodeFailed);
+    }
+    deadline.check()?;
+
+    let mut ictx = open_input(input)?;
+    let metadata = metadata::collect(&ictx)?;
+
+    let stream = ictx.streams()
+        .best(media::Type::Video)
+        .ok_or(MediaError::NoStreamsFound)?;
+    let index = stream.index();
+
+    // Declared animation is refused before any decoding
+    if stream.frames() > 1 {
+        return Err(MediaError::DecodeFailed);
+    }
+
+    let ctx =
+        codec::context::Context::from_parameters(stream.parameters())
+            .map_err(|_| MediaError::DecodeFailed)?;
+
+    if !IMAGE_CODECS.contains(&ctx.id()) {
+        return Err(MediaError::UnsupportedCodec);
+    }
+
+    let mut decoder =
+        ctx.decoder().video()
+            .map_err(|_| MediaError::DecodeFailed)?;
+
+    let (w, h) = (decoder.width(), decoder.height());
+    if w == 0 || h == 0 {
+        return Err(MediaError::DecodeFailed);
+    }
+    if w > MAX_WIDTH || h > MAX_HEIGHT {
+        return Err(MediaError::DimensionsTooLarge);
+    }
+
+    let mut scaler =
+        scaling::Context::get(
+            decoder.format(),
+            w,
+            h,
+            ffmpeg::format::Pixel::RGBA,
+            w,
+            h,
+            scaling::flag::Flags::BILINEAR,
+        )
+        .map_err(|_| MediaError::DecodeFailed)?;
+
+    let mut pixels: Option<Vec<u8>> = None;
+    let mut packets = 0usize;
+
+    let mut take = |decoder: &mut ffmpeg::decoder::Video| -> Result<(), MediaError> {
+        let mut raw = frame::Video::empty();
+        while decoder.receive_frame(&mut raw).is_ok() {
+            deadline.check()?;
+            // Second frame => animated input
+            if pixels.is_some() {
+                return Err(MediaError::DecodeFailed);
+            }
+
+            let mut rgba = frame::Video::empty();
+            scaler.run(&raw, &mut rgba)
+                .map_err(|_| MediaError::DecodeFailed)?;
+
+            pixels = Some(pack_rgba(rgba.data(0), rgba.stride(0), w, h)?);
+        }
+        Ok(())
+    };
+
+    for (s, packet) in ictx.packets() {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(MediaError::DecodeFailed);
+        }
+        deadline.check()?;
+
+        if s.index() != index {
+            continue;
+        }
+
+        packets += 1;
+        if packets > MAX_IMAGE_PACKETS {
+            return Err(MediaError::DecodeFailed);
+        }
+
+        decoder.send_packet(&packet)
+            .map_err(|_| MediaError::DecodeFailed)?;
+        take(&mut decoder)?;
+    }
+
+    decoder.send_eof().map_err(|_| MediaError::DecodeFailed)?;
+    take(&mut decoder)?;
+
+    Ok(DecodedImage {
+        pixels: pixels.ok_or(MediaError::DecodeFailed)?,
+        width: w,
+        height: h,
+        metadata,
+    })
+}
+
+/// Copy `h` rows of `w` RGBA pixels out of a strided plane.
+pub(crate) fn pack_rgba(plane: &[u8], stride: usize, w: u32, h: u32) -> Result<Vec<u8>, MediaError> {
+    let row = (w as usize).checked_mul(4).ok_or(MediaError::DecodeFailed)?;
+    if h == 0 || stride < row || plane.len() < stride * (h as usize - 1) + row {
+        return Err(MediaError::DecodeFailed);
+    }
+
+    let mut out = Vec::with_capacity(row * h as usize);
+    for y in 0..h as usize {
+        out.extend_from_slice(&plane[y * stride..y * stride + row]);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stride_padding_is_dropped() -> Result<(), MediaError> {
+        // 2x2 RGBA, stride 12 (4 bytes padding per row)
+        let mut plane = vec![0xEEu8; 24];
+        plane[..8].fill(1);
+        plane[12..20].fill(2);
+
+        let px = pack_rgba(&plane, 12, 2, 2)?;
+        assert_eq!(px, [[1u8; 8], [2u8; 8]].concat());
+
+        assert_eq!(pack_rgba(&plane, 4, 2, 2), Err(MediaError::DecodeFailed));
+        assert_eq!(pack_rgba(&plane[..19], 12, 2, 2), Err(MediaError::DecodeFailed));
+        Ok(())
+    }
+}
@@ -1,2 +1,226 @@
+//! Decoding stage (FFmpeg) and its corruption policy
+
+use crate::media::errors::MediaError;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use crate::media::limits::MAX_CORRUPT_UNITS;
+
 pub mod audio;
+pub mod image;
+pub mod thumbnail;
 pub mod video;
+
+/// How decode reacts to corrupt packets / frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodeMode {
+    /// Any bad frame rejects the whole media
+    #[default]
+    Strict,
+    /// Skip up to `MAX_CORRUPT_UNITS` bad packets / frames
+    /// (reported as `MediaWarning::CorruptSkipped`)
+    Lenient,
+}
+
+/// Bad packets / frames seen during ONE decode.
+pub(crate) struct Corruption {
+    mode: DecodeMode,
+    skipped: u32,
+}
+
+impl Corruption {
+    pub(crate) fn new(mode: DecodeMode) -> Self {
+        Self { mode, skipped: 0 }
+    }
+
+    #[inline(always)]
+    pub(crate) fn is_lenient(&self) -> bool {
+        self.mode == DecodeMode::Lenient
+    }
+
+    /// Record one bad packet / frame.
+    ///
+    /// `Ok` => drop it and continue; strict mode, or a budget
+    /// already used up => `DecodeFailed` (fail-closed).
+    pub(crate) fn skip(&mut self) -> Result<(), MediaError> {
+        if !self.is_lenient() || self.skipped >= MAX_CORRUPT_UNITS {
+            return Err(MediaError::DecodeFailed);
+        }
+
+        self.skipped += 1;
+        Ok(())
+    }
+
+    pub(crate) fn skipped(&self) -> u32 {
+        self.skipped
+    }
+}
+
+/// Host-side cancellation + progress for ONE media call.
+///
+/// No threads: the host calls `cancel` from its own thread and the
+/// decode loops observe it between packets / frames (same points
+/// as the deadline), returning `MediaError::Cancelled`.
+#[derive(Default)]
+pub struct MediaControl {
+    cancel: AtomicBool,
+    progress: AtomicU64,
+}
+
+impl MediaControl {
+    pub const fn new() -> Self {
+        Self {
+            cancel: AtomicBool::new(false),
+            progress: AtomicU64::new(0),
+        }
+    }
+
+    /// Request cancellation (sticky; cannot be undone).
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::SeqCst);
+    }
+
+    #[inline(always)]
+    pub fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+
+    /// Packets + frames decoded so far (monotonic; UI only).
+    pub fn progress(&self) -> u64 {
+        self.progress.load(Ordering::Relaxed)
+    }
+
+    #[inline(always)]
+    fn advance(&self) {
+        self.progress.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Wall-clock budget for ONE `process_media` call.
+///
+/// Cooperative (the core has no async): every packet / frame loop
+/// calls `check`, so a decompression bomb is cut off between units.
+/// Optionally carries the host's `MediaControl`.
+#[derive(Clone, Copy)]
+pub struct Deadline<'a> {
+    /// `None` only if `now + budget` overflows `Instant`
+    at: Option<Instant>,
+    ctl: Option<&'a MediaControl>,
+    /// Test-only: checks left before the budget counts as spent
+    #[cfg(test)]
+    checks: Option<&'a AtomicU64>,
+}
+
+impl<'a> Deadline<'a> {
+    pub(crate) fn after(budget: Duration) -> Self {
+        Self {
+            at: Instant::now().checked_add(budget),
+            ctl: None,
+            #[cfg(test)]
+            checks: None,
+        }
+    }
+
+    /// Deadline that expires on check number `checks + 1`, so tests
+    /// can land the expiry mid-loop deterministically.
+    #[cfg(test)]
+    pub(crate) fn after_checks(checks: &'a AtomicU64) -> Self {
+        Self { checks: Some(checks), ..Self::after(Duration::MAX) }
+    }
+
+    /// `after`, also observing `ctl` (cancel + progress).
+    pub(crate) fn with_control(budget: Duration, ctl: &'a MediaControl) -> Self {
+        Self { ctl: Some(ctl), ..Self::after(budget) }
+    }
+
+    /// `Cancelled` once the host cancels; `Timeout` once the budget
+    /// is spent (a zero budget never passes).
+    #[inline(always)]
+    pub(crate) fn check(&self) -> Result<(), MediaError> {
+        if self.ctl.is_some_and(MediaControl::cancelled) {
+            return Err(MediaError::Cancelled);
+        }
+
+        #[cfg(test)]
+        if self.checks.is_some_and(|n| {
+            n.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)).is_err()
+        }) {
+            return Err(MediaError::Timeout);
+        }
+
+        match self.at {
+            Some(at) if Instant::now() >= at => Err(MediaError::Timeout),
+            _ => Ok(()),
+        }
+    }
+
+    /// `check`, then count one decoded packet / frame as progress.
+    #[inline(always)]
+    pub(crate) fn tick(&self) -> Result<(), MediaError> {
+        self.check()?;
+        if let Some(ctl) = self.ctl {
+            ctl.advance();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strict_rejects_first_corrupt_frame() {
+        let mut c = Corruption::new(DecodeMode::Strict);
+        assert_eq!(c.skip(), Err(MediaError::DecodeFailed));
+        assert_eq!(c.skipped(), 0);
+    }
+
+    #[test]
+    fn lenient_skips_up_to_the_threshold() {
+        let mut c = Corruption::new(DecodeMode::Lenient);
+
+        for _ in 0..3 {
+            assert_eq!(c.skip(), Ok(()));
+        }
+        assert_eq!(c.skipped(), 3);
+
+        for _ in 3..MAX_CORRUPT_UNITS {
+            assert_eq!(c.skip(), Ok(()));
+        }
+        assert_eq!(c.skip(), Err(MediaError::DecodeFailed));
+        assert_eq!(c.skipped(), MAX_CORRUPT_UNITS);
+    }
+
+    #[test]
+    fn spent_deadline_times_out() {
+        assert_eq!(Deadline::after(Duration::ZERO).check(), Err(MediaError::Timeout));
+        assert_eq!(Deadline::after(Duration::from_secs(3600)).check(), Ok(()));
+        assert_eq!(Deadline::after(Duration::MAX).check(), Ok(()));
+    }
+
+    #[test]
+    fn check_budget_expires_on_the_next_check() {
+        let checks = AtomicU64::new(2);
+        let deadline = Deadline::after_checks(&checks);
+
+        assert_eq!(deadline.check(), Ok(()));
+        assert_eq!(deadline.check(), Ok(()));
+        assert_eq!(deadline.check(), Err(MediaError::Timeout));
+        assert_eq!(deadline.check(), Err(MediaError::Timeout));
+    }
+
+    #[test]
+    fn cancelled_control_aborts_and_progress_counts_ticks() {
+        let ctl = MediaControl::new();
+        let deadline = Deadline::with_control(Duration::from_secs(3600), &ctl);
+
+        assert_eq!(deadline.tick(), Ok(()));
+        assert_eq!(deadline.tick(), Ok(()));
+        assert_eq!(ctl.progress(), 2);
+
+        ctl.cancel();
+        assert_eq!(deadline.check(), Err(MediaError::Cancelled));
+        assert_eq!(deadline.tick(), Err(MediaError::Cancelled));
+        assert_eq!(ctl.progress(), 2);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::media::container::demux::open_input;
+use crate::media::container::metadata;
+use crate::media::decode::image::{pack_rgba, DecodedImage};
+use crate::media::decode::Deadline;
+use crate::media::errors::MediaError;
+use crate::media::limits::{MAX_HEIGHT, MAX_THUMBNAIL_PACKETS, MAX_WIDTH};
+use crate::media::probe::ALLOWED_VIDEO_CODECS;
+use crate::keystore::master::GLOBAL_KILLED;
+
+use ffmpeg_next as ffmpeg;
+use ffmpeg::{codec, frame, media, software::scaling};
+use core::sync::atomic::Ordering;
+
+/// Decode ONLY the first keyframe, downscaled to fit `max_dim`.
+///
+/// SECURITY:
+/// - Non-key packets are never handed to the decoder
+/// - Kill- and deadline-aware packet loop
+/// - No keyframe within `MAX_THUMBNAIL_PACKETS` => `PacketLimitExceeded`
+pub fn decode_thumbnail(
+    input: &[u8],
+    max_dim: u32,
+    deadline: &Deadline,
+) -> Result<DecodedImage, MediaError> {
+    if GLOBAL_KILLED.load(Ordering::SeqCst) || input.is_empty() {
+        return Err(MediaError::DecodeFailed);
+    }
+    deadline.check()?;
+
+    let mut ictx = open_input(input)?;
+    let metadata = metadata::collect(&ictx)?;
+
+    let stream = ictx.streams()
+        .best(media::Type::Video)
+        .ok_or(MediaError::NoStreamsFound)?;
+    let index = stream.index();
+
+    let ctx =
+        codec::context::Context::from_parameters(stream.parameters())
+            .map_err(|_| MediaError::DecodeFailed)?;
+
+    if !ALLOWED_VIDEO_CODECS.contains(&ctx.id()) {
+        return Err(MediaError::UnsupportedCodec);
+    }
+
+    let mut decoder =
+        ctx.decoder().video()
+            .map_err(|_| MediaError::DecodeFailed)?;
+
+    let (w, h) = (decoder.width(), decoder.height());
+    if w == 0 || h == 0 {
+        return Err(MediaError::DecodeFailed);
+    }
+    if w > MAX_WIDTH || h > MAX_HEIGHT {
+        return Err(MediaError::DimensionsTooLarge);
+    }
+
+    let (tw, th) = fit_within(w, h, max_dim)?;
+
+    let mut scaler =
+        scaling::Context::get(
+            decoder.format(),
+            w,
+            h,
+            ffmpeg::format::Pixel::RGBA,
+            tw,
+            th,
+            scaling::flag::Flags::BILINEAR,
+        )
+        .map_err(|_| MediaError::DecodeFailed)?;
+
+    let mut first = |decoder: &mut ffmpeg::decoder::Video| -> Option<Vec<u8>> {
+        let mut raw = frame::Video::empty();
+        while decoder.receive_frame(&mut raw).is_ok() {
+            let mut rgba = frame::Video::empty();
+            if scaler.run(&raw, &mut rgba).is_err() {
+                continue;
+            }
+            if let Ok(px) = pack_rgba(rgba.data(0), rgba.stride(0), tw, th) {
+                return Some(px);
+            }
+        }
+        None
+    };
+
+    let mut packets = 0usize;
+    let mut pixels = None;
+
+    for (s, packet) in ictx.packets() {
+        if GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return Err(MediaError::DecodeFailed);
+        }
+        deadline.check()?;
+
+        if s.index() != index {
+            continue;
+        }
+
+        packets += 1;
+        if packets > MAX_THUMBNAIL_PACKETS {
+            return Err(MediaError::PacketLimitExceeded);
+        }
+
+        if !packet.is_key() || decoder.send_packet(&packet).is_err() {
+            continue;
+        }
+
+        pixels = first(&mut decoder);
+        if pixels.is_some() {
+            break;
+        }
+    }
+
+    // Decoders with reorder delay only emit the keyframe on flush
+    if pixels.is_none() && decoder.send_eof().is_ok() {
+        pixels = first(&mut decoder);
+    }
+
+    Ok(DecodedImage {
+        pixels: pixels.ok_or(MediaError::DecodeFailed)?,
+        width: tw,
+        height: th,
+        metadata,
+    })
+}
+
+/// Aspect-preserving fit of `w`x`h` into a `max_dim` square (never upscales).
+fn fit_within(w: u32, h: u32, max_dim: u32) -> Result<(u32, u32), MediaError> {
+    if w == 0 || h == 0 || max_dim == 0 {
+        return Err(MediaError::DecodeFailed);
+    }
+
+    let long = w.max(h);
+    if long <= max_dim {
+        return Ok((w, h));
+    }
+
+    let scale = |d: u32| {
+        let v = u64::from(d) * u64::from(max_dim) / u64::from(long);
+        u32::try_from(v).unwrap_or(max_dim).max(1)
+    };
+    Ok((scale(w), scale(h)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumbnail_fits_and_keeps_aspect() {
+        assert_eq!(fit_within(1920, 1080, 320), Ok((320, 180)));
+        assert_eq!(fit_within(1080, 1920, 320), Ok((180, 320)));
+        assert_eq!(fit_within(4096, 1, 64), Ok((64, 1)));
+        assert_eq!(fit_within(100, 50, 320), Ok((100, 50)));
+        assert_eq!(fit_within(100, 50, 0), Err(MediaError::DecodeFailed));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::media::decode::{Corruption, DecodeMode, Deadline};
 use crate::media::errors::MediaError;
 use crate::media::limits::{MAX_HEIGHT, MAX_VIDEO_FRAMES, MAX_WIDTH};
 use crate::keystore::master::GLOBAL_KILLED;
@@ -10,12 +11,26 @@ pub(crate) struct DecodedVideo {
     pub frames: Vec<Vec<u8>>,
     pub width: u32,
     pub height: u32,
+    /// Corrupt units dropped (lenient mode only)
+    pub skipped: u32,
 }
 
-pub fn decode_video(input: &[u8]) -> Result<DecodedVideo, MediaError> {
+#[cfg(feature = "wipe-media")]
+impl Drop for DecodedVideo {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.frames);
+    }
+}
+
+pub fn decode_video(
+    input: &[u8],
+    mode: DecodeMode,
+    deadline: &Deadline,
+) -> Result<DecodedVideo, MediaError> {
     if GLOBAL_KILLED.load(Ordering::SeqCst) || input.is_empty() {
         return Err(MediaError::DecodeFailed);
     }
+    deadline.check()?;
 
     ffmpeg::init().map_err(|_| MediaError::DecodeFailed)?;
 
@@ -26,7 +41,7 @@ pub fn decode_video(input: &[u8]) -> Result<DecodedVideo, MediaError> {
 
     let stream = ictx.streams()
         .best(media::Type::Video)
-        .ok_or(MediaError::DecodeFailed)?;
+        .ok_or(MediaError::NoStreamsFound)?;
 
     let ctx =
         codec::context::Context::from_parameters(stream.parameters())
@@ -37,9 +52,12 @@ pub fn decode_video(input: &[u8]) -> Result<DecodedVideo, MediaError> {
             .map_err(|_| MediaError::DecodeFailed)?;
 
     let (w, h) = (decoder.width(), decoder.height());
-    if w == 0 || h == 0 || w > MAX_WIDTH || h > MAX_HEIGHT {
+    if w == 0 || h == 0 {
         return Err(MediaError::DecodeFailed);
     }
+    if w > MAX_WIDTH || h > MAX_HEIGHT {
+        return Err(MediaError::DimensionsTooLarge);
+    }
 
     let mut scaler =
         scaling::Context::get(
@@ -54,23 +72,33 @@ pub fn decode_video(input: &[u8]) -> Result<DecodedVideo, MediaError> {
         .map_err(|_| MediaError::DecodeFailed)?;
 
     let mut frames = Vec::new();
+    let mut corruption = Corruption::new(mode);
 
     for (_, packet) in ictx.packets() {
         if GLOBAL_KILLED.load(Ordering::SeqCst) {
             return Err(MediaError::DecodeFailed);
         }
+        deadline.tick()?;
 
-        decoder.send_packet(&packet).ok();
+        // Strict keeps ignoring send errors (decoder resyncs);
+        // lenient counts them against the corruption budget
+        if decoder.send_packet(&packet).is_err() && corruption.is_lenient() {
+            corruption.skip()?;
+            continue;
+        }
 
         let mut raw = frame::Video::empty();
         while decoder.receive_frame(&mut raw).is_ok() {
+            deadline.tick()?;
             if frames.len() >= MAX_VIDEO_FRAMES {
-                return Err(MediaError::DecodeFailed);
+                return Err(MediaError::PacketLimitExceeded);
             }
 
             let mut rgba = frame::Video::empty();
-            scaler.run(&raw, &mut rgba)
-                .map_err(|_| MediaError::DecodeFailed)?;
+            if scaler.run(&raw, &mut rgba).is_err() {
+                corruption.skip()?;
+                continue;
+            }
 
             frames.push(rgba.data(0).to_vec());
         }
@@ -82,5 +110,6 @@ pub fn decode_video(input: &[u8]) -> Result<DecodedVideo, MediaError> {
         frames,
         width: w,
         height: h,
+        skipped: corruption.skipped(),
     })
 }
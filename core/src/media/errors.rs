@@ -1,9 +1,37 @@
 //! Media pipeline errors (fail-closed)
+//!
+//! SECURITY:
+//! - Variants are plain tags; none ever carries hostile input
+//!   (tag text, codec names, container strings) back to the host
+//! - `#[non_exhaustive]`: hosts must keep a catch-all arm
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum MediaError {
     InputTooLarge,
     UnsupportedFormat,
+    /// Stream codec is outside the allowlist
+    UnsupportedCodec,
+    /// Declared width / height above `MAX_WIDTH` / `MAX_HEIGHT`
+    DimensionsTooLarge,
+    /// Packet / frame count cap hit while demuxing or decoding
+    PacketLimitExceeded,
+    /// Byte / sample budget hit while demuxing or decoding
+    ByteLimitExceeded,
+    /// Container holds no stream of the requested kind
+    NoStreamsFound,
     DemuxFailed,
     DecodeFailed,
     SanitizationFailed,
+    /// Wall-clock decode deadline exceeded
+    Timeout,
+    /// Host cancelled via `MediaControl::cancel`
+    Cancelled,
+}
+
+/// Non-fatal media condition (output is still sanitized)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaWarning {
+    /// Lenient decode dropped this many corrupt packets / frames
+    CorruptSkipped(u32),
+}
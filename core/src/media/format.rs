@@ -5,4 +5,6 @@
 pub enum MediaFormat {
     Audio,
     Video,
+    /// Single still (JPEG / PNG / WebP); animation is rejected
+    Image,
 }
@@ -11,9 +11,30 @@ pub const MAX_VIDEO_FRAMES: usize = 2_000;
 pub const MAX_WIDTH: u32 = 4096;
 pub const MAX_HEIGHT: u32 = 4096;
 
+/// Max video packets scanned for a thumbnail keyframe
+pub const MAX_THUMBNAIL_PACKETS: usize = 512;
+
+/// Max framed subtitle bytes per stream (demux + decode)
+pub const MAX_SUBTITLE_BYTES: usize = 4 * 1024 * 1024;
+
 /// Max audio samples per track
 pub const MAX_AUDIO_SAMPLES: usize = 10 * 60 * 48_000; // 10 min @ 48kHz
 
+/// Max container-declared duration
+pub const MAX_DURATION_MS: u64 = 60 * 60 * 1000; // 1 hour
+
+/// Max audio channels
+pub const MAX_AUDIO_CHANNELS: u16 = 8;
+
+/// Max audio sample rate
+pub const MAX_SAMPLE_RATE: u32 = 192_000;
+
+/// Max total container + stream tag bytes (metadata is never kept)
+pub const MAX_METADATA_BYTES: usize = 64 * 1024;
+
+/// Max corrupt packets / frames skipped in lenient decode
+pub const MAX_CORRUPT_UNITS: u32 = 32;
+
 #[inline(always)]
 pub fn check_media_size(len: usize) -> bool {
     len <= MAX_MEDIA_BYTES
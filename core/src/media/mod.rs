@@ -5,50 +5,113 @@
 
 use crate::media::{
     container::demux,
-    decode,
+    decode::{self, Deadline, MediaControl},
     errors::MediaError,
     format::MediaFormat,
     limits::check_media_size,
-    output::{SanitizedAudio, SanitizedMedia, SanitizedVideo},
-    sanitize,
+    output::{SanitizedAudio, SanitizedImage, SanitizedMedia, SanitizedVideo},
+    sanitize::{self, SanitizeConfig},
     subtitles,
 };
+use std::time::Duration;
 
+pub mod bundle;
 pub mod container;
 pub mod decode;
 pub mod errors;
 pub mod format;
 pub mod limits;
 pub mod output;
+pub mod probe;
 pub mod sanitize;
 pub mod subtitles;
 
+// Dry-run validation (cheap early rejection, no decode)
+pub use probe::{validate, MediaProbe};
+
+pub use decode::{DecodeMode, MediaControl};
+pub use errors::MediaWarning;
+pub use output::Pcm;
+pub use sanitize::{SampleFormat, SanitizeConfig};
+
 /// 🔒 Single public media entry point
+///
+/// `deadline` is a wall-clock budget for the whole call; exceeding it
+/// aborts with `MediaError::Timeout` (checked per packet / frame).
 pub fn process_media(
     input: &[u8],
     format: MediaFormat,
+    deadline: Duration,
+) -> Result<SanitizedMedia, MediaError> {
+    process_media_with(input, format, &SanitizeConfig::default(), deadline)
+}
+
+/// `process_media` with explicit sanitization options.
+///
+/// Options select output representation only; limits are fixed.
+pub fn process_media_with(
+    input: &[u8],
+    format: MediaFormat,
+    config: &SanitizeConfig,
+    deadline: Duration,
+) -> Result<SanitizedMedia, MediaError> {
+    process_media_controlled(input, format, config, deadline, &MediaControl::new())
+}
+
+/// `process_media_with`, observing a host `MediaControl`.
+///
+/// The host may `ctl.cancel()` from another thread at any time
+/// (=> `MediaError::Cancelled` at the next packet / frame) and poll
+/// `ctl.progress()` for UI. Kill and deadline still apply.
+pub fn process_media_controlled(
+    input: &[u8],
+    format: MediaFormat,
+    config: &SanitizeConfig,
+    deadline: Duration,
+    ctl: &MediaControl,
 ) -> Result<SanitizedMedia, MediaError> {
     if !check_media_size(input.len()) {
         return Err(MediaError::InputTooLarge);
     }
 
-    let streams = demux::demux(input)?;
+    sanitize_within(input, format, config, &Deadline::with_control(deadline, ctl))
+}
+
+/// Pipeline body, against an already-running `deadline`.
+fn sanitize_within(
+    input: &[u8],
+    format: MediaFormat,
+    config: &SanitizeConfig,
+    deadline: &Deadline,
+) -> Result<SanitizedMedia, MediaError> {
+    // A still is decoded straight from its container (no stream split)
+    if format == MediaFormat::Image {
+        let decoded = decode::image::decode_image(input, deadline)?;
+        let safe = sanitize::image::sanitize_image(decoded)?;
+        return Ok(SanitizedMedia::Image(safe));
+    }
+
+    let streams = demux::demux(input, deadline)?;
 
     match format {
         MediaFormat::Audio => {
-            let decoded = decode::audio::decode_audio(&streams.audio)?;
-            let safe = sanitize::audio::sanitize_audio(decoded)?;
+            let decoded = decode::audio::decode_audio(&streams.audio, config.decode, deadline)?;
+            let warnings = corruption_warnings(decoded.skipped);
+            let safe = sanitize::audio::sanitize_audio(decoded, config, streams.metadata)?;
 
             Ok(SanitizedMedia::Audio(SanitizedAudio {
                 pcm: safe.pcm,
                 sample_rate: safe.sample_rate,
                 channels: safe.channels,
+                stripped_tags: safe.stripped_tags,
+                warnings,
             }))
         }
 
         MediaFormat::Video => {
-    let decoded = decode::video::decode_video(&streams.video)?;
-    let safe_core = sanitize::video::sanitize_video(decoded)?;
+    let decoded = decode::video::decode_video(&streams.video, config.decode, deadline)?;
+    let warnings = corruption_warnings(decoded.skipped);
+    let mut safe_core = sanitize::video::sanitize_video(decoded, streams.metadata)?;
 
     let subtitles = match subtitles::decode::decode_subtitles(&streams.subtitles) {
         Ok(s) => s,
@@ -56,12 +119,194 @@ pub fn process_media(
     };
 
     Ok(SanitizedMedia::Video(SanitizedVideo {
-        frames: safe_core.frames,
+        frames: core::mem::take(&mut safe_core.frames),
         width: safe_core.width,
         height: safe_core.height,
         subtitles,
+        stripped_tags: safe_core.stripped_tags,
+        warnings,
     }))
 }
+
+        // Handled above
+        MediaFormat::Image => Err(MediaError::UnsupportedFormat),
+    }
+}
+
+/// Poster frame: first keyframe only, downscaled to fit `max_dim`.
+///
+/// Far cheaper (and a smaller attack surface) than `process_media`
+/// for UI previews; same size, kill and resolution limits.
+pub fn process_thumbnail(
+    input: &[u8],
+    max_dim: u32,
+    deadline: Duration,
+) -> Result<SanitizedImage, MediaError> {
+    if !check_media_size(input.len()) {
+        return Err(MediaError::InputTooLarge);
+    }
+
+    let deadline = Deadline::after(deadline);
+    let decoded = decode::thumbnail::decode_thumbnail(input, max_dim, &deadline)?;
+    sanitize::image::sanitize_image(decoded)
+}
+
+fn corruption_warnings(skipped: u32) -> Vec<MediaWarning> {
+    if skipped == 0 {
+        Vec::new()
+    } else {
+        vec![MediaWarning::CorruptSkipped(skipped)]
     }
 }
 
+/// Test fixture: minimal RIFF/WAVE, 8 kHz mono s16le, `samples`
+/// of silence (demuxes into many packets).
+#[cfg(test)]
+pub(crate) fn pcm_wav(samples: u32) -> Vec<u8> {
+    let data_len = samples * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&8_000u32.to_le_bytes());
+    wav.extend_from_slice(&16_000u32.to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    wav.resize(44 + data_len as usize, 0);
+    wav
+}
+
+/// Test fixture: minimal Matroska with ONE subtitle track of
+/// `codec_id` (e.g. `S_TEXT/UTF8`). Each cue is
+/// `(start_ms, duration_ms, payload)`, all in a single cluster.
+#[cfg(test)]
+pub(crate) fn mkv_subtitles(codec_id: &str, cues: &[(i16, u64, &[u8])]) -> Vec<u8> {
+    // EBML element: id || size (8-byte vint) || body
+    fn el(id: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut out = id.to_vec();
+        out.push(0x01);
+        out.extend_from_slice(&(body.len() as u64).to_be_bytes()[1..]);
+        out.extend_from_slice(body);
+        out
+    }
+
+    fn uint(id: &[u8], value: u64) -> Vec<u8> {
+        el(id, &value.to_be_bytes())
+    }
+
+    let header = [
+        uint(&[0x42, 0x86], 1), // EBMLVersion
+        uint(&[0x42, 0xF7], 1), // EBMLReadVersion
+        uint(&[0x42, 0xF2], 4), // EBMLMaxIDLength
+        uint(&[0x42, 0xF3], 8), // EBMLMaxSizeLength
+        el(&[0x42, 0x82], b"matroska"),
+        uint(&[0x42, 0x87], 4), // DocTypeVersion
+        uint(&[0x42, 0x85], 2), // DocTypeReadVersion
+    ]
+    .concat();
+
+    let info = [
+        uint(&[0x2A, 0xD7, 0xB1], 1_000_000), // 1 ms timestamps
+        el(&[0x4D, 0x80], b"rcx"),
+        el(&[0x57, 0x41], b"rcx"),
+    ]
+    .concat();
+
+    let track = [
+        uint(&[0xD7], 1), // TrackNumber
+        uint(&[0x73, 0xC5], 1), // TrackUID
+        uint(&[0x83], 0x11), // TrackType: subtitle
+        el(&[0x86], codec_id.as_bytes()),
+    ]
+    .concat();
+
+    let mut cluster = uint(&[0xE7], 0);
+    for (start_ms, duration_ms, payload) in cues {
+        // Block: track 1 || relative timestamp (i16) || flags
+        let mut block = vec![0x81];
+        block.extend_from_slice(&start_ms.to_be_bytes());
+        block.push(0x00);
+        block.extend_from_slice(payload);
+
+        let group = [el(&[0xA1], &block), uint(&[0x9B], *duration_ms)].concat();
+        cluster.extend_from_slice(&el(&[0xA0], &group));
+    }
+
+    let segment = [
+        el(&[0x15, 0x49, 0xA9, 0x66], &info),
+        el(&[0x16, 0x54, 0xAE, 0x6B], &el(&[0xAE], &track)),
+        el(&[0x1F, 0x43, 0xB6, 0x75], &cluster),
+    ]
+    .concat();
+
+    [
+        el(&[0x1A, 0x45, 0xDF, 0xA3], &header),
+        el(&[0x18, 0x53, 0x80, 0x67], &segment),
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn spent_deadline_aborts_instead_of_decoding() {
+        // Synthetic MPEG-TS-looking junk; must never reach ffmpeg
+        let input = [0x47u8, 0x40, 0x00, 0x10].repeat(64 * 1024);
+
+        for format in [MediaFormat::Audio, MediaFormat::Video, MediaFormat::Image] {
+            let out = process_media(&input, format, Duration::ZERO);
+            assert!(matches!(out, Err(MediaError::Timeout)));
+        }
+        assert!(matches!(
+            process_thumbnail(&input, 64, Duration::ZERO),
+            Err(MediaError::Timeout)
+        ));
+    }
+
+    #[test]
+    fn cancelled_control_aborts_before_decoding() {
+        let input = [0x47u8, 0x40, 0x00, 0x10].repeat(64 * 1024);
+        let ctl = MediaControl::new();
+        ctl.cancel();
+
+        for format in [MediaFormat::Audio, MediaFormat::Video, MediaFormat::Image] {
+            let out = process_media_controlled(
+                &input,
+                format,
+                &SanitizeConfig::default(),
+                Duration::from_secs(3600),
+                &ctl,
+            );
+            assert!(matches!(out, Err(MediaError::Cancelled)));
+        }
+    }
+
+    #[test]
+    fn deadline_expiring_mid_demux_aborts() {
+        // 2 s of audio: well over a handful of demuxer packets
+        let input = pcm_wav(16_000);
+        assert!(demux::demux(&input, &Deadline::after(Duration::from_secs(3600)))
+            .is_ok_and(|s| !s.audio.is_empty()));
+
+        // Entry check + first packets pass, then the budget is spent
+        let checks = AtomicU64::new(3);
+        let out = sanitize_within(
+            &input,
+            MediaFormat::Audio,
+            &SanitizeConfig::default(),
+            &Deadline::after_checks(&checks),
+        );
+        assert!(matches!(out, Err(MediaError::Timeout)));
+
+        // Expired inside the packet loop, not at entry
+        assert_eq!(checks.load(Ordering::SeqCst), 0);
+    }
+}
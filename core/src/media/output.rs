@@ -1,12 +1,53 @@
 //! Sanitized media output (TRUST BOUNDARY)
+//!
+//! With `wipe-media`, PCM, frame and pixel buffers are zeroized on drop
+//! (user content is not a key, but it is still wiped).
 
+use crate::media::errors::MediaWarning;
 use crate::media::subtitles::SubtitleCue;
+#[cfg(feature = "wipe-media")]
+use zeroize::Zeroize;
+
+/// Canonical PCM samples (interleaved)
+pub enum Pcm {
+    /// Signed 16-bit
+    I16(Vec<i16>),
+    /// 32-bit float, normalized to [-1.0, 1.0)
+    F32(Vec<f32>),
+}
+
+impl Pcm {
+    /// Total interleaved sample count (any format)
+    pub fn len(&self) -> usize {
+        match self {
+            Pcm::I16(s) => s.len(),
+            Pcm::F32(s) => s.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(feature = "wipe-media")]
+impl Drop for Pcm {
+    fn drop(&mut self) {
+        match self {
+            Pcm::I16(s) => s.zeroize(),
+            Pcm::F32(s) => s.zeroize(),
+        }
+    }
+}
 
 /// Canonical PCM audio
 pub struct SanitizedAudio {
-    pub pcm: Vec<i16>,
+    pub pcm: Pcm,
     pub sample_rate: u32,
     pub channels: u8,
+    /// Metadata tags dropped during sanitization
+    pub stripped_tags: u32,
+    pub warnings: Vec<MediaWarning>,
 }
 
 /// Canonical video output
@@ -15,9 +56,120 @@ pub struct SanitizedVideo {
     pub width: u32,
     pub height: u32,
     pub subtitles: Vec<SubtitleCue>,
+    /// Metadata tags dropped during sanitization
+    pub stripped_tags: u32,
+    pub warnings: Vec<MediaWarning>,
+}
+
+#[cfg(feature = "wipe-media")]
+impl Drop for SanitizedVideo {
+    fn drop(&mut self) {
+        self.frames.zeroize();
+    }
+}
+
+/// Canonical still image (metadata stripped)
+pub struct SanitizedImage {
+    pub pixels: Vec<u8>, // RGBA, tightly packed
+    pub width: u32,
+    pub height: u32,
+    /// Metadata tags dropped during sanitization
+    pub stripped_tags: u32,
+}
+
+#[cfg(feature = "wipe-media")]
+impl Drop for SanitizedImage {
+    fn drop(&mut self) {
+        self.pixels.zeroize();
+    }
 }
+
 #[non_exhaustive]
 pub enum SanitizedMedia {
     Audio(SanitizedAudio),
     Video(SanitizedVideo),
+    Image(SanitizedImage),
+}
+
+#[cfg(all(test, feature = "wipe-media"))]
+#[allow(unsafe_code)]
+mod tests {
+    use super::*;
+    use crate::media::decode::audio::DecodedAudio;
+    use crate::media::decode::video::DecodedVideo;
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::alloc::{GlobalAlloc, Layout, System};
+
+    /// Tracking allocator: records whether the watched block was
+    /// all-zero at the moment it was freed.
+    struct Tracking;
+
+    static WATCHED: AtomicUsize = AtomicUsize::new(0);
+    static WIPED: AtomicBool = AtomicBool::new(false);
+
+    unsafe impl GlobalAlloc for Tracking {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            if ptr as usize == WATCHED.load(Ordering::SeqCst) {
+                let bytes = core::slice::from_raw_parts(ptr, layout.size());
+                WIPED.store(bytes.iter().all(|b| *b == 0), Ordering::SeqCst);
+                WATCHED.store(0, Ordering::SeqCst);
+            }
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOC: Tracking = Tracking;
+
+    fn freed_zeroed<T>(block: *const T, value: impl Sized) -> bool {
+        WIPED.store(false, Ordering::SeqCst);
+        WATCHED.store(block as usize, Ordering::SeqCst);
+        drop(value);
+        WIPED.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn media_buffers_are_zeroized_on_drop() {
+        let decoded = DecodedAudio {
+            pcm: vec![0x5A5A; 256],
+            sample_rate: 48_000,
+            channels: 2,
+            skipped: 0,
+        };
+        assert!(freed_zeroed(decoded.pcm.as_ptr(), decoded));
+
+        let decoded = DecodedVideo {
+            frames: vec![vec![0xFF; 256]],
+            width: 8,
+            height: 8,
+            skipped: 0,
+        };
+        assert!(freed_zeroed(decoded.frames[0].as_ptr(), decoded));
+
+        let samples = vec![0.5f32; 256];
+        let block = samples.as_ptr();
+        assert!(freed_zeroed(block, Pcm::F32(samples)));
+
+        let video = SanitizedVideo {
+            frames: vec![vec![0xFF; 256]],
+            width: 8,
+            height: 8,
+            subtitles: Vec::new(),
+            stripped_tags: 0,
+            warnings: Vec::new(),
+        };
+        assert!(freed_zeroed(video.frames[0].as_ptr(), video));
+
+        let image = SanitizedImage {
+            pixels: vec![0xFF; 256],
+            width: 8,
+            height: 8,
+            stripped_tags: 0,
+        };
+        assert!(freed_zeroed(image.pixels.as_ptr(), image));
+    }
 }
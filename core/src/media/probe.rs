@@ -0,0 +1,232 @@
+//! Media dry-run validation (NO decode, NO sanitize)
+//!
+//! SECURITY:
+//! - Same size + kill checks as `process_media`
+//! - Container parameters only (no frame / sample decoding)
+//! - Codec allowlist enforced
+//! - Fail-closed on any missing or out-of-limit parameter
+
+use crate::media::container::demux::open_input;
+use crate::media::decode::image::IMAGE_CODECS;
+use crate::media::errors::MediaError;
+use crate::media::format::MediaFormat;
+use crate::media::limits::{
+    check_media_size, MAX_AUDIO_CHANNELS, MAX_DURATION_MS, MAX_HEIGHT,
+    MAX_SAMPLE_RATE, MAX_WIDTH,
+};
+
+use ffmpeg_next as ffmpeg;
+use ffmpeg::{codec, media};
+
+/* ───────────── CODEC ALLOWLIST ───────────── */
+
+pub(crate) const ALLOWED_VIDEO_CODECS: &[codec::Id] = &[
+    codec::Id::H264,
+    codec::Id::HEVC,
+    codec::Id::VP8,
+    codec::Id::VP9,
+    codec::Id::AV1,
+];
+
+const ALLOWED_AUDIO_CODECS: &[codec::Id] = &[
+    codec::Id::AAC,
+    codec::Id::MP3,
+    codec::Id::OPUS,
+    codec::Id::VORBIS,
+    codec::Id::FLAC,
+    codec::Id::PCM_S16LE,
+];
+
+/* ───────────── OUTPUT ───────────── */
+
+/// Non-secret summary of a validated media file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MediaProbe {
+    pub format: MediaFormat,
+    pub duration_ms: u64,
+    /// Video / image only (0 for audio)
+    pub width: u32,
+    pub height: u32,
+    /// Audio only (0 for video / image)
+    pub channels: u16,
+    pub sample_rate: u32,
+}
+
+/* ───────────── ENTRY POINT ───────────── */
+
+/// Validate hostile input WITHOUT running decode / sanitize.
+///
+/// Much cheaper than `process_media`; intended for early rejection.
+/// A passing probe does NOT guarantee `process_media` succeeds.
+pub fn validate(
+    input: &[u8],
+    format: MediaFormat,
+) -> Result<MediaProbe, MediaError> {
+    if !check_media_size(input.len()) {
+        return Err(MediaError::InputTooLarge);
+    }
+
+    let ictx = open_input(input)?;
+
+    let kind = match format {
+        MediaFormat::Audio => media::Type::Audio,
+        MediaFormat::Video | MediaFormat::Image => media::Type::Video,
+    };
+
+    let stream = ictx
+        .streams()
+        .best(kind)
+        .ok_or(MediaError::NoStreamsFound)?;
+
+    // Container duration is in AV_TIME_BASE (microseconds);
+    // a still has none
+    let duration_ms = match format {
+        MediaFormat::Image => 0,
+        _ => u64::try_from(ictx.duration())
+            .map_err(|_| MediaError::DemuxFailed)?
+            / 1000,
+    };
+
+    let ctx = codec::context::Context::from_parameters(stream.parameters())
+        .map_err(|_| MediaError::DemuxFailed)?;
+
+    let probe = match format {
+        MediaFormat::Video | MediaFormat::Image => {
+            let allowed = match format {
+                MediaFormat::Image => IMAGE_CODECS,
+                _ => ALLOWED_VIDEO_CODECS,
+            };
+            if !allowed.contains(&ctx.id()) {
+                return Err(MediaError::UnsupportedCodec);
+            }
+
+            // Animated stills are not images
+            if format == MediaFormat::Image && stream.frames() > 1 {
+                return Err(MediaError::DecodeFailed);
+            }
+
+            let video = ctx
+                .decoder()
+                .video()
+                .map_err(|_| MediaError::DemuxFailed)?;
+
+            MediaProbe {
+                format,
+                duration_ms,
+                width: video.width(),
+                height: video.height(),
+                channels: 0,
+                sample_rate: 0,
+            }
+        }
+
+        MediaFormat::Audio => {
+            if !ALLOWED_AUDIO_CODECS.contains(&ctx.id()) {
+                return Err(MediaError::UnsupportedCodec);
+            }
+
+            let audio = ctx
+                .decoder()
+                .audio()
+                .map_err(|_| MediaError::DemuxFailed)?;
+
+            MediaProbe {
+                format,
+                duration_ms,
+                width: 0,
+                height: 0,
+                channels: audio.channels(),
+                sample_rate: audio.rate(),
+            }
+        }
+    };
+
+    check_probe_limits(&probe)?;
+    Ok(probe)
+}
+
+/* ───────────── LIMITS ───────────── */
+
+/// Enforce resource limits on probed parameters.
+fn check_probe_limits(p: &MediaProbe) -> Result<(), MediaError> {
+    if p.duration_ms > MAX_DURATION_MS {
+        return Err(MediaError::InputTooLarge);
+    }
+
+    match p.format {
+        MediaFormat::Video | MediaFormat::Image => {
+            if p.width == 0 || p.height == 0 {
+                return Err(MediaError::UnsupportedFormat);
+            }
+            if p.width > MAX_WIDTH || p.height > MAX_HEIGHT {
+                return Err(MediaError::DimensionsTooLarge);
+            }
+        }
+
+        MediaFormat::Audio => {
+            if p.channels == 0 || p.sample_rate == 0 {
+                return Err(MediaError::UnsupportedFormat);
+            }
+            if p.channels > MAX_AUDIO_CHANNELS || p.sample_rate > MAX_SAMPLE_RATE {
+                return Err(MediaError::InputTooLarge);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(width: u32, height: u32) -> MediaProbe {
+        MediaProbe {
+            format: MediaFormat::Video,
+            duration_ms: 30_000,
+            width,
+            height,
+            channels: 0,
+            sample_rate: 0,
+        }
+    }
+
+    #[test]
+    fn in_limits_probe_is_accepted() {
+        assert_eq!(check_probe_limits(&video(1920, 1080)), Ok(()));
+    }
+
+    #[test]
+    fn oversized_probe_is_rejected() {
+        assert_eq!(
+            check_probe_limits(&video(MAX_WIDTH + 1, 1080)),
+            Err(MediaError::DimensionsTooLarge)
+        );
+    }
+
+    #[test]
+    fn empty_input_is_rejected() {
+        assert!(validate(&[], MediaFormat::Video).is_err());
+    }
+
+    #[test]
+    fn encoded_fixture_reports_container_parameters() {
+        // 2 s of 8 kHz mono PCM through the real demuxer
+        let wav = crate::media::pcm_wav(16_000);
+
+        assert_eq!(
+            validate(&wav, MediaFormat::Audio),
+            Ok(MediaProbe {
+                format: MediaFormat::Audio,
+                duration_ms: 2_000,
+                width: 0,
+                height: 0,
+                channels: 1,
+                sample_rate: 8_000,
+            })
+        );
+
+        // No video stream in an audio-only container
+        assert_eq!(validate(&wav, MediaFormat::Video), Err(MediaError::NoStreamsFound));
+    }
+}
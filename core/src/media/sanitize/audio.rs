@@ -1,19 +1,125 @@
-
 //! Audio sanitization (canonicalization)
 
+use crate::media::container::metadata::Metadata;
 use crate::media::decode::audio::DecodedAudio;
 use crate::media::errors::MediaError;
+use crate::media::limits::MAX_AUDIO_SAMPLES;
+use crate::media::output::Pcm;
+use crate::media::sanitize::{SampleFormat, SanitizeConfig};
+
+/// Internal sanitized audio (format selected by `SanitizeConfig`)
+pub(crate) struct SafeAudio {
+    pub pcm: Pcm,
+    pub sample_rate: u32,
+    pub channels: u8,
+    pub stripped_tags: u32,
+}
 
 pub(crate) fn sanitize_audio(
-    decoded: DecodedAudio,
-) -> Result<DecodedAudio, MediaError> {
+    mut decoded: DecodedAudio,
+    config: &SanitizeConfig,
+    metadata: Metadata,
+) -> Result<SafeAudio, MediaError> {
+    // Tags are wiped first, whatever the outcome below
+    let stripped_tags = metadata.strip();
+
     if decoded.sample_rate == 0 {
         return Err(MediaError::SanitizationFailed);
     }
     if decoded.channels == 0 {
         return Err(MediaError::SanitizationFailed);
     }
+    // Limit applies regardless of output sample format
+    if decoded.pcm.len() > MAX_AUDIO_SAMPLES {
+        return Err(MediaError::SanitizationFailed);
+    }
 
-    Ok(decoded)
+    let pcm = match config.sample_format {
+        SampleFormat::I16 => Pcm::I16(core::mem::take(&mut decoded.pcm)),
+        SampleFormat::F32 => Pcm::F32(
+            decoded
+                .pcm
+                .iter()
+                .map(|&s| f32::from(s) / 32_768.0)
+                .collect(),
+        ),
+    };
+
+    Ok(SafeAudio {
+        pcm,
+        sample_rate: decoded.sample_rate,
+        channels: decoded.channels,
+        stripped_tags,
+    })
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decoded(pcm: Vec<i16>) -> DecodedAudio {
+        DecodedAudio {
+            pcm,
+            sample_rate: 48_000,
+            channels: 2,
+            skipped: 0,
+        }
+    }
+
+    const SAMPLES: [i16; 4] = [i16::MIN, -1, 0, i16::MAX];
+
+    #[test]
+    fn i16_output_is_passthrough() {
+        let out = sanitize_audio(
+            decoded(SAMPLES.to_vec()),
+            &SanitizeConfig::default(),
+            Metadata::default(),
+        );
+        assert!(matches!(out, Ok(SafeAudio { pcm: Pcm::I16(ref s), .. }) if s[..] == SAMPLES));
+    }
+
+    #[test]
+    fn f32_output_is_normalized() -> Result<(), MediaError> {
+        let config = SanitizeConfig {
+            sample_format: SampleFormat::F32,
+            ..SanitizeConfig::default()
+        };
+
+        let out = sanitize_audio(decoded(SAMPLES.to_vec()), &config, Metadata::default())?;
+        assert_eq!(out.pcm.len(), SAMPLES.len());
+
+        assert!(matches!(&out.pcm, Pcm::F32(_)));
+        if let Pcm::F32(s) = &out.pcm {
+            assert!(s.iter().all(|v| (-1.0..1.0).contains(v)));
+            assert_eq!(s[0], -1.0);
+            assert_eq!(s[2], 0.0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sample_limit_applies_to_every_format() {
+        for sample_format in [SampleFormat::I16, SampleFormat::F32] {
+            let out = sanitize_audio(
+                decoded(vec![0; MAX_AUDIO_SAMPLES + 1]),
+                &SanitizeConfig {
+                    sample_format,
+                    ..SanitizeConfig::default()
+                },
+                Metadata::default(),
+            );
+            assert!(matches!(out, Err(MediaError::SanitizationFailed)));
+        }
+    }
+
+    #[test]
+    fn hostile_tags_are_stripped_and_counted() {
+        let mut meta = Metadata::default();
+        for (k, v) in [("TPE1", "artist"), ("GPSLatitude", "48.8584"), ("XMP", "<x/>")] {
+            assert_eq!(meta.push(k, v), Ok(()));
+        }
+
+        let out = sanitize_audio(decoded(SAMPLES.to_vec()), &SanitizeConfig::default(), meta);
+        assert!(matches!(out, Ok(SafeAudio { stripped_tags: 3, .. })));
+    }
+}
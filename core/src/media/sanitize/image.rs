@@ -0,0 +1,29 @@
+//! Image sanitization (canonical RGBA still)
+
+use crate::media::decode::image::DecodedImage;
+use crate::media::errors::MediaError;
+use crate::media::output::SanitizedImage;
+
+/// Re-check geometry against the pixel buffer; only pixels survive.
+pub(crate) fn sanitize_image(
+    mut decoded: DecodedImage,
+) -> Result<SanitizedImage, MediaError> {
+    // Tags are wiped first, whatever the outcome below
+    let stripped_tags = core::mem::take(&mut decoded.metadata).strip();
+
+    let expected = (decoded.width as usize)
+        .checked_mul(decoded.height as usize)
+        .and_then(|n| n.checked_mul(4))
+        .ok_or(MediaError::SanitizationFailed)?;
+
+    if expected == 0 || decoded.pixels.len() != expected {
+        return Err(MediaError::SanitizationFailed);
+    }
+
+    Ok(SanitizedImage {
+        pixels: core::mem::take(&mut decoded.pixels),
+        width: decoded.width,
+        height: decoded.height,
+        stripped_tags,
+    })
+}
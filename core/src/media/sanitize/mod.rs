@@ -1,2 +1,23 @@
+//! Sanitization (canonicalization) stage configuration
+
+use crate::media::decode::DecodeMode;
+
 pub mod audio;
+pub mod image;
 pub mod video;
+
+/// Output PCM sample format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SampleFormat {
+    #[default]
+    I16,
+    F32,
+}
+
+/// Sanitization options (limits are NOT configurable)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SanitizeConfig {
+    pub sample_format: SampleFormat,
+    /// Corrupt-frame policy (the skip threshold is fixed)
+    pub decode: DecodeMode,
+}
@@ -1,5 +1,6 @@
 //! Video sanitization (canonical frames)
 
+use crate::media::container::metadata::Metadata;
 use crate::media::decode::video::DecodedVideo;
 use crate::media::errors::MediaError;
 
@@ -8,19 +9,32 @@ pub(crate) struct SafeVideoCore {
     pub frames: Vec<Vec<u8>>,
     pub width: u32,
     pub height: u32,
+    pub stripped_tags: u32,
+}
+
+#[cfg(feature = "wipe-media")]
+impl Drop for SafeVideoCore {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.frames);
+    }
 }
 
 pub(crate) fn sanitize_video(
-    decoded: DecodedVideo,
+    mut decoded: DecodedVideo,
+    metadata: Metadata,
 ) -> Result<SafeVideoCore, MediaError> {
+    // Tags are wiped first, whatever the outcome below
+    let stripped_tags = metadata.strip();
+
     if decoded.width == 0 || decoded.height == 0 {
         return Err(MediaError::SanitizationFailed);
     }
 
     Ok(SafeVideoCore {
-        frames: decoded.frames,
+        frames: core::mem::take(&mut decoded.frames),
         width: decoded.width,
         height: decoded.height,
+        stripped_tags,
     })
 }
 
@@ -1,24 +1,117 @@
 //! Subtitle decoding (best-effort, text only)
 
 use crate::media::errors::MediaError;
-use super::SubtitleCue;
+use crate::media::limits::MAX_SUBTITLE_BYTES;
+use super::text::{clean_text, has_cue_timing, parse_cues};
+use super::{SubtitleCue, FRAME_HEADER_LEN};
 
-/// Decode subtitles from hostile media input.
+/// Max cues accepted from one stream.
+const MAX_CUES: usize = 50_000;
+
+/// Decode framed subtitle packets (see `subtitles` framing).
 ///
 /// SECURITY:
-/// - Text-only subtitles
-/// - Best-effort (failure is NON-fatal)
+/// - Text-only subtitles (non-text codecs rejected at demux)
+/// - Best-effort (failure is NON-fatal to the caller)
+/// - Input over `MAX_SUBTITLE_BYTES` => `DecodeFailed`
+/// - Truncated / malformed frame => `DecodeFailed`
+/// - Non-UTF-8 payload => `DecodeFailed`
+/// - Raw SRT / WebVTT payloads are parsed for their own timing;
+///   malformed or control-character cues are skipped (see `text`)
 /// - No panics
 /// - No logging
 /// - No filesystem access
-///
-/// NOTE:
-/// ffmpeg-next integration will be implemented here.
-/// This stub is intentionally safe and minimal.
 pub fn decode_subtitles(
-    _input: &[u8],
+    input: &[u8],
 ) -> Result<Vec<SubtitleCue>, MediaError> {
-    // ffmpeg-next subtitle decoding goes here
-    // Any failure should return MediaError::DecodeFailed
-    Ok(Vec::new())
+    if input.len() > MAX_SUBTITLE_BYTES {
+        return Err(MediaError::DecodeFailed);
+    }
+
+    let mut cues = Vec::new();
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        if rest.len() < FRAME_HEADER_LEN || cues.len() >= MAX_CUES {
+            return Err(MediaError::DecodeFailed);
+        }
+
+        let (header, body) = rest.split_at(FRAME_HEADER_LEN);
+
+        let mut start = [0u8; 8];
+        let mut end = [0u8; 8];
+        let mut len = [0u8; 4];
+        start.copy_from_slice(&header[0..8]);
+        end.copy_from_slice(&header[8..16]);
+        len.copy_from_slice(&header[16..20]);
+
+        let len = u32::from_be_bytes(len) as usize;
+        if body.len() < len {
+            return Err(MediaError::DecodeFailed);
+        }
+
+        let (payload, tail) = body.split_at(len);
+
+        let text = core::str::from_utf8(payload)
+            .map_err(|_| MediaError::DecodeFailed)?;
+
+        if has_cue_timing(text) {
+            parse_cues(text, &mut cues, MAX_CUES);
+        } else if let Some(text) = clean_text(text) {
+            cues.push(SubtitleCue {
+                start_ms: u64::from_be_bytes(start),
+                end_ms: u64::from_be_bytes(end),
+                text,
+            });
+        }
+
+        rest = tail;
+    }
+
+    Ok(cues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::subtitles::write_frame;
+
+    #[test]
+    fn framed_packets_decode_per_cue() {
+        let mut framed = Vec::new();
+        assert_eq!(write_frame(&mut framed, 0, 1_500, b"Hello"), Ok(()));
+        assert_eq!(write_frame(&mut framed, 2_000, 3_000, b"world"), Ok(()));
+
+        let cues = decode_subtitles(&framed);
+
+        assert_eq!(
+            cues,
+            Ok(vec![
+                SubtitleCue { start_ms: 0, end_ms: 1_500, text: "Hello".into() },
+                SubtitleCue { start_ms: 2_000, end_ms: 3_000, text: "world".into() },
+            ])
+        );
+    }
+
+    #[test]
+    fn truncated_frame_is_rejected() {
+        let mut framed = Vec::new();
+        assert_eq!(write_frame(&mut framed, 0, 10, b"cue"), Ok(()));
+        framed.pop();
+
+        assert_eq!(decode_subtitles(&framed), Err(MediaError::DecodeFailed));
+    }
+
+    #[test]
+    fn raw_srt_payload_uses_its_own_timing() {
+        let mut framed = Vec::new();
+        let srt = b"1\n00:00:01,000 --> 00:00:02,000\nHi\n\n2\nbad --> 00:00:03,000\nno\n";
+        assert_eq!(write_frame(&mut framed, 0, 0, srt), Ok(()));
+        assert_eq!(write_frame(&mut framed, 5_000, 6_000, b"esc\x1b[31m"), Ok(()));
+
+        assert_eq!(
+            decode_subtitles(&framed),
+            Ok(vec![SubtitleCue { start_ms: 1_000, end_ms: 2_000, text: "Hi".into() }])
+        );
+    }
 }
@@ -1,4 +1,7 @@
 //! Subtitle decoding (text only)
+//!
+//! Demuxed subtitle packets are FRAMED (never concatenated):
+//! `start_ms_be (8) || end_ms_be (8) || len_be (4) || payload`
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SubtitleCue {
@@ -8,3 +11,27 @@ pub struct SubtitleCue {
 }
 
 pub mod decode;
+pub mod text;
+
+/* ───────────── FRAMING ───────────── */
+
+/// Per-packet frame header length (bytes).
+pub(crate) const FRAME_HEADER_LEN: usize = 8 + 8 + 4;
+
+/// Append one framed subtitle packet.
+///
+/// Fails if the payload length does not fit the `u32` prefix.
+pub(crate) fn write_frame(
+    out: &mut Vec<u8>,
+    start_ms: u64,
+    end_ms: u64,
+    payload: &[u8],
+) -> Result<(), ()> {
+    let len = u32::try_from(payload.len()).map_err(|_| ())?;
+
+    out.extend_from_slice(&start_ms.to_be_bytes());
+    out.extend_from_slice(&end_ms.to_be_bytes());
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(payload);
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! WebVTT / SRT cue parsing (hostile text)
+//!
+//! SECURITY:
+//! - Malformed timing => that cue is skipped, never the whole stream
+//! - Control characters (except `\n` / `\t`) => cue skipped
+//! - Cue text capped at `MAX_CUE_TEXT_BYTES` (UTF-8 boundary safe)
+
+use super::SubtitleCue;
+
+/// Max UTF-8 bytes of text kept per cue.
+pub const MAX_CUE_TEXT_BYTES: usize = 1024;
+
+/// Does this payload carry its own cue timing (a raw SRT / VTT block)?
+pub(crate) fn has_cue_timing(text: &str) -> bool {
+    text.contains("-->")
+}
+
+/// Parse every well-formed SRT / WebVTT cue in `text` into `out`.
+///
+/// Stops once `out` holds `max_cues` cues.
+pub(crate) fn parse_cues(text: &str, out: &mut Vec<SubtitleCue>, max_cues: usize) {
+    let text = text.strip_prefix('\u{FEFF}').unwrap_or(text);
+    let normalized = text.replace("\r\n", "\n");
+
+    for block in normalized.split("\n\n") {
+        if out.len() >= max_cues {
+            return;
+        }
+
+        let mut lines = block.lines().skip_while(|l| l.trim().is_empty());
+
+        // SRT: optional numeric index; VTT: optional cue identifier.
+        // Header / NOTE / STYLE / REGION blocks carry no timing line.
+        let Some(timing) = lines.by_ref().take(2).find(|l| has_cue_timing(l)) else {
+            continue;
+        };
+
+        let Some((start_ms, end_ms)) = parse_timing(timing) else {
+            continue;
+        };
+
+        let body: Vec<&str> = lines.collect();
+        if let Some(text) = clean_text(&body.join("\n")) {
+            out.push(SubtitleCue { start_ms, end_ms, text });
+        }
+    }
+}
+
+/// `start --> end [vtt settings]` => `(start_ms, end_ms)`.
+fn parse_timing(line: &str) -> Option<(u64, u64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+
+    let start = parse_timestamp(start.trim())?;
+    let end = parse_timestamp(end)?;
+    (end >= start).then_some((start, end))
+}
+
+/// `HH:MM:SS,mmm` (SRT), `HH:MM:SS.mmm` / `MM:SS.mmm` (WebVTT).
+fn parse_timestamp(s: &str) -> Option<u64> {
+    let (clock, ms) = s.rsplit_once([',', '.'])?;
+    if ms.len() != 3 {
+        return None;
+    }
+    let ms = digits(ms)?;
+
+    let mut parts = clock.rsplitn(3, ':');
+    let sec = digits(parts.next()?)?;
+    let min = digits(parts.next()?)?;
+    let hour = match parts.next() {
+        Some(h) => digits(h)?,
+        None => 0,
+    };
+
+    if sec >= 60 || min >= 60 {
+        return None;
+    }
+
+    hour.checked_mul(3_600_000)?
+        .checked_add(min * 60_000 + sec * 1000 + ms)
+}
+
+/// Strict ASCII-digit parse (no sign, no whitespace, bounded width).
+fn digits(s: &str) -> Option<u64> {
+    if s.is_empty() || s.len() > 9 || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Reject control characters, trim, cap length. `None` => drop the cue.
+pub(crate) fn clean_text(raw: &str) -> Option<String> {
+    if raw.chars().any(|c| c.is_control() && c != '\n' && c != '\t') {
+        return None;
+    }
+
+    let text = raw.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut end = text.len().min(MAX_CUE_TEXT_BYTES);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    Some(text[..end].to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cues(text: &str) -> Vec<SubtitleCue> {
+        let mut out = Vec::new();
+        parse_cues(text, &mut out, usize::MAX);
+        out
+    }
+
+    #[test]
+    fn srt_and_webvtt_cues_parse() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:02,500\r\nHello\r\nthere\r\n\r\n\
+                   2\r\n00:00:03,000 --> 00:00:04,000\r\nworld\r\n";
+        assert_eq!(
+            cues(srt),
+            vec![
+                SubtitleCue { start_ms: 1_000, end_ms: 2_500, text: "Hello\nthere".into() },
+                SubtitleCue { start_ms: 3_000, end_ms: 4_000, text: "world".into() },
+            ]
+        );
+
+        let vtt = "WEBVTT\n\nNOTE comment\n\nintro\n01:02.003 --> 01:03.000 align:start\nHi\n";
+        assert_eq!(
+            cues(vtt),
+            vec![SubtitleCue { start_ms: 62_003, end_ms: 63_000, text: "Hi".into() }]
+        );
+    }
+
+    #[test]
+    fn malformed_or_hostile_cues_are_skipped() {
+        let text = "00:00:01,00 --> 00:00:02,000\nshort ms\n\n\
+                    00:00:05,000 --> 00:00:04,000\nbackwards\n\n\
+                    00:00:01,000 --> 00:00:02,000\nbell\u{7}\n\n\
+                    00:00:01,000 --> 00:00:02,000\nkept\n";
+        assert_eq!(
+            cues(text),
+            vec![SubtitleCue { start_ms: 1_000, end_ms: 2_000, text: "kept".into() }]
+        );
+    }
+
+    #[test]
+    fn cue_text_is_capped_on_a_char_boundary() -> Result<(), ()> {
+        let long = "é".repeat(MAX_CUE_TEXT_BYTES);
+        let text = clean_text(&long).ok_or(())?;
+        assert!(text.len() <= MAX_CUE_TEXT_BYTES);
+        assert!(text.chars().all(|c| c == 'é'));
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+//! Constant-time comparison (single audited primitive).
+//!
+//! SECURITY:
+//! - No data-dependent branches or early exits
+//! - Length mismatch is folded into the result AFTER a full scan
+//! - Only lengths (public) influence the iteration count
+
+use subtle::{Choice, ConstantTimeEq};
+
+/// Constant-time byte-slice equality.
+///
+/// Always scans all of `a`; bytes missing from a shorter `b`
+/// compare against zero, and the length check is combined last.
+#[must_use]
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    let len_eq = (a.len() as u64).ct_eq(&(b.len() as u64));
+
+    let mut acc = Choice::from(1u8);
+    for (i, x) in a.iter().enumerate() {
+        let y = b.get(i).copied().unwrap_or(0);
+        acc &= x.ct_eq(&y);
+    }
+
+    bool::from(len_eq & acc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_and_unequal_slices() {
+        assert!(ct_eq(b"", b""));
+        assert!(ct_eq(b"device-id", b"device-id"));
+
+        assert!(!ct_eq(b"device-id", b"device-iD"));
+        assert!(!ct_eq(b"\x01device-id", b"\x02device-id"));
+    }
+
+    #[test]
+    fn length_mismatch_is_false_even_with_equal_prefix() {
+        // Shorter `b` padded with zeros must still fail on length
+        assert!(!ct_eq(b"abc\0", b"abc"));
+        assert!(!ct_eq(b"abc", b"abc\0"));
+        assert!(!ct_eq(b"abc", b""));
+    }
+}
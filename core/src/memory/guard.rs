@@ -4,13 +4,18 @@
 //!
 //! FORMAL SECURITY INVARIANTS
 //! G1. Heap-only allocation
-//! G2. Memory is locked (mlock / VirtualLock) or PANIC
+//! G2. Memory is locked (mlock / VirtualLock) whenever the OS
+//!     permits; if it refuses (e.g. RLIMIT_MEMLOCK), the allocation
+//!     still succeeds and `is_locked()` reports `false`
 //! G3. No stack copies of secrets
 //! G4. Panic during init MUST NOT leak memory
 //! G5. Zeroize BEFORE unlock + dealloc
 //! G6. No Clone / Copy / Debug
 
-use core::alloc::{Layout, System};
+// Memory locking is FFI (mlock / VirtualLock): explicit opt-in
+#![allow(unsafe_code)]
+
+use core::alloc::Layout;
 use core::cell::Cell;
 use core::marker::PhantomData;
 use core::mem::MaybeUninit;
@@ -21,13 +26,87 @@ use zeroize::Zeroize;
 use libc::{mlock, munlock};
 
 #[cfg(windows)]
-use winapi::um::winbase::{VirtualLock, VirtualUnlock};
+use winapi::um::memoryapi::{VirtualLock, VirtualUnlock};
+
+/* ───────────── MEMORY LOCKING ───────────── */
+
+/// Page-lock `len` bytes at `addr`.
+///
+/// Returns `false` (never panics) when the OS refuses, e.g. when
+/// `RLIMIT_MEMLOCK` is exhausted or locking is unsupported.
+#[inline]
+fn try_lock(addr: *const u8, len: usize) -> bool {
+    #[cfg(unix)]
+    let locked = unsafe { mlock(addr as *const _, len) == 0 };
+
+    #[cfg(windows)]
+    let locked = unsafe { VirtualLock(addr as *mut _, len) != 0 };
+
+    #[cfg(not(any(unix, windows)))]
+    let locked = {
+        let _ = (addr, len);
+        false
+    };
+
+    locked
+}
+
+#[inline]
+fn unlock(addr: *const u8, len: usize) {
+    #[cfg(unix)]
+    unsafe {
+        munlock(addr as *const _, len);
+    }
+
+    #[cfg(windows)]
+    unsafe {
+        VirtualUnlock(addr as *mut _, len);
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (addr, len);
+    }
+}
+
+/* ───────────── ALLOCATION ───────────── */
+
+// std: the system allocator directly (never a host-installed
+// global allocator). no-std: the embedder-provided allocator.
+#[cfg(feature = "std")]
+mod heap {
+    use core::alloc::Layout;
+    use std::alloc::{GlobalAlloc, System};
+
+    pub(super) unsafe fn alloc(layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    pub(super) unsafe fn alloc_zeroed(layout: Layout) -> *mut u8 {
+        System.alloc_zeroed(layout)
+    }
+
+    pub(super) unsafe fn dealloc(ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod heap {
+    pub(super) use alloc::alloc::{alloc, alloc_zeroed, dealloc};
+}
+
+#[inline]
+fn system_alloc(layout: Layout) -> *mut u8 {
+    unsafe { heap::alloc(layout) }
+}
 
 /// Page-locked, heap-only guarded allocation.
 #[must_use = "GuardedBox must be held to keep memory locked"]
 pub struct GuardedBox<T: Zeroize> {
     ptr: NonNull<T>,
     layout: Layout,
+    locked: bool,
     _no_clone_copy: PhantomData<Cell<()>>,
 }
 
@@ -38,25 +117,64 @@ impl<T: Zeroize> GuardedBox<T> {
     /// - Heap-only
     /// - Panic-safe
     /// - No stack intermediates
-    /// - Panics if memory locking fails
+    /// - Panics if allocation fails; a refused lock is reported
+    ///   by `is_locked()`
     pub fn init_with<F>(initializer: F) -> Self
+    where
+        F: FnOnce(&mut T),
+    {
+        Self::try_init_with(initializer)
+            .expect("GuardedBox allocation failed")
+    }
+
+    /// Like `init_with`, but returns `None` on allocation failure.
+    ///
+    /// For constrained hosts (e.g. WASM) where OOM must fail
+    /// closed instead of aborting.
+    pub fn try_init_with<F>(initializer: F) -> Option<Self>
+    where
+        F: FnOnce(&mut T),
+    {
+        Self::try_init_with_in(system_alloc, try_lock, initializer)
+    }
+
+    /// Move `value` into guarded memory; `None` on allocation failure.
+    ///
+    /// SECURITY:
+    /// - `value` passes through the caller's stack (G3 does NOT hold)
+    /// - Prefer `try_init_with` for secret material
+    pub fn try_new(value: T) -> Option<Self> {
+        let mut value = Some(value);
+        Self::try_init_with_in(system_alloc, try_lock, |slot| {
+            if let Some(v) = value.take() {
+                // Slot is uninitialized: write without dropping it
+                unsafe { core::ptr::write(slot as *mut T, v) };
+            }
+        })
+    }
+
+    fn try_init_with_in<F>(
+        alloc: fn(Layout) -> *mut u8,
+        lock: fn(*const u8, usize) -> bool,
+        initializer: F,
+    ) -> Option<Self>
     where
         F: FnOnce(&mut T),
     {
         let layout = Layout::new::<T>();
 
         // Allocate raw heap memory
-        let raw = unsafe { System.alloc(layout) };
-        let raw = NonNull::new(raw as *mut MaybeUninit<T>)
-            .expect("GuardedBox allocation failed");
+        let raw = alloc(layout);
+        let raw = NonNull::new(raw as *mut MaybeUninit<T>)?;
 
-        // Lock memory (fail-closed)
-        Self::lock_or_panic(raw.as_ptr() as *const u8, layout.size());
+        // Lock memory (best effort, see G2)
+        let locked = lock(raw.as_ptr() as *const u8, layout.size());
 
         // Panic safety guard
         struct InitGuard<T: Zeroize> {
             ptr: *mut MaybeUninit<T>,
             layout: Layout,
+            locked: bool,
         }
 
         impl<T: Zeroize> Drop for InitGuard<T> {
@@ -70,13 +188,12 @@ impl<T: Zeroize> GuardedBox<T> {
                     bytes.zeroize();
 
                     // Unlock
-                    #[cfg(unix)]
-                    munlock(self.ptr as *const _, self.layout.size());
-                    #[cfg(windows)]
-                    VirtualUnlock(self.ptr as *mut _, self.layout.size());
+                    if self.locked {
+                        unlock(self.ptr as *const u8, self.layout.size());
+                    }
 
                     // Deallocate
-                    System.dealloc(self.ptr as *mut u8, self.layout);
+                    heap::dealloc(self.ptr as *mut u8, self.layout);
                 }
             }
         }
@@ -84,6 +201,7 @@ impl<T: Zeroize> GuardedBox<T> {
         let mut guard = InitGuard {
             ptr: raw.as_ptr(),
             layout,
+            locked,
         };
 
         // Initialize in place
@@ -95,11 +213,22 @@ impl<T: Zeroize> GuardedBox<T> {
         // Initialization succeeded — disarm guard
         core::mem::forget(guard);
 
-        Self {
+        Some(Self {
             ptr: unsafe { NonNull::new_unchecked(raw.as_ptr() as *mut T) },
             layout,
+            locked,
             _no_clone_copy: PhantomData,
-        }
+        })
+    }
+
+    /// Whether the allocation is page-locked.
+    ///
+    /// Security-critical callers SHOULD assert this; `false` means
+    /// the OS refused the lock (e.g. RLIMIT_MEMLOCK) and the secret
+    /// may be swapped out.
+    #[inline]
+    pub fn is_locked(&self) -> bool {
+        self.locked
     }
 
     /// Immutable access — KEEP SCOPE MINIMAL.
@@ -113,47 +242,6 @@ impl<T: Zeroize> GuardedBox<T> {
     pub fn borrow_mut(&mut self) -> &mut T {
         unsafe { &mut *self.ptr.as_ptr() }
     }
-
-    #[inline]
-    fn lock_or_panic(addr: *const u8, len: usize) {
-        #[cfg(unix)]
-        unsafe {
-            if mlock(addr as *const _, len) != 0 {
-                panic!("mlock failed: Secure Core requires locked memory");
-            }
-        }
-
-        #[cfg(windows)]
-        unsafe {
-            if VirtualLock(addr as *mut _, len) == 0 {
-                panic!("VirtualLock failed: Secure Core requires locked memory");
-            }
-        }
-
-        #[cfg(not(any(unix, windows)))]
-        {
-            let _ = (addr, len);
-            panic!("Secure Core requires memory locking support");
-        }
-    }
-
-    #[inline]
-    fn unlock(addr: *const u8, len: usize) {
-        #[cfg(unix)]
-        unsafe {
-            munlock(addr as *const _, len);
-        }
-
-        #[cfg(windows)]
-        unsafe {
-            VirtualUnlock(addr as *mut _, len);
-        }
-
-        #[cfg(not(any(unix, windows)))]
-        {
-            let _ = (addr, len);
-        }
-    }
 }
 
 impl<T: Zeroize> Drop for GuardedBox<T> {
@@ -167,8 +255,10 @@ impl<T: Zeroize> Drop for GuardedBox<T> {
             bytes.zeroize();
 
             // Unlock and deallocate
-            Self::unlock(self.ptr.as_ptr() as *const u8, self.layout.size());
-            System.dealloc(self.ptr.as_ptr() as *mut u8, self.layout);
+            if self.locked {
+                unlock(self.ptr.as_ptr() as *const u8, self.layout.size());
+            }
+            heap::dealloc(self.ptr.as_ptr() as *mut u8, self.layout);
         }
     }
 }
@@ -176,11 +266,184 @@ impl<T: Zeroize> Drop for GuardedBox<T> {
 /// Canonical guarded 256-bit key type.
 pub type GuardedKey32 = GuardedBox<[u8; 32]>;
 
+/// Inner type of `GuardedKey32` (what `borrow()` yields).
+type GuardedKey32Inner = [u8; 32];
+
+// The crypto layer feeds `borrow()` straight into AES / HKDF / HMAC
+// as a 256-bit key: pin the layout at compile time.
+const _: () = assert!(core::mem::size_of::<GuardedKey32Inner>() == 32);
+const _: () = assert!(core::mem::align_of::<GuardedKey32Inner>() == 1);
+const _: fn(&GuardedKey32) -> &GuardedKey32Inner = GuardedKey32::borrow;
+
 impl GuardedKey32 {
     /// Create a zeroed, locked 32-byte key buffer.
     pub fn zeroed() -> Self {
         Self::init_with(|buf| buf.fill(0))
     }
+
+    /// Import a 32-byte key from `src` (e.g. a hardware keystore).
+    ///
+    /// SECURITY:
+    /// - Guarded memory is allocated FIRST, then `src` is copied
+    ///   straight into it: no stack intermediate (G3)
+    /// - `src.len() != 32` or allocation failure => `Err`
+    /// - Zeroizing `src` remains the caller's responsibility
+    pub fn from_slice_into(src: &[u8]) -> Result<Self, ()> {
+        if src.len() != 32 {
+            return Err(());
+        }
+
+        let mut key = Self::try_init_with(|buf| buf.fill(0)).ok_or(())?;
+        key.borrow_mut().copy_from_slice(src);
+        Ok(key)
+    }
+}
+
+/* ───────────── VARIABLE-LENGTH BUFFER ───────────── */
+
+/// Largest `GuardedVec` allocation (bounds locked memory).
+///
+/// Sized to hold one maximum file chunk of plaintext.
+pub const MAX_GUARDED_VEC_LEN: usize = 4 * 1024 * 1024;
+
+#[inline]
+fn system_alloc_zeroed(layout: Layout) -> *mut u8 {
+    unsafe { heap::alloc_zeroed(layout) }
+}
+
+/// Page-locked, heap-only byte buffer of runtime length.
+///
+/// Used where secret or plaintext material is not exactly
+/// 32 bytes (e.g. 64-byte KDF outputs, KEM shared secrets,
+/// decrypted chunk plaintext handed to the host).
+///
+/// SECURITY:
+/// - Same guarantees as `GuardedBox` (G1–G6)
+/// - Length fixed at allocation, never reallocated
+/// - Length bounded by `MAX_GUARDED_VEC_LEN`
+/// - Zero-length buffers perform no allocation
+#[must_use = "GuardedVec must be held to keep memory locked"]
+pub struct GuardedVec {
+    ptr: NonNull<u8>,
+    len: usize,
+    locked: bool,
+    _no_clone_copy: PhantomData<Cell<()>>,
+}
+
+impl GuardedVec {
+    /// Allocate a zeroed, locked buffer of `len` bytes.
+    ///
+    /// SECURITY:
+    /// - Panics if `len` exceeds `MAX_GUARDED_VEC_LEN`
+    /// - Panics if allocation fails; a refused lock is reported
+    ///   by `is_locked()`
+    pub fn zeroed(len: usize) -> Self {
+        Self::try_zeroed(len)
+            .expect("GuardedVec allocation failed")
+    }
+
+    /// Like `zeroed`, but returns `None` on an oversized request or
+    /// allocation failure.
+    pub fn try_zeroed(len: usize) -> Option<Self> {
+        Self::try_zeroed_in(system_alloc_zeroed, try_lock, len)
+    }
+
+    /// Allocate `len` locked bytes and initialize them in place.
+    ///
+    /// SECURITY:
+    /// - Buffer starts zeroed; a panicking initializer drops
+    ///   (and wipes) it
+    pub fn init_with<F>(len: usize, initializer: F) -> Self
+    where
+        F: FnOnce(&mut [u8]),
+    {
+        let mut v = Self::zeroed(len);
+        initializer(v.borrow_mut());
+        v
+    }
+
+    fn try_zeroed_in(
+        alloc: fn(Layout) -> *mut u8,
+        lock: fn(*const u8, usize) -> bool,
+        len: usize,
+    ) -> Option<Self> {
+        if len > MAX_GUARDED_VEC_LEN {
+            return None;
+        }
+
+        if len == 0 {
+            // Nothing to lock: no secret byte can be swapped out
+            return Some(Self {
+                ptr: NonNull::dangling(),
+                len: 0,
+                locked: true,
+                _no_clone_copy: PhantomData,
+            });
+        }
+
+        let layout = Layout::array::<u8>(len).ok()?;
+
+        // Zeroed allocation: no uninitialized bytes are ever observable
+        let ptr = NonNull::new(alloc(layout))?;
+
+        let locked = lock(ptr.as_ptr() as *const u8, len);
+
+        Some(Self {
+            ptr,
+            len,
+            locked,
+            _no_clone_copy: PhantomData,
+        })
+    }
+
+    /// Whether the buffer is page-locked (see `GuardedBox::is_locked`).
+    #[inline]
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Immutable access — KEEP SCOPE MINIMAL.
+    #[inline]
+    pub fn borrow(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Mutable access — KEEP SCOPE MINIMAL.
+    #[inline]
+    pub fn borrow_mut(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for GuardedVec {
+    fn drop(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+
+        self.borrow_mut().zeroize();
+
+        if self.locked {
+            unlock(self.ptr.as_ptr() as *const u8, self.len);
+        }
+
+        unsafe {
+            heap::dealloc(
+                self.ptr.as_ptr(),
+                Layout::from_size_align_unchecked(self.len, 1),
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -206,10 +469,113 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn guarded_box_try_new_reports_alloc_failure() {
+        fn failing_alloc(_: Layout) -> *mut u8 {
+            core::ptr::null_mut()
+        }
+
+        let g = GuardedBox::<[u8; 32]>::try_init_with_in(failing_alloc, try_lock, |buf| {
+            buf.fill(0xAA);
+        });
+        assert!(g.is_none());
+
+        let g = GuardedBox::try_new([0x11u8; 32]);
+        assert!(matches!(g, Some(ref b) if b.borrow().iter().all(|x| *x == 0x11)));
+    }
+
+    #[test]
+    fn refused_lock_degrades_without_abort() {
+        fn refuse_lock(_: *const u8, _: usize) -> bool {
+            false
+        }
+
+        let g = GuardedBox::<[u8; 32]>::try_init_with_in(system_alloc, refuse_lock, |buf| {
+            buf.fill(0x22);
+        });
+        assert!(matches!(g, Some(ref b) if !b.is_locked() && b.borrow()[0] == 0x22));
+
+        let v = GuardedVec::try_zeroed_in(system_alloc_zeroed, refuse_lock, 64);
+        assert!(matches!(v, Some(ref b) if !b.is_locked() && b.len() == 64));
+
+        // Dropping an unlocked allocation must not munlock it
+        drop(g);
+        drop(v);
+    }
+
+    #[test]
+    fn granted_lock_is_reported() {
+        // Attempts the real lock (so drop's unlock is genuine) but
+        // reports success even under a zero RLIMIT_MEMLOCK
+        fn grant_lock(addr: *const u8, len: usize) -> bool {
+            let _ = try_lock(addr, len);
+            true
+        }
+
+        let g = GuardedBox::<[u8; 32]>::try_init_with_in(system_alloc, grant_lock, |buf| {
+            buf.fill(0x33);
+        });
+        assert!(matches!(g, Some(ref b) if b.is_locked() && b.borrow()[0] == 0x33));
+
+        let v = GuardedVec::try_zeroed_in(system_alloc_zeroed, grant_lock, 64);
+        assert!(matches!(v, Some(ref b) if b.is_locked() && b.len() == 64));
+    }
+
+    #[test]
+    fn guarded_key32_borrow_is_exactly_32_bytes() {
+        let k = GuardedKey32::zeroed();
+        let bytes: &[u8] = k.borrow();
+
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(core::mem::size_of_val(k.borrow()), 32);
+    }
+
+    #[test]
+    fn slice_import_round_trips_and_checks_length() -> Result<(), ()> {
+        let src: Vec<u8> = (0u8..32).collect();
+
+        let k = GuardedKey32::from_slice_into(&src).map_err(|_| ())?;
+        assert_eq!(k.borrow().as_slice(), src.as_slice());
+
+        assert!(GuardedKey32::from_slice_into(&src[..31]).is_err());
+        assert!(GuardedKey32::from_slice_into(&[0u8; 33]).is_err());
+        Ok(())
+    }
+
     #[test]
     fn guarded_box_drop_is_safe() {
         {
             let _ = GuardedKey32::zeroed();
         }
     }
+
+    #[test]
+    fn guarded_vec_is_zeroed_and_sized() {
+        let mut v = GuardedVec::zeroed(48);
+        assert_eq!(v.len(), 48);
+        assert!(v.borrow().iter().all(|b| *b == 0));
+
+        v.borrow_mut().fill(0x5A);
+        assert!(v.borrow().iter().all(|b| *b == 0x5A));
+    }
+
+    #[test]
+    fn guarded_vec_bounds_and_alloc_failure() {
+        fn failing_alloc(_: Layout) -> *mut u8 {
+            core::ptr::null_mut()
+        }
+
+        assert!(GuardedVec::try_zeroed(MAX_GUARDED_VEC_LEN + 1).is_none());
+        assert!(GuardedVec::try_zeroed_in(failing_alloc, try_lock, 64).is_none());
+
+        let v = GuardedVec::init_with(64, |b| b.fill(0x11));
+        assert!(v.borrow().len() == 64 && v.borrow().iter().all(|x| *x == 0x11));
+    }
+
+    #[test]
+    fn guarded_vec_empty_is_safe() {
+        let v = GuardedVec::zeroed(0);
+        assert!(v.is_empty());
+        assert!(v.borrow().is_empty());
+    }
 }
\ No newline at end of file
@@ -21,6 +21,11 @@
 
 pub mod zeroize;
 pub mod guard;
+#[cfg(feature = "std")]
+pub mod sensitive;
+#[cfg(feature = "std")]
+pub mod scratch;
+pub mod ct;
 
 // ─────────────────────────────────────────────────────────────
 // Curated public surface (EXPLICIT EXPORTS ONLY)
@@ -43,6 +48,25 @@ pub use zeroize::{
 pub use guard::{
     GuardedBox,    // Page-locked heap allocation
     GuardedKey32, // Canonical 256-bit secret key
+    GuardedVec,   // Page-locked variable-length buffer
+    MAX_GUARDED_VEC_LEN,
+};
+
+// ───── Pooled zeroizing scratch (allocation-churn reduction) ─────
+#[cfg(feature = "std")]
+pub use scratch::{
+    ScratchGuard, // Exclusive buffer, wiped + pooled on drop
+    ScratchPool,  // Capped free-list of wiped buffers
+};
+
+// ───── Constant-time comparison ─────
+pub use ct::ct_eq;
+
+// ───── Host-held buffers wiped on kill ─────
+#[cfg(feature = "std")]
+pub use sensitive::{
+    SensitiveBuffer,   // Core-owned buffer, host-held handle
+    SensitiveRegistry, // Weak registry, wiped on kill
 };
 
 #[cfg(test)]
@@ -0,0 +1,169 @@
+//! Reusable zeroizing scratch buffers (Secure Core).
+//!
+//! Short-lived plaintext scratch (kill blobs, verify-only
+//! decryption) used to be a fresh `Vec` per call plus `wipe_vec`.
+//! A `ScratchPool` keeps a few of those allocations around instead.
+//!
+//! DESIGN:
+//! - Small fixed free-list (`max_buffers`)
+//! - Retained capacity capped (`max_retained` bytes, total)
+//! - `acquire` hands out a `ScratchGuard`; dropping it zeroizes the
+//!   WHOLE allocation (spare capacity included), THEN returns it
+//!
+//! SECURITY:
+//! - A pooled buffer is always zero: wipe-on-return is done by
+//!   `Drop`, before the buffer becomes reachable again
+//! - Over-cap buffers are wiped and freed, never retained
+//! - Poisoned lock => behaves as an empty pool (still wipes)
+//! - Heap-only; no unsafe
+
+#![deny(clippy::derive_debug)]
+
+use core::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+use zeroize::Zeroize;
+
+/* ───────────── POOL ───────────── */
+
+/// Fixed-size pool of reusable zeroizing buffers.
+pub struct ScratchPool {
+    free: Mutex<Vec<Vec<u8>>>,
+    max_buffers: usize,
+    max_retained: usize,
+}
+
+impl ScratchPool {
+    /// Empty pool retaining at most `max_buffers` buffers and
+    /// `max_retained` bytes of capacity in total.
+    pub const fn new(max_buffers: usize, max_retained: usize) -> Self {
+        Self {
+            free: Mutex::new(Vec::new()),
+            max_buffers,
+            max_retained,
+        }
+    }
+
+    /// Borrow a zero-filled buffer of exactly `len` bytes.
+    ///
+    /// Reuses the smallest pooled buffer that fits, else allocates.
+    pub fn acquire(&self, len: usize) -> ScratchGuard<'_> {
+        let pooled = self.free.lock().ok().and_then(|mut free| {
+            let best = free
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| b.capacity() >= len)
+                .min_by_key(|(_, b)| b.capacity())
+                .map(|(i, _)| i)?;
+            Some(free.swap_remove(best))
+        });
+
+        let mut buf = pooled.unwrap_or_default();
+        // Pooled buffers are already zero (wiped on return)
+        buf.resize(len, 0);
+
+        ScratchGuard { buf, pool: self }
+    }
+
+    /// Number of buffers currently pooled.
+    pub fn pooled(&self) -> usize {
+        self.free.lock().map(|f| f.len()).unwrap_or(0)
+    }
+
+    /// Wipe and free every pooled buffer.
+    pub fn clear(&self) {
+        if let Ok(mut free) = self.free.lock() {
+            for buf in free.iter_mut() {
+                buf.zeroize();
+            }
+            free.clear();
+        }
+    }
+
+    /// Return an ALREADY WIPED buffer (dropped if over any cap).
+    fn release(&self, buf: Vec<u8>) {
+        let Ok(mut free) = self.free.lock() else {
+            return;
+        };
+
+        let retained: usize = free.iter().map(Vec::capacity).sum();
+        let fits = retained
+            .checked_add(buf.capacity())
+            .is_some_and(|total| total <= self.max_retained);
+
+        if free.len() < self.max_buffers && fits {
+            free.push(buf);
+        }
+    }
+}
+
+/* ───────────── GUARD ───────────── */
+
+/// Exclusive scratch buffer; zeroized and pooled on drop.
+pub struct ScratchGuard<'a> {
+    buf: Vec<u8>,
+    pool: &'a ScratchPool,
+}
+
+impl Deref for ScratchGuard<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+impl DerefMut for ScratchGuard<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+}
+
+impl Drop for ScratchGuard<'_> {
+    fn drop(&mut self) {
+        // Wipes len AND spare capacity, then clears
+        self.buf.zeroize();
+        self.pool.release(core::mem::take(&mut self.buf));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returned_buffers_are_wiped_and_reused() {
+        let pool = ScratchPool::new(2, 1024);
+
+        {
+            let mut a = pool.acquire(64);
+            a.fill(0xAA);
+        }
+        assert_eq!(pool.pooled(), 1);
+
+        // Shorter request reuses the same allocation, zero-filled
+        let b = pool.acquire(16);
+        assert_eq!(b.len(), 16);
+        assert!(b.iter().all(|x| *x == 0));
+        assert_eq!(pool.pooled(), 0);
+        drop(b);
+
+        // Growing past prior length still exposes only zeros
+        let c = pool.acquire(64);
+        assert!(c.iter().all(|x| *x == 0));
+    }
+
+    #[test]
+    fn retention_is_capped() {
+        let pool = ScratchPool::new(2, 100);
+
+        drop(pool.acquire(200));
+        assert_eq!(pool.pooled(), 0);
+
+        let (a, b, c) = (pool.acquire(10), pool.acquire(10), pool.acquire(10));
+        drop((a, b, c));
+        assert_eq!(pool.pooled(), 2);
+
+        pool.clear();
+        assert_eq!(pool.pooled(), 0);
+    }
+}
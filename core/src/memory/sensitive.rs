@@ -0,0 +1,216 @@
+//! Host-held sensitive buffers, wiped on kill (Secure Core).
+//!
+//! The Secure Core cannot reach arbitrary host memory. Instead,
+//! hosts that want decrypted data wiped on kill allocate their
+//! buffers THROUGH the core and keep only a handle.
+//!
+//! DESIGN:
+//! - The core owns the memory (`GuardedVec`)
+//! - The host holds a `SensitiveBuffer` handle (strong reference)
+//! - The registry holds weak references only
+//! - Deregistration = dropping the handle (no dangling access)
+//!
+//! SECURITY:
+//! - No raw pointers
+//! - No lifetime extension of host borrows
+//! - `wipe_all` zeroizes every live buffer in place
+//! - Every kill cause wipes (`master::escalate` → `wipe_thread`);
+//!   buffers of other threads are wiped on their next access
+
+#![deny(clippy::derive_debug)]
+
+use core::cell::RefCell;
+use std::rc::{Rc, Weak};
+use core::sync::atomic::Ordering;
+use zeroize::Zeroize;
+
+use crate::keystore::master::GLOBAL_KILLED;
+use crate::memory::guard::GuardedVec;
+
+/* ───────────── HANDLE ───────────── */
+
+/// Host-held handle to a core-owned sensitive buffer.
+///
+/// SECURITY:
+/// - Not clonable
+/// - Memory is page-locked and zeroized on drop
+/// - Contents are zeroized by the core on kill
+#[must_use = "dropping the handle releases and wipes the buffer"]
+pub struct SensitiveBuffer {
+    inner: Rc<RefCell<GuardedVec>>,
+}
+
+impl SensitiveBuffer {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.borrow().len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Immutable access — KEEP SCOPE MINIMAL.
+    ///
+    /// Returns `None` if the buffer is already borrowed.
+    /// After kill the contents are zeroized first.
+    pub fn with<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Option<R> {
+        self.wipe_if_killed();
+        let g = self.inner.try_borrow().ok()?;
+        Some(f(g.borrow()))
+    }
+
+    /// Mutable access — KEEP SCOPE MINIMAL.
+    ///
+    /// Returns `None` if the buffer is already borrowed, or after kill.
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut [u8]) -> R) -> Option<R> {
+        if self.wipe_if_killed() {
+            return None;
+        }
+        let mut g = self.inner.try_borrow_mut().ok()?;
+        Some(f(g.borrow_mut()))
+    }
+
+    /// Kill on another thread never reached this buffer's registry:
+    /// wipe it here before any access.
+    fn wipe_if_killed(&self) -> bool {
+        if !GLOBAL_KILLED.load(Ordering::SeqCst) {
+            return false;
+        }
+        if let Ok(mut g) = self.inner.try_borrow_mut() {
+            g.borrow_mut().zeroize();
+        }
+        true
+    }
+
+    /// Raw contents are all zero (no kill-time wipe on the way).
+    #[cfg(test)]
+    pub(crate) fn is_zeroed(&self) -> bool {
+        self.inner.try_borrow().is_ok_and(|g| g.borrow().iter().all(|b| *b == 0))
+    }
+}
+
+/* ───────────── REGISTRY ───────────── */
+
+/// Registry of live sensitive buffers (weak references only).
+pub struct SensitiveRegistry {
+    entries: RefCell<Vec<Weak<RefCell<GuardedVec>>>>,
+}
+
+impl SensitiveRegistry {
+    pub const fn new() -> Self {
+        Self {
+            entries: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Allocate and register a zeroed sensitive buffer.
+    ///
+    /// Dead entries (dropped handles) are pruned on every call.
+    pub fn register(&self, len: usize) -> SensitiveBuffer {
+        let inner = Rc::new(RefCell::new(GuardedVec::zeroed(len)));
+
+        let mut entries = self.entries.borrow_mut();
+        entries.retain(|w| w.strong_count() > 0);
+        entries.push(Rc::downgrade(&inner));
+
+        SensitiveBuffer { inner }
+    }
+
+    /// Number of live registered buffers.
+    pub fn live(&self) -> usize {
+        self.entries
+            .borrow()
+            .iter()
+            .filter(|w| w.strong_count() > 0)
+            .count()
+    }
+
+    /// Zeroize every live registered buffer.
+    ///
+    /// SECURITY:
+    /// - Best-effort over a buffer borrowed at kill time
+    ///   (only possible via re-entrancy from its own closure)
+    /// - Registry is cleared afterwards
+    pub fn wipe_all(&self) {
+        let mut entries = match self.entries.try_borrow_mut() {
+            Ok(e) => e,
+            Err(_) => return,
+        };
+
+        for w in entries.iter() {
+            if let Some(buf) = w.upgrade() {
+                if let Ok(mut g) = buf.try_borrow_mut() {
+                    g.borrow_mut().zeroize();
+                }
+            }
+        }
+
+        entries.clear();
+    }
+}
+
+/* ───────────── THREAD REGISTRY ───────────── */
+
+// Handles are `!Send`: a buffer lives on the thread that registered
+// it, so the registry the kill fuse wipes is per thread.
+thread_local! {
+    static THREAD_REGISTRY: SensitiveRegistry = const { SensitiveRegistry::new() };
+}
+
+/// Allocate a buffer on the current thread's kill-wiped registry.
+pub(crate) fn register(len: usize) -> SensitiveBuffer {
+    THREAD_REGISTRY.with(|r| r.register(len))
+}
+
+/// Wipe the current thread's registered buffers (kill fuse only).
+///
+/// Safe during thread teardown (registry already gone => no-op).
+pub(crate) fn wipe_thread() {
+    let _ = THREAD_REGISTRY.try_with(|r| r.wipe_all());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kill_on_any_thread_wipes_registered_buffers() {
+        assert!(crate::test_support::isolated(
+            "memory::sensitive::tests::kill_on_any_thread_wipes_registered_buffers",
+            || {
+                use crate::keystore::master::{escalate, KillCause};
+
+                let buf = register(16);
+                assert_eq!(buf.with_mut(|b| b.fill(0xEE)), Some(()));
+                assert!(!buf.is_zeroed());
+
+                // Kill lands on ANOTHER thread: this thread's registry
+                // is not wiped by the fuse, only on next access
+                let killer = std::thread::spawn(|| escalate(KillCause::Poison));
+                assert!(killer.join().is_ok());
+                assert!(GLOBAL_KILLED.load(Ordering::SeqCst));
+                assert!(!buf.is_zeroed());
+
+                assert_eq!(buf.with(|b| b.iter().all(|x| *x == 0)), Some(true));
+                assert_eq!(buf.with_mut(|b| b.fill(0xEE)), None);
+                assert!(buf.is_zeroed());
+            },
+        ));
+    }
+
+    #[test]
+    fn dropped_handle_is_deregistered() {
+        let reg = SensitiveRegistry::new();
+        let a = reg.register(8);
+        let b = reg.register(8);
+        assert_eq!(reg.live(), 2);
+
+        drop(a);
+        assert_eq!(reg.live(), 1);
+
+        drop(b);
+        assert_eq!(reg.live(), 0);
+    }
+}
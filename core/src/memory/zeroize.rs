@@ -16,6 +16,8 @@
 
 #![deny(clippy::derive_debug)]
 
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
 use core::cell::Cell;
 use core::fmt;
 use core::marker::PhantomData;
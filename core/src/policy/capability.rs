@@ -36,4 +36,85 @@ pub enum Capability {
 
     /* ───── Diagnostics ───── */
     ViewLogs,
-}
\ No newline at end of file
+}
+
+/* ───────────── CAPABILITY SET ───────────── */
+
+/// Time-bounded grant (e.g. temporary admin).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedCapability {
+    pub cap: Capability,
+    /// Monotonic seconds; valid while `now < expires_at`
+    pub expires_at: u64,
+}
+
+/// Read-only capability set (application supplied).
+///
+/// SECURITY:
+/// - Immutable once handed to the enforcer
+/// - Non-owning for permanent caps
+/// - Cannot be escalated
+/// - Timed grants are fail-closed: `now == 0` (unknown clock)
+///   or `now >= expires_at` => denied
+pub struct CapabilitySet {
+    caps: &'static [Capability],
+    timed: Vec<TimedCapability>,
+}
+
+impl CapabilitySet {
+    /// Permanent capabilities only.
+    pub const fn new(caps: &'static [Capability]) -> Self {
+        Self {
+            caps,
+            timed: Vec::new(),
+        }
+    }
+
+    /// Add a grant of `cap` that expires at `expires_at`
+    /// (monotonic seconds).
+    pub fn grant_until(mut self, cap: Capability, expires_at: u64) -> Self {
+        self.timed.push(TimedCapability { cap, expires_at });
+        self
+    }
+
+    /// Whether `cap` is granted at monotonic time `now`.
+    #[inline(always)]
+    pub fn allows(&self, cap: Capability, now: u64) -> bool {
+        if self.caps.iter().any(|c| *c == cap) {
+            return true;
+        }
+
+        now != 0
+            && self
+                .timed
+                .iter()
+                .any(|t| t.cap == cap && now < t.expires_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permanent_caps_never_expire() {
+        let set = CapabilitySet::new(&[Capability::Upload]);
+
+        assert!(set.allows(Capability::Upload, 0));
+        assert!(set.allows(Capability::Upload, u64::MAX));
+        assert!(!set.allows(Capability::Download, 1));
+    }
+
+    #[test]
+    fn timed_caps_expire_and_fail_closed() {
+        let set = CapabilitySet::new(&[]).grant_until(Capability::IssueKill, 100);
+
+        assert!(set.allows(Capability::IssueKill, 1));
+        assert!(set.allows(Capability::IssueKill, 99));
+        assert!(!set.allows(Capability::IssueKill, 100));
+
+        // Unknown clock never grants a timed capability
+        assert!(!set.allows(Capability::IssueKill, 0));
+        assert!(!set.allows(Capability::Upload, 1));
+    }
+}
@@ -13,13 +13,14 @@
 //! - Kill is process-lifetime irreversible
 //! - Policy NEVER orchestrates kill mechanics
 
-use crate::policy::capability::Capability;
+use crate::policy::capability::{Capability, CapabilitySet};
 use crate::keystore::KeyStore;
 use crate::device::registry::DeviceRegistry;
 use crate::kill;
 use crate::keystore::master::GLOBAL_KILLED;
 
 use core::sync::atomic::Ordering;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 
 /* ───────────── OPERATIONS ───────────── */
 
@@ -36,27 +37,15 @@ pub enum Operation {
     IssueKill,
 }
 
-/* ───────────── CAPABILITY SET ───────────── */
+/* ───────────── DECISION AUDIT ───────────── */
 
-/// Read-only capability set (application supplied).
+/// Compliance receiver for every policy decision.
 ///
 /// SECURITY:
-/// - Immutable
-/// - Non-owning
-/// - Cannot be escalated
-pub struct CapabilitySet {
-    caps: &'static [Capability],
-}
-
-impl CapabilitySet {
-    pub const fn new(caps: &'static [Capability]) -> Self {
-        Self { caps }
-    }
-
-    #[inline(always)]
-    pub fn allows(&self, cap: Capability) -> bool {
-        self.caps.iter().any(|c| *c == cap)
-    }
+/// - Called AFTER the decision is made; cannot change it
+/// - A panicking sink is contained (never reaches the caller)
+pub trait DecisionSink {
+    fn on_decision(&self, op: Operation, allowed: bool, killed: bool);
 }
 
 /* ───────────── POLICY ENFORCER ───────────── */
@@ -71,6 +60,8 @@ pub struct PolicyEnforcer<'a> {
     keystore: &'a KeyStore,
     registry: &'a DeviceRegistry,
     caps: CapabilitySet,
+    // Optional audit trail (None = silent)
+    sink: Option<&'a dyn DecisionSink>,
 }
 
 impl<'a> PolicyEnforcer<'a> {
@@ -83,30 +74,52 @@ impl<'a> PolicyEnforcer<'a> {
             keystore,
             registry,
             caps,
+            sink: None,
         }
     }
 
+    /// Report every `allow` decision to `sink`.
+    pub fn with_decision_sink(mut self, sink: &'a dyn DecisionSink) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
     /* ───────────── PERMISSION CHECK ───────────── */
 
-    /// Check whether an operation is allowed.
+    /// Check whether an operation is allowed at `now`
+    /// (monotonic seconds; 0 = unknown).
     ///
     /// SECURITY:
     /// - Kill state overrides ALL permissions
+    /// - Expired / unknown-clock timed capabilities are denied
     /// - Fail-closed
-    pub fn allow(&self, op: Operation) -> bool {
-        if GLOBAL_KILLED.load(Ordering::SeqCst) || self.registry.is_killed() {
-            return false;
-        }
+    /// - Every decision is reported to the decision sink (if any)
+    pub fn allow(&self, op: Operation, now: u64) -> bool {
+        let killed = GLOBAL_KILLED.load(Ordering::SeqCst) || self.registry.is_killed();
+        let allowed = !killed && self.permits(op, now);
 
-        match op {
-            Operation::Upload         => self.caps.allows(Capability::Upload),
-            Operation::Download       => self.caps.allows(Capability::Download),
-            Operation::Restore        => self.caps.allows(Capability::Restore),
-            Operation::Route          => self.caps.allows(Capability::RouteContent),
-            Operation::ViewStatus     => self.caps.allows(Capability::ViewStatus),
-            Operation::RegisterDevice => self.caps.allows(Capability::RegisterDevice),
-            Operation::RemoveDevice   => self.caps.allows(Capability::RemoveDevice),
-            Operation::IssueKill      => self.caps.allows(Capability::IssueKill),
+        self.record(op, allowed, killed);
+        allowed
+    }
+
+    fn permits(&self, op: Operation, now: u64) -> bool {
+        let cap = match op {
+            Operation::Upload         => Capability::Upload,
+            Operation::Download       => Capability::Download,
+            Operation::Restore        => Capability::Restore,
+            Operation::Route          => Capability::RouteContent,
+            Operation::ViewStatus     => Capability::ViewStatus,
+            Operation::RegisterDevice => Capability::RegisterDevice,
+            Operation::RemoveDevice   => Capability::RemoveDevice,
+            Operation::IssueKill      => Capability::IssueKill,
+        };
+
+        self.caps.allows(cap, now)
+    }
+
+    fn record(&self, op: Operation, allowed: bool, killed: bool) {
+        if let Some(sink) = self.sink {
+            let _ = catch_unwind(AssertUnwindSafe(|| sink.on_decision(op, allowed, killed)));
         }
     }
 
@@ -118,9 +131,10 @@ impl<'a> PolicyEnforcer<'a> {
     /// - Authorization handled here
     /// - Execution delegated to kill subsystem
     /// - NEVER RETURNS
-    pub fn execute_kill(&self, reason: &str) -> ! {
-        // Authorization check (fail closed)
-        if !self.allow(Operation::IssueKill) {
+    pub fn execute_kill(&self, reason: &str, now: u64) -> ! {
+        // Authorization check (fail closed; audited by `allow`,
+        // including the unauthorized branch below)
+        if !self.allow(Operation::IssueKill, now) {
             // Unauthorized kill attempt → immediate local kill
             kill::execute("unauthorized kill attempt");
         }
@@ -128,4 +142,68 @@ impl<'a> PolicyEnforcer<'a> {
         // Delegate full execution
         kill::execute(reason)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::registry::RegistryError;
+    use std::cell::RefCell;
+
+    struct Recorder(RefCell<Vec<(Operation, bool, bool)>>);
+
+    impl DecisionSink for Recorder {
+        fn on_decision(&self, op: Operation, allowed: bool, killed: bool) {
+            self.0.borrow_mut().push((op, allowed, killed));
+        }
+    }
+
+    struct Panics;
+
+    impl DecisionSink for Panics {
+        fn on_decision(&self, _: Operation, _: bool, _: bool) {
+            std::panic::panic_any("sink failure");
+        }
+    }
+
+    #[test]
+    fn every_decision_reaches_the_sink() -> Result<(), RegistryError> {
+        crate::logging::encrypted::init_test_log_root();
+
+        let keystore = KeyStore::new();
+        let registry = DeviceRegistry::load_or_init(b"policy-test-device")?;
+        let caps = CapabilitySet::new(&[Capability::Upload]);
+
+        let recorder = Recorder(RefCell::new(Vec::new()));
+        let policy = PolicyEnforcer::new(&keystore, &registry, caps).with_decision_sink(&recorder);
+
+        let up = policy.allow(Operation::Upload, 1);
+        let down = policy.allow(Operation::Download, 1);
+
+        let seen = recorder.0.borrow();
+        assert_eq!(
+            &seen[..],
+            &[(Operation::Upload, up, false), (Operation::Download, false, false)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn panicking_sink_cannot_change_the_decision() -> Result<(), RegistryError> {
+        crate::logging::encrypted::init_test_log_root();
+
+        let keystore = KeyStore::new();
+        let registry = DeviceRegistry::load_or_init(b"policy-test-device")?;
+        let caps = || CapabilitySet::new(&[Capability::Upload]);
+
+        let silent = PolicyEnforcer::new(&keystore, &registry, caps());
+        let policy = PolicyEnforcer::new(&keystore, &registry, caps()).with_decision_sink(&Panics);
+
+        assert_eq!(
+            policy.allow(Operation::Upload, 1),
+            silent.allow(Operation::Upload, 1)
+        );
+        assert!(!policy.allow(Operation::IssueKill, 1));
+        Ok(())
+    }
+}
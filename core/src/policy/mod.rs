@@ -15,9 +15,11 @@ pub mod enforcement;
 pub use capability::{
     Capability,
     CapabilitySet,
+    TimedCapability,
 };
 
 pub use enforcement::{
+    DecisionSink,
     PolicyEnforcer,
     Operation,
 };
\ No newline at end of file
@@ -0,0 +1,39 @@
+//! Test-only helpers (never compiled into the library).
+
+use std::process::Command;
+
+/// Child marker: holds the full test path being re-run.
+const ISOLATED_ENV: &str = "RCX_ISOLATED_TEST";
+
+/// Run `body` in a fresh process and report whether it passed.
+///
+/// `GLOBAL_KILLED` can never be reset, so a test that trips it
+/// would kill every other test in this binary. The parent re-runs
+/// ONLY `test` (its full path, e.g. `keystore::tests::name`) in a
+/// child with `ISOLATED_ENV` set; the child executes `body`, whose
+/// failing asserts fail the child.
+///
+/// Usage (inside the test named `test`):
+/// `assert!(isolated("keystore::tests::name", || { ... }));`
+pub(crate) fn isolated(test: &str, body: impl FnOnce()) -> bool {
+    if std::env::var(ISOLATED_ENV).is_ok_and(|t| t == test) {
+        body();
+        return true;
+    }
+
+    let Ok(exe) = std::env::current_exe() else {
+        return false;
+    };
+
+    Command::new(exe)
+        .args([test, "--exact", "--test-threads=1"])
+        .env(ISOLATED_ENV, test)
+        .output()
+        .is_ok_and(|out| out.status.success() && ran_one(&out.stdout))
+}
+
+/// The child really ran the test (a typo'd path would run nothing
+/// and still exit 0).
+fn ran_one(stdout: &[u8]) -> bool {
+    String::from_utf8_lossy(stdout).contains("1 passed")
+}